@@ -0,0 +1,100 @@
+//! Automatic analysis of code, for targets without external tooling (symbol files, disassembler
+//! databases...) to label and structure their disassembly.
+
+use crate::{
+    cfg_view::{read_flows, ControlFlowGraph},
+    instruction_view::{
+        Flow, InstructionFlow, InstructionProvider, XrefProvider, INSTRUCTION_SIZE,
+    },
+    symbol::{Symbol, SymbolProvider, SymbolTable},
+    Address,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::Range,
+};
+
+/// What was found by [sweeping](Analysis::linear_sweep) through code.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Analysis {
+    /// Labels of the branch and call targets, like `sub_00001000` for call targets and
+    /// `loc_00001040` for the others.
+    pub labels: SymbolTable,
+    /// Basic blocks of the swept range and the edges between them, e.g. for a
+    /// [`CfgView`](crate::cfg_view::CfgView).
+    pub graph: ControlFlowGraph,
+    /// Call targets, sorted: the entry points of the functions called from the swept range.
+    pub functions: Vec<Address>,
+    /// Addresses of the instructions referencing each target.
+    xrefs: BTreeMap<Address, Vec<Address>>,
+}
+
+impl Analysis {
+    /// Sweeps linearly through `range`, decoding every instruction regardless of whether it is
+    /// reachable, and collects the targets of known jumps, branches and calls. Targets outside of
+    /// the range are labelled too, but only instructions inside of it make up the graph.
+    pub fn linear_sweep<I>(provider: &dyn InstructionProvider<I>, range: Range<Address>) -> Self
+    where
+        I: InstructionFlow + Clone,
+    {
+        let flows = read_flows(provider, range.clone());
+        let graph = ControlFlowGraph::from_flows(range.start, &flows);
+
+        let mut functions = BTreeSet::new();
+        let mut xrefs: BTreeMap<Address, Vec<Address>> = BTreeMap::new();
+        for (index, flow) in flows.iter().enumerate() {
+            let address = range.start + index as Address * INSTRUCTION_SIZE;
+            let target = match *flow {
+                Flow::Call(target) => {
+                    functions.insert(target);
+                    target
+                }
+                Flow::Jump(target) | Flow::Branch(target) => target,
+                _ => continue,
+            };
+
+            // sweeping in order keeps the references sorted
+            xrefs.entry(target).or_default().push(address);
+        }
+
+        let labels = xrefs
+            .keys()
+            .map(|&target| {
+                let prefix = if functions.contains(&target) {
+                    "sub"
+                } else {
+                    "loc"
+                };
+                Symbol::new(target, format!("{prefix}_{target:08X}"))
+            })
+            .collect();
+
+        Self {
+            labels,
+            graph,
+            functions: functions.into_iter().collect(),
+            xrefs,
+        }
+    }
+
+    /// Adds the labels to `symbols` where it has none yet, so that known names take precedence.
+    /// Returns the amount of labels added, which is 0 if `symbols` is read-only.
+    pub fn label(&self, symbols: &mut dyn SymbolProvider) -> usize {
+        if !symbols.is_mutable() {
+            return 0;
+        }
+
+        self.labels
+            .iter()
+            .filter(|&(address, name)| {
+                symbols.symbol_at(address).is_none() && symbols.add_label(address, name).is_ok()
+            })
+            .count()
+    }
+}
+
+impl XrefProvider for Analysis {
+    fn xrefs_to(&self, address: Address) -> Vec<Address> {
+        self.xrefs.get(&address).cloned().unwrap_or_default()
+    }
+}
@@ -0,0 +1,318 @@
+//! A run loop for applications built out of anton's widgets, taking care of setting up the
+//! terminal and restoring it afterwards, even on panics. Sessions can be recorded and played back
+//! with the [`recording`](crate::recording) module.
+
+use crate::{
+    provider::SnapshotProvider,
+    recording::{Entry, Recorder, Recording},
+};
+use crossterm::{
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use eyre::Result;
+use ratatui::{
+    backend::CrosstermBackend,
+    prelude::{Buffer, Rect},
+    widgets::Widget,
+    Terminal,
+};
+use std::{
+    io::{self, Stdout},
+    panic::{self, PanicHookInfo},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Whether the run loop should keep going.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    Continue,
+    Quit,
+}
+
+pub trait App {
+    /// Called once every tick, e.g. to refresh memory of a running target.
+    fn update(&mut self) -> Result<Control> {
+        Ok(Control::Continue)
+    }
+
+    /// Renders the application to the whole terminal.
+    fn draw(&mut self, area: Rect, buf: &mut Buffer);
+
+    /// Handles a terminal event. Resizes are handled by the run loop already, but are passed
+    /// along too.
+    fn handle_event(&mut self, event: Event) -> Result<Control>;
+
+    /// A snapshot of the memory currently shown, taken regularly while
+    /// [recording](Runner::record). Nothing is captured by default.
+    fn snapshot(&self) -> Option<SnapshotProvider> {
+        None
+    }
+
+    /// Shows the memory of a snapshot taken by [`snapshot`](Self::snapshot), while
+    /// [playing a recording back](Runner::play). Ignored by default.
+    fn restore(&mut self, _snapshot: &SnapshotProvider) {}
+
+    /// Called before [playing a recording back](Runner::play). The recorded events are fed to
+    /// the live application, so it should stop writing to its target until
+    /// [`playback_finished`](Self::playback_finished).
+    fn playback_started(&mut self) {}
+
+    /// Called once playback ends, whether the recording was played through, skipped or failed.
+    fn playback_finished(&mut self) {}
+}
+
+/// Runs an [`App`] in the alternate screen of the terminal.
+#[derive(Debug, Clone)]
+pub struct Runner {
+    tick_rate: Duration,
+    mouse: bool,
+}
+
+impl Default for Runner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Runner {
+    pub fn new() -> Self {
+        Self {
+            tick_rate: Duration::from_millis(250),
+            mouse: false,
+        }
+    }
+
+    /// How often [`App::update`] is called. Defaults to 250ms.
+    pub fn tick_rate(self, tick_rate: Duration) -> Self {
+        Self { tick_rate, ..self }
+    }
+
+    /// Whether to capture mouse events. Defaults to `false`.
+    pub fn mouse(self, mouse: bool) -> Self {
+        Self { mouse, ..self }
+    }
+
+    /// Runs the application until it quits or returns an error. The terminal is restored in any
+    /// case, including when the application panics.
+    pub fn run(&self, app: &mut impl App) -> Result<()> {
+        self.with_terminal(|terminal| self.run_loop(terminal, app, None))
+    }
+
+    /// Runs the application like [`run`](Self::run), recording the session with `recorder`:
+    /// every event and tick, and [snapshots](App::snapshot) of the memory shown when starting and
+    /// then as often as the recorder is configured to. The recording is finished once the
+    /// application quits, even if it returns an error.
+    pub fn record(&self, app: &mut impl App, mut recorder: Recorder) -> Result<()> {
+        let result = app
+            .snapshot()
+            .map_or(Ok(()), |snapshot| recorder.record_snapshot(&snapshot))
+            .and_then(|()| {
+                self.with_terminal(|terminal| self.run_loop(terminal, app, Some(&mut recorder)))
+            });
+
+        result.and(recorder.finish())
+    }
+
+    /// Plays `recording` back through the application, feeding it the recorded events, ticks and
+    /// [snapshots](App::restore) at the pace they were recorded, and then runs it like
+    /// [`run`](Self::run) so that the final state can be looked into. Esc skips to the end of the
+    /// recording. Playback stops early if the application quits, e.g. on the key that ended the
+    /// recorded session.
+    pub fn play(&self, app: &mut impl App, recording: &Recording) -> Result<()> {
+        self.with_terminal(|terminal| {
+            app.playback_started();
+            let played = self.play_loop(terminal, app, recording);
+            app.playback_finished();
+
+            played?;
+            self.run_loop(terminal, app, None)
+        })
+    }
+
+    /// Sets up the terminal for `f`, and restores it afterwards.
+    fn with_terminal(
+        &self,
+        f: impl FnOnce(&mut Terminal<CrosstermBackend<Stdout>>) -> Result<()>,
+    ) -> Result<()> {
+        let mouse = self.mouse;
+        let _hook = PanicHookGuard::install(mouse);
+
+        let result = setup(mouse).and_then(|mut terminal| {
+            let result = f(&mut terminal);
+            terminal.show_cursor()?;
+            result
+        });
+        let restored = restore(mouse);
+
+        result.and(restored)
+    }
+
+    fn run_loop(
+        &self,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+        app: &mut impl App,
+        mut recorder: Option<&mut Recorder>,
+    ) -> Result<()> {
+        let mut last_tick = Instant::now();
+        loop {
+            terminal.draw(|frame| frame.render_widget(Draw(app), frame.size()))?;
+
+            let timeout = self.tick_rate.saturating_sub(last_tick.elapsed());
+            if event::poll(timeout)? {
+                let event = event::read()?;
+                if let Some(recorder) = recorder.as_deref_mut() {
+                    recorder.record_event(&event)?;
+                }
+
+                if app.handle_event(event)? == Control::Quit {
+                    return Ok(());
+                }
+            }
+
+            if last_tick.elapsed() >= self.tick_rate {
+                if app.update()? == Control::Quit {
+                    return Ok(());
+                }
+
+                if let Some(recorder) = recorder.as_deref_mut() {
+                    if recorder.record_tick()? {
+                        if let Some(snapshot) = app.snapshot() {
+                            recorder.record_snapshot(&snapshot)?;
+                        }
+                    }
+                }
+
+                last_tick = Instant::now();
+            }
+        }
+    }
+
+    fn play_loop(
+        &self,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+        app: &mut impl App,
+        recording: &Recording,
+    ) -> Result<()> {
+        let start = Instant::now();
+        let mut skipping = false;
+        for (time, entry) in recording.entries() {
+            terminal.draw(|frame| frame.render_widget(Draw(app), frame.size()))?;
+
+            // events of the user are dropped, as the application is driven by the recording
+            while !skipping && start.elapsed() < *time {
+                let timeout = time.saturating_sub(start.elapsed());
+                if event::poll(timeout)? {
+                    if let Event::Key(key) = event::read()? {
+                        skipping = key.code == KeyCode::Esc && key.kind != KeyEventKind::Release;
+                    }
+                }
+            }
+
+            let control = match entry {
+                Entry::Event(event) => app.handle_event(event.clone())?,
+                Entry::Tick => app.update()?,
+                Entry::Snapshot(snapshot) => {
+                    app.restore(snapshot);
+                    Control::Continue
+                }
+            };
+            if control == Control::Quit {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs an [`App`] with the default [`Runner`].
+pub fn run(app: &mut impl App) -> Result<()> {
+    Runner::new().run(app)
+}
+
+fn setup(mouse: bool) -> Result<Terminal<CrosstermBackend<Stdout>>> {
+    terminal::enable_raw_mode()?;
+
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    if mouse {
+        execute!(stdout, EnableMouseCapture)?;
+    }
+
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+    terminal.hide_cursor()?;
+    terminal.clear()?;
+
+    Ok(terminal)
+}
+
+fn restore(mouse: bool) -> Result<()> {
+    let mut stdout = io::stdout();
+    if mouse {
+        execute!(stdout, DisableMouseCapture)?;
+    }
+
+    execute!(stdout, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+
+    Ok(())
+}
+
+/// An application as a widget, since frames only lend their buffer to widgets.
+struct Draw<'a, A>(&'a mut A);
+
+impl<A: App> Widget for Draw<'_, A> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        self.0.draw(area, buf);
+    }
+}
+
+type PanicHook = Box<dyn Fn(&PanicHookInfo<'_>) + Sync + Send>;
+
+/// Replaces the panic hook with one restoring the terminal, and puts the previous one back when
+/// dropped, including when unwinding from a panic.
+struct PanicHookGuard {
+    previous: Arc<PanicHook>,
+    /// Whether the hook still restores the terminal.
+    armed: Arc<AtomicBool>,
+}
+
+impl PanicHookGuard {
+    fn install(mouse: bool) -> Self {
+        let previous = Arc::new(panic::take_hook());
+        let armed = Arc::new(AtomicBool::new(true));
+        {
+            let previous = previous.clone();
+            let armed = armed.clone();
+            panic::set_hook(Box::new(move |info| {
+                // restore before printing, or the message would be lost in the alternate screen
+                if armed.load(Ordering::SeqCst) {
+                    let _ = restore(mouse);
+                }
+                (*previous)(info);
+            }));
+        }
+
+        Self { previous, armed }
+    }
+}
+
+impl Drop for PanicHookGuard {
+    fn drop(&mut self) {
+        self.armed.store(false, Ordering::SeqCst);
+
+        // the hook can't be replaced while unwinding, but disarmed it only calls the previous one
+        if !thread::panicking() {
+            let previous = self.previous.clone();
+            let _ = panic::take_hook();
+            panic::set_hook(Box::new(move |info| (*previous)(info)));
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Preferences read from `~/.config/anton/config.toml`, e.g.:
+//!
+//! ```toml
+//! theme = "solarized"
+//! bytes_per_row = 16
+//! endianness = "big"
+//! checksum = "crc8"
+//! record = "x: u16, y: u16, f32"
+//! scripts = ["follow.rhai"]
+//!
+//! [keymap]
+//! "ctrl+n" = "down"
+//! "ctrl+p" = "up"
+//!
+//! [layout]
+//! regions = true
+//! regions_width = 56
+//! focus = "memory"
+//! ```
+
+use anton::{
+    memory_view::{Endianness, RowChecksum},
+    Keymap, Theme,
+};
+use eyre::{bail, Result, WrapErr};
+use serde::{de, Deserialize, Deserializer};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub theme: ThemeConfig,
+    /// Bindings added to the default keymap, replacing conflicting ones.
+    pub keymap: Keymap,
+    /// Amount of bytes per row of the hex view. As many as fit if unset.
+    pub bytes_per_row: Option<u16>,
+    pub endianness: Endianness,
+    /// Checksum shown next to each row of the hex view, if any.
+    pub checksum: Option<RowChecksum>,
+    /// Fields the rows of the hex view can be decoded as, e.g. `x: u16, y: u16, f32`.
+    pub record: Option<String>,
+    /// Rhai scripts to load on startup. Relative paths are relative to the config file.
+    pub scripts: Vec<PathBuf>,
+    pub layout: LayoutConfig,
+}
+
+/// Either the name of a preset, or a theme whose unset styles are taken from the default one.
+#[derive(Debug)]
+pub enum ThemeConfig {
+    Preset(String),
+    Custom(Box<Theme>),
+}
+
+impl<'de> Deserialize<'de> for ThemeConfig {
+    // not untagged, so that errors in custom themes aren't swallowed
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match toml::Value::deserialize(deserializer)? {
+            toml::Value::String(name) => Ok(Self::Preset(name)),
+            value => Theme::deserialize(value)
+                .map(|theme| Self::Custom(Box::new(theme)))
+                .map_err(de::Error::custom),
+        }
+    }
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        Self::Custom(Box::default())
+    }
+}
+
+impl ThemeConfig {
+    pub fn theme(&self) -> Result<Theme> {
+        Ok(match self {
+            Self::Preset(name) => match Theme::preset(name) {
+                Some(theme) => theme,
+                None => bail!(
+                    "unknown theme `{name}`, expected one of {}",
+                    Theme::PRESETS.join(", ")
+                ),
+            },
+            Self::Custom(theme) => (**theme).clone(),
+        })
+    }
+}
+
+/// Which pane has focus on startup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StartupFocus {
+    #[default]
+    Memory,
+    Regions,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LayoutConfig {
+    /// Whether the region map is shown next to the hex view when attached to a process.
+    pub regions: bool,
+    /// Width of the region map, in cells.
+    pub regions_width: u16,
+    pub focus: StartupFocus,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            regions: true,
+            regions_width: 56,
+            focus: StartupFocus::Memory,
+        }
+    }
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/anton/config.toml`, or `~/.config/anton/config.toml`.
+    fn default_path() -> Option<PathBuf> {
+        let config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+
+        Some(config_home.join("anton").join("config.toml"))
+    }
+
+    /// Reads the config at the default path. A missing file is the same as an empty one.
+    pub fn load_default() -> Result<Self> {
+        match Self::default_path() {
+            Some(path) if path.exists() => Self::load(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .wrap_err_with(|| format!("failed to read {}", path.display()))?;
+
+        let mut config: Self = toml::from_str(&text)
+            .wrap_err_with(|| format!("invalid config in {}", path.display()))?;
+        if let Some(directory) = path.parent() {
+            for script in &mut config.scripts {
+                *script = directory.join(&*script);
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// The default keymap, extended with the configured bindings.
+    pub fn keymap(&self) -> Keymap {
+        let mut keymap = Keymap::default();
+        keymap.extend(&self.keymap);
+        keymap
+    }
+}
@@ -0,0 +1,1177 @@
+//! `anton`, a terminal hex viewer and editor built out of the library's widgets. Opens files, or
+//! attaches to running processes where a process provider is available for the platform.
+
+mod config;
+
+use anton::{
+    app::{App, Control, Runner},
+    control::{Command, ControlServer},
+    dialog::{ConfirmDialog, ConfirmDialogState, DialogResult, InputDialog, InputDialogState},
+    help_overlay::{HelpEntry, HelpOverlay},
+    keymap::Action,
+    memory_map_view::{MemoryMapView, MemoryMapViewState},
+    memory_view::{
+        Endianness, MemoryProvider, MemoryView, MemoryViewState, MemoryWriter, Region, RowChecksum,
+    },
+    provider::{EditBuffer, SnapshotProvider, VecProvider},
+    recording::{Recorder, Recording},
+    scripting::ScriptHost,
+    search::{self, SearchEvent, SearchState},
+    search_bar::SearchBar,
+    session::Session,
+    status_bar::StatusBar,
+    struct_view::{self, Field},
+    FocusManager, Keymap, Theme,
+};
+use clap::Parser;
+use config::{Config, LayoutConfig, StartupFocus};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use eyre::{bail, ensure, Result, WrapErr};
+use ratatui::{
+    prelude::{Buffer, Constraint, Direction, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, StatefulWidget, Widget},
+};
+use std::{
+    cell::RefCell,
+    fs,
+    ops::Range,
+    path::{Component, PathBuf},
+    rc::Rc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[cfg(all(feature = "process-linux", target_os = "linux"))]
+use anton::provider::LinuxProcessProvider as ProcessProvider;
+
+#[cfg(all(feature = "process-macos", target_os = "macos"))]
+use anton::provider::MachProcessProvider as ProcessProvider;
+
+#[cfg(all(feature = "process-windows", windows))]
+use anton::provider::WindowsProcessProvider as ProcessProvider;
+
+/// Browse and edit a file, or the memory of a running process, in a hex view.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Args {
+    /// The file to open.
+    #[arg(required_unless_present = "pid", conflicts_with = "pid")]
+    file: Option<PathBuf>,
+
+    /// Attach to the process with this pid instead of opening a file.
+    #[arg(long)]
+    pid: Option<u32>,
+
+    /// Allow writing to the memory of the process. Processes are read-only otherwise.
+    #[arg(long, conflicts_with = "file")]
+    write: bool,
+
+    /// Address the first byte of the file is mapped at, in hex. When attaching to a process,
+    /// the address of the process the 4 GiB window starts at instead.
+    #[arg(long, value_parser = parse_u64)]
+    base: Option<u64>,
+
+    /// Offset from the base to start at, in hex.
+    #[arg(long, value_parser = parse_address)]
+    offset: Option<u32>,
+
+    /// Config file to use instead of `~/.config/anton/config.toml`.
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Rhai script to load, in addition to the ones of the config. Can be repeated.
+    #[arg(long)]
+    script: Vec<PathBuf>,
+
+    /// Accept commands from other processes on this address: `unix:<path>`, or a loopback TCP
+    /// address such as `127.0.0.1:7777`.
+    #[arg(long, value_name = "ADDRESS")]
+    control: Option<String>,
+
+    /// Fields the rows can be decoded as, e.g. `x: u16, y: u16, f32`, shown with `t`. Replaces
+    /// the record of the config.
+    #[arg(long, value_name = "FIELDS")]
+    record: Option<String>,
+
+    /// Read the whole view again only every this many ticks of 250 ms, e.g. for targets that are
+    /// slow to read. Every frame otherwise.
+    #[arg(long, value_name = "TICKS")]
+    refresh: Option<u32>,
+
+    /// Record the session to this file, along with snapshots of the memory shown, e.g. to attach
+    /// to a bug report.
+    #[arg(long, value_name = "FILE", conflicts_with = "replay")]
+    record_session: Option<PathBuf>,
+
+    /// Replay a session recorded with `--record-session`, showing the recorded memory instead of
+    /// the target's, then carry on from where it ended. Esc skips to the end.
+    #[arg(long, value_name = "FILE")]
+    replay: Option<PathBuf>,
+}
+
+fn strip_hex_prefix(text: &str) -> &str {
+    let text = text.trim();
+    text.strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))
+        .unwrap_or(text)
+}
+
+/// Parses a hex address, with or without a `0x` prefix.
+fn parse_address(text: &str) -> Result<u32, String> {
+    u32::from_str_radix(strip_hex_prefix(text), 16)
+        .map_err(|_| format!("`{}` is not a hex address", text.trim()))
+}
+
+fn parse_u64(text: &str) -> Result<u64, String> {
+    u64::from_str_radix(strip_hex_prefix(text), 16)
+        .map_err(|_| format!("`{}` is not a hex address", text.trim()))
+}
+
+const HELP: &[(&str, &str)] = &[
+    ("arrows/hjkl", "move"),
+    ("pgup/pgdn", "scroll a page"),
+    ("home/end", "start/end of file"),
+    ("g", "go to address"),
+    ("/", "search hex bytes or \"text\""),
+    ("n/N", "next/previous match"),
+    ("'", "next bookmark"),
+    ("space", "peek at where the selected value points"),
+    ("*", "highlight bytes equal to the selected one"),
+    ("f", "follow the value at the selected address / stop"),
+    ("t", "decode rows as records / show bytes"),
+    ("m", "show offsets from the selected address / clear them"),
+    ("tab", "switch between the map and the hex view"),
+    ("enter", "toggle edit mode / jump to region"),
+    ("0-9 a-f", "edit the selected byte"),
+    ("p", "paste hex bytes or \"text\" at the selected byte"),
+    ("z", "fill bytes from the selected one with a pattern"),
+    ("u", "undo unsaved edits"),
+    ("ctrl+s", "save / write edits to the process"),
+    ("?", "toggle this help"),
+    ("q", "quit"),
+];
+
+/// What is being viewed.
+enum Target {
+    File(PathBuf),
+    /// A process, by pid and address of the window into its address space.
+    Process(u32, u64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pane {
+    Memory,
+    Map,
+}
+
+enum Mode {
+    Normal,
+    /// Typing hex digits overwrites bytes. Holds the high nibble already typed, if any.
+    Edit(Option<u8>),
+    /// Editing the search query. Holds the pointer when the search was opened.
+    Search(u32),
+    /// Typing bytes to write at the pointer.
+    Paste(InputDialogState),
+    /// Typing the length and pattern of bytes to write from the pointer.
+    Fill(InputDialogState),
+    ConfirmQuit(ConfirmDialogState),
+    Help,
+}
+
+struct Viewer<P> {
+    target: Target,
+    /// Shared with the scripts.
+    provider: Rc<RefCell<EditBuffer<P>>>,
+    scripts: ScriptHost,
+    control: Option<ControlServer>,
+    /// Bookmarks added through the control channel.
+    session: Session,
+    /// First and last address the pointer can move to.
+    bounds: (u32, u32),
+    /// Whether edits can be made.
+    writable: bool,
+    state: MemoryViewState,
+    /// Edits of the provider as of the last draw, to read it again after local changes even
+    /// between refreshes.
+    edits: u64,
+    search: SearchState,
+    mode: Mode,
+    /// A message shown in the status bar until the next key press.
+    message: Option<String>,
+    /// Height of the hex view, as of the last draw.
+    rows: u16,
+    /// Regions of the process, refreshed every tick.
+    regions: Vec<Region>,
+    map: MemoryMapViewState,
+    focus: FocusManager<Pane>,
+    bytes_per_row: Option<u16>,
+    endianness: Endianness,
+    checksum: Option<RowChecksum>,
+    /// Fields the rows are decoded as, and whether they are.
+    record: Option<(Vec<Field>, bool)>,
+    layout: LayoutConfig,
+    theme: Theme,
+    keymap: Keymap,
+    /// Memory of the session being replayed, shown instead of the target's.
+    replayed: Option<SnapshotProvider>,
+    /// While a session is played back, whether edits can be made once it ends.
+    writable_after_playback: Option<bool>,
+}
+
+impl Viewer<VecProvider> {
+    fn open(path: PathBuf, base: u64, offset: u32, config: Config) -> Result<Self> {
+        let data =
+            fs::read(&path).wrap_err_with(|| format!("failed to read {}", path.display()))?;
+        if data.is_empty() {
+            bail!("{} is empty", path.display());
+        }
+
+        if base
+            .checked_add(data.len() as u64)
+            .is_none_or(|end| end > 1 << 32)
+        {
+            bail!(
+                "{} doesn't fit in the address space at base {base:#X}",
+                path.display()
+            );
+        }
+
+        if offset as usize >= data.len() {
+            bail!(
+                "offset {offset:#X} is past the end of {} ({:#X} bytes)",
+                path.display(),
+                data.len()
+            );
+        }
+
+        let base = base as u32;
+        let last = base + (data.len() - 1) as u32;
+        Self::new(
+            Target::File(path),
+            VecProvider::new(base, data),
+            (base, last),
+            base + offset,
+            true,
+            config,
+        )
+    }
+}
+
+#[cfg(any(
+    all(feature = "process-linux", target_os = "linux"),
+    all(feature = "process-macos", target_os = "macos"),
+    all(feature = "process-windows", windows),
+))]
+impl Viewer<ProcessProvider> {
+    fn attach(
+        pid: u32,
+        base: Option<u64>,
+        offset: Option<u32>,
+        writable: bool,
+        config: Config,
+    ) -> Result<Self> {
+        let provider = ProcessProvider::attach(pid)?;
+        let base = match base {
+            Some(base) => base,
+            None => default_base(&provider),
+        };
+        let provider = provider.with_base(base);
+
+        // start at the first readable region unless told otherwise
+        let pointer = offset.unwrap_or_else(|| {
+            provider
+                .regions()
+                .iter()
+                .find(|region| region.permissions.read)
+                .map_or(0, |region| region.start)
+        });
+
+        Self::new(
+            Target::Process(pid, base),
+            provider,
+            (0, u32::MAX),
+            pointer,
+            writable,
+            config,
+        )
+    }
+}
+
+/// The start of the first mapping of the process, so that the window covers its executable.
+#[cfg(all(feature = "process-linux", target_os = "linux"))]
+fn default_base(provider: &ProcessProvider) -> u64 {
+    provider
+        .maps()
+        .ok()
+        .and_then(|maps| maps.first().map(|mapping| mapping.start))
+        .unwrap_or(0)
+}
+
+#[cfg(any(
+    all(feature = "process-macos", target_os = "macos"),
+    all(feature = "process-windows", windows),
+))]
+fn default_base(_provider: &ProcessProvider) -> u64 {
+    0
+}
+
+impl<P> Viewer<P>
+where
+    P: MemoryProvider + MemoryWriter + 'static,
+{
+    fn new(
+        target: Target,
+        provider: P,
+        bounds: (u32, u32),
+        pointer: u32,
+        writable: bool,
+        config: Config,
+    ) -> Result<Self> {
+        let theme = config.theme.theme()?;
+        let keymap = config.keymap();
+        let record = match &config.record {
+            Some(spec) => match struct_view::parse_record(spec) {
+                Some(fields) => Some((fields, true)),
+                None => {
+                    bail!("invalid record `{spec}`, expected fields like `x: u16, y: u16, f32`")
+                }
+            },
+            None => None,
+        };
+        let show_regions = matches!(target, Target::Process(..)) && config.layout.regions;
+        let panes = if show_regions {
+            vec![Pane::Memory, Pane::Map]
+        } else {
+            vec![Pane::Memory]
+        };
+
+        let mut focus = FocusManager::new(panes).theme(&theme);
+        if config.layout.focus == StartupFocus::Regions {
+            focus.focus(Pane::Map);
+        }
+
+        let provider = Rc::new(RefCell::new(EditBuffer::new(provider)));
+        let mut scripts = if writable {
+            ScriptHost::new(provider.clone())
+        } else {
+            ScriptHost::read_only(provider.clone())
+        };
+        for path in &config.scripts {
+            scripts.load_file(path)?;
+        }
+
+        let mut viewer = Self {
+            target,
+            provider,
+            scripts,
+            control: None,
+            session: Session::new(),
+            bounds,
+            writable,
+            state: MemoryViewState::new(pointer),
+            edits: 0,
+            search: SearchState::new(),
+            mode: Mode::Normal,
+            message: None,
+            rows: 1,
+            regions: Vec::new(),
+            map: MemoryMapViewState::new(),
+            focus,
+            bytes_per_row: config.bytes_per_row,
+            endianness: config.endianness,
+            checksum: config.checksum,
+            record,
+            layout: config.layout,
+            theme,
+            keymap,
+            replayed: None,
+            writable_after_playback: None,
+        };
+        viewer.refresh_regions();
+        Ok(viewer)
+    }
+
+    fn refresh_regions(&mut self) {
+        if let Target::Process(..) = self.target {
+            self.regions = self.provider.borrow().regions();
+        }
+    }
+
+    fn name(&self) -> String {
+        match &self.target {
+            Target::File(path) => path.display().to_string(),
+            Target::Process(pid, _) => format!("pid {pid}"),
+        }
+    }
+
+    fn first_address(&self) -> u32 {
+        self.bounds.0
+    }
+
+    fn last_address(&self) -> u32 {
+        self.bounds.1
+    }
+
+    /// Moves the pointer by `delta` bytes, staying inside of the file.
+    fn move_by(&mut self, delta: i64) {
+        let pointer = (self.state.pointer as i64 + delta)
+            .clamp(self.first_address() as i64, self.last_address() as i64);
+        self.state.pointer = pointer as u32;
+    }
+
+    fn row_len(&self) -> i64 {
+        self.state.bytes_per_bucket().max(1) as i64
+    }
+
+    fn goto(&mut self, address: u32) {
+        if (self.first_address()..=self.last_address()).contains(&address) {
+            self.state.pointer = address;
+        } else {
+            self.message = Some(format!("{address:#X} is out of bounds"));
+        }
+    }
+
+    /// The ranges searches go through: the whole file, or the readable regions of a process.
+    /// Ranges searched, as `u64` so that they can include the last byte of the address space.
+    fn search_ranges(&self) -> Vec<Range<u64>> {
+        match self.target {
+            Target::File(_) => {
+                let file = self.first_address() as u64..self.last_address() as u64 + 1;
+                vec![file]
+            }
+            Target::Process(..) => self
+                .regions
+                .iter()
+                .filter(|region| region.permissions.read)
+                .map(|region| region.start as u64..region.end())
+                .collect(),
+        }
+    }
+
+    fn run_search(&mut self) {
+        let Some(pattern) = search::parse_pattern(self.search.query()) else {
+            self.search.set_matches(Vec::new());
+            return;
+        };
+
+        let provider = self.provider.borrow();
+        let matches = self
+            .search_ranges()
+            .into_iter()
+            .flat_map(|range| search::find_in_memory(&*provider, range, &pattern, usize::MAX))
+            .collect();
+        drop(provider);
+        self.search.set_matches(matches);
+    }
+
+    fn jump_to_match(&mut self, next: bool) {
+        let pointer = self.state.pointer;
+        let found = if next {
+            self.search.next_match(pointer)
+        } else {
+            self.search.previous_match(pointer)
+        };
+
+        match found {
+            Some(address) => self.state.pointer = address,
+            None if self.search.query().is_empty() => (),
+            None => self.message = Some("no matches".to_owned()),
+        }
+    }
+
+    /// Writes a hex digit to the selected byte: the high nibble first, then the low one, after
+    /// which the pointer moves to the next byte.
+    fn edit(&mut self, digit: u8, high: Option<u8>) {
+        let mut current = [None];
+        self.provider
+            .borrow()
+            .read_to_buf(self.state.pointer, &mut current);
+        let current = current[0].unwrap_or(0);
+
+        let value = match high {
+            None => (digit << 4) | (current & 0x0F),
+            Some(high) => (high << 4) | digit,
+        };
+        self.provider
+            .borrow_mut()
+            .write_from_buf(self.state.pointer, &[value]);
+
+        self.mode = match high {
+            None => Mode::Edit(Some(digit)),
+            Some(_) => {
+                self.move_by(1);
+                Mode::Edit(None)
+            }
+        };
+    }
+
+    fn paste(&mut self, input: &str) {
+        let pointer = self.state.pointer;
+        let mut provider = self.provider.borrow_mut();
+        let Some(len) = provider.paste_hex(pointer, input) else {
+            self.message = Some(format!(
+                "`{}` is neither hex bytes nor \"text\"",
+                input.trim()
+            ));
+            return;
+        };
+
+        // bytes past the end of the file can't be saved
+        let end = pointer.saturating_add(len as u32 - 1);
+        let last = self.last_address();
+        self.message = Some(if end > last {
+            provider.rollback_range(last + 1, end);
+            format!(
+                "pasted {} bytes, the rest is past the end",
+                last - pointer + 1
+            )
+        } else {
+            format!("pasted {len} bytes")
+        });
+    }
+
+    /// Fills bytes from the pointer, given their amount and the pattern to repeat, e.g. `100 00`.
+    fn fill(&mut self, input: &str) {
+        let input = input.trim();
+        let (len, pattern) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+        let len = match parse_address(len) {
+            Ok(len) if len > 0 => len,
+            _ => {
+                self.message = Some(format!("`{}` is not a hex length", len.trim()));
+                return;
+            }
+        };
+        let Some(pattern) = search::parse_pattern(pattern) else {
+            self.message = Some(format!(
+                "`{}` is neither hex bytes nor \"text\"",
+                pattern.trim()
+            ));
+            return;
+        };
+
+        let pointer = self.state.pointer;
+        let end = pointer.saturating_add(len - 1).min(self.last_address());
+        self.provider
+            .borrow_mut()
+            .fill_range(pointer..=end, &pattern);
+        self.message = Some(format!("filled {:#X} bytes", end - pointer + 1));
+    }
+
+    /// Applies the edits, and writes the file back if a file is being viewed.
+    fn save(&mut self) -> Result<()> {
+        ensure!(self.writable, "read-only, pass --write to save");
+
+        let mut provider = self.provider.borrow_mut();
+        if !provider.is_dirty() {
+            self.message = Some("nothing to save".to_owned());
+            return Ok(());
+        }
+
+        let written = provider.pending_len();
+        if !provider.commit() {
+            bail!("failed to write {} bytes", provider.pending_len());
+        }
+
+        if let Target::File(path) = &self.target {
+            let (first, last) = self.bounds;
+            let mut data = vec![None; (last - first) as usize + 1];
+            provider.read_to_buf(first, &mut data);
+
+            let data: Vec<u8> = data.into_iter().map(|value| value.unwrap_or(0)).collect();
+            fs::write(path, data)
+                .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+        }
+
+        self.message = Some(format!("saved {written} bytes"));
+        Ok(())
+    }
+
+    /// Jumps to the first bookmark after the pointer, wrapping around.
+    fn next_bookmark(&mut self) {
+        let pointer = self.state.pointer;
+        let addresses = self
+            .session
+            .bookmarks
+            .iter()
+            .map(|bookmark| bookmark.address);
+        let next = addresses
+            .clone()
+            .filter(|&address| address > pointer)
+            .min()
+            .or_else(|| addresses.min());
+
+        match next {
+            Some(address) => self.goto(address),
+            None => self.message = Some("no bookmarks".to_owned()),
+        }
+    }
+
+    fn save_snapshot(&self, path: Option<PathBuf>) -> Result<()> {
+        let path = path.unwrap_or_else(|| {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            PathBuf::from(format!("anton-{}.snap", now.as_secs()))
+        });
+
+        let provider = self.provider.borrow();
+        SnapshotProvider::capture(&*provider, &self.search_ranges())
+            .with_target(self.name())
+            .save(path)
+    }
+
+    fn handle_command(&mut self, command: Command) -> Result<()> {
+        match command {
+            Command::Goto { address } => {
+                ensure!(
+                    (self.first_address()..=self.last_address()).contains(&address),
+                    "{address:#X} is out of bounds"
+                );
+                self.state.pointer = address;
+                self.focus.focus(Pane::Memory);
+            }
+            Command::Bookmark { name, address } => {
+                let address = address.unwrap_or(self.state.pointer);
+                self.message = Some(format!("bookmarked {name} at {address:08X}"));
+                self.session.bookmark(name, address);
+            }
+            Command::Snapshot { path } => {
+                // clients only get to name the file, in the working directory
+                if let Some(path) = &path {
+                    let mut components = path.components();
+                    ensure!(
+                        matches!(
+                            (components.next(), components.next()),
+                            (Some(Component::Normal(_)), None)
+                        ),
+                        "{} isn't a plain file name",
+                        path.display()
+                    );
+                }
+                self.save_snapshot(path)?;
+                self.message = Some("snapshot saved".to_owned());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn poll_control(&mut self) {
+        // taken out so that commands can borrow the viewer
+        let Some(mut control) = self.control.take() else {
+            return;
+        };
+
+        control.poll(|command| self.handle_command(command));
+        self.control = Some(control);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Result<Control> {
+        self.message = None;
+
+        if self.state.is_goto_open() {
+            // jumps are bounds checked like any other
+            let pointer = self.state.pointer;
+            self.state.handle_key_with(key, &self.keymap, None);
+            let target = std::mem::replace(&mut self.state.pointer, pointer);
+            if target != pointer {
+                self.goto(target);
+            }
+
+            return Ok(Control::Continue);
+        }
+
+        let mode = std::mem::replace(&mut self.mode, Mode::Normal);
+        match mode {
+            Mode::Normal => return self.handle_normal_key(key),
+            Mode::Edit(high) => {
+                let digit = match key.code {
+                    KeyCode::Char(c)
+                        if key.modifiers.difference(KeyModifiers::SHIFT).is_empty() =>
+                    {
+                        c.to_digit(16)
+                    }
+                    _ => None,
+                };
+
+                match (digit, key.code) {
+                    (Some(digit), _) => self.edit(digit as u8, high),
+                    (None, KeyCode::Esc | KeyCode::Enter) => (),
+                    _ => {
+                        self.mode = Mode::Edit(None);
+                        return self.handle_normal_key(key);
+                    }
+                }
+            }
+            Mode::Search(origin) => {
+                self.mode = Mode::Search(origin);
+                match self.search.handle_key(key) {
+                    Some(SearchEvent::QueryChanged) => {
+                        self.run_search();
+                        self.state.pointer = origin;
+                        if let Some(address) = self.search.next_match(origin.saturating_sub(1)) {
+                            self.state.pointer = address;
+                        }
+                    }
+                    Some(SearchEvent::Next) => self.jump_to_match(true),
+                    Some(SearchEvent::Previous) => self.jump_to_match(false),
+                    Some(SearchEvent::Closed) => self.mode = Mode::Normal,
+                    Some(SearchEvent::DirectionChanged | SearchEvent::Cancelled) | None => (),
+                }
+            }
+            Mode::Paste(mut dialog) => {
+                dialog.handle_key(key);
+                match dialog.poll() {
+                    Some(DialogResult::Submitted(input)) => self.paste(&input),
+                    Some(DialogResult::Cancelled) => (),
+                    None => self.mode = Mode::Paste(dialog),
+                }
+            }
+            Mode::Fill(mut dialog) => {
+                dialog.handle_key(key);
+                match dialog.poll() {
+                    Some(DialogResult::Submitted(input)) => self.fill(&input),
+                    Some(DialogResult::Cancelled) => (),
+                    None => self.mode = Mode::Fill(dialog),
+                }
+            }
+            Mode::ConfirmQuit(mut dialog) => {
+                dialog.handle_key(key);
+                match dialog.poll() {
+                    Some(DialogResult::Submitted(true)) => return Ok(Control::Quit),
+                    Some(_) => (),
+                    None => self.mode = Mode::ConfirmQuit(dialog),
+                }
+            }
+            Mode::Help => (),
+        }
+
+        Ok(Control::Continue)
+    }
+
+    /// Runs the script command bound to a key, if any. Returns whether there was one.
+    fn run_script(&mut self, key: KeyEvent) -> bool {
+        let Some(result) = self.scripts.handle_key(key, self.state.pointer) else {
+            return false;
+        };
+
+        match result {
+            Ok(Some(address)) => self.goto(address),
+            Ok(None) => (),
+            Err(error) => self.message = Some(format!("{error:#}")),
+        }
+
+        if let Some(output) = self.scripts.take_output().pop() {
+            self.message.get_or_insert(output);
+        }
+
+        true
+    }
+
+    fn handle_normal_key(&mut self, key: KeyEvent) -> Result<Control> {
+        let Some(pane) = self.focus.handle_key(key, &self.keymap) else {
+            return Ok(Control::Continue);
+        };
+
+        if self.run_script(key) {
+            return Ok(Control::Continue);
+        }
+
+        let action = self.keymap.action(key);
+        let navigation = matches!(
+            action,
+            Some(
+                Action::Up
+                    | Action::Down
+                    | Action::PageUp
+                    | Action::PageDown
+                    | Action::Home
+                    | Action::End
+                    | Action::Activate
+            )
+        );
+        if pane == Pane::Map && navigation {
+            if let Some(address) = self.map.handle_key_with(key, &self.keymap) {
+                self.state.pointer = address;
+                self.focus.focus(Pane::Memory);
+            }
+
+            return Ok(Control::Continue);
+        }
+
+        let page = self.row_len() * self.rows.saturating_sub(6).max(1) as i64;
+        match action {
+            Some(Action::Up) => self.move_by(-self.row_len()),
+            Some(Action::Down) => self.move_by(self.row_len()),
+            Some(Action::Left) => self.move_by(-1),
+            Some(Action::Right) => self.move_by(1),
+            Some(Action::PageUp) => self.move_by(-page),
+            Some(Action::PageDown) => self.move_by(page),
+            Some(Action::Home) => self.state.pointer = self.first_address(),
+            Some(Action::End) => self.state.pointer = self.last_address(),
+            Some(Action::Activate) if self.writable => self.mode = Mode::Edit(None),
+            Some(Action::Activate) => {
+                self.message = Some("read-only, pass --write to edit".to_owned())
+            }
+            Some(Action::Goto) => self.state.open_goto(),
+            Some(Action::Search) => {
+                self.search.set_query("");
+                self.run_search();
+                self.mode = Mode::Search(self.state.pointer);
+            }
+            Some(Action::Toggle) => self.state.toggle_preview(),
+            Some(Action::Follow) => match self.state.follow() {
+                Some(_) => self.state.set_follow(None),
+                None => self.state.set_follow(Some(self.state.pointer)),
+            },
+            Some(Action::SearchNext) => self.jump_to_match(true),
+            Some(Action::SearchPrevious) => self.jump_to_match(false),
+            _ => match key.code {
+                KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    if let Err(error) = self.save() {
+                        self.message = Some(format!("{error:#}"));
+                    }
+                }
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(Control::Quit)
+                }
+                KeyCode::Char('q') if self.provider.borrow().is_dirty() => {
+                    self.mode = Mode::ConfirmQuit(ConfirmDialogState::new())
+                }
+                KeyCode::Char('q') => return Ok(Control::Quit),
+                KeyCode::Char('u') => self.provider.borrow_mut().rollback(),
+                KeyCode::Char('\'') => self.next_bookmark(),
+                KeyCode::Char('p') if self.writable => {
+                    self.mode = Mode::Paste(InputDialogState::new())
+                }
+                KeyCode::Char('z') if self.writable => {
+                    self.mode = Mode::Fill(InputDialogState::new())
+                }
+                KeyCode::Char('p' | 'z') => {
+                    self.message = Some("read-only, pass --write to edit".to_owned())
+                }
+                KeyCode::Char('*') => self.state.toggle_highlight(),
+                KeyCode::Char('t') => match &mut self.record {
+                    Some((_, shown)) => *shown = !*shown,
+                    None => self.message = Some("no record, pass --record".to_owned()),
+                },
+                KeyCode::Char('m') => match self.state.base() {
+                    Some((_, base)) if base == self.state.pointer => self.state.clear_base(),
+                    _ => self.state.mark_base("@", self.state.pointer),
+                },
+                KeyCode::Char('?') => self.mode = Mode::Help,
+                _ => (),
+            },
+        }
+
+        Ok(Control::Continue)
+    }
+
+    fn render_status_bar(&self, area: Rect, buf: &mut Buffer) {
+        let mode = match self.mode {
+            Mode::Edit(_) => "EDIT",
+            Mode::Search(_) => "SEARCH",
+            _ => "VIEW",
+        };
+
+        let pending = self.provider.borrow().pending_len();
+        let center = match &self.message {
+            Some(message) => message.clone(),
+            None if pending > 0 => format!("{pending} bytes modified"),
+            None => "? for help".to_owned(),
+        };
+
+        let right = match self.target {
+            Target::File(_) => {
+                let offset = self.state.pointer - self.first_address();
+                format!("{:08X} (+{offset:X})", self.state.pointer)
+            }
+            Target::Process(_, base) => {
+                format!(
+                    "{:08X} ({:#X})",
+                    self.state.pointer,
+                    base + self.state.pointer as u64
+                )
+            }
+        };
+
+        StatusBar::new()
+            .theme(&self.theme)
+            .left(mode)
+            .center(center)
+            .right(right)
+            .render(area, buf);
+    }
+}
+
+impl<P> App for Viewer<P>
+where
+    P: MemoryProvider + MemoryWriter + 'static,
+{
+    fn update(&mut self) -> Result<Control> {
+        self.state.tick();
+        self.refresh_regions();
+        self.poll_control();
+        Ok(Control::Continue)
+    }
+
+    /// The rows shown, and a page before and after them.
+    fn snapshot(&self) -> Option<SnapshotProvider> {
+        let page = (self.row_len() * self.rows.max(1) as i64) as u32;
+        let start = self
+            .state
+            .pointer
+            .saturating_sub(2 * page)
+            .max(self.first_address());
+        let end = (self.state.pointer as u64 + 2 * page as u64).min(self.last_address() as u64 + 1);
+        let range = start as u64..end;
+
+        let provider = self.provider.borrow();
+        Some(
+            SnapshotProvider::capture(&*provider, std::slice::from_ref(&range))
+                .with_target(self.name()),
+        )
+    }
+
+    fn restore(&mut self, snapshot: &SnapshotProvider) {
+        self.replayed = Some(snapshot.clone());
+    }
+
+    /// The recorded keys reach the live target, so edits are disabled until the end.
+    fn playback_started(&mut self) {
+        self.writable_after_playback = Some(self.writable);
+        self.writable = false;
+        self.scripts.set_writable(false);
+    }
+
+    fn playback_finished(&mut self) {
+        if let Some(writable) = self.writable_after_playback.take() {
+            self.writable = writable;
+            self.scripts.set_writable(writable);
+        }
+        self.replayed = None;
+    }
+
+    fn draw(&mut self, area: Rect, buf: &mut Buffer) {
+        let searching = matches!(self.mode, Mode::Search(_));
+        let interpreting = self.scripts.has_interpreters();
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(1),
+                Constraint::Length(searching as u16),
+                Constraint::Length(interpreting as u16),
+                Constraint::Length(1),
+            ])
+            .split(area);
+
+        // before borrowing the provider for rendering, as interpreters borrow it too
+        let interpretations = if interpreting {
+            self.scripts.interpret(self.state.pointer)
+        } else {
+            Vec::new()
+        };
+
+        let mut title = format!(" {} ", self.name());
+        if !self.writable {
+            title.push_str("(read-only) ");
+        }
+        if self.provider.borrow().is_dirty() {
+            title.push_str("[+] ");
+        }
+        if self.replayed.is_some() {
+            title.push_str("(replay) ");
+        }
+
+        let (map_area, memory_area) = match self.target {
+            Target::Process(..) if self.layout.regions => {
+                let columns = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([
+                        Constraint::Length(self.layout.regions_width),
+                        Constraint::Min(1),
+                    ])
+                    .split(chunks[0]);
+                (Some(columns[0]), columns[1])
+            }
+            _ => (None, chunks[0]),
+        };
+        self.focus.set_area(Pane::Map, map_area);
+        self.focus.set_area(Pane::Memory, Some(memory_area));
+
+        if let Some(map_area) = map_area {
+            let block = Block::new()
+                .borders(Borders::ALL)
+                .title(Span::styled(" Regions ", self.theme.title));
+            MemoryMapView::new(&self.regions)
+                .pointer(self.state.pointer)
+                .theme(&self.theme)
+                .block(self.focus.block(Pane::Map, block))
+                .render(map_area, buf, &mut self.map);
+        }
+
+        let block = Block::new()
+            .borders(Borders::ALL)
+            .title(Span::styled(title, self.theme.title));
+        self.rows = memory_area.height;
+        let provider = self.provider.borrow();
+        if provider.edits() != self.edits {
+            self.edits = provider.edits();
+            self.state.invalidate();
+        }
+        let view = match &self.replayed {
+            Some(replayed) => MemoryView::new(replayed),
+            None => MemoryView::new(&*provider),
+        };
+        let view = view
+            .theme(&self.theme)
+            .endianness(self.endianness)
+            .block(self.focus.block(Pane::Memory, block));
+        let view = match self.bytes_per_row {
+            Some(bytes_per_row) => view.bytes_per_row(bytes_per_row),
+            None => view,
+        };
+        let view = match self.checksum {
+            Some(checksum) => view.checksum(checksum),
+            None => view,
+        };
+        let view = match &self.record {
+            Some((fields, true)) => view.record(fields),
+            _ => view,
+        };
+        view.render(memory_area, buf, &mut self.state);
+        if self.state.follow().is_some() {
+            // followed values may point anywhere
+            self.state.pointer = self
+                .state
+                .pointer
+                .clamp(self.first_address(), self.last_address());
+        }
+        drop(provider);
+
+        if searching {
+            SearchBar::new()
+                .theme(&self.theme)
+                .render(chunks[1], buf, &mut self.search);
+        }
+
+        if interpreting {
+            let spans: Vec<_> = interpretations
+                .into_iter()
+                .enumerate()
+                .flat_map(|(index, (name, text))| {
+                    let separator = if index == 0 { " " } else { " │ " };
+                    [
+                        Span::styled(separator, self.theme.muted),
+                        Span::styled(format!("{name}: "), self.theme.header),
+                        Span::styled(text, self.theme.info),
+                    ]
+                })
+                .collect();
+            buf.set_line(
+                chunks[2].x,
+                chunks[2].y,
+                &Line::from(spans),
+                chunks[2].width,
+            );
+        }
+
+        self.render_status_bar(chunks[3], buf);
+
+        match &mut self.mode {
+            Mode::Paste(dialog) => InputDialog::new("Paste")
+                .prompt("Hex bytes or \"text\":")
+                .theme(&self.theme)
+                .render(area, buf, dialog),
+            Mode::Fill(dialog) => InputDialog::new("Fill")
+                .prompt("Length and pattern, e.g. 100 00:")
+                .theme(&self.theme)
+                .render(area, buf, dialog),
+            Mode::ConfirmQuit(dialog) => ConfirmDialog::new("Quit", "Discard unsaved changes?")
+                .theme(&self.theme)
+                .render(area, buf, dialog),
+            Mode::Help => {
+                let entries: Vec<_> = HELP
+                    .iter()
+                    .map(|(key, description)| HelpEntry::new(*key, *description))
+                    .collect();
+                HelpOverlay::new(&entries)
+                    .theme(&self.theme)
+                    .render(area, buf);
+            }
+            _ => (),
+        }
+    }
+
+    fn handle_event(&mut self, event: Event) -> Result<Control> {
+        match event {
+            Event::Key(key) if key.kind != KeyEventKind::Release => self.handle_key(key),
+            _ => Ok(Control::Continue),
+        }
+    }
+}
+
+#[cfg(any(
+    all(feature = "process-linux", target_os = "linux"),
+    all(feature = "process-macos", target_os = "macos"),
+    all(feature = "process-windows", windows),
+))]
+fn run_process(args: &Args, pid: u32, config: Config) -> Result<()> {
+    let viewer = Viewer::attach(pid, args.base, args.offset, args.write, config)?;
+    run(viewer, args)
+}
+
+#[cfg(not(any(
+    all(feature = "process-linux", target_os = "linux"),
+    all(feature = "process-macos", target_os = "macos"),
+    all(feature = "process-windows", windows),
+)))]
+fn run_process(_args: &Args, _pid: u32, _config: Config) -> Result<()> {
+    bail!("attaching to processes isn't supported on this platform")
+}
+
+fn run<P>(mut viewer: Viewer<P>, args: &Args) -> Result<()>
+where
+    P: MemoryProvider + MemoryWriter + 'static,
+{
+    if let Some(address) = &args.control {
+        viewer.control = Some(ControlServer::bind(address)?);
+    }
+    viewer.state.set_refresh_interval(args.refresh);
+
+    let runner = Runner::new();
+    if let Some(path) = &args.record_session {
+        runner.record(&mut viewer, Recorder::create(path)?)
+    } else if let Some(path) = &args.replay {
+        runner.play(&mut viewer, &Recording::load(path)?)
+    } else {
+        runner.run(&mut viewer)
+    }
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let mut config = match &args.config {
+        Some(path) => Config::load(path)?,
+        None => Config::load_default()?,
+    };
+    config.scripts.extend(args.script.iter().cloned());
+    if args.record.is_some() {
+        config.record = args.record.clone();
+    }
+
+    match (&args.file, args.pid) {
+        (_, Some(pid)) => run_process(&args, pid, config),
+        (Some(file), None) => {
+            let viewer = Viewer::open(
+                file.clone(),
+                args.base.unwrap_or(0),
+                args.offset.unwrap_or(0),
+                config,
+            )?;
+            run(viewer, &args)
+        }
+        // clap requires one of them
+        (None, None) => unreachable!(),
+    }
+}
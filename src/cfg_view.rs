@@ -0,0 +1,495 @@
+use crate::{
+    instruction_view::{Flow, InstructionFlow, InstructionProvider, INSTRUCTION_SIZE},
+    keymap::Action,
+    Address, Keymap, Theme,
+};
+use crossterm::event::{KeyEvent, KeyEventKind};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, Borders, StatefulWidget, Widget},
+};
+use std::{
+    collections::{BTreeSet, HashMap, VecDeque},
+    ops::Range,
+};
+
+/// A straight-line sequence of instructions with a single entry and exit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BasicBlock {
+    /// Address of the first instruction.
+    pub start: Address,
+    /// Address right after the last instruction.
+    pub end: Address,
+}
+
+impl BasicBlock {
+    /// Amount of instructions in the block.
+    pub fn len(&self) -> usize {
+        (self.end.saturating_sub(self.start) / INSTRUCTION_SIZE) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    pub fn contains(&self, address: Address) -> bool {
+        (self.start..self.end).contains(&address)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    /// Unconditional jump.
+    Jump,
+    /// Conditional branch, when taken.
+    Taken,
+    /// Execution continuing into the next block.
+    FallThrough,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edge {
+    /// Start of the source block.
+    pub from: Address,
+    /// Start of the target block.
+    pub to: Address,
+    pub kind: EdgeKind,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ControlFlowGraph {
+    /// The blocks, sorted by address.
+    pub blocks: Vec<BasicBlock>,
+    pub edges: Vec<Edge>,
+}
+
+impl ControlFlowGraph {
+    pub fn new(mut blocks: Vec<BasicBlock>, edges: Vec<Edge>) -> Self {
+        blocks.sort_by_key(|block| block.start);
+        Self { blocks, edges }
+    }
+
+    /// Index of the block containing `address`.
+    pub fn block_at(&self, address: Address) -> Option<usize> {
+        let index = self.blocks.partition_point(|block| block.start <= address);
+        index
+            .checked_sub(1)
+            .filter(|&index| self.blocks[index].contains(address))
+    }
+
+    /// Edges leaving the block starting at `start`.
+    pub fn successors(&self, start: Address) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(move |edge| edge.from == start)
+    }
+
+    /// Builds a graph by linearly sweeping through `range`, splitting blocks at branch and call
+    /// targets and after control flow instructions. Unreadable instructions stop execution.
+    /// Ranges shorter than an instruction give an empty graph.
+    pub fn linear_sweep<I>(provider: &dyn InstructionProvider<I>, range: Range<Address>) -> Self
+    where
+        I: InstructionFlow + Clone,
+    {
+        if range.end.saturating_sub(range.start) < INSTRUCTION_SIZE {
+            return Self::default();
+        }
+
+        Self::from_flows(range.start, &read_flows(provider, range))
+    }
+
+    /// Builds a graph out of the flows of consecutive instructions starting at `start`, as read by
+    /// [`read_flows`].
+    pub(crate) fn from_flows(start: Address, flows: &[Flow]) -> Self {
+        let count = flows.len();
+        let range = start..start + count as Address * INSTRUCTION_SIZE;
+        let address_of = |index: usize| range.start + index as Address * INSTRUCTION_SIZE;
+
+        // find block leaders
+        let in_range = |address: Address| {
+            range.contains(&address) && (address - range.start) & (INSTRUCTION_SIZE - 1) == 0
+        };
+        let mut leaders = BTreeSet::new();
+        if count > 0 {
+            leaders.insert(range.start);
+        }
+        for (index, flow) in flows.iter().enumerate() {
+            if let Flow::Jump(target) | Flow::Branch(target) | Flow::Call(target) = flow {
+                if in_range(*target) {
+                    leaders.insert(*target);
+                }
+            }
+
+            if flow.ends_block() && index + 1 < count {
+                leaders.insert(address_of(index + 1));
+            }
+        }
+
+        // build blocks and edges
+        let leaders: Vec<Address> = leaders.into_iter().collect();
+        let mut blocks = Vec::with_capacity(leaders.len());
+        let mut edges = Vec::new();
+        for (i, &start) in leaders.iter().enumerate() {
+            let end = leaders.get(i + 1).copied().unwrap_or(address_of(count));
+            blocks.push(BasicBlock { start, end });
+
+            let last = ((end - range.start) / INSTRUCTION_SIZE) as usize - 1;
+            let flow = flows[last];
+            match flow {
+                Flow::Jump(target) if in_range(target) => edges.push(Edge {
+                    from: start,
+                    to: target,
+                    kind: EdgeKind::Jump,
+                }),
+                Flow::Branch(target) if in_range(target) => edges.push(Edge {
+                    from: start,
+                    to: target,
+                    kind: EdgeKind::Taken,
+                }),
+                _ => (),
+            }
+
+            if flow.falls_through() && end < address_of(count) {
+                edges.push(Edge {
+                    from: start,
+                    to: end,
+                    kind: EdgeKind::FallThrough,
+                });
+            }
+        }
+
+        Self { blocks, edges }
+    }
+
+    /// Assigns each block to a layer by breadth-first distance from the first block. Blocks that
+    /// can't be reached are put in layers below the rest, in address order.
+    fn layers(&self) -> Vec<Vec<usize>> {
+        let index_of: HashMap<Address, usize> = self
+            .blocks
+            .iter()
+            .enumerate()
+            .map(|(index, block)| (block.start, index))
+            .collect();
+
+        let mut depth = vec![None; self.blocks.len()];
+        let mut layers: Vec<Vec<usize>> = Vec::new();
+        for root in 0..self.blocks.len() {
+            if depth[root].is_some() {
+                continue;
+            }
+
+            let base = layers.len();
+            depth[root] = Some(base);
+            let mut queue = VecDeque::from([root]);
+            while let Some(index) = queue.pop_front() {
+                let layer = depth[index].unwrap();
+                if layers.len() <= layer {
+                    layers.resize_with(layer + 1, Vec::new);
+                }
+                layers[layer].push(index);
+
+                for edge in self.successors(self.blocks[index].start) {
+                    if let Some(&target) = index_of.get(&edge.to) {
+                        if depth[target].is_none() {
+                            depth[target] = Some(layer + 1);
+                            queue.push_back(target);
+                        }
+                    }
+                }
+            }
+        }
+
+        layers
+    }
+}
+
+/// Classifies every instruction in `range` by its effect on control flow. Unreadable instructions
+/// stop execution.
+pub(crate) fn read_flows<I>(
+    provider: &dyn InstructionProvider<I>,
+    range: Range<Address>,
+) -> Vec<Flow>
+where
+    I: InstructionFlow + Clone,
+{
+    let count = (range.end.saturating_sub(range.start) / INSTRUCTION_SIZE) as usize;
+    if count == 0 {
+        return Vec::new();
+    }
+
+    let mut instructions = vec![None; count];
+    provider.read_to_buf(range.start, &mut instructions);
+
+    instructions
+        .iter()
+        .enumerate()
+        .map(|(index, instruction)| {
+            let address = range.start + index as Address * INSTRUCTION_SIZE;
+            instruction
+                .as_ref()
+                .map_or(Flow::Stop, |instruction| instruction.flow(address))
+        })
+        .collect()
+}
+
+pub struct CfgViewState {
+    /// Index of the selected block.
+    pub selected: usize,
+
+    /// Layers of blocks, as of the last render.
+    layers: Vec<Vec<usize>>,
+    /// Start addresses of the blocks, as of the last render.
+    starts: Vec<Address>,
+    offset: usize,
+}
+
+impl Default for CfgViewState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CfgViewState {
+    pub fn new() -> Self {
+        Self {
+            selected: 0,
+            layers: Vec::new(),
+            starts: Vec::new(),
+            offset: 0,
+        }
+    }
+
+    /// Start address of the selected block, as of the last render.
+    pub fn selected_address(&self) -> Option<Address> {
+        self.starts.get(self.selected).copied()
+    }
+
+    /// Selects the block containing `address`, if any, e.g. to follow the instruction view.
+    pub fn select_address(&mut self, graph: &ControlFlowGraph, address: Address) {
+        if let Some(index) = graph.block_at(address) {
+            self.selected = index;
+        }
+    }
+
+    /// Position of the selected block as (layer, index inside of the layer).
+    fn position(&self) -> Option<(usize, usize)> {
+        self.layers.iter().enumerate().find_map(|(layer, blocks)| {
+            blocks
+                .iter()
+                .position(|&block| block == self.selected)
+                .map(|index| (layer, index))
+        })
+    }
+
+    fn move_to_layer(&mut self, layer: usize, index: usize) {
+        if let Some(blocks) = self.layers.get(layer) {
+            if let Some(&block) = blocks.get(index.min(blocks.len().saturating_sub(1))) {
+                self.selected = block;
+            }
+        }
+    }
+
+    /// Handles a key event with the [standard keymap](Keymap::standard).
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Address> {
+        self.handle_key_with(key, Keymap::standard())
+    }
+
+    /// Handles a key event, translated to an action by `keymap`. Returns the start of the selected
+    /// block when it is activated, so that the instruction view can be retargeted to it.
+    pub fn handle_key_with(&mut self, key: KeyEvent, keymap: &Keymap) -> Option<Address> {
+        if key.kind == KeyEventKind::Release {
+            return None;
+        }
+
+        let (layer, index) = self.position().unwrap_or((0, 0));
+        match keymap.action(key) {
+            Some(Action::Up) => {
+                if let Some(layer) = layer.checked_sub(1) {
+                    self.move_to_layer(layer, index);
+                }
+            }
+            Some(Action::Down) => self.move_to_layer(layer + 1, index),
+            Some(Action::Left) => self.move_to_layer(layer, index.saturating_sub(1)),
+            Some(Action::Right) => self.move_to_layer(layer, index + 1),
+            Some(Action::Activate) => return self.selected_address(),
+            _ => (),
+        }
+
+        None
+    }
+}
+
+/// A layered drawing of a [`ControlFlowGraph`]: each layer is a row of blocks, with the edges
+/// leaving a block listed inside of it.
+pub struct CfgView<'a> {
+    /// The graph to draw.
+    graph: &'a ControlFlowGraph,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> CfgView<'a> {
+    /// Width of a drawn block, in cells.
+    const BOX_WIDTH: u16 = 24;
+    /// Height of a drawn block, in cells, plus one for the connector below it.
+    const BOX_HEIGHT: u16 = 5;
+
+    pub fn new(graph: &'a ControlFlowGraph) -> Self {
+        Self {
+            graph,
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+
+    fn render_block(&self, index: usize, selected: bool, area: Rect, buf: &mut Buffer) {
+        let block = &self.graph.blocks[index];
+        let style = if selected {
+            Style::default().light_yellow().bold()
+        } else {
+            Style::default().gray()
+        };
+
+        let frame = Block::new().borders(Borders::ALL).border_style(style);
+        let inner = frame.inner(area);
+        frame.render(area, buf);
+        if inner.width == 0 || inner.height == 0 {
+            return;
+        }
+
+        buf.set_stringn(
+            inner.x,
+            inner.y,
+            format!("{:08X}", block.start),
+            inner.width as usize,
+            self.theme.address.bold(),
+        );
+        if inner.width > 8 {
+            let count = format!(" {} instr", block.len());
+            buf.set_stringn(
+                inner.x + 8,
+                inner.y,
+                count,
+                (inner.width - 8) as usize,
+                self.theme.muted,
+            );
+        }
+
+        if inner.height < 2 {
+            return;
+        }
+
+        let successors: Vec<Span> = self
+            .graph
+            .successors(block.start)
+            .map(|edge| {
+                let (arrow, style) = match edge.kind {
+                    EdgeKind::Jump => ("→", Style::default().light_blue()),
+                    EdgeKind::Taken => ("↘", Style::default().light_green()),
+                    EdgeKind::FallThrough => ("↓", self.theme.muted),
+                };
+                Span::styled(format!("{arrow}{:X} ", edge.to), style)
+            })
+            .collect();
+        let successors = if successors.is_empty() {
+            Line::from(Span::styled("■ end", Style::default().light_red()))
+        } else {
+            Line::from(successors)
+        };
+        buf.set_line(inner.x, inner.y + 1, &successors, inner.width);
+    }
+}
+
+impl<'a> StatefulWidget for CfgView<'a> {
+    type State = CfgViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = self.wrap_in_block(area, buf);
+
+        // update state
+        state.layers = self.graph.layers();
+        state.starts = self.graph.blocks.iter().map(|block| block.start).collect();
+        state.selected = state
+            .selected
+            .min(self.graph.blocks.len().saturating_sub(1));
+
+        let visible_layers = ((area.height / Self::BOX_HEIGHT) as usize).max(1);
+        let (selected_layer, selected_index) = state.position().unwrap_or((0, 0));
+        if selected_layer < state.offset {
+            state.offset = selected_layer;
+        } else if selected_layer >= state.offset + visible_layers {
+            state.offset = selected_layer + 1 - visible_layers;
+        }
+
+        // render!
+        let per_row = ((area.width / (Self::BOX_WIDTH + 1)) as usize).max(1);
+        for (row, blocks) in state
+            .layers
+            .iter()
+            .enumerate()
+            .skip(state.offset)
+            .take(visible_layers)
+            .map(|(layer, blocks)| (layer - state.offset, blocks))
+        {
+            let y = area.y + row as u16 * Self::BOX_HEIGHT;
+            let height = (Self::BOX_HEIGHT - 1).min(area.bottom().saturating_sub(y));
+
+            // scroll horizontally inside of the selected layer
+            let skip = if row + state.offset == selected_layer {
+                (selected_index + 1).saturating_sub(per_row)
+            } else {
+                0
+            };
+            let hidden = blocks.len().saturating_sub(skip + per_row);
+
+            for (column, &index) in blocks.iter().skip(skip).take(per_row).enumerate() {
+                let x = area.x + column as u16 * (Self::BOX_WIDTH + 1);
+                let rect = Rect::new(x, y, Self::BOX_WIDTH.min(area.right() - x), height);
+                self.render_block(index, index == state.selected, rect, buf);
+
+                let has_edges_down = self
+                    .graph
+                    .successors(self.graph.blocks[index].start)
+                    .next()
+                    .is_some();
+                let connector_x = x + Self::BOX_WIDTH / 2;
+                let connector_y = y + Self::BOX_HEIGHT - 1;
+                if has_edges_down && connector_x < area.right() && connector_y < area.bottom() {
+                    buf.set_string(connector_x, connector_y, "▼", self.theme.muted);
+                }
+            }
+
+            if hidden > 0 || skip > 0 {
+                let text = format!("‹{skip} {hidden}›");
+                let x = area.right().saturating_sub(text.chars().count() as u16);
+                let x = x.max(area.x);
+                buf.set_stringn(x, y, text, (area.right() - x) as usize, self.theme.muted);
+            }
+        }
+    }
+}
@@ -0,0 +1,270 @@
+//! A channel through which another process can drive a frontend, e.g. an emulator telling the
+//! viewer to jump to the faulting address as soon as an exception fires.
+//!
+//! Clients connect over TCP, on a loopback address only, or, on Unix, a Unix socket, and send one
+//! JSON command per line:
+//!
+//! - `{"command": "goto", "address": 4096}`: moves the pointer.
+//! - `{"command": "bookmark", "name": "fault", "address": 4096}`: bookmarks an address, or the
+//!   pointer if `address` is omitted.
+//! - `{"command": "snapshot", "path": "fault.snap"}`: captures a snapshot of the memory, saved to
+//!   `path` if given. Frontends should only accept a plain file name there, as clients needn't
+//!   be trusted with the whole file system.
+//!
+//! Every command is replied to with a line, either `{"ok":true}` or
+//! `{"ok":false,"error":"..."}`.
+//!
+//! The server never blocks: the frontend calls [`ControlServer::poll`] from its run loop, e.g. in
+//! [`App::update`](crate::app::App::update), and handles the commands received since the last
+//! call.
+
+use crate::Address;
+use eyre::{Result, WrapErr};
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{self, ErrorKind, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    path::PathBuf,
+};
+
+#[cfg(unix)]
+use std::{
+    os::unix::net::{UnixListener, UnixStream},
+    path::Path,
+};
+
+/// Maximum length of a command, in bytes. Clients sending longer lines are disconnected.
+const MAX_LINE: usize = 64 << 10;
+
+/// A command sent by a client.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "command", rename_all = "kebab-case")]
+pub enum Command {
+    Goto {
+        address: Address,
+    },
+    Bookmark {
+        name: String,
+        /// The pointer if unset.
+        #[serde(default)]
+        address: Option<Address>,
+    },
+    Snapshot {
+        /// Where to save the snapshot. Up to the frontend if unset.
+        #[serde(default)]
+        path: Option<PathBuf>,
+    },
+}
+
+#[derive(Serialize)]
+struct Reply {
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+enum Listener {
+    Tcp(TcpListener),
+    /// Along with the path of the socket, removed when the server is dropped.
+    #[cfg(unix)]
+    Unix(UnixListener, PathBuf),
+}
+
+enum Stream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Tcp(stream) => stream.read(buf),
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Tcp(stream) => stream.write(buf),
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Tcp(stream) => stream.flush(),
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.flush(),
+        }
+    }
+}
+
+struct Client {
+    stream: Stream,
+    /// Received bytes not making up a whole line yet.
+    buffer: Vec<u8>,
+}
+
+impl Client {
+    fn reply(line: &[u8], handle: &mut impl FnMut(Command) -> Result<()>) -> Vec<u8> {
+        let result = serde_json::from_slice(line)
+            .wrap_err("invalid command")
+            .and_then(&mut *handle);
+        let reply = match result {
+            Ok(()) => Reply {
+                ok: true,
+                error: None,
+            },
+            Err(error) => Reply {
+                ok: false,
+                error: Some(format!("{error:#}")),
+            },
+        };
+
+        let mut reply = serde_json::to_vec(&reply).expect("replies are serializable");
+        reply.push(b'\n');
+        reply
+    }
+
+    /// Handles the commands received since the last call. Returns whether the client is still
+    /// connected.
+    fn poll(&mut self, handle: &mut impl FnMut(Command) -> Result<()>) -> bool {
+        let mut connected = true;
+        let mut chunk = [0; 4096];
+        loop {
+            match self.stream.read(&mut chunk) {
+                Ok(0) => {
+                    connected = false;
+                    break;
+                }
+                Ok(len) => self.buffer.extend_from_slice(&chunk[..len]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => (),
+                Err(_) => return false,
+            }
+        }
+
+        // commands sent right before disconnecting are still handled
+        while let Some(end) = self.buffer.iter().position(|&byte| byte == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+
+            let reply = Self::reply(&line, handle);
+            if connected && self.stream.write_all(&reply).is_err() {
+                connected = false;
+            }
+        }
+
+        connected && self.buffer.len() <= MAX_LINE
+    }
+}
+
+/// Accepts clients and receives their commands, see the [module docs](self).
+pub struct ControlServer {
+    listener: Listener,
+    clients: Vec<Client>,
+}
+
+impl ControlServer {
+    fn new(listener: Listener) -> Self {
+        Self {
+            listener,
+            clients: Vec::new(),
+        }
+    }
+
+    /// Binds to a TCP address, which must be a loopback one so that only local processes can
+    /// connect.
+    pub fn bind_tcp(address: impl ToSocketAddrs) -> Result<Self> {
+        let addresses: Vec<SocketAddr> = address.to_socket_addrs()?.collect();
+        if let Some(address) = addresses.iter().find(|address| !address.ip().is_loopback()) {
+            eyre::bail!("{address} isn't a loopback address");
+        }
+
+        let listener = TcpListener::bind(&*addresses)?;
+        listener.set_nonblocking(true)?;
+
+        Ok(Self::new(Listener::Tcp(listener)))
+    }
+
+    /// Creates a Unix socket at `path`, which must not exist yet.
+    #[cfg(unix)]
+    pub fn bind_unix(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let listener = UnixListener::bind(path)
+            .wrap_err_with(|| format!("failed to bind to {}", path.display()))?;
+        listener.set_nonblocking(true)?;
+
+        Ok(Self::new(Listener::Unix(listener, path.to_owned())))
+    }
+
+    /// Binds to `unix:<path>` or, otherwise, to a TCP address such as `127.0.0.1:7777`.
+    pub fn bind(address: &str) -> Result<Self> {
+        match address.strip_prefix("unix:") {
+            #[cfg(unix)]
+            Some(path) => Self::bind_unix(path),
+            #[cfg(not(unix))]
+            Some(_) => eyre::bail!("Unix sockets aren't supported on this platform"),
+            None => {
+                Self::bind_tcp(address).wrap_err_with(|| format!("failed to bind to {address}"))
+            }
+        }
+    }
+
+    fn accept(&self) -> io::Result<Stream> {
+        match &self.listener {
+            Listener::Tcp(listener) => {
+                let (stream, _) = listener.accept()?;
+                stream.set_nonblocking(true)?;
+                stream.set_nodelay(true)?;
+                Ok(Stream::Tcp(stream))
+            }
+            #[cfg(unix)]
+            Listener::Unix(listener, _) => {
+                let (stream, _) = listener.accept()?;
+                stream.set_nonblocking(true)?;
+                Ok(Stream::Unix(stream))
+            }
+        }
+    }
+
+    /// Accepts pending clients and calls `handle` with every command received since the last
+    /// call, in order. Errors returned by `handle` are sent back to the client.
+    pub fn poll(&mut self, mut handle: impl FnMut(Command) -> Result<()>) {
+        loop {
+            match self.accept() {
+                Ok(stream) => self.clients.push(Client {
+                    stream,
+                    buffer: Vec::new(),
+                }),
+                Err(e) if e.kind() == ErrorKind::Interrupted => (),
+                // e.g. a client that hung up before being accepted, or running out of file
+                // descriptors: the others are still served, and accepting is retried next time
+                Err(_) => break,
+            }
+        }
+
+        self.clients.retain_mut(|client| client.poll(&mut handle));
+    }
+
+    /// Amount of connected clients.
+    pub fn clients(&self) -> usize {
+        self.clients.len()
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Listener::Unix(_, path) = &self.listener {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use crate::{keymap::Action, Address, Keymap, Theme};
+use crossterm::event::{KeyEvent, KeyEventKind};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, StatefulWidget, Widget},
+};
+use std::{
+    collections::{BTreeSet, HashSet},
+    ops::Range,
+};
+
+pub trait Coverage {
+    /// Whether the instruction at `address` has been executed.
+    fn is_covered(&self, address: Address) -> bool;
+
+    /// Counts the executed addresses in `range`, looking at one every `granularity` bytes.
+    /// Returns the amount of executed addresses and the amount of addresses looked at.
+    fn count_covered(&self, range: Range<Address>, granularity: Address) -> (u64, u64) {
+        let granularity = granularity.max(1);
+        let total = range.len() as u64 / granularity as u64;
+        let covered = range
+            .step_by(granularity as usize)
+            .filter(|&address| self.is_covered(address))
+            .count() as u64;
+
+        (covered, total.max(1))
+    }
+}
+
+impl Coverage for HashSet<Address> {
+    fn is_covered(&self, address: Address) -> bool {
+        self.contains(&address)
+    }
+}
+
+impl Coverage for BTreeSet<Address> {
+    fn is_covered(&self, address: Address) -> bool {
+        self.contains(&address)
+    }
+
+    fn count_covered(&self, range: Range<Address>, granularity: Address) -> (u64, u64) {
+        let granularity = granularity.max(1);
+        let total = range.len() as u64 / granularity as u64;
+        (self.range(range).count() as u64, total.max(1))
+    }
+}
+
+/// A bitmap of executed addresses, one bit per `granularity` bytes starting at `base`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageBitmap {
+    base: Address,
+    granularity: Address,
+    bits: Vec<u64>,
+}
+
+impl CoverageBitmap {
+    pub fn new(range: Range<Address>, granularity: Address) -> Self {
+        let granularity = granularity.max(1);
+        let len = (range.len() as u64).div_ceil(granularity as u64);
+
+        Self {
+            base: range.start,
+            granularity,
+            bits: vec![0; len.div_ceil(64) as usize],
+        }
+    }
+
+    fn bit(&self, address: Address) -> Option<(usize, u64)> {
+        let index = address.checked_sub(self.base)? / self.granularity;
+        let word = index as usize / 64;
+        (word < self.bits.len()).then_some((word, 1 << (index % 64)))
+    }
+
+    /// Marks the address as executed. Addresses outside of the bitmap are ignored.
+    pub fn insert(&mut self, address: Address) {
+        if let Some((word, mask)) = self.bit(address) {
+            self.bits[word] |= mask;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.fill(0);
+    }
+}
+
+impl Coverage for CoverageBitmap {
+    fn is_covered(&self, address: Address) -> bool {
+        self.bit(address)
+            .is_some_and(|(word, mask)| self.bits[word] & mask != 0)
+    }
+}
+
+pub struct CoverageViewState {
+    /// Address under the cursor.
+    pub cursor: Address,
+    /// Amount of bytes represented by each cell.
+    pub bytes_per_cell: Address,
+
+    beginning: Address,
+    columns: u32,
+    rows: u32,
+}
+
+impl CoverageViewState {
+    pub fn new(cursor: Address) -> Self {
+        Self {
+            cursor,
+            bytes_per_cell: 64,
+            beginning: 0,
+            columns: 1,
+            rows: 1,
+        }
+    }
+
+    /// Handles a key event with the [standard keymap](Keymap::standard).
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Address> {
+        self.handle_key_with(key, Keymap::standard())
+    }
+
+    /// Handles a key event, translated to an action by `keymap`. Returns the address under the
+    /// cursor when it is activated, so that the instruction view can jump to it.
+    pub fn handle_key_with(&mut self, key: KeyEvent, keymap: &Keymap) -> Option<Address> {
+        if key.kind == KeyEventKind::Release {
+            return None;
+        }
+
+        let cell = self.bytes_per_cell;
+        let row = cell.saturating_mul(self.columns);
+        match keymap.action(key) {
+            Some(Action::Left) => self.cursor = self.cursor.saturating_sub(cell),
+            Some(Action::Right) => self.cursor = self.cursor.saturating_add(cell),
+            Some(Action::Up) => self.cursor = self.cursor.saturating_sub(row),
+            Some(Action::Down) => self.cursor = self.cursor.saturating_add(row),
+            Some(Action::PageUp) => {
+                self.cursor = self.cursor.saturating_sub(row.saturating_mul(self.rows))
+            }
+            Some(Action::PageDown) => {
+                self.cursor = self.cursor.saturating_add(row.saturating_mul(self.rows))
+            }
+            Some(Action::ZoomIn) => self.bytes_per_cell = (self.bytes_per_cell / 2).max(1),
+            Some(Action::ZoomOut) => {
+                self.bytes_per_cell = self.bytes_per_cell.saturating_mul(2).min(1 << 24)
+            }
+            Some(Action::Activate) => return Some(self.cursor),
+            _ => (),
+        }
+
+        None
+    }
+}
+
+/// A compressed map of which parts of an address range have been executed.
+pub struct CoverageView<'a> {
+    /// The executed addresses.
+    coverage: &'a dyn Coverage,
+
+    /// The range being mapped.
+    range: Range<Address>,
+
+    /// Only one address every `granularity` bytes is checked, e.g. the instruction size.
+    granularity: Address,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> CoverageView<'a> {
+    pub fn new(coverage: &'a dyn Coverage, range: Range<Address>) -> Self {
+        Self {
+            coverage,
+            range,
+            granularity: std::mem::size_of::<Address>() as Address,
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn granularity(self, granularity: Address) -> Self {
+        Self {
+            granularity: granularity.max(1),
+            ..self
+        }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+}
+
+impl<'a> StatefulWidget for CoverageView<'a> {
+    type State = CoverageViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = self.wrap_in_block(area, buf);
+        if area.height < 2 || area.width < 10 || self.range.is_empty() {
+            return;
+        }
+
+        // the address column takes 9 cells, the info line takes the last row
+        let grid = Rect {
+            x: area.x + 9,
+            width: area.width - 9,
+            height: area.height - 1,
+            ..area
+        };
+
+        // update state
+        state.bytes_per_cell = state.bytes_per_cell.max(self.granularity);
+        state.columns = grid.width as u32;
+        state.rows = grid.height as u32;
+        state.cursor = state
+            .cursor
+            .clamp(self.range.start, self.range.end.saturating_sub(1));
+
+        let cell = state.bytes_per_cell as u64;
+        let row_size = cell * state.columns as u64;
+        let cursor_row = (state.cursor - self.range.start) as u64 / row_size;
+        let first_row = cursor_row.saturating_sub(state.rows as u64 / 2);
+        state.beginning = (self.range.start as u64 + first_row * row_size) as Address;
+
+        // render!
+        let (mut visible_covered, mut visible_total) = (0, 0);
+        for row in 0..state.rows {
+            let row_start = state.beginning as u64 + row as u64 * row_size;
+            if row_start >= self.range.end as u64 {
+                break;
+            }
+
+            let y = grid.y + row as u16;
+            buf.set_string(area.x, y, format!("{row_start:08X}"), self.theme.address);
+
+            for column in 0..state.columns {
+                let start = row_start + column as u64 * cell;
+                if start >= self.range.end as u64 {
+                    break;
+                }
+                let end = (start + cell).min(self.range.end as u64);
+
+                let (covered, total) = self
+                    .coverage
+                    .count_covered(start as Address..end as Address, self.granularity);
+                visible_covered += covered;
+                visible_total += total;
+
+                let (symbol, style) = if covered == 0 {
+                    ("·", self.theme.muted)
+                } else if covered >= total {
+                    ("█", Style::default().light_green())
+                } else {
+                    ("▒", Style::default().light_yellow())
+                };
+
+                let style = if (start..end).contains(&(state.cursor as u64)) {
+                    style.reversed()
+                } else {
+                    style
+                };
+                buf.set_string(grid.x + column as u16, y, symbol, style);
+            }
+        }
+
+        let info = format!(
+            "{:08X}  {} bytes/cell  {:.1}% of view covered",
+            state.cursor,
+            state.bytes_per_cell,
+            visible_covered as f64 * 100.0 / visible_total.max(1) as f64
+        );
+        buf.set_stringn(
+            area.x,
+            area.bottom() - 1,
+            info,
+            area.width as usize,
+            Style::default().light_green(),
+        );
+    }
+}
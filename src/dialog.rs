@@ -0,0 +1,478 @@
+//! Modal dialogs rendered over a dimmed area.
+//!
+//! Every dialog is a [`StatefulWidget`] whose state handles key events through `handle_key`
+//! and exposes the outcome through `poll`, which the application can check after feeding
+//! it input.
+
+use crate::{i18n::strings, popup, Theme};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{
+        Block, Borders, Clear, List, ListItem, ListState, Paragraph, StatefulWidget, Widget, Wrap,
+    },
+};
+use std::borrow::Cow;
+
+/// The outcome of a dialog.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DialogResult<T> {
+    /// The user confirmed the dialog with the given value.
+    Submitted(T),
+    /// The user dismissed the dialog.
+    Cancelled,
+}
+
+fn popup_block<'a>(title: &Cow<'a, str>, theme: &Theme) -> Block<'a> {
+    Block::new()
+        .borders(Borders::ALL)
+        .border_style(theme.border_focused)
+        .title(Span::styled(title.clone(), theme.title))
+}
+
+fn is_press(key: &KeyEvent) -> bool {
+    key.kind != KeyEventKind::Release
+}
+
+pub struct InputDialogState {
+    input: String,
+    /// Cursor position, in chars.
+    cursor: usize,
+    result: Option<DialogResult<String>>,
+}
+
+impl Default for InputDialogState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InputDialogState {
+    pub fn new() -> Self {
+        Self::with_input(String::new())
+    }
+
+    pub fn with_input(input: impl Into<String>) -> Self {
+        let input = input.into();
+        Self {
+            cursor: input.chars().count(),
+            input,
+            result: None,
+        }
+    }
+
+    /// The text currently typed in.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Takes the outcome of the dialog, if it has been submitted or cancelled.
+    pub fn poll(&mut self) -> Option<DialogResult<String>> {
+        self.result.take()
+    }
+
+    fn byte_index(&self, cursor: usize) -> usize {
+        self.input
+            .char_indices()
+            .nth(cursor)
+            .map(|(index, _)| index)
+            .unwrap_or(self.input.len())
+    }
+
+    /// Handles a key event. Returns whether the event was consumed.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if !is_press(&key) {
+            return false;
+        }
+
+        match key.code {
+            KeyCode::Enter => self.result = Some(DialogResult::Submitted(self.input.clone())),
+            KeyCode::Esc => self.result = Some(DialogResult::Cancelled),
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.input.chars().count()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.input.chars().count(),
+            KeyCode::Backspace => {
+                if self.cursor > 0 {
+                    self.cursor -= 1;
+                    let index = self.byte_index(self.cursor);
+                    self.input.remove(index);
+                }
+            }
+            KeyCode::Delete => {
+                if self.cursor < self.input.chars().count() {
+                    let index = self.byte_index(self.cursor);
+                    self.input.remove(index);
+                }
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                let index = self.byte_index(self.cursor);
+                self.input.insert(index, c);
+                self.cursor += 1;
+            }
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+/// A single-line text input, e.g. for "goto address" or "rename symbol".
+pub struct InputDialog<'a> {
+    /// Title of the dialog.
+    title: Cow<'a, str>,
+
+    /// Optional text shown above the input line.
+    prompt: Option<Line<'a>>,
+
+    /// Width of the dialog, in cells.
+    width: u16,
+
+    theme: Theme,
+}
+
+impl<'a> InputDialog<'a> {
+    pub fn new(title: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            title: title.into(),
+            prompt: None,
+            width: 40,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn prompt(self, prompt: impl Into<Line<'a>>) -> Self {
+        Self {
+            prompt: Some(prompt.into()),
+            ..self
+        }
+    }
+
+    pub fn width(self, width: u16) -> Self {
+        Self { width, ..self }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+}
+
+impl<'a> StatefulWidget for InputDialog<'a> {
+    type State = InputDialogState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        popup::dim(area, buf);
+
+        let height = if self.prompt.is_some() { 4 } else { 3 };
+        let popup_area = popup::centered_rect(self.width, height, area);
+        Clear.render(popup_area, buf);
+
+        let block = popup_block(&self.title, &self.theme);
+        let inner_area = block.inner(popup_area);
+        block.render(popup_area, buf);
+        if inner_area.height == 0 || inner_area.width == 0 {
+            return;
+        }
+
+        let mut input_y = inner_area.y;
+        if let Some(prompt) = &self.prompt {
+            buf.set_line(inner_area.x, inner_area.y, prompt, inner_area.width);
+            input_y += 1;
+            if input_y >= inner_area.bottom() {
+                return;
+            }
+        }
+
+        // scroll horizontally so the cursor is always visible
+        let visible = inner_area.width as usize - 1;
+        let skip = state.cursor.saturating_sub(visible);
+        let text: String = state.input.chars().skip(skip).take(visible + 1).collect();
+        buf.set_stringn(
+            inner_area.x,
+            input_y,
+            text,
+            inner_area.width as usize,
+            self.theme.info,
+        );
+
+        let cursor_x = inner_area.x + (state.cursor - skip) as u16;
+        buf.get_mut(cursor_x, input_y)
+            .set_style(Style::default().add_modifier(Modifier::REVERSED));
+    }
+}
+
+pub struct ConfirmDialogState {
+    /// Whether "yes" is currently highlighted.
+    yes_selected: bool,
+    result: Option<DialogResult<bool>>,
+}
+
+impl Default for ConfirmDialogState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfirmDialogState {
+    pub fn new() -> Self {
+        Self {
+            yes_selected: false,
+            result: None,
+        }
+    }
+
+    /// Takes the outcome of the dialog, if it has been answered or cancelled.
+    pub fn poll(&mut self) -> Option<DialogResult<bool>> {
+        self.result.take()
+    }
+
+    /// Handles a key event. Returns whether the event was consumed.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if !is_press(&key) {
+            return false;
+        }
+
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                self.result = Some(DialogResult::Submitted(true))
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') => {
+                self.result = Some(DialogResult::Submitted(false))
+            }
+            KeyCode::Left | KeyCode::Right | KeyCode::Tab | KeyCode::BackTab => {
+                self.yes_selected = !self.yes_selected
+            }
+            KeyCode::Enter => self.result = Some(DialogResult::Submitted(self.yes_selected)),
+            KeyCode::Esc => self.result = Some(DialogResult::Cancelled),
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+/// A yes/no question.
+pub struct ConfirmDialog<'a> {
+    /// Title of the dialog.
+    title: Cow<'a, str>,
+
+    /// The question being asked.
+    message: Text<'a>,
+
+    /// Width of the dialog, in cells.
+    width: u16,
+
+    theme: Theme,
+}
+
+impl<'a> ConfirmDialog<'a> {
+    pub fn new(title: impl Into<Cow<'a, str>>, message: impl Into<Text<'a>>) -> Self {
+        Self {
+            title: title.into(),
+            message: message.into(),
+            width: 40,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn width(self, width: u16) -> Self {
+        Self { width, ..self }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+}
+
+impl<'a> StatefulWidget for ConfirmDialog<'a> {
+    type State = ConfirmDialogState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        popup::dim(area, buf);
+
+        let height = self.message.height() as u16 + 4;
+        let popup_area = popup::centered_rect(self.width, height, area);
+        Clear.render(popup_area, buf);
+
+        let block = popup_block(&self.title, &self.theme);
+        let inner_area = block.inner(popup_area);
+        block.render(popup_area, buf);
+        if inner_area.height < 2 {
+            return;
+        }
+
+        let message_area = Rect {
+            height: inner_area.height - 1,
+            ..inner_area
+        };
+        Paragraph::new(self.message)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .render(message_area, buf);
+
+        let (yes_style, no_style) = if state.yes_selected {
+            (self.theme.status_accent, Style::default())
+        } else {
+            (Style::default(), self.theme.alert)
+        };
+        let strings = strings();
+        let buttons = Line::from(vec![
+            Span::styled(format!(" {} ", strings.yes), yes_style),
+            Span::raw("   "),
+            Span::styled(format!(" {} ", strings.no), no_style),
+        ]);
+        let buttons_area = Rect {
+            y: inner_area.bottom() - 1,
+            height: 1,
+            ..inner_area
+        };
+        Paragraph::new(buttons)
+            .alignment(Alignment::Center)
+            .render(buttons_area, buf);
+    }
+}
+
+pub struct ListPickerState {
+    list_state: ListState,
+    /// Amount of items in the list, as of the last render.
+    len: usize,
+    /// Amount of visible items, as of the last render.
+    page: usize,
+    result: Option<DialogResult<usize>>,
+}
+
+impl Default for ListPickerState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ListPickerState {
+    pub fn new() -> Self {
+        let mut list_state = ListState::default();
+        list_state.select(Some(0));
+
+        Self {
+            list_state,
+            len: 0,
+            page: 1,
+            result: None,
+        }
+    }
+
+    /// Index of the highlighted item.
+    pub fn selected(&self) -> usize {
+        self.list_state.selected().unwrap_or(0)
+    }
+
+    pub fn select(&mut self, index: usize) {
+        self.list_state.select(Some(index));
+    }
+
+    /// Takes the outcome of the dialog, if an item has been picked or it has been cancelled.
+    pub fn poll(&mut self) -> Option<DialogResult<usize>> {
+        self.result.take()
+    }
+
+    /// Handles a key event. Returns whether the event was consumed.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if !is_press(&key) {
+            return false;
+        }
+
+        let last = self.len.saturating_sub(1);
+        let selected = self.selected();
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.select(selected.saturating_sub(1)),
+            KeyCode::Down | KeyCode::Char('j') => self.select((selected + 1).min(last)),
+            KeyCode::PageUp => self.select(selected.saturating_sub(self.page)),
+            KeyCode::PageDown => self.select((selected + self.page).min(last)),
+            KeyCode::Home => self.select(0),
+            KeyCode::End => self.select(last),
+            KeyCode::Enter if self.len > 0 => self.result = Some(DialogResult::Submitted(selected)),
+            KeyCode::Esc => self.result = Some(DialogResult::Cancelled),
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+/// A list of items to pick one from.
+pub struct ListPicker<'a> {
+    /// Title of the dialog.
+    title: Cow<'a, str>,
+
+    /// Items to pick from.
+    items: &'a [Cow<'a, str>],
+
+    /// Maximum size of the dialog, in cells.
+    width: u16,
+    height: u16,
+
+    theme: Theme,
+}
+
+impl<'a> ListPicker<'a> {
+    pub fn new(title: impl Into<Cow<'a, str>>, items: &'a [Cow<'a, str>]) -> Self {
+        Self {
+            title: title.into(),
+            items,
+            width: 40,
+            height: 16,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn width(self, width: u16) -> Self {
+        Self { width, ..self }
+    }
+
+    pub fn height(self, height: u16) -> Self {
+        Self { height, ..self }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+}
+
+impl<'a> StatefulWidget for ListPicker<'a> {
+    type State = ListPickerState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        popup::dim(area, buf);
+
+        let height = self.items.len().saturating_add(2).min(self.height as usize) as u16;
+        let popup_area = popup::centered_rect(self.width, height, area);
+        Clear.render(popup_area, buf);
+
+        let block = popup_block(&self.title, &self.theme);
+        let inner_area = block.inner(popup_area);
+        block.render(popup_area, buf);
+
+        // update state
+        state.len = self.items.len();
+        state.page = (inner_area.height as usize).max(1);
+        state.select(state.selected().min(state.len.saturating_sub(1)));
+
+        let items: Vec<_> = self
+            .items
+            .iter()
+            .map(|item| ListItem::new(item.clone()))
+            .collect();
+        let list = List::new(items)
+            .highlight_style(self.theme.selection)
+            .highlight_symbol("> ");
+        StatefulWidget::render(list, inner_area, buf, &mut state.list_state);
+    }
+}
@@ -0,0 +1,62 @@
+//! Failures that widgets show in place of their contents rather than panicking, so that a
+//! widget embedded in a long-running debugger can't take the whole interface down.
+
+use crate::{
+    i18n::{self, strings},
+    Theme,
+};
+use ratatui::prelude::{Buffer, Rect};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The area given to a widget is too small for it to show anything useful.
+    AreaTooSmall { width: u16, height: u16 },
+    /// A provider failed to reach its target, e.g. because the connection to it was lost.
+    Provider(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AreaTooSmall { width, height } => {
+                let text = i18n::fill(&strings().too_small, &[width, height]);
+                f.write_str(&text)
+            }
+            Self::Provider(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Error {
+    /// Wraps the error of a provider, keeping its whole chain of causes.
+    pub fn provider(error: impl fmt::Display) -> Self {
+        Self::Provider(format!("{error:#}"))
+    }
+}
+
+/// Renders an error in place of a widget: the area is cleared and the message is written on its
+/// first row, truncated to fit.
+pub fn render_error(error: &Error, area: Rect, buf: &mut Buffer, theme: &Theme) {
+    let area = area.intersection(buf.area);
+    if area.width == 0 || area.height == 0 {
+        return;
+    }
+
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            buf.get_mut(x, y).reset();
+        }
+    }
+
+    buf.set_stringn(
+        area.x,
+        area.y,
+        format!("! {error}"),
+        area.width as usize,
+        theme.alert,
+    );
+}
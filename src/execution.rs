@@ -0,0 +1,47 @@
+//! Control over the execution of a target, for frontends to debuggers and emulators.
+
+use crate::Address;
+use eyre::Result;
+
+/// Why a target stopped executing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// Stopped by a signal or exception (e.g. `SIGTRAP` after a step or breakpoint).
+    Signal(u8),
+    /// Stopped on a breakpoint.
+    Breakpoint(Address),
+    /// Exited with the given status.
+    Exited(u8),
+    /// Killed by the given signal.
+    Terminated(u8),
+}
+
+pub trait ExecutionControl {
+    /// Resumes execution without waiting for the target to stop. Use
+    /// [`poll_stop`](Self::poll_stop) to find out when it does.
+    fn resume(&mut self) -> Result<()>;
+
+    /// Interrupts a running target and waits for it to stop.
+    fn halt(&mut self) -> Result<StopReason>;
+
+    /// Executes a single instruction and waits for the target to stop.
+    fn step(&mut self) -> Result<StopReason>;
+
+    /// Checks, without blocking, whether a resumed target has stopped.
+    fn poll_stop(&mut self) -> Result<Option<StopReason>>;
+
+    /// Whether the target is currently running.
+    fn is_running(&self) -> bool;
+
+    fn set_breakpoint(&mut self, address: Address) -> Result<()>;
+
+    fn remove_breakpoint(&mut self, address: Address) -> Result<()>;
+
+    /// Addresses of the breakpoints currently set.
+    fn breakpoints(&self) -> Vec<Address>;
+
+    /// Address of the next instruction to execute, if known.
+    fn program_counter(&self) -> Option<Address> {
+        None
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{keymap::Action, Keymap, Theme};
+use crossterm::event::{KeyEvent, KeyEventKind, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{layout::Rect, widgets::Block};
+
+/// Tracks which of the panes of an application has focus and decides which pane an event is
+/// meant for.
+///
+/// Panes are identified by `P`, usually an enum of the application, and cycled through in the
+/// order they were given in. Their areas are set with [`set_area`](Self::set_area) while
+/// rendering, so that mouse events can be routed to them.
+///
+/// Key and mouse events go through [`handle_key`](Self::handle_key) and
+/// [`handle_mouse`](Self::handle_mouse) first, which return the pane whose widget state should
+/// handle them.
+#[derive(Debug, Clone)]
+pub struct FocusManager<P> {
+    panes: Vec<(P, Option<Rect>)>,
+    focused: usize,
+    theme: Theme,
+}
+
+impl<P> FocusManager<P>
+where
+    P: Copy + PartialEq,
+{
+    /// Creates a manager of the given panes, focusing the first one.
+    pub fn new(panes: impl IntoIterator<Item = P>) -> Self {
+        Self {
+            panes: panes.into_iter().map(|pane| (pane, None)).collect(),
+            focused: 0,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    /// The focused pane, if there are any panes.
+    pub fn focused(&self) -> Option<P> {
+        self.panes.get(self.focused).map(|(pane, _)| *pane)
+    }
+
+    pub fn is_focused(&self, pane: P) -> bool {
+        self.focused() == Some(pane)
+    }
+
+    /// Focuses a pane. Returns whether it is one of the managed panes.
+    pub fn focus(&mut self, pane: P) -> bool {
+        match self.index(pane) {
+            Some(index) => {
+                self.focused = index;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn focus_next(&mut self) {
+        if !self.panes.is_empty() {
+            self.focused = (self.focused + 1) % self.panes.len();
+        }
+    }
+
+    pub fn focus_previous(&mut self) {
+        if !self.panes.is_empty() {
+            self.focused = self.focused.checked_sub(1).unwrap_or(self.panes.len() - 1);
+        }
+    }
+
+    /// Sets the area a pane was rendered to. Panes without an area (e.g. hidden ones) don't
+    /// receive mouse events.
+    pub fn set_area(&mut self, pane: P, area: Option<Rect>) {
+        if let Some(index) = self.index(pane) {
+            self.panes[index].1 = area;
+        }
+    }
+
+    /// The pane at the given position, if any.
+    pub fn pane_at(&self, column: u16, row: u16) -> Option<P> {
+        self.panes
+            .iter()
+            .find(|(_, area)| {
+                area.is_some_and(|area| {
+                    (area.left()..area.right()).contains(&column)
+                        && (area.top()..area.bottom()).contains(&row)
+                })
+            })
+            .map(|(pane, _)| *pane)
+    }
+
+    /// Handles the focus-cycling actions of `keymap`. Returns the pane the key event should be
+    /// routed to, or `None` if it was consumed.
+    pub fn handle_key(&mut self, key: KeyEvent, keymap: &Keymap) -> Option<P> {
+        if key.kind == KeyEventKind::Release {
+            return self.focused();
+        }
+
+        match keymap.action(key) {
+            Some(Action::FocusNext) => self.focus_next(),
+            Some(Action::FocusPrevious) => self.focus_previous(),
+            _ => return self.focused(),
+        }
+
+        None
+    }
+
+    /// Focuses the pane that was clicked. Returns the pane under the mouse, which the mouse event
+    /// should be routed to.
+    pub fn handle_mouse(&mut self, mouse: MouseEvent) -> Option<P> {
+        let pane = self.pane_at(mouse.column, mouse.row)?;
+        if let MouseEventKind::Down(MouseButton::Left) = mouse.kind {
+            self.focus(pane);
+        }
+
+        Some(pane)
+    }
+
+    /// Applies the border style of the theme to the block of a pane, depending on whether it
+    /// is focused.
+    pub fn block<'b>(&self, pane: P, block: Block<'b>) -> Block<'b> {
+        if self.is_focused(pane) {
+            block.border_style(self.theme.border_focused)
+        } else {
+            block.border_style(self.theme.border)
+        }
+    }
+
+    fn index(&self, pane: P) -> Option<usize> {
+        self.panes.iter().position(|(p, _)| *p == pane)
+    }
+}
@@ -0,0 +1,43 @@
+/// Matches `pattern` against `text` as a case-insensitive subsequence. Returns a score, higher
+/// being better, or `None` if it doesn't match.
+///
+/// Consecutive matches and matches at the start of words are favored, gaps are penalized.
+pub(crate) fn fuzzy_score(pattern: &str, text: &str) -> Option<i64> {
+    let mut score = 0;
+    let mut text_chars = text.char_indices();
+    let mut previous_match: Option<usize> = None;
+    let mut previous_char: Option<char> = None;
+
+    for pattern_char in pattern.chars().flat_map(char::to_lowercase) {
+        loop {
+            let (index, c) = text_chars.next()?;
+            let is_match = c.to_lowercase().eq(std::iter::once(pattern_char));
+            let boundary = match previous_char {
+                None => true,
+                Some(previous) => {
+                    !previous.is_alphanumeric() || (previous.is_lowercase() && c.is_uppercase())
+                }
+            };
+            previous_char = Some(c);
+
+            if !is_match {
+                continue;
+            }
+
+            score += 1;
+            match previous_match {
+                Some(previous) if previous + 1 == index => score += 5,
+                Some(previous) => score -= (index - previous).min(10) as i64,
+                None => score -= index.min(10) as i64,
+            }
+            if boundary {
+                score += 3;
+            }
+
+            previous_match = Some(index);
+            break;
+        }
+    }
+
+    Some(score)
+}
@@ -0,0 +1,455 @@
+//! Real images in the terminal, through the graphics protocols of Kitty, iTerm2 and terminals
+//! supporting sixels, so that widgets like the [`TileView`](crate::tile_view::TileView) can show
+//! pixel-accurate previews rather than block characters.
+//!
+//! Images can't be drawn into a ratatui [`Buffer`](ratatui::buffer::Buffer): widgets leave their
+//! area blank and hand the image to the application, which [writes](write_image) it to the
+//! terminal once the frame is drawn.
+
+use ratatui::{layout::Rect, style::Color};
+use std::{
+    env,
+    io::{self, Write},
+};
+
+/// Size of the chunks images are sent to Kitty in, in base64 bytes.
+const KITTY_CHUNK_SIZE: usize = 4096;
+/// Size of a cell in pixels, when the terminal doesn't tell.
+const DEFAULT_CELL_SIZE: (u16, u16) = (10, 20);
+
+/// A protocol for drawing images in the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    /// The Kitty graphics protocol, also supported by WezTerm, Konsole and Ghostty.
+    Kitty,
+    /// iTerm2's inline images, also supported by WezTerm and mintty.
+    Iterm2,
+    /// Sixels, supported by foot, mlterm, xterm with `-ti vt340` and others.
+    Sixel,
+}
+
+impl GraphicsProtocol {
+    /// Guesses the protocol supported by the terminal from the environment, if any. Terminals
+    /// don't always advertise what they support, so this errs on the side of `None`, in which
+    /// case widgets fall back to drawing with characters.
+    pub fn detect() -> Option<Self> {
+        let var = |name| env::var(name).unwrap_or_default();
+        let term = var("TERM");
+        let program = var("TERM_PROGRAM");
+
+        if env::var_os("KITTY_WINDOW_ID").is_some()
+            || term == "xterm-kitty"
+            || term == "xterm-ghostty"
+            || program == "ghostty"
+        {
+            Some(Self::Kitty)
+        } else if program == "iTerm.app"
+            || program == "WezTerm"
+            || env::var_os("ITERM_SESSION_ID").is_some()
+        {
+            Some(Self::Iterm2)
+        } else if term.contains("sixel")
+            || term.starts_with("foot")
+            || term.starts_with("mlterm")
+            || program == "mlterm"
+        {
+            Some(Self::Sixel)
+        } else {
+            None
+        }
+    }
+}
+
+/// An RGB image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: u16,
+    height: u16,
+    /// Pixels, row by row.
+    pixels: Vec<[u8; 3]>,
+}
+
+impl Image {
+    /// A black image.
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![[0; 3]; width as usize * height as usize],
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Sets the pixel at (`x`, `y`), if inside of the image.
+    pub fn set(&mut self, x: u16, y: u16, color: Color) {
+        if x < self.width && y < self.height {
+            self.pixels[y as usize * self.width as usize + x as usize] = rgb(color);
+        }
+    }
+
+    fn get(&self, x: u16, y: u16) -> [u8; 3] {
+        self.pixels[y as usize * self.width as usize + x as usize]
+    }
+
+    /// The image scaled to `width` by `height` pixels, with nearest neighbour sampling.
+    fn scaled(&self, width: u16, height: u16) -> Self {
+        let mut scaled = Self::new(width, height);
+        if self.width == 0 || self.height == 0 {
+            return scaled;
+        }
+
+        for y in 0..height {
+            let source_y = (y as u32 * self.height as u32 / height as u32) as u16;
+            for x in 0..width {
+                let source_x = (x as u32 * self.width as u32 / width as u32) as u16;
+                scaled.pixels[y as usize * width as usize + x as usize] =
+                    self.get(source_x, source_y);
+            }
+        }
+
+        scaled
+    }
+}
+
+/// Writes `image` to the terminal with `protocol`, stretched over `area`, leaving the cursor where
+/// it was. Should be called after the frame is drawn, as drawing cells over the area erases the
+/// image on most terminals.
+///
+/// With Kitty, the image replaces any other drawn at the same position by this function.
+pub fn write_image(
+    mut writer: impl Write,
+    protocol: GraphicsProtocol,
+    area: Rect,
+    image: &Image,
+) -> io::Result<()> {
+    if area.width == 0 || area.height == 0 || image.width == 0 || image.height == 0 {
+        return Ok(());
+    }
+
+    // save the cursor and move it to the top left of the area
+    write!(writer, "\x1b7\x1b[{};{}H", area.y + 1, area.x + 1)?;
+    match protocol {
+        GraphicsProtocol::Kitty => write_kitty(&mut writer, area, image)?,
+        GraphicsProtocol::Iterm2 => {
+            let png = encode_png(image);
+            write!(
+                writer,
+                "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=0:{}\x07",
+                png.len(),
+                area.width,
+                area.height,
+                base64(&png),
+            )?;
+        }
+        GraphicsProtocol::Sixel => {
+            let (cell_width, cell_height) = cell_size();
+            let scaled = image.scaled(
+                area.width.saturating_mul(cell_width),
+                area.height.saturating_mul(cell_height),
+            );
+            writer.write_all(encode_sixel(&scaled).as_bytes())?;
+        }
+    }
+    write!(writer, "\x1b8")?;
+
+    writer.flush()
+}
+
+fn write_kitty(mut writer: impl Write, area: Rect, image: &Image) -> io::Result<()> {
+    let id = ((area.y as u32) << 16 | area.x as u32) + 1;
+    let data: Vec<u8> = image.pixels.iter().flatten().copied().collect();
+    let data = base64(&data);
+
+    // deletes the previous image at the same position, if any
+    write!(writer, "\x1b_Ga=d,d=I,i={id},q=2\x1b\\")?;
+
+    let chunks: Vec<_> = data.as_bytes().chunks(KITTY_CHUNK_SIZE).collect();
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = (index + 1 < chunks.len()) as u8;
+        if index == 0 {
+            write!(
+                writer,
+                "\x1b_Ga=T,f=24,i={id},s={},v={},c={},r={},C=1,q=2,m={more};",
+                image.width, image.height, area.width, area.height,
+            )?;
+        } else {
+            write!(writer, "\x1b_Gm={more};")?;
+        }
+        writer.write_all(chunk)?;
+        write!(writer, "\x1b\\")?;
+    }
+
+    Ok(())
+}
+
+/// Size of a cell in pixels, as reported by the terminal.
+fn cell_size() -> (u16, u16) {
+    crossterm::terminal::window_size()
+        .ok()
+        .filter(|size| size.columns > 0 && size.rows > 0 && size.width > 0 && size.height > 0)
+        .map_or(DEFAULT_CELL_SIZE, |size| {
+            (
+                (size.width / size.columns).max(1),
+                (size.height / size.rows).max(1),
+            )
+        })
+}
+
+/// An approximation of a terminal color in RGB. The default color is black.
+fn rgb(color: Color) -> [u8; 3] {
+    const ANSI: [[u8; 3]; 16] = [
+        [0x00, 0x00, 0x00],
+        [0xCD, 0x00, 0x00],
+        [0x00, 0xCD, 0x00],
+        [0xCD, 0xCD, 0x00],
+        [0x00, 0x00, 0xEE],
+        [0xCD, 0x00, 0xCD],
+        [0x00, 0xCD, 0xCD],
+        [0xE5, 0xE5, 0xE5],
+        [0x7F, 0x7F, 0x7F],
+        [0xFF, 0x00, 0x00],
+        [0x00, 0xFF, 0x00],
+        [0xFF, 0xFF, 0x00],
+        [0x5C, 0x5C, 0xFF],
+        [0xFF, 0x00, 0xFF],
+        [0x00, 0xFF, 0xFF],
+        [0xFF, 0xFF, 0xFF],
+    ];
+
+    let index = match color {
+        Color::Rgb(r, g, b) => return [r, g, b],
+        Color::Reset | Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::Gray => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::White => 15,
+        Color::Indexed(index) => index,
+    };
+
+    match index {
+        0..=15 => ANSI[index as usize],
+        // 6x6x6 color cube
+        16..=231 => {
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+            let index = index - 16;
+            [level(index / 36), level(index / 6 % 6), level(index % 6)]
+        }
+        // grayscale ramp
+        _ => {
+            let level = 8 + (index - 232) * 10;
+            [level; 3]
+        }
+    }
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut text = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let bits = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let sextet = (bits >> (18 - index * 6)) & 0x3F;
+                text.push(ALPHABET[sextet as usize] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+
+    text
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, byte| {
+        (0..8).fold(crc ^ *byte as u32, |crc, _| {
+            if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (a, b) = data.iter().fold((1u32, 0u32), |(a, b), byte| {
+        let a = (a + *byte as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+
+    b << 16 | a
+}
+
+/// Encodes an image as an uncompressed PNG, which is enough for images sent to a local terminal.
+fn encode_png(image: &Image) -> Vec<u8> {
+    fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+        png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        let start = png.len();
+        png.extend_from_slice(kind);
+        png.extend_from_slice(data);
+        let crc = crc32(&png[start..]);
+        png.extend_from_slice(&crc.to_be_bytes());
+    }
+
+    // each row starts with its filter, none
+    let mut raw = Vec::with_capacity(image.pixels.len() * 3 + image.height as usize);
+    for row in image.pixels.chunks(image.width as usize) {
+        raw.push(0);
+        raw.extend(row.iter().flatten());
+    }
+
+    // a zlib stream of stored deflate blocks
+    let mut zlib = vec![0x78, 0x01];
+    let blocks: Vec<_> = raw.chunks(u16::MAX as usize).collect();
+    for (index, block) in blocks.iter().enumerate() {
+        let last = (index + 1 == blocks.len()) as u8;
+        let len = block.len() as u16;
+        zlib.push(last);
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(image.width as u32).to_be_bytes());
+    header.extend_from_slice(&(image.height as u32).to_be_bytes());
+    // 8 bits per channel, RGB, default compression, filtering and no interlacing
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    chunk(&mut png, b"IHDR", &header);
+    chunk(&mut png, b"IDAT", &zlib);
+    chunk(&mut png, b"IEND", &[]);
+
+    png
+}
+
+/// Encodes an image as sixels. Images with more than 256 colors are reduced to a 6x6x6 color cube.
+fn encode_sixel(image: &Image) -> String {
+    let mut palette: Vec<[u8; 3]> = Vec::new();
+    for pixel in &image.pixels {
+        if palette.len() > 256 {
+            break;
+        }
+        if !palette.contains(pixel) {
+            palette.push(*pixel);
+        }
+    }
+
+    let reduce = palette.len() > 256;
+    let cube = |[r, g, b]: [u8; 3]| {
+        let level = |value: u8| (value as u16 * 5 / 255) as usize;
+        level(r) * 36 + level(g) * 6 + level(b)
+    };
+    if reduce {
+        palette = (0..216)
+            .map(|index| {
+                let level = |value: usize| (value * 255 / 5) as u8;
+                [level(index / 36), level(index / 6 % 6), level(index % 6)]
+            })
+            .collect();
+    }
+    let color_index = |pixel: [u8; 3]| {
+        if reduce {
+            cube(pixel)
+        } else {
+            palette
+                .iter()
+                .position(|color| *color == pixel)
+                .unwrap_or(0)
+        }
+    };
+
+    let mut text = format!("\x1bP0;1;0q\"1;1;{};{}", image.width, image.height);
+    for (index, [r, g, b]) in palette.iter().enumerate() {
+        let percent = |value: u8| value as u16 * 100 / 255;
+        text.push_str(&format!(
+            "#{index};2;{};{};{}",
+            percent(*r),
+            percent(*g),
+            percent(*b)
+        ));
+    }
+
+    let indices: Vec<usize> = image
+        .pixels
+        .iter()
+        .map(|pixel| color_index(*pixel))
+        .collect();
+    for band in (0..image.height).step_by(6) {
+        let rows = (image.height - band).min(6);
+        let mut colors: Vec<usize> = (band..band + rows)
+            .flat_map(|y| {
+                let start = y as usize * image.width as usize;
+                indices[start..start + image.width as usize].iter().copied()
+            })
+            .collect();
+        colors.sort_unstable();
+        colors.dedup();
+
+        for color in colors {
+            text.push_str(&format!("#{color}"));
+
+            // run-length encoded columns of 6 pixels
+            let mut run: Option<(u8, usize)> = None;
+            let flush = |text: &mut String, run: (u8, usize)| {
+                let symbol = (0x3F + run.0) as char;
+                match run.1 {
+                    1..=3 => (0..run.1).for_each(|_| text.push(symbol)),
+                    count => text.push_str(&format!("!{count}{symbol}")),
+                }
+            };
+            for x in 0..image.width {
+                let bits = (0..rows).fold(0, |bits, dy| {
+                    let index = (band + dy) as usize * image.width as usize + x as usize;
+                    bits | ((indices[index] == color) as u8) << dy
+                });
+
+                run = match run {
+                    Some((previous, count)) if previous == bits => Some((bits, count + 1)),
+                    Some(previous) => {
+                        flush(&mut text, previous);
+                        Some((bits, 1))
+                    }
+                    None => Some((bits, 1)),
+                };
+            }
+            if let Some(run) = run {
+                flush(&mut text, run);
+            }
+
+            // back to the start of the band, for the next color
+            text.push('$');
+        }
+        text.push('-');
+    }
+    text.push_str("\x1b\\");
+
+    text
+}
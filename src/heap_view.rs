@@ -0,0 +1,341 @@
+use crate::{i18n::strings, keymap::Action, Address, Keymap, Theme};
+use crossterm::event::{KeyEvent, KeyEventKind};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, Row, StatefulWidget, Table, Widget},
+};
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Allocation {
+    pub address: Address,
+    /// Size of the allocation, in bytes.
+    pub size: u32,
+    /// Tag or callsite of the allocation.
+    pub tag: Option<String>,
+}
+
+impl Allocation {
+    fn end(&self) -> u64 {
+        self.address as u64 + self.size as u64
+    }
+}
+
+pub trait AllocationProvider {
+    /// Returns the live allocations.
+    fn allocations(&self) -> Vec<Allocation>;
+}
+
+impl AllocationProvider for [Allocation] {
+    fn allocations(&self) -> Vec<Allocation> {
+        self.to_vec()
+    }
+}
+
+impl AllocationProvider for Vec<Allocation> {
+    fn allocations(&self) -> Vec<Allocation> {
+        self.clone()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AllocationSort {
+    #[default]
+    Address,
+    Size,
+    Tag,
+}
+
+pub struct HeapViewState {
+    pub sort: AllocationSort,
+    pub descending: bool,
+
+    selected: usize,
+    offset: usize,
+    page: usize,
+    len: usize,
+    selected_address: Option<Address>,
+}
+
+impl Default for HeapViewState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HeapViewState {
+    pub fn new() -> Self {
+        Self {
+            sort: AllocationSort::default(),
+            descending: false,
+            selected: 0,
+            offset: 0,
+            page: 1,
+            len: 0,
+            selected_address: None,
+        }
+    }
+
+    /// Address of the selected allocation, as of the last render.
+    pub fn selected_address(&self) -> Option<Address> {
+        self.selected_address
+    }
+
+    /// Handles a key event with the [standard keymap](Keymap::standard).
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Address> {
+        self.handle_key_with(key, Keymap::standard())
+    }
+
+    /// Handles a key event, translated to an action by `keymap`. Returns the address of the
+    /// selected allocation when it is activated, so that the memory view can jump to it.
+    pub fn handle_key_with(&mut self, key: KeyEvent, keymap: &Keymap) -> Option<Address> {
+        if key.kind == KeyEventKind::Release {
+            return None;
+        }
+
+        let last = self.len.saturating_sub(1);
+        match keymap.action(key) {
+            Some(Action::Up) => self.selected = self.selected.saturating_sub(1),
+            Some(Action::Down) => self.selected = (self.selected + 1).min(last),
+            Some(Action::PageUp) => self.selected = self.selected.saturating_sub(self.page),
+            Some(Action::PageDown) => self.selected = (self.selected + self.page).min(last),
+            Some(Action::Home) => self.selected = 0,
+            Some(Action::End) => self.selected = last,
+            Some(Action::Sort) => {
+                self.sort = match self.sort {
+                    AllocationSort::Address => AllocationSort::Size,
+                    AllocationSort::Size => AllocationSort::Tag,
+                    AllocationSort::Tag => AllocationSort::Address,
+                }
+            }
+            Some(Action::Reverse) => self.descending = !self.descending,
+            Some(Action::Activate) => return self.selected_address,
+            _ => (),
+        }
+
+        None
+    }
+}
+
+/// A sortable table of heap allocations, with an optional fragmentation bar.
+pub struct HeapView<'a> {
+    /// The allocation provider.
+    allocation_provider: &'a dyn AllocationProvider,
+
+    /// Range of the heap, for the fragmentation bar.
+    heap_range: Option<Range<Address>>,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> HeapView<'a> {
+    pub fn new(allocation_provider: &'a dyn AllocationProvider) -> Self {
+        Self {
+            allocation_provider,
+            heap_range: None,
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    /// Shows a fragmentation bar of the given heap range above the table.
+    pub fn heap_range(self, heap_range: Range<Address>) -> Self {
+        Self {
+            heap_range: Some(heap_range),
+            ..self
+        }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+
+    fn render_fragmentation_bar(
+        allocations: &[Allocation],
+        heap: &Range<Address>,
+        area: Rect,
+        buf: &mut Buffer,
+    ) {
+        let (start, end) = (heap.start as u64, heap.end as u64);
+        let span = end.saturating_sub(start);
+        if span == 0 || area.width == 0 {
+            return;
+        }
+
+        // amount of allocated bytes covered by each cell
+        let width = area.width as u64;
+        let cell_start = |cell: u64| start + span * cell / width;
+        let mut used = vec![0u64; area.width as usize];
+        for allocation in allocations {
+            let (a, b) = (
+                (allocation.address as u64).max(start),
+                allocation.end().min(end),
+            );
+            if a >= b {
+                continue;
+            }
+
+            let first = (a - start) * width / span;
+            let last = ((b - 1 - start) * width / span).min(width - 1);
+            for cell in first..=last {
+                let (cs, ce) = (cell_start(cell), cell_start(cell + 1));
+                used[cell as usize] += b.min(ce).saturating_sub(a.max(cs));
+            }
+        }
+
+        for (cell, used) in used.into_iter().enumerate() {
+            let size = cell_start(cell as u64 + 1) - cell_start(cell as u64);
+            let (symbol, style) = if size == 0 || used == 0 {
+                ("·", Style::default().dark_gray())
+            } else if used >= size {
+                ("█", Style::default().light_green())
+            } else if used * 2 >= size {
+                ("▓", Style::default().light_yellow())
+            } else {
+                ("░", Style::default().light_yellow())
+            };
+
+            buf.set_string(area.x + cell as u16, area.y, symbol, style);
+        }
+    }
+}
+
+impl<'a> StatefulWidget for HeapView<'a> {
+    type State = HeapViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let mut area = self.wrap_in_block(area, buf);
+
+        let mut allocations = self.allocation_provider.allocations();
+        match state.sort {
+            AllocationSort::Address => allocations.sort_by_key(|a| a.address),
+            AllocationSort::Size => allocations.sort_by_key(|a| (a.size, a.address)),
+            AllocationSort::Tag => {
+                allocations.sort_by(|a, b| a.tag.cmp(&b.tag).then(a.address.cmp(&b.address)))
+            }
+        }
+        if state.descending {
+            allocations.reverse();
+        }
+
+        if let Some(heap) = &self.heap_range {
+            if area.height >= 3 {
+                let used: u64 = allocations.iter().map(|a| a.size as u64).sum();
+                let total = (heap.end - heap.start) as u64;
+                let summary = format!(
+                    "{} allocations, {used} of {total} bytes used ({:.1}%)",
+                    allocations.len(),
+                    used as f64 * 100.0 / total.max(1) as f64
+                );
+                buf.set_stringn(
+                    area.x,
+                    area.y,
+                    summary,
+                    area.width as usize,
+                    Style::default().white(),
+                );
+
+                let bar = Rect {
+                    y: area.y + 1,
+                    height: 1,
+                    ..area
+                };
+                Self::render_fragmentation_bar(&allocations, heap, bar, buf);
+
+                area.y += 2;
+                area.height -= 2;
+            }
+        }
+
+        if area.height == 0 {
+            return;
+        }
+
+        // update state
+        let list_height = area.height as usize - 1;
+        state.len = allocations.len();
+        state.selected = state.selected.min(state.len.saturating_sub(1));
+        state.page = list_height.max(1);
+        if state.selected < state.offset {
+            state.offset = state.selected;
+        } else if state.selected >= state.offset + state.page {
+            state.offset = state.selected + 1 - state.page;
+        }
+        state.selected_address = allocations.get(state.selected).map(|a| a.address);
+
+        let arrow = if state.descending { "▼" } else { "▲" };
+        let title = |name: &str, sort: AllocationSort| {
+            if state.sort == sort {
+                Line::from(format!("{name} {arrow}"))
+            } else {
+                Line::from(name.to_owned())
+            }
+        };
+        let strings = strings();
+        let header = Row::new([
+            title(&strings.address, AllocationSort::Address),
+            title(&strings.size, AllocationSort::Size),
+            title(&strings.tag, AllocationSort::Tag),
+        ])
+        .style(self.theme.header);
+
+        let rows = allocations
+            .iter()
+            .enumerate()
+            .skip(state.offset)
+            .take(list_height)
+            .map(|(index, allocation)| {
+                let row = Row::new([
+                    Line::from(Span::styled(
+                        format!("{:08X}", allocation.address),
+                        self.theme.address,
+                    )),
+                    Line::from(Span::styled(
+                        allocation.size.to_string(),
+                        Style::default().light_green(),
+                    )),
+                    Line::from(Span::styled(
+                        allocation.tag.clone().unwrap_or_default(),
+                        Style::default().light_blue(),
+                    )),
+                ]);
+
+                if index == state.selected {
+                    row.style(self.theme.selection)
+                } else {
+                    row
+                }
+            });
+
+        let constraints = [
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Min(1),
+        ];
+        let table = Table::new(rows).header(header).widths(&constraints);
+        Widget::render(table, area, buf);
+    }
+}
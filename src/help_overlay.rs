@@ -0,0 +1,133 @@
+use crate::{i18n::strings, popup, Theme};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, Borders, Clear, Row, Table, Widget},
+};
+use std::borrow::Cow;
+
+/// A single keybinding entry shown by the [`HelpOverlay`].
+#[derive(Debug, Clone)]
+pub struct HelpEntry<'a> {
+    /// The key (or key chord) that triggers the action.
+    pub key: Cow<'a, str>,
+    /// What the key does.
+    pub description: Cow<'a, str>,
+}
+
+impl<'a> HelpEntry<'a> {
+    pub fn new(key: impl Into<Cow<'a, str>>, description: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            key: key.into(),
+            description: description.into(),
+        }
+    }
+}
+
+/// A centered popup listing keybindings, drawn over a dimmed version of whatever was
+/// already rendered in the area.
+pub struct HelpOverlay<'a> {
+    /// The keybindings to list.
+    entries: &'a [HelpEntry<'a>],
+
+    /// Title of the popup.
+    title: Cow<'a, str>,
+
+    /// Block to draw inside. Replaces the default bordered block.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> HelpOverlay<'a> {
+    pub fn new(entries: &'a [HelpEntry<'a>]) -> Self {
+        Self {
+            entries,
+            title: Cow::from(strings().help.to_string()),
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn title(self, title: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            title: title.into(),
+            ..self
+        }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn popup_area(&self, area: Rect) -> Rect {
+        let key_width = self
+            .entries
+            .iter()
+            .map(|entry| entry.key.chars().count())
+            .max()
+            .unwrap_or(0);
+        let description_width = self
+            .entries
+            .iter()
+            .map(|entry| entry.description.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        // borders + padding + column spacing
+        let width = (key_width + description_width + 5).max(self.title.chars().count() + 4);
+        let height = self.entries.len() + 2;
+
+        popup::centered_rect(
+            width.min(u16::MAX as usize) as u16,
+            height.min(u16::MAX as usize) as u16,
+            area,
+        )
+    }
+}
+
+impl<'a> Widget for HelpOverlay<'a> {
+    fn render(mut self, area: Rect, buf: &mut Buffer) {
+        popup::dim(area, buf);
+
+        let popup_area = self.popup_area(area);
+        Clear.render(popup_area, buf);
+
+        let block = self.block.take().unwrap_or_else(|| {
+            Block::new()
+                .borders(Borders::ALL)
+                .border_style(self.theme.border_focused)
+                .title(Span::styled(self.title.clone(), self.theme.title))
+        });
+        let inner_area = block.inner(popup_area);
+        block.render(popup_area, buf);
+
+        let key_width = self
+            .entries
+            .iter()
+            .map(|entry| entry.key.chars().count())
+            .max()
+            .unwrap_or(0)
+            .min(u16::MAX as usize) as u16;
+
+        let rows = self.entries.iter().map(|entry| {
+            Row::new([
+                Span::styled(entry.key.clone(), self.theme.header),
+                Span::raw(entry.description.clone()),
+            ])
+        });
+
+        let constraints = [Constraint::Length(key_width), Constraint::Min(1)];
+        let table = Table::new(rows).widths(&constraints);
+        Widget::render(table, inner_area, buf);
+    }
+}
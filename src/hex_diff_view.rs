@@ -0,0 +1,358 @@
+use crate::{
+    error::{render_error, Error},
+    memory_view::MemoryProvider,
+    Address, Theme,
+};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, Borders, StatefulWidget, Widget},
+};
+use std::borrow::Cow;
+
+/// How many bytes are compared at once while looking for differences.
+const CHUNK_SIZE: usize = 4096;
+/// Width needed for the address column and a single byte on each side.
+const MIN_WIDTH: u16 = 21;
+
+pub struct HexDiffViewState {
+    /// The memory address being pointed at, in both providers.
+    pub pointer: Address,
+
+    left_buffer: Vec<Option<u8>>,
+    right_buffer: Vec<Option<u8>>,
+    beginning_row: Address,
+    bytes_per_row: u16,
+}
+
+impl HexDiffViewState {
+    pub fn new(pointer: Address) -> Self {
+        Self {
+            pointer,
+            left_buffer: Vec::new(),
+            right_buffer: Vec::new(),
+            beginning_row: 0,
+            bytes_per_row: 0,
+        }
+    }
+
+    pub fn bytes_per_row(&self) -> u16 {
+        self.bytes_per_row
+    }
+
+    /// Looks for the first differing address in `start..end`, scanning in the given direction.
+    fn find_difference(
+        left: &dyn MemoryProvider,
+        right: &dyn MemoryProvider,
+        mut start: Address,
+        mut end: Address,
+        forward: bool,
+    ) -> Option<Address> {
+        let mut left_buf = vec![None; CHUNK_SIZE];
+        let mut right_buf = vec![None; CHUNK_SIZE];
+
+        while start < end {
+            let len = ((end - start) as usize).min(CHUNK_SIZE);
+            let chunk_start = if forward { start } else { end - len as Address };
+
+            let left_buf = &mut left_buf[..len];
+            let right_buf = &mut right_buf[..len];
+            left_buf.fill(None);
+            right_buf.fill(None);
+            left.read_to_buf(chunk_start, left_buf);
+            right.read_to_buf(chunk_start, right_buf);
+
+            let mut differing = left_buf
+                .iter()
+                .zip(right_buf.iter())
+                .enumerate()
+                .filter(|(_, (a, b))| a != b)
+                .map(|(offset, _)| chunk_start + offset as Address);
+
+            let found = if forward {
+                differing.next()
+            } else {
+                differing.next_back()
+            };
+            if found.is_some() {
+                return found;
+            }
+
+            if forward {
+                start += len as Address;
+            } else {
+                end = chunk_start;
+            }
+        }
+
+        None
+    }
+
+    /// Moves the pointer to the next differing byte, looking at most `limit` bytes ahead.
+    /// Returns the new pointer, if a difference was found.
+    pub fn next_difference(
+        &mut self,
+        left: &dyn MemoryProvider,
+        right: &dyn MemoryProvider,
+        limit: Address,
+    ) -> Option<Address> {
+        let start = self.pointer.checked_add(1)?;
+        let end = start.saturating_add(limit);
+        let found = Self::find_difference(left, right, start, end, true)?;
+
+        self.pointer = found;
+        Some(found)
+    }
+
+    /// Moves the pointer to the previous differing byte, looking at most `limit` bytes behind.
+    /// Returns the new pointer, if a difference was found.
+    pub fn previous_difference(
+        &mut self,
+        left: &dyn MemoryProvider,
+        right: &dyn MemoryProvider,
+        limit: Address,
+    ) -> Option<Address> {
+        let end = self.pointer;
+        let start = end.saturating_sub(limit);
+        let found = Self::find_difference(left, right, start, end, false)?;
+
+        self.pointer = found;
+        Some(found)
+    }
+}
+
+/// Two hex grids side by side, with differing bytes highlighted and scrolling synchronized.
+pub struct HexDiffView<'a> {
+    /// The provider shown on the left.
+    left: &'a dyn MemoryProvider,
+    /// The provider shown on the right.
+    right: &'a dyn MemoryProvider,
+
+    left_title: Cow<'a, str>,
+    right_title: Cow<'a, str>,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> HexDiffView<'a> {
+    pub fn new(left: &'a dyn MemoryProvider, right: &'a dyn MemoryProvider) -> Self {
+        Self {
+            left,
+            right,
+            left_title: Cow::from("A"),
+            right_title: Cow::from("B"),
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn titles(
+        self,
+        left_title: impl Into<Cow<'a, str>>,
+        right_title: impl Into<Cow<'a, str>>,
+    ) -> Self {
+        Self {
+            left_title: left_title.into(),
+            right_title: right_title.into(),
+            ..self
+        }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+
+    fn render_grid(
+        area: Rect,
+        buf: &mut Buffer,
+        state: &HexDiffViewState,
+        theme: &Theme,
+        this: &[Option<u8>],
+        other: &[Option<u8>],
+    ) {
+        let bytes_per_row = state.bytes_per_row as usize;
+        for (row, (bytes, other_bytes)) in this
+            .chunks(bytes_per_row)
+            .zip(other.chunks(bytes_per_row))
+            .take(area.height as usize)
+            .enumerate()
+        {
+            let y = area.y + row as u16;
+            for (column, (byte, other_byte)) in bytes.iter().zip(other_bytes).enumerate() {
+                let index = row * bytes_per_row + column;
+                let address = state.beginning_row.wrapping_add(index as Address);
+
+                let text = byte
+                    .map(|x| Cow::from(format!("{x:02X}")))
+                    .unwrap_or(Cow::from("◦◦"));
+
+                let style = if byte != other_byte {
+                    theme.alert
+                } else {
+                    theme.muted
+                };
+                let style = if address == state.pointer {
+                    style.underlined().reversed()
+                } else {
+                    style
+                };
+
+                buf.set_string(area.x + column as u16 * 3, y, text, style);
+            }
+        }
+    }
+}
+
+impl<'a> StatefulWidget for HexDiffView<'a> {
+    type State = HexDiffViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = self.wrap_in_block(area, buf);
+        if area.height < 2 || area.width < MIN_WIDTH {
+            let error = Error::AreaTooSmall {
+                width: area.width,
+                height: area.height,
+            };
+            render_error(&error, area, buf, &self.theme);
+            return;
+        }
+
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                [
+                    Constraint::Length(11),
+                    Constraint::Length(1),
+                    Constraint::Min(6),
+                ]
+                .as_ref(),
+            )
+            .split(area);
+        let address_column = chunks[0];
+
+        // each side needs 3 cells per byte, plus a separator between them
+        let bytes_per_row = (chunks[2].width.saturating_sub(3) / 6).max(1);
+        let bytes_per_row = if bytes_per_row >= 4 {
+            bytes_per_row & !3
+        } else {
+            bytes_per_row
+        };
+        let grid_width = bytes_per_row * 3;
+
+        let grids = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                [
+                    Constraint::Length(grid_width),
+                    Constraint::Length(3),
+                    Constraint::Length(grid_width),
+                    Constraint::Min(0),
+                ]
+                .as_ref(),
+            )
+            .split(chunks[2]);
+
+        // update state
+        let rows = area.height - 1;
+        state.bytes_per_row = bytes_per_row;
+        let pointed_row = state.pointer - state.pointer % bytes_per_row as Address;
+        state.beginning_row =
+            pointed_row.saturating_sub(bytes_per_row as Address * (rows / 2) as Address);
+
+        let value_count = bytes_per_row as usize * rows as usize;
+        for (provider, buffer) in [
+            (self.left, &mut state.left_buffer),
+            (self.right, &mut state.right_buffer),
+        ] {
+            buffer.clear();
+            buffer.resize(value_count, None);
+            provider.read_to_buf(state.beginning_row, buffer);
+        }
+
+        // render!
+        let title_style = self.theme.title;
+        buf.set_stringn(
+            grids[0].x,
+            area.y,
+            &self.left_title,
+            grids[0].width as usize,
+            title_style,
+        );
+        buf.set_stringn(
+            grids[2].x,
+            area.y,
+            &self.right_title,
+            grids[2].width as usize,
+            title_style,
+        );
+
+        let body = |rect: Rect| Rect {
+            y: rect.y + 1,
+            height: rows,
+            ..rect
+        };
+
+        let address_area = body(address_column);
+        let block = Block::new().borders(Borders::RIGHT);
+        let inner_area = block.inner(address_area);
+        block.render(address_area, buf);
+        for row in 0..rows {
+            let text = state
+                .beginning_row
+                .checked_add(bytes_per_row as Address * row as Address)
+                .map(|addr| Cow::from(format!("{addr:08X}")))
+                .unwrap_or(Cow::from("--------"));
+            buf.set_stringn(
+                inner_area.x + 1,
+                inner_area.y + row,
+                text,
+                inner_area.width.saturating_sub(1) as usize,
+                self.theme.address,
+            );
+        }
+
+        let separator = body(grids[1]);
+        for y in separator.top()..separator.bottom() {
+            buf.set_string(separator.x + 1, y, "│", self.theme.muted);
+        }
+
+        Self::render_grid(
+            body(grids[0]),
+            buf,
+            state,
+            &self.theme,
+            &state.left_buffer,
+            &state.right_buffer,
+        );
+        Self::render_grid(
+            body(grids[2]),
+            buf,
+            state,
+            &self.theme,
+            &state.right_buffer,
+            &state.left_buffer,
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! The text built into the widgets, e.g. labels of the info bar of the memory view, prompts and
+//! table headers, gathered into a [`Strings`] table so that applications can translate it.
+//!
+//! The table is shared by every widget, and replaced with [`set_strings`]. Strings holding `{}`
+//! are templates, with each `{}` replaced by a value in order, e.g. `"{} of {} matches"`.
+
+use std::{
+    borrow::Cow,
+    fmt::{self, Write},
+    sync::{Arc, PoisonError, RwLock},
+};
+
+static STRINGS: RwLock<Option<Arc<Strings>>> = RwLock::new(None);
+
+/// Text of the widgets, in English by default.
+///
+/// With the `serde` feature, missing strings deserialize to their default, so that translations
+/// can be partial.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Strings {
+    /// Info bar label of the address under the pointer, followed by it.
+    pub selected: Cow<'static, str>,
+    pub little_endian: Cow<'static, str>,
+    pub big_endian: Cow<'static, str>,
+    /// Prompt of the goto input of the memory view.
+    pub go_to: Cow<'static, str>,
+    /// Error of the goto input when it is empty.
+    pub no_address: Cow<'static, str>,
+    /// Title of the memory view while following a pointer, followed by its address.
+    pub following: Cow<'static, str>,
+    /// Template of the time since the last refresh, in seconds.
+    pub refreshed_seconds_ago: Cow<'static, str>,
+    /// Template of the time since the last refresh, in minutes.
+    pub refreshed_minutes_ago: Cow<'static, str>,
+    /// Template of the error of the goto input when its text isn't an address, nor a symbol if
+    /// symbols are available.
+    pub not_an_address: Cow<'static, str>,
+    pub not_an_address_or_symbol: Cow<'static, str>,
+
+    /// Prompt of the label input of the instruction view.
+    pub label: Cow<'static, str>,
+    /// Prompt of the label input of the instruction view, when renaming a label.
+    pub rename: Cow<'static, str>,
+    /// Error of the label input when it is empty.
+    pub no_name: Cow<'static, str>,
+    /// Template of the error of the label input when the name is taken, followed by the address
+    /// it labels.
+    pub name_taken: Cow<'static, str>,
+    /// Templates of the title of the xref picker, given the amount of xrefs and their target.
+    pub no_xrefs: Cow<'static, str>,
+    pub one_xref: Cow<'static, str>,
+    pub xrefs: Cow<'static, str>,
+
+    /// Template of the amount of matches of a search.
+    pub matches: Cow<'static, str>,
+    /// Template of the index of the current match of a search and the amount of matches.
+    pub match_of: Cow<'static, str>,
+    pub no_matches: Cow<'static, str>,
+    /// Template of the progress of a background search, in percent.
+    pub searching: Cow<'static, str>,
+
+    /// Templates of the header of the symbol list, given the amount of symbols and their order.
+    pub symbol_count: Cow<'static, str>,
+    pub by_address: Cow<'static, str>,
+    pub by_name: Cow<'static, str>,
+
+    /// Templates of the labels of the memory statistics. The latest value is `--` if there is
+    /// none.
+    pub stats_max: Cow<'static, str>,
+    pub stats_min: Cow<'static, str>,
+    pub stats_now: Cow<'static, str>,
+
+    /// Template of the info line of the tile view: the index and address of the tile, its width,
+    /// height and bits per pixel.
+    pub tile_info: Cow<'static, str>,
+
+    /// Kinds of regions.
+    pub rom: Cow<'static, str>,
+    pub ram: Cow<'static, str>,
+    pub mmio: Cow<'static, str>,
+    pub unmapped: Cow<'static, str>,
+
+    /// Labels of the legend.
+    pub legend_cursor: Cow<'static, str>,
+    pub legend_occurrence: Cow<'static, str>,
+    pub legend_modified: Cow<'static, str>,
+    pub legend_stale: Cow<'static, str>,
+    pub legend_alert: Cow<'static, str>,
+    pub legend_current: Cow<'static, str>,
+    pub legend_breakpoint: Cow<'static, str>,
+
+    /// Template of the error shown in place of a widget given too small an area, given its width
+    /// and height.
+    pub too_small: Cow<'static, str>,
+    /// Default title of the help overlay.
+    pub help: Cow<'static, str>,
+    /// Buttons of confirmation dialogs.
+    pub yes: Cow<'static, str>,
+    pub no: Cow<'static, str>,
+
+    /// Table headers.
+    pub address: Cow<'static, str>,
+    pub start: Cow<'static, str>,
+    pub end: Cow<'static, str>,
+    pub size: Cow<'static, str>,
+    pub permissions: Cow<'static, str>,
+    pub name: Cow<'static, str>,
+    pub tag: Cow<'static, str>,
+    pub count: Cow<'static, str>,
+    pub reload: Cow<'static, str>,
+    pub prescaler: Cow<'static, str>,
+    pub irq: Cow<'static, str>,
+    pub progress: Cow<'static, str>,
+}
+
+impl Default for Strings {
+    fn default() -> Self {
+        Self {
+            selected: Cow::from("Selected"),
+            little_endian: Cow::from("Little Endian"),
+            big_endian: Cow::from("Big Endian"),
+            go_to: Cow::from("Go to"),
+            no_address: Cow::from("no address given"),
+            following: Cow::from("following"),
+            refreshed_seconds_ago: Cow::from("refreshed {}s ago"),
+            refreshed_minutes_ago: Cow::from("refreshed {}m ago"),
+            not_an_address: Cow::from("`{}` is not a hex address"),
+            not_an_address_or_symbol: Cow::from("`{}` is neither an address nor a symbol"),
+
+            label: Cow::from("Label"),
+            rename: Cow::from("Rename"),
+            no_name: Cow::from("no name given"),
+            name_taken: Cow::from("`{}` already labels {}"),
+            no_xrefs: Cow::from("No xrefs to {}"),
+            one_xref: Cow::from("1 xref to {}"),
+            xrefs: Cow::from("{} xrefs to {}"),
+
+            matches: Cow::from("{} matches"),
+            match_of: Cow::from("{} of {} matches"),
+            no_matches: Cow::from("no matches"),
+            searching: Cow::from("searching {}%"),
+
+            symbol_count: Cow::from("{} symbols, {}"),
+            by_address: Cow::from("by address"),
+            by_name: Cow::from("by name"),
+
+            stats_max: Cow::from("max {}"),
+            stats_min: Cow::from("min {}"),
+            stats_now: Cow::from("now {}"),
+
+            tile_info: Cow::from("Tile {} @ {} ({}x{}, {}bpp)"),
+
+            rom: Cow::from("ROM"),
+            ram: Cow::from("RAM"),
+            mmio: Cow::from("MMIO"),
+            unmapped: Cow::from("unmapped"),
+
+            legend_cursor: Cow::from("cursor"),
+            legend_occurrence: Cow::from("same value"),
+            legend_modified: Cow::from("modified"),
+            legend_stale: Cow::from("outdated"),
+            legend_alert: Cow::from("difference"),
+            legend_current: Cow::from("current"),
+            legend_breakpoint: Cow::from("breakpoint"),
+
+            too_small: Cow::from("too small ({}x{})"),
+            help: Cow::from("Help"),
+            yes: Cow::from("Yes"),
+            no: Cow::from("No"),
+
+            address: Cow::from("Address"),
+            start: Cow::from("Start"),
+            end: Cow::from("End"),
+            size: Cow::from("Size"),
+            permissions: Cow::from("Perm"),
+            name: Cow::from("Name"),
+            tag: Cow::from("Tag"),
+            count: Cow::from("Count"),
+            reload: Cow::from("Reload"),
+            prescaler: Cow::from("Presc"),
+            irq: Cow::from("IRQ"),
+            progress: Cow::from("Progress"),
+        }
+    }
+}
+
+/// The table used by the widgets: the last one [set](set_strings), or the default one.
+pub fn strings() -> Arc<Strings> {
+    if let Some(strings) = &*STRINGS.read().unwrap_or_else(PoisonError::into_inner) {
+        return strings.clone();
+    }
+
+    STRINGS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_or_insert_with(Default::default)
+        .clone()
+}
+
+/// Replaces the table used by the widgets, from their next render on.
+pub fn set_strings(strings: Strings) {
+    *STRINGS.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(strings));
+}
+
+/// Replaces each `{}` of `template` with the next of `args`. Extra `{}` are left as is.
+pub(crate) fn fill(template: &str, args: &[&dyn fmt::Display]) -> String {
+    let mut text = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut parts = template.split("{}");
+    if let Some(first) = parts.next() {
+        text.push_str(first);
+    }
+
+    for part in parts {
+        match args.next() {
+            Some(arg) => {
+                // writing to a string never fails
+                let _ = write!(text, "{arg}");
+            }
+            None => text.push_str("{}"),
+        }
+        text.push_str(part);
+    }
+
+    text
+}
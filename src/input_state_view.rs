@@ -0,0 +1,281 @@
+use crate::{keymap::Action, Keymap, Theme};
+use crossterm::event::{KeyEvent, KeyEventKind};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, StatefulWidget, Widget},
+};
+use std::{borrow::Cow, collections::VecDeque};
+
+/// A ring buffer of per-frame input states, one bit per button. Once full, the oldest frames are
+/// dropped.
+#[derive(Debug, Clone)]
+pub struct InputHistory {
+    frames: VecDeque<u64>,
+    capacity: usize,
+    /// Amount of frames ever pushed.
+    pushed: u64,
+}
+
+impl InputHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            frames: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+            pushed: 0,
+        }
+    }
+
+    /// Pushes the input state of a new frame. Bit `n` is set if button `n` is pressed.
+    pub fn push(&mut self, state: u64) {
+        if self.frames.len() == self.capacity {
+            self.frames.pop_front();
+        }
+
+        self.frames.push_back(state);
+        self.pushed += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// The most recent input state.
+    pub fn latest(&self) -> Option<u64> {
+        self.frames.back().copied()
+    }
+
+    /// Input state `back` frames before the most recent one.
+    pub fn get_back(&self, back: usize) -> Option<u64> {
+        let index = self.frames.len().checked_sub(back + 1)?;
+        self.frames.get(index).copied()
+    }
+
+    /// Number of the most recent frame, counting from zero.
+    pub fn latest_frame(&self) -> Option<u64> {
+        self.pushed.checked_sub(1)
+    }
+}
+
+pub struct InputStateViewState {
+    /// How many frames before the most recent one the cursor is. Zero follows the latest frame.
+    pub back: usize,
+
+    /// Amount of frames in the history, as of the last render.
+    len: usize,
+}
+
+impl Default for InputStateViewState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InputStateViewState {
+    pub fn new() -> Self {
+        Self { back: 0, len: 0 }
+    }
+
+    /// Handles a key event with the [standard keymap](Keymap::standard).
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        self.handle_key_with(key, Keymap::standard())
+    }
+
+    /// Handles a key event, translated to an action by `keymap`. Returns whether the event was
+    /// consumed.
+    pub fn handle_key_with(&mut self, key: KeyEvent, keymap: &Keymap) -> bool {
+        if key.kind == KeyEventKind::Release {
+            return false;
+        }
+
+        let oldest = self.len.saturating_sub(1);
+        match keymap.action(key) {
+            Some(Action::Left) => self.back = (self.back + 1).min(oldest),
+            Some(Action::Right) => self.back = self.back.saturating_sub(1),
+            Some(Action::PageUp) => self.back = (self.back + 10).min(oldest),
+            Some(Action::PageDown) => self.back = self.back.saturating_sub(10),
+            Some(Action::Home) => self.back = oldest,
+            Some(Action::End) => self.back = 0,
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+/// Labeled pressed/released indicators for a controller or keyboard matrix, with a history strip
+/// of the previous frames.
+pub struct InputStateView<'a> {
+    history: &'a InputHistory,
+
+    /// Button labels. The label at index `n` corresponds to bit `n`.
+    labels: &'a [Cow<'a, str>],
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> InputStateView<'a> {
+    pub fn new(history: &'a InputHistory, labels: &'a [Cow<'a, str>]) -> Self {
+        Self {
+            history,
+            labels,
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+
+    fn buttons(&self) -> impl Iterator<Item = (u32, &Cow<'a, str>)> {
+        (0..64).zip(self.labels.iter())
+    }
+
+    /// Renders the indicators, wrapping them over as many lines as needed. Returns the amount of
+    /// lines used.
+    fn render_indicators(&self, state: Option<u64>, area: Rect, buf: &mut Buffer) -> u16 {
+        let mut x = area.x;
+        let mut y = area.y;
+        for (bit, label) in self.buttons() {
+            let width = label.chars().count() as u16 + 2;
+            if x > area.x && x + width > area.right() {
+                x = area.x;
+                y += 1;
+            }
+            if y >= area.bottom() {
+                break;
+            }
+
+            let style = match state.map(|state| state & (1 << bit) != 0) {
+                Some(true) => self.theme.status_accent,
+                Some(false) => self.theme.status_bar,
+                None => self.theme.muted,
+            };
+            let (next, _) = buf.set_stringn(
+                x,
+                y,
+                format!(" {label} "),
+                (area.right() - x) as usize,
+                style,
+            );
+            x = next + 1;
+        }
+
+        y + 1 - area.y
+    }
+
+    /// Renders one line per button with its state on the previous frames, oldest on the left.
+    fn render_history(&self, back: usize, area: Rect, buf: &mut Buffer) {
+        let label_width = self
+            .labels
+            .iter()
+            .map(|label| label.chars().count())
+            .max()
+            .unwrap_or(0) as u16;
+        let strip_x = area.x + label_width + 1;
+        if strip_x >= area.right() {
+            return;
+        }
+
+        // keep the cursor in the middle of the strip unless following the latest frame
+        let columns = (area.right() - strip_x) as usize;
+        let newest = back.saturating_sub(columns / 2);
+
+        for ((bit, label), y) in self.buttons().zip(area.top()..area.bottom()) {
+            buf.set_stringn(area.x, y, label, label_width as usize, self.theme.header);
+
+            for column in 0..columns {
+                let frame_back = newest + (columns - 1 - column);
+                let pressed = self
+                    .history
+                    .get_back(frame_back)
+                    .map(|state| state & (1 << bit) != 0);
+                let (symbol, style) = match pressed {
+                    Some(true) => ("█", self.theme.ascii),
+                    Some(false) => ("·", self.theme.muted),
+                    None => (" ", Style::default()),
+                };
+                let style = if frame_back == back {
+                    style.patch(self.theme.current)
+                } else {
+                    style
+                };
+
+                buf.set_string(strip_x + column as u16, y, symbol, style);
+            }
+        }
+    }
+}
+
+impl<'a> StatefulWidget for InputStateView<'a> {
+    type State = InputStateViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = self.wrap_in_block(area, buf);
+        if area.height == 0 || area.width == 0 {
+            return;
+        }
+
+        // update state
+        state.len = self.history.len();
+        state.back = state.back.min(state.len.saturating_sub(1));
+
+        let current = self.history.get_back(state.back);
+        let frame = self
+            .history
+            .latest_frame()
+            .map(|latest| latest.saturating_sub(state.back as u64));
+
+        // render!
+        let info = match frame {
+            Some(frame) => format!("frame {frame} ({:#X})", current.unwrap_or(0)),
+            None => "no input".to_owned(),
+        };
+        buf.set_stringn(area.x, area.y, info, area.width as usize, self.theme.info);
+
+        let indicators = Rect {
+            y: area.y + 1,
+            height: area.height - 1,
+            ..area
+        };
+        let used = self.render_indicators(current, indicators, buf);
+
+        let history = Rect {
+            y: indicators.y + used + 1,
+            height: indicators.height.saturating_sub(used + 1),
+            ..area
+        };
+        self.render_history(state.back, history, buf);
+    }
+}
@@ -1,30 +1,155 @@
-use crate::Address;
+use crate::{
+    dialog::{DialogResult, ListPicker, ListPickerState},
+    error::{render_error, Error},
+    execution::ExecutionControl,
+    i18n::{self, strings},
+    keymap::Action,
+    memory_view::{hex_address, render_centered},
+    symbol::SymbolProvider,
+    Address, Keymap, Theme,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
     prelude::{Buffer, Rect, *},
-    widgets::{Block, Borders, Row, StatefulWidget, Table, Widget},
+    widgets::{Block, Borders, StatefulWidget, Widget},
 };
-use std::borrow::Cow;
+use std::{borrow::Cow, ops::Range};
 
 pub trait InstructionDisplay {
     fn instruction_display(&self) -> Line;
 }
 
+/// How an instruction affects control flow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    /// Execution continues with the next instruction.
+    Continue,
+    /// Unconditional jump to a known target.
+    Jump(Address),
+    /// Conditional branch to a known target, falling through otherwise.
+    Branch(Address),
+    /// Subroutine call to a known target. Execution resumes after it.
+    Call(Address),
+    /// Jump to a target that isn't known statically.
+    IndirectJump,
+    /// Return from a subroutine.
+    Return,
+    /// Execution doesn't continue (halt, invalid instruction...).
+    Stop,
+}
+
+impl Flow {
+    /// Whether execution may continue with the next instruction.
+    pub fn falls_through(self) -> bool {
+        matches!(self, Self::Continue | Self::Branch(_) | Self::Call(_))
+    }
+
+    /// Whether this instruction ends a basic block.
+    pub fn ends_block(self) -> bool {
+        !matches!(self, Self::Continue | Self::Call(_))
+    }
+}
+
+pub trait InstructionFlow {
+    /// Classifies the instruction, located at `address`, by its effect on control flow.
+    fn flow(&self, address: Address) -> Flow;
+}
+
+/// Size of an instruction, in bytes. Instructions are fixed-width: rows of an
+/// [`InstructionView`] are this far apart, and so are the instructions an
+/// [`InstructionProvider`] reads.
+pub const INSTRUCTION_SIZE: Address = 4;
+
 pub trait InstructionProvider<I> {
-    /// Reads instructions starting from `pointer` into the buffer.
+    /// Reads instructions starting from `pointer` into the buffer, [`INSTRUCTION_SIZE`] bytes
+    /// apart.
     fn read_to_buf(&self, pointer: Address, buf: &mut [Option<I>]);
+
+    /// Whether the instruction at `address` might be outdated, e.g. while it is fetched again in
+    /// the background. Stale instructions are drawn in a subtle style.
+    fn is_stale(&self, _address: Address) -> bool {
+        false
+    }
+}
+
+/// A line of pseudo-code, lifted from the instructions in `range`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecompiledLine {
+    pub range: Range<Address>,
+    pub text: String,
+}
+
+impl DecompiledLine {
+    pub fn new(range: Range<Address>, text: impl Into<String>) -> Self {
+        Self {
+            range,
+            text: text.into(),
+        }
+    }
+}
+
+/// A lifter of instructions to pseudo-code, e.g. pseudo-C, shown next to them.
+pub trait Decompiler {
+    /// Returns the lines of pseudo-code lifted from instructions overlapping `range`, sorted by
+    /// the start of their ranges.
+    fn decompile(&self, range: Range<Address>) -> Vec<DecompiledLine>;
+}
+
+/// Cross-references between code and data, e.g. from a prior analysis of the program.
+pub trait XrefProvider {
+    /// Returns the addresses of the instructions referencing `address`, e.g. calling the function
+    /// or writing the variable located there, sorted.
+    fn xrefs_to(&self, address: Address) -> Vec<Address>;
 }
 
+/// Width of the call depth column, in cells. Deeper calls are cut off.
+const CALL_DEPTH_WIDTH: u16 = 6;
+
 struct InstructionViewLayout {
     address_column: Rect,
+    call_depth_column: Option<Rect>,
     instruction_table: Rect,
+    decompiler_column: Option<Rect>,
 }
 
+/// State of an [`InstructionView`]. With the `serde` feature, only the pointer is
+/// (de)serialized: decoded instructions are read again on the next render, so the state is
+/// (de)serializable whatever the instruction type.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct InstructionViewState<I> {
     /// The memory address being pointed at.
     pub pointer: Address,
 
+    #[cfg_attr(feature = "serde", serde(skip))]
     beggining_address: Address,
+    #[cfg_attr(feature = "serde", serde(skip))]
     instruction_buffer: Vec<Option<I>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    label: Option<LabelInput>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    xrefs: Option<XrefPicker>,
+}
+
+/// The name being typed for the label of an instruction.
+#[derive(Debug)]
+struct LabelInput {
+    address: Address,
+    text: String,
+    /// Whether the instruction already had a label, which is renamed rather than added.
+    renaming: bool,
+    /// Why the last submitted name was rejected.
+    error: Option<String>,
+}
+
+/// The references to an address, one of which can be jumped to.
+struct XrefPicker {
+    target: Address,
+    xrefs: Vec<Address>,
+    picker: ListPickerState,
 }
 
 impl<I> InstructionViewState<I> {
@@ -33,7 +158,143 @@ impl<I> InstructionViewState<I> {
             pointer,
             beggining_address: 0,
             instruction_buffer: Vec::new(),
+            label: None,
+            xrefs: None,
+        }
+    }
+
+    /// Opens a popup listing the references to the address under the pointer. Picking one moves
+    /// the pointer there.
+    pub fn open_xrefs(&mut self, xrefs: &dyn XrefProvider) {
+        self.xrefs = Some(XrefPicker {
+            target: self.pointer,
+            xrefs: xrefs.xrefs_to(self.pointer),
+            picker: ListPickerState::new(),
+        });
+    }
+
+    pub fn is_xrefs_open(&self) -> bool {
+        self.xrefs.is_some()
+    }
+
+    /// Opens the label input on the instruction under the pointer, filled with its current label
+    /// if it has one. The input is shown in place of the instruction.
+    pub fn open_label(&mut self, symbols: &dyn SymbolProvider) {
+        let symbol = symbols.symbol_at(self.pointer);
+        self.label = Some(LabelInput {
+            address: self.pointer,
+            renaming: symbol.is_some(),
+            text: symbol.map(|symbol| symbol.name).unwrap_or_default(),
+            error: None,
+        });
+    }
+
+    pub fn is_label_open(&self) -> bool {
+        self.label.is_some()
+    }
+
+    /// Handles a key event with the [standard keymap](Keymap::standard).
+    pub fn handle_key(
+        &mut self,
+        key: KeyEvent,
+        symbols: Option<&mut dyn SymbolProvider>,
+        xrefs: Option<&dyn XrefProvider>,
+    ) -> bool {
+        self.handle_key_with(key, Keymap::standard(), symbols, xrefs)
+    }
+
+    /// Handles a key event, translated to an action by `keymap`. [`Action::Rename`] opens the
+    /// [label input](Self::open_label) if `symbols` is [mutable](SymbolProvider::is_mutable),
+    /// which then takes every key until it is submitted or cancelled. Submitted names rename the
+    /// label of the instruction, or add one, through `symbols`: the views reading from the same
+    /// provider show them right away. [`Action::Xrefs`] opens the [xref picker](Self::open_xrefs)
+    /// if `xrefs` is given, which likewise takes every key until it is closed. Returns whether the
+    /// event was consumed.
+    pub fn handle_key_with(
+        &mut self,
+        key: KeyEvent,
+        keymap: &Keymap,
+        symbols: Option<&mut dyn SymbolProvider>,
+        xrefs: Option<&dyn XrefProvider>,
+    ) -> bool {
+        if key.kind == KeyEventKind::Release {
+            return false;
+        }
+
+        if let Some(picker) = &mut self.xrefs {
+            picker.picker.handle_key(key);
+            match picker.picker.poll() {
+                Some(DialogResult::Submitted(index)) => {
+                    self.pointer = picker.xrefs[index];
+                    self.xrefs = None;
+                }
+                Some(DialogResult::Cancelled) => self.xrefs = None,
+                None => (),
+            }
+
+            return true;
         }
+
+        let symbols = symbols.filter(|symbols| symbols.is_mutable());
+        let Some(input) = &mut self.label else {
+            match (keymap.action(key), symbols, xrefs) {
+                (Some(Action::Rename), Some(symbols), _) => self.open_label(symbols),
+                (Some(Action::Xrefs), _, Some(xrefs)) => self.open_xrefs(xrefs),
+                _ => return false,
+            }
+
+            return true;
+        };
+
+        let Some(symbols) = symbols else {
+            if key.code == KeyCode::Esc {
+                self.label = None;
+            }
+
+            return true;
+        };
+
+        match key.code {
+            KeyCode::Esc => self.label = None,
+            KeyCode::Enter => {
+                let name = input.text.trim();
+                let taken = symbols
+                    .lookup(name)
+                    .filter(|&address| address != input.address);
+                let result = if name.is_empty() {
+                    Err(strings().no_name.to_string())
+                } else if let Some(address) = taken {
+                    Err(i18n::fill(
+                        &strings().name_taken,
+                        &[&name, &format!("{address:08X}")],
+                    ))
+                } else if input.renaming {
+                    symbols
+                        .rename(input.address, name)
+                        .map_err(|error| format!("{error:#}"))
+                } else {
+                    symbols
+                        .add_label(input.address, name)
+                        .map_err(|error| format!("{error:#}"))
+                };
+
+                match result {
+                    Ok(()) => self.label = None,
+                    Err(error) => input.error = Some(error),
+                }
+            }
+            KeyCode::Backspace => {
+                input.text.pop();
+                input.error = None;
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                input.text.push(c);
+                input.error = None;
+            }
+            _ => (),
+        }
+
+        true
     }
 }
 
@@ -41,8 +302,22 @@ pub struct InstructionView<'a, I> {
     /// The memory provider.
     instruction_provider: &'a dyn InstructionProvider<I>,
 
+    /// Source of breakpoints and of the program counter.
+    execution: Option<&'a dyn ExecutionControl>,
+
+    /// Source of the pseudo-code shown next to the instructions.
+    decompiler: Option<&'a dyn Decompiler>,
+
+    /// Source of the labels shown before the instructions.
+    symbols: Option<&'a dyn SymbolProvider>,
+
+    /// Classifies instructions for the call depth column, if shown.
+    call_depth: Option<fn(&I, Address) -> Flow>,
+
     /// Block to draw inside.
     block: Option<Block<'a>>,
+
+    theme: Theme,
 }
 
 impl<'a, I> InstructionView<'a, I>
@@ -52,7 +327,51 @@ where
     pub fn new(instruction_provider: &'a dyn InstructionProvider<I>) -> Self {
         Self {
             instruction_provider,
+            execution: None,
+            decompiler: None,
+            symbols: None,
+            call_depth: None,
             block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    /// Marks breakpoints and the program counter of the given target.
+    pub fn execution(self, execution: &'a dyn ExecutionControl) -> Self {
+        Self {
+            execution: Some(execution),
+            ..self
+        }
+    }
+
+    /// Shows the pseudo-code of the decompiler in a column right of the instructions. Each line is
+    /// shown on the row of the first instruction it was lifted from, and the rows of the others
+    /// are marked as belonging to it.
+    pub fn decompiler(self, decompiler: &'a dyn Decompiler) -> Self {
+        Self {
+            decompiler: Some(decompiler),
+            ..self
+        }
+    }
+
+    /// Shows the label of each instruction that has one before it, as `name:`.
+    pub fn symbols(self, symbols: &'a dyn SymbolProvider) -> Self {
+        Self {
+            symbols: Some(symbols),
+            ..self
+        }
+    }
+
+    /// Shows a column left of the instructions tracking how deeply calls nest within the visible
+    /// rows, read top to bottom as if executed in order: a call opens a level, which its return
+    /// closes. The shallowest row is at depth 0.
+    pub fn call_depth(self) -> Self
+    where
+        I: InstructionFlow,
+    {
+        Self {
+            call_depth: Some(I::flow),
+            ..self
         }
     }
 
@@ -63,6 +382,13 @@ where
         }
     }
 
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
     fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
         if let Some(block) = self.block.take() {
             let inner_area = block.inner(area);
@@ -87,11 +413,34 @@ where
             .split(area);
 
         let address_column = chunks[0];
-        let instruction_table = chunks[2];
+        let (call_depth_column, instructions) = match self.call_depth {
+            Some(_) => {
+                let chunks = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints(
+                        [Constraint::Length(CALL_DEPTH_WIDTH + 1), Constraint::Min(0)].as_ref(),
+                    )
+                    .split(chunks[2]);
+                (Some(chunks[0]), chunks[1])
+            }
+            None => (None, chunks[2]),
+        };
+        let (instruction_table, decompiler_column) = match self.decompiler {
+            Some(_) => {
+                let chunks = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+                    .split(instructions);
+                (chunks[0], Some(chunks[1]))
+            }
+            None => (instructions, None),
+        };
 
         InstructionViewLayout {
             address_column,
+            call_depth_column,
             instruction_table,
+            decompiler_column,
         }
     }
 
@@ -101,79 +450,392 @@ where
         buf: &mut Buffer,
         state: &InstructionViewState<I>,
     ) {
-        let addresses = (0..area.height)
-            .map(|index| {
-                state
-                    .beggining_address
-                    .checked_add((std::mem::size_of::<Address>() * index as usize) as Address)
-            })
-            .map(|addr| {
-                let mut text = Text::from(
-                    addr.map(|x| (Cow::from(format!("{x:08X}"))))
-                        .unwrap_or(Cow::from("--------")),
-                );
-                text.lines[0].alignment = Some(Alignment::Center);
-                Row::new([text]).style(Style::default().light_magenta())
-            });
-
         let block = Block::new().borders(Borders::RIGHT);
         let inner_area = block.inner(area);
         block.render(area, buf);
 
-        let column_table = Table::new(addresses).widths(&[Constraint::Percentage(100)]);
-        Widget::render(column_table, inner_area, buf);
+        let mut digits = [0; 8];
+        for index in 0..inner_area.height {
+            let address = state
+                .beggining_address
+                .checked_add(index as Address * INSTRUCTION_SIZE);
+            let text = match address {
+                Some(address) => hex_address(address, &mut digits),
+                None => "--------",
+            };
+
+            let row = Rect::new(inner_area.x, inner_area.y + index, inner_area.width, 1);
+            render_centered(buf, row, text, self.theme.address);
+        }
     }
 
     fn render_instruction_table(
         &mut self,
         area: Rect,
         buf: &mut Buffer,
-        state: &mut InstructionViewState<I>,
+        state: &InstructionViewState<I>,
     ) {
-        let mut instructions = Vec::new();
+        let (breakpoints, program_counter) = match self.execution {
+            Some(execution) => (execution.breakpoints(), execution.program_counter()),
+            None => (Vec::new(), None),
+        };
+
         let mut current = state.beggining_address;
-        for instruction in &state.instruction_buffer {
+        let rows = state.instruction_buffer.iter().take(area.height as usize);
+        for (index, instruction) in rows.enumerate() {
+            let row = Rect::new(area.x, area.y + index as u16, area.width, 1);
+            let address = current;
+            current = current.wrapping_add(INSTRUCTION_SIZE);
+
             let Some(instruction) = instruction else {
-                instructions.push(Row::new(["--"]));
+                buf.set_stringn(row.x, row.y, "--", row.width as usize, Style::default());
                 continue;
             };
 
-            let prefix = Line::from(if current == state.pointer { ">" } else { " " });
-            current += std::mem::size_of::<I>() as u32;
+            if program_counter == Some(address) {
+                buf.set_style(row, self.theme.current);
+            }
+
+            if self.instruction_provider.is_stale(address) {
+                buf.set_style(row, self.theme.stale);
+            }
+
+            let (breakpoint, breakpoint_style) = if breakpoints.contains(&address) {
+                ("●", self.theme.breakpoint)
+            } else {
+                (" ", Style::default())
+            };
+            let pointer = if address == state.pointer { ">" } else { " " };
+            let prefix = Line::from(vec![
+                Span::styled(breakpoint, breakpoint_style),
+                Span::raw(pointer),
+            ]);
+            buf.set_line(row.x, row.y, &prefix, row.width);
+
+            // the prefix takes two columns, followed by a space
+            let mut offset = 3.min(row.width);
+            if let Some(input) = state
+                .label
+                .as_ref()
+                .filter(|input| input.address == address)
+            {
+                self.render_label_input(row, offset, buf, input);
+                continue;
+            }
+
+            if let Some(symbol) = self.symbols.and_then(|symbols| symbols.symbol_at(address)) {
+                let (x, _) = buf.set_stringn(
+                    row.x + offset,
+                    row.y,
+                    format!("{}: ", symbol.name),
+                    (row.width - offset) as usize,
+                    self.theme.title,
+                );
+                offset = x - row.x;
+            }
+
+            buf.set_line(
+                row.x + offset,
+                row.y,
+                &instruction.instruction_display(),
+                row.width - offset,
+            );
+        }
+    }
+
+    /// Describes an address by the symbol containing it, if any, e.g. `main+0x1C`.
+    fn describe(&self, address: Address) -> Option<String> {
+        let symbol = self
+            .symbols?
+            .symbols()
+            .into_iter()
+            .filter(|symbol| symbol.address <= address)
+            .max_by_key(|symbol| symbol.address)?;
+
+        Some(match address - symbol.address {
+            0 => symbol.name,
+            offset => format!("{}+0x{offset:X}", symbol.name),
+        })
+    }
+
+    fn render_xref_picker(&self, area: Rect, buf: &mut Buffer, picker: &mut XrefPicker) {
+        let target = match self.describe(picker.target) {
+            Some(name) => format!("{:08X} ({name})", picker.target),
+            None => format!("{:08X}", picker.target),
+        };
+        let strings = strings();
+        let title = match picker.xrefs.len() {
+            0 => i18n::fill(&strings.no_xrefs, &[&target]),
+            1 => i18n::fill(&strings.one_xref, &[&target]),
+            len => i18n::fill(&strings.xrefs, &[&len, &target]),
+        };
+        let title = format!(" {title} ");
 
-            let instr_text = instruction.instruction_display();
-            instructions.push(Row::new([prefix, instr_text]));
+        let items: Vec<Cow<str>> = picker
+            .xrefs
+            .iter()
+            .map(|&address| match self.describe(address) {
+                Some(name) => Cow::Owned(format!("{address:08X}  {name}")),
+                None => Cow::Owned(format!("{address:08X}")),
+            })
+            .collect();
+        let width = items
+            .iter()
+            .map(|item| item.len())
+            .chain([title.len()])
+            .max()
+            .unwrap_or(0)
+            + 4;
+        ListPicker::new(title, &items)
+            .width(width.min(u16::MAX as usize) as u16)
+            .theme(&self.theme)
+            .render(area, buf, &mut picker.picker);
+    }
+
+    fn render_label_input(&self, row: Rect, offset: u16, buf: &mut Buffer, input: &LabelInput) {
+        let strings = strings();
+        let prompt = if input.renaming {
+            format!("{}: ", strings.rename)
+        } else {
+            format!("{}: ", strings.label)
+        };
+        let mut spans = vec![
+            Span::styled(prompt, self.theme.title),
+            Span::styled(input.text.as_str(), self.theme.info),
+            Span::styled(" ", self.theme.cursor),
+        ];
+        if let Some(error) = &input.error {
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(error.as_str(), self.theme.alert));
         }
 
-        let constraint = [Constraint::Length(1), Constraint::Length(area.width)];
-        let instruction_table = Table::new(instructions).widths(&constraint);
-        Widget::render(instruction_table, area, buf);
+        buf.set_line(
+            row.x + offset,
+            row.y,
+            &Line::from(spans),
+            row.width - offset,
+        );
+    }
+
+    fn render_call_depth_column(
+        &mut self,
+        area: Rect,
+        buf: &mut Buffer,
+        state: &InstructionViewState<I>,
+        flow: fn(&I, Address) -> Flow,
+    ) {
+        let rows = state.instruction_buffer.iter().take(area.height as usize);
+        let flows: Vec<Flow> = rows
+            .enumerate()
+            .map(|(index, instruction)| {
+                let address = state
+                    .beggining_address
+                    .wrapping_add(index as Address * INSTRUCTION_SIZE);
+                instruction
+                    .as_ref()
+                    .map_or(Flow::Continue, |instruction| flow(instruction, address))
+            })
+            .collect();
+
+        // depth before each row, relative to the top one
+        let mut depths = Vec::with_capacity(flows.len());
+        let mut depth = 0i32;
+        for flow in &flows {
+            depths.push(depth);
+            match flow {
+                Flow::Call(_) => depth += 1,
+                Flow::Return => depth -= 1,
+                _ => (),
+            }
+        }
+
+        // returns of calls made above the window nest the rows above them deeper
+        let shallowest = depths.iter().copied().min().unwrap_or(0);
+        for (index, (flow, depth)) in flows.iter().zip(depths).enumerate() {
+            let depth = (depth - shallowest) as usize;
+            let meter = match flow {
+                Flow::Call(_) => "│".repeat(depth) + "┐",
+                Flow::Return if depth > 0 => "│".repeat(depth - 1) + "┘",
+                _ => "│".repeat(depth),
+            };
+
+            buf.set_stringn(
+                area.x,
+                area.y + index as u16,
+                &meter,
+                area.width.min(CALL_DEPTH_WIDTH) as usize,
+                self.theme.muted,
+            );
+        }
+    }
+
+    fn render_decompiler_column(
+        &mut self,
+        area: Rect,
+        buf: &mut Buffer,
+        state: &InstructionViewState<I>,
+        decompiler: &dyn Decompiler,
+    ) {
+        let block = Block::new().borders(Borders::LEFT);
+        let inner_area = block.inner(area);
+        block.render(area, buf);
+        if inner_area.width == 0 {
+            return;
+        }
+
+        let program_counter = self
+            .execution
+            .and_then(|execution| execution.program_counter());
+        let rows = state
+            .instruction_buffer
+            .len()
+            .min(inner_area.height as usize);
+        let start = state.beggining_address;
+        let end = start.saturating_add(rows as Address * INSTRUCTION_SIZE);
+        let lines = decompiler.decompile(start..end);
+
+        let mut text = String::new();
+        for index in 0..rows {
+            let Some(current) = start.checked_add(index as Address * INSTRUCTION_SIZE) else {
+                break;
+            };
+
+            let row = Rect::new(
+                inner_area.x,
+                inner_area.y + index as u16,
+                inner_area.width,
+                1,
+            );
+            if program_counter == Some(current) {
+                buf.set_style(row, self.theme.current);
+            }
+
+            // lines lifted from several instructions start on the row of the first one
+            text.clear();
+            for line in lines.iter().filter(|line| line.range.start == current) {
+                if !text.is_empty() {
+                    text.push(' ');
+                }
+                text.push_str(&line.text);
+            }
+
+            let continued = lines
+                .iter()
+                .any(|line| line.range.start < current && line.range.contains(&current));
+            if !text.is_empty() {
+                buf.set_stringn(
+                    row.x + 1,
+                    row.y,
+                    &text,
+                    row.width as usize - 1,
+                    self.theme.info,
+                );
+            } else if continued {
+                buf.set_stringn(
+                    row.x + 1,
+                    row.y,
+                    "│",
+                    row.width as usize - 1,
+                    self.theme.muted,
+                );
+            }
+        }
     }
 }
 
 impl<'a, I> StatefulWidget for InstructionView<'a, I>
 where
-    I: InstructionDisplay + Clone,
+    I: InstructionDisplay,
 {
     type State = InstructionViewState<I>;
 
     fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
         let area = self.wrap_in_block(area, buf);
         let layout = self.layout(area);
+        if layout.instruction_table.width == 0 {
+            let error = Error::AreaTooSmall {
+                width: area.width,
+                height: area.height,
+            };
+            render_error(&error, area, buf, &self.theme);
+            return;
+        }
 
         // update state
         state.beggining_address = state
             .pointer
-            .saturating_sub((layout.address_column.height / 2) as Address * 4);
+            .saturating_sub((layout.address_column.height / 2) as Address * INSTRUCTION_SIZE);
 
         let value_count = area.height as usize;
         state.instruction_buffer.clear();
-        state.instruction_buffer.resize(value_count, None);
+        state.instruction_buffer.resize_with(value_count, || None);
         self.instruction_provider
             .read_to_buf(state.beggining_address, &mut state.instruction_buffer);
 
         // render!
         self.render_address_column(layout.address_column, buf, state);
+        if let (Some(area), Some(flow)) = (layout.call_depth_column, self.call_depth) {
+            self.render_call_depth_column(area, buf, state, flow);
+        }
         self.render_instruction_table(layout.instruction_table, buf, state);
+        if let (Some(area), Some(decompiler)) = (layout.decompiler_column, self.decompiler) {
+            self.render_decompiler_column(area, buf, state, decompiler);
+        }
+
+        if let Some(picker) = &mut state.xrefs {
+            self.render_xref_picker(area, buf, picker);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::render_stateful_to_string;
+
+    /// A decoded instruction, larger than the instruction it was decoded from.
+    #[derive(Debug, Clone)]
+    struct Op(&'static str);
+
+    impl InstructionDisplay for Op {
+        fn instruction_display(&self) -> Line<'_> {
+            Line::from(self.0)
+        }
+    }
+
+    /// A program starting at 0, read from aligned pointers only.
+    struct Program(Vec<Op>);
+
+    impl InstructionProvider<Op> for Program {
+        fn read_to_buf(&self, pointer: Address, buf: &mut [Option<Op>]) {
+            let first = (pointer / INSTRUCTION_SIZE) as usize;
+            for (index, instruction) in buf.iter_mut().enumerate() {
+                *instruction = self.0.get(first + index).cloned();
+            }
+        }
+    }
+
+    #[test]
+    fn renders_instructions() {
+        let program = Program(vec![
+            Op("li r3, 0"),
+            Op("addi r3, r3, 1"),
+            Op("cmpwi r3, 10"),
+            Op("blt -8"),
+            Op("blr"),
+        ]);
+        let mut state = InstructionViewState::new(4);
+
+        let view = InstructionView::new(&program);
+        let text = render_stateful_to_string(view, &mut state, 40, 6);
+        let expected = [
+            " 00000000 │    li r3, 0",
+            " 00000004 │  > addi r3, r3, 1",
+            " 00000008 │    cmpwi r3, 10",
+            " 0000000C │    blt -8",
+            " 00000010 │    blr",
+            " 00000014 │ --",
+        ];
+        assert_eq!(text, expected.join("\n"));
     }
 }
@@ -0,0 +1,294 @@
+use crate::{keymap::Action, Address, Keymap, Theme};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, Row, StatefulWidget, Table, Widget},
+};
+use std::{collections::VecDeque, ops::Range};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessKind {
+    Read,
+    Write,
+}
+
+/// A single bus or port access.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IoAccess {
+    /// Timestamp or cycle count of the access.
+    pub timestamp: u64,
+    pub address: Address,
+    pub kind: AccessKind,
+    pub value: u64,
+    /// Size of the access, in bytes.
+    pub size: u8,
+}
+
+impl IoAccess {
+    /// Whether both accesses are identical except for their timestamp.
+    fn same_as(&self, other: &IoAccess) -> bool {
+        self.address == other.address
+            && self.kind == other.kind
+            && self.value == other.value
+            && self.size == other.size
+    }
+}
+
+/// A ring buffer of accesses. Once full, the oldest entries are dropped.
+#[derive(Debug, Clone)]
+pub struct IoLog {
+    entries: VecDeque<IoAccess>,
+    capacity: usize,
+}
+
+impl IoLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn push(&mut self, access: IoAccess) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+
+        self.entries.push_back(access);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &IoAccess> {
+        self.entries.iter()
+    }
+}
+
+pub struct IoLogViewState {
+    /// Whether the view sticks to the most recent access.
+    pub follow_tail: bool,
+    /// Only accesses inside of this range are shown.
+    pub filter: Option<Range<Address>>,
+    /// Whether runs of identical accesses are collapsed into a single row.
+    pub collapse: bool,
+
+    selected: usize,
+    page: usize,
+    len: usize,
+    selected_address: Option<Address>,
+}
+
+impl Default for IoLogViewState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IoLogViewState {
+    pub fn new() -> Self {
+        Self {
+            follow_tail: true,
+            filter: None,
+            collapse: true,
+            selected: 0,
+            page: 1,
+            len: 0,
+            selected_address: None,
+        }
+    }
+
+    /// Address of the selected access, as of the last render.
+    pub fn selected_address(&self) -> Option<Address> {
+        self.selected_address
+    }
+
+    fn select(&mut self, selected: usize) {
+        self.selected = selected.min(self.len.saturating_sub(1));
+        self.follow_tail = false;
+    }
+
+    /// Handles a key event with the [standard keymap](Keymap::standard).
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Address> {
+        self.handle_key_with(key, Keymap::standard())
+    }
+
+    /// Handles a key event, translated to an action by `keymap`. Returns the address of the
+    /// selected access when it is activated.
+    pub fn handle_key_with(&mut self, key: KeyEvent, keymap: &Keymap) -> Option<Address> {
+        if key.kind == KeyEventKind::Release {
+            return None;
+        }
+
+        match keymap.action(key) {
+            Some(Action::Up) => self.select(self.selected.saturating_sub(1)),
+            Some(Action::Down) => self.select(self.selected + 1),
+            Some(Action::PageUp) => self.select(self.selected.saturating_sub(self.page)),
+            Some(Action::PageDown) => self.select(self.selected + self.page),
+            Some(Action::Home) => self.select(0),
+            Some(Action::End | Action::Follow) => self.follow_tail = true,
+            Some(Action::Activate) => return self.selected_address,
+            // keys specific to this view, used unless bound to an action
+            None => match key.code {
+                KeyCode::Char('c') => self.collapse = !self.collapse,
+                KeyCode::Char('x') => self.filter = None,
+                KeyCode::Char('a') => {
+                    // filter by the address of the selected access
+                    self.filter = self
+                        .selected_address
+                        .map(|address| address..address.saturating_add(1));
+                }
+                _ => (),
+            },
+            _ => (),
+        }
+
+        None
+    }
+}
+
+/// A scrolling log of bus/port accesses.
+pub struct IoLogView<'a> {
+    /// The log being displayed.
+    log: &'a IoLog,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> IoLogView<'a> {
+    pub fn new(log: &'a IoLog) -> Self {
+        Self {
+            log,
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+}
+
+impl<'a> StatefulWidget for IoLogView<'a> {
+    type State = IoLogViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = self.wrap_in_block(area, buf);
+
+        // filter and collapse, keeping the amount of repetitions
+        let mut entries: Vec<(&IoAccess, usize)> = Vec::new();
+        let filtered = self.log.iter().filter(|access| match &state.filter {
+            Some(filter) => filter.contains(&access.address),
+            None => true,
+        });
+        for access in filtered {
+            match entries.last_mut() {
+                Some((last, count)) if state.collapse && last.same_as(access) => {
+                    *last = access;
+                    *count += 1;
+                }
+                _ => entries.push((access, 1)),
+            }
+        }
+
+        // update state
+        state.len = entries.len();
+        state.page = (area.height as usize).max(1);
+        state.selected = if state.follow_tail {
+            state.len.saturating_sub(1)
+        } else {
+            state.selected.min(state.len.saturating_sub(1))
+        };
+        state.selected_address = entries
+            .get(state.selected)
+            .map(|(access, _)| access.address);
+
+        let end = if state.follow_tail {
+            state.len
+        } else {
+            (state.selected + state.page / 2 + 1).clamp(state.page.min(state.len), state.len)
+        };
+        let start = end.saturating_sub(state.page);
+
+        let rows = entries[start..end]
+            .iter()
+            .enumerate()
+            .map(|(index, (access, count))| {
+                let (kind, kind_style) = match access.kind {
+                    AccessKind::Read => ("R", self.theme.info),
+                    AccessKind::Write => ("W", self.theme.modified),
+                };
+                let digits = access.size as usize * 2;
+                let repeat = if *count > 1 {
+                    Span::styled(format!("×{count}"), Style::default().light_yellow())
+                } else {
+                    Span::raw("")
+                };
+
+                let row = Row::new([
+                    Line::from(Span::styled(
+                        format!("{:>12}", access.timestamp),
+                        self.theme.muted,
+                    )),
+                    Line::from(Span::styled(kind, kind_style.bold())),
+                    Line::from(Span::styled(
+                        format!("{:08X}", access.address),
+                        self.theme.address,
+                    )),
+                    Line::from(Span::styled(
+                        format!("{:0digits$X}", access.value),
+                        Style::default().light_blue(),
+                    )),
+                    Line::from(repeat),
+                ]);
+
+                if start + index == state.selected && !state.follow_tail {
+                    row.style(self.theme.selection)
+                } else {
+                    row
+                }
+            });
+
+        let constraints = [
+            Constraint::Length(12),
+            Constraint::Length(1),
+            Constraint::Length(8),
+            Constraint::Length(16),
+            Constraint::Min(0),
+        ];
+        let table = Table::new(rows).widths(&constraints);
+        Widget::render(table, area, buf);
+    }
+}
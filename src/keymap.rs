@@ -0,0 +1,395 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::{collections::HashMap, fmt, str::FromStr, sync::OnceLock};
+
+/// Something a key can be bound to. Widgets handle the actions that make sense for them and
+/// ignore the others, which are left for the application (like switching tabs).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    /// Activate the selected item, e.g. jump to its address.
+    Activate,
+    /// Cancel or clear the current operation.
+    Cancel,
+    /// Toggle the selected item, e.g. expand or collapse it.
+    Toggle,
+    /// Stick to the most recent entry of a log.
+    Follow,
+    /// Cycle the sort order.
+    Sort,
+    /// Reverse the sort order.
+    Reverse,
+    ZoomIn,
+    ZoomOut,
+    Goto,
+    /// Rename the selected item, e.g. label the instruction under the pointer.
+    Rename,
+    /// List the references to the selected item, e.g. the callers of a function.
+    Xrefs,
+    Search,
+    SearchNext,
+    SearchPrevious,
+    /// Show or hide a panel.
+    TogglePanel,
+    /// Grow the focused pane.
+    Grow,
+    /// Shrink the focused pane.
+    Shrink,
+    /// Maximize the focused pane, or restore the others.
+    Maximize,
+    /// Move the focus to the next pane.
+    FocusNext,
+    /// Move the focus to the previous pane.
+    FocusPrevious,
+    NextTab,
+    PreviousTab,
+}
+
+/// A key along with its modifiers.
+///
+/// Parsed from and displayed as modifiers and a key joined by `+`, like `ctrl+g`, `shift+tab`,
+/// `pagedown` or `N`. Shift isn't part of chords of character keys: it's reflected by the case of
+/// the character instead. Likewise, `shift+tab` is the same as `backtab`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let code = match code {
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            code => code,
+        };
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+
+        Self { code, modifiers }
+    }
+
+    pub fn key(code: KeyCode) -> Self {
+        Self::new(code, KeyModifiers::NONE)
+    }
+
+    pub fn char(c: char) -> Self {
+        Self::key(KeyCode::Char(c))
+    }
+}
+
+impl From<KeyEvent> for KeyChord {
+    fn from(event: KeyEvent) -> Self {
+        Self::new(event.code, event.modifiers)
+    }
+}
+
+const NAMED_KEYS: &[(&str, KeyCode)] = &[
+    ("backspace", KeyCode::Backspace),
+    ("enter", KeyCode::Enter),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("pageup", KeyCode::PageUp),
+    ("pagedown", KeyCode::PageDown),
+    ("tab", KeyCode::Tab),
+    ("backtab", KeyCode::BackTab),
+    ("delete", KeyCode::Delete),
+    ("insert", KeyCode::Insert),
+    ("esc", KeyCode::Esc),
+    ("space", KeyCode::Char(' ')),
+    ("plus", KeyCode::Char('+')),
+];
+
+const MODIFIERS: &[(&str, KeyModifiers)] = &[
+    ("ctrl", KeyModifiers::CONTROL),
+    ("alt", KeyModifiers::ALT),
+    ("shift", KeyModifiers::SHIFT),
+];
+
+impl FromStr for KeyChord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts: Vec<&str> = s.split('+').collect();
+        // a lone `+` splits into two empty parts
+        let key = match parts.pop() {
+            Some("") if s.ends_with('+') => {
+                parts.pop();
+                "plus"
+            }
+            Some(key) => key,
+            None => return Err("empty key chord".to_owned()),
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for part in parts {
+            let (_, modifier) = MODIFIERS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(part))
+                .ok_or_else(|| format!("unknown modifier `{part}`"))?;
+            modifiers |= *modifier;
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            (Some(c), None) => KeyCode::Char(c),
+            _ => {
+                let named = NAMED_KEYS
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(key))
+                    .map(|(_, code)| *code);
+                let function = key
+                    .strip_prefix(['f', 'F'])
+                    .and_then(|number| number.parse().ok())
+                    .map(KeyCode::F);
+
+                named
+                    .or(function)
+                    .ok_or_else(|| format!("unknown key `{key}`"))?
+            }
+        };
+
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, modifier) in MODIFIERS {
+            if self.modifiers.contains(*modifier) {
+                write!(f, "{name}+")?;
+            }
+        }
+
+        if let Some((name, _)) = NAMED_KEYS.iter().find(|(_, code)| *code == self.code) {
+            return f.write_str(name);
+        }
+
+        match self.code {
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::F(number) => write!(f, "f{number}"),
+            code => write!(f, "{code:?}"),
+        }
+    }
+}
+
+/// Bindings of key chords to actions, used by the `handle_key_with` methods of the widgets'
+/// states.
+///
+/// With the `serde` feature, a keymap (de)serializes as a map of chords to actions, e.g.
+/// `{ "ctrl+g" = "goto", "w" = "up" }`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: HashMap<KeyChord, Action>,
+}
+
+impl Default for Keymap {
+    /// Arrow keys and their vim equivalents for navigation, `/`, `n` and `N` to search and `g` to
+    /// go to an address.
+    fn default() -> Self {
+        let mut keymap = Self::arrows();
+        for (c, action) in [
+            ('k', Action::Up),
+            ('j', Action::Down),
+            ('h', Action::Left),
+            ('l', Action::Right),
+        ] {
+            keymap.bind(KeyChord::char(c), action);
+        }
+
+        keymap
+    }
+}
+
+impl Keymap {
+    /// A keymap without any bindings.
+    pub fn new() -> Self {
+        Self {
+            bindings: HashMap::new(),
+        }
+    }
+
+    /// The default keymap, shared.
+    pub fn standard() -> &'static Self {
+        static STANDARD: OnceLock<Keymap> = OnceLock::new();
+        STANDARD.get_or_init(Self::default)
+    }
+
+    /// Like the default keymap, but without the vim navigation keys.
+    pub fn arrows() -> Self {
+        let mut keymap = Self::new();
+        let bindings = [
+            (KeyChord::key(KeyCode::Up), Action::Up),
+            (KeyChord::key(KeyCode::Down), Action::Down),
+            (KeyChord::key(KeyCode::Left), Action::Left),
+            (KeyChord::key(KeyCode::Right), Action::Right),
+            (KeyChord::key(KeyCode::PageUp), Action::PageUp),
+            (KeyChord::key(KeyCode::PageDown), Action::PageDown),
+            (KeyChord::key(KeyCode::Home), Action::Home),
+            (KeyChord::key(KeyCode::End), Action::End),
+            (KeyChord::key(KeyCode::Enter), Action::Activate),
+            (KeyChord::key(KeyCode::Esc), Action::Cancel),
+            (KeyChord::char(' '), Action::Toggle),
+            (KeyChord::char('f'), Action::Follow),
+            (KeyChord::char('s'), Action::Sort),
+            (KeyChord::char('r'), Action::Reverse),
+            (KeyChord::char('+'), Action::ZoomIn),
+            (KeyChord::char('='), Action::ZoomIn),
+            (KeyChord::char('-'), Action::ZoomOut),
+            (KeyChord::char('g'), Action::Goto),
+            (KeyChord::char(':'), Action::Rename),
+            (KeyChord::char('x'), Action::Xrefs),
+            (KeyChord::char('/'), Action::Search),
+            (KeyChord::char('n'), Action::SearchNext),
+            (KeyChord::char('N'), Action::SearchPrevious),
+            (
+                KeyChord::new(KeyCode::Char('b'), KeyModifiers::CONTROL),
+                Action::TogglePanel,
+            ),
+            (KeyChord::char('>'), Action::Grow),
+            (KeyChord::char('<'), Action::Shrink),
+            (KeyChord::char('Z'), Action::Maximize),
+            (KeyChord::key(KeyCode::Tab), Action::FocusNext),
+            (KeyChord::key(KeyCode::BackTab), Action::FocusPrevious),
+            (KeyChord::char(']'), Action::NextTab),
+            (KeyChord::char('['), Action::PreviousTab),
+        ];
+
+        for (chord, action) in bindings {
+            keymap.bind(chord, action);
+        }
+
+        keymap
+    }
+
+    /// Binds a chord to an action, replacing its previous binding.
+    pub fn bind(&mut self, chord: KeyChord, action: Action) {
+        self.bindings.insert(chord, action);
+    }
+
+    /// Removes the binding of a chord. Returns the action it was bound to.
+    pub fn unbind(&mut self, chord: KeyChord) -> Option<Action> {
+        self.bindings.remove(&chord)
+    }
+
+    /// Adds the bindings of `other`, replacing conflicting ones.
+    pub fn extend(&mut self, other: &Keymap) {
+        self.bindings.extend(
+            other
+                .bindings
+                .iter()
+                .map(|(chord, action)| (*chord, *action)),
+        );
+    }
+
+    /// The action bound to a key event, if any.
+    pub fn action(&self, key: KeyEvent) -> Option<Action> {
+        self.bindings.get(&KeyChord::from(key)).copied()
+    }
+
+    /// The chords bound to an action, sorted by their textual representation. Useful to show
+    /// bindings in a help overlay.
+    pub fn chords(&self, action: Action) -> Vec<KeyChord> {
+        let mut chords: Vec<_> = self
+            .bindings
+            .iter()
+            .filter(|(_, bound)| **bound == action)
+            .map(|(chord, _)| *chord)
+            .collect();
+        chords.sort_by_cached_key(|chord| chord.to_string());
+
+        chords
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Keymap {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut bindings: Vec<_> = self
+            .bindings
+            .iter()
+            .map(|(chord, action)| (chord.to_string(), action))
+            .collect();
+        bindings.sort_by(|a, b| a.0.cmp(&b.0));
+
+        serializer.collect_map(bindings)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Keymap {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bindings = HashMap::<String, Action>::deserialize(deserializer)?;
+
+        let mut keymap = Self::new();
+        for (chord, action) in bindings {
+            let chord = chord.parse().map_err(serde::de::Error::custom)?;
+            keymap.bind(chord, action);
+        }
+
+        Ok(keymap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chords_round_trip() {
+        for text in [
+            "g",
+            "N",
+            "ctrl+g",
+            "alt+enter",
+            "ctrl+alt+delete",
+            "backtab",
+            "pagedown",
+            "f5",
+            "shift+f5",
+            "space",
+            "plus",
+            "ctrl+plus",
+        ] {
+            let chord: KeyChord = text.parse().unwrap();
+            assert_eq!(chord.to_string(), text);
+        }
+    }
+
+    #[test]
+    fn parses_equivalent_chords() {
+        let parse = |text: &str| text.parse::<KeyChord>().unwrap();
+
+        assert_eq!(parse("shift+tab"), KeyChord::key(KeyCode::BackTab));
+        assert_eq!(parse("shift+n"), KeyChord::char('N'));
+        assert_eq!(parse("+"), KeyChord::char('+'));
+        assert_eq!(
+            parse("Ctrl++"),
+            KeyChord::new(KeyCode::Char('+'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(parse("PageUp"), KeyChord::key(KeyCode::PageUp));
+    }
+
+    #[test]
+    fn rejects_unknown_chords() {
+        for text in ["", "hyper+g", "ctrl+nope", "fx"] {
+            assert!(text.parse::<KeyChord>().is_err(), "{text}");
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use crate::{i18n::strings, memory_view::MemoryViewState, region_bar_view::RegionKind, Theme};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, Widget},
+};
+
+/// Something colored by the widgets that a [`LegendView`] can explain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LegendItem {
+    /// The gradient byte values are colored with, from `00` to `FF`.
+    Gradient,
+    /// The value under the cursor.
+    Cursor,
+    /// Values equal to the one under the cursor, while they are highlighted.
+    Occurrence,
+    /// Values modified but not committed yet.
+    Modified,
+    /// Values that might be outdated.
+    Stale,
+    /// Differences and invalid entries.
+    Alert,
+    /// The current position of execution.
+    Current,
+    /// Breakpoint markers.
+    Breakpoint,
+    /// The colors of the [kinds of regions](RegionKind), as drawn by a
+    /// [`RegionBarView`](crate::region_bar_view::RegionBarView).
+    Regions,
+}
+
+/// Explains what the colors of the other widgets mean, as of the theme they use, e.g. in a corner
+/// of the screen or in a help pane. Each [item](LegendItem) takes a row, except for
+/// [regions](LegendItem::Regions) which take one per kind.
+pub struct LegendView<'a> {
+    /// The items to explain, in order.
+    items: Vec<LegendItem>,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> LegendView<'a> {
+    pub fn new(items: impl IntoIterator<Item = LegendItem>) -> Self {
+        Self {
+            items: items.into_iter().collect(),
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    /// The items a [`MemoryView`](crate::memory_view::MemoryView) drawn with `state` currently
+    /// shows: the gradient, the cursor and modified values, along with occurrences while they are
+    /// highlighted and stale values if refreshes are throttled.
+    pub fn memory_view(state: &MemoryViewState) -> Self {
+        let mut items = vec![LegendItem::Gradient, LegendItem::Cursor];
+        if state.is_highlighting() {
+            items.push(LegendItem::Occurrence);
+        }
+        items.push(LegendItem::Modified);
+        if state.refresh_interval().is_some() {
+            items.push(LegendItem::Stale);
+        }
+
+        Self::new(items)
+    }
+
+    /// Adds an item after the others, unless it is already there.
+    pub fn item(mut self, item: LegendItem) -> Self {
+        if !self.items.contains(&item) {
+            self.items.push(item);
+        }
+
+        self
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+
+    /// The swatch and label of each row, or `None` for the row of the gradient.
+    fn rows(&self) -> Vec<Option<(Span<'static>, String)>> {
+        let theme = &self.theme;
+        let strings = strings();
+        let sample = |style: Style| Span::styled("00", style);
+
+        let mut rows = Vec::with_capacity(self.items.len());
+        for item in &self.items {
+            let (swatch, label) = match item {
+                LegendItem::Gradient => {
+                    rows.push(None);
+                    continue;
+                }
+                LegendItem::Cursor => (
+                    sample(theme.byte(0).patch(theme.cursor)),
+                    &strings.legend_cursor,
+                ),
+                LegendItem::Occurrence => (
+                    sample(theme.byte(0).patch(theme.occurrence)),
+                    &strings.legend_occurrence,
+                ),
+                LegendItem::Modified => (sample(theme.modified), &strings.legend_modified),
+                LegendItem::Stale => (
+                    sample(theme.byte(0).patch(theme.stale)),
+                    &strings.legend_stale,
+                ),
+                LegendItem::Alert => (Span::styled("!!", theme.alert), &strings.legend_alert),
+                LegendItem::Current => (Span::styled("  ", theme.current), &strings.legend_current),
+                LegendItem::Breakpoint => (
+                    Span::styled("●", theme.breakpoint),
+                    &strings.legend_breakpoint,
+                ),
+                LegendItem::Regions => {
+                    rows.extend(RegionKind::ALL.into_iter().map(|kind| {
+                        Some((Span::styled(kind.symbol(), kind.style(theme)), kind.label()))
+                    }));
+                    continue;
+                }
+            };
+            rows.push(Some((swatch, label.to_string())));
+        }
+
+        rows
+    }
+
+    /// Draws the gradient as a bar between the lowest and highest values.
+    fn render_gradient(&self, area: Rect, buf: &mut Buffer) {
+        buf.set_stringn(
+            area.x,
+            area.y,
+            "00",
+            area.width as usize,
+            self.theme.byte(0x00),
+        );
+        if area.width < 6 {
+            return;
+        }
+
+        let bar = area.width - 6;
+        for x in 0..bar {
+            let value = (x as u32 * 255 / (bar - 1).max(1) as u32) as u8;
+            buf.set_string(area.x + 3 + x, area.y, "█", self.theme.byte(value));
+        }
+        buf.set_string(area.right() - 2, area.y, "FF", self.theme.byte(0xFF));
+    }
+}
+
+impl<'a> Widget for LegendView<'a> {
+    fn render(mut self, area: Rect, buf: &mut Buffer) {
+        let area = self.wrap_in_block(area, buf);
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        let rows = self.rows();
+        let swatch_width = rows
+            .iter()
+            .flatten()
+            .map(|(swatch, _)| swatch.width())
+            .max()
+            .unwrap_or(0) as u16;
+
+        for (y, row) in (area.top()..area.bottom()).zip(rows) {
+            let row_area = Rect {
+                y,
+                height: 1,
+                ..area
+            };
+            let Some((swatch, label)) = row else {
+                self.render_gradient(row_area, buf);
+                continue;
+            };
+
+            buf.set_span(area.x, y, &swatch, area.width);
+            let x = area.x + swatch_width + 1;
+            if x < area.right() {
+                buf.set_stringn(x, y, &label, (area.right() - x) as usize, Style::default());
+            }
+        }
+    }
+}
@@ -1,5 +1,73 @@
+pub mod analysis;
+pub mod cfg_view;
+pub mod coverage_view;
+pub mod dialog;
+pub mod error;
+pub mod execution;
+pub mod focus;
+pub mod heap_view;
+pub mod help_overlay;
+pub mod hex_diff_view;
+pub mod i18n;
+pub mod input_state_view;
 pub mod instruction_view;
+pub mod io_log_view;
+pub mod keymap;
+pub mod legend_view;
+pub mod mem_stats_view;
+pub mod memory_map_view;
 pub mod memory_view;
+pub mod mmio_view;
+pub mod palette_view;
+pub mod pane;
+pub mod property_view;
+pub mod provider;
+pub mod recording;
+pub mod region_bar_view;
+pub mod register_view;
+pub mod search;
+pub mod search_bar;
+pub mod session;
+pub mod status_bar;
+pub mod strings_view;
+pub mod struct_view;
+pub mod symbol;
+pub mod symbol_list_view;
 pub mod tabs;
+pub mod testing;
+pub mod text_view;
+pub mod theme;
+pub mod tile_view;
+pub mod timers_view;
+pub mod trace_view;
+pub mod vector_table_view;
+pub mod watch_hit_view;
+pub mod waveform_view;
+
+mod fuzzy;
+mod popup;
+
+pub use error::Error;
+pub use focus::FocusManager;
+pub use keymap::Keymap;
+pub use theme::Theme;
+
+#[cfg(feature = "app")]
+pub mod app;
+
+#[cfg(feature = "control")]
+pub mod control;
+
+#[cfg(feature = "graphics")]
+pub mod graphics;
+
+#[cfg(feature = "remote")]
+pub mod remote;
+
+#[cfg(feature = "scripting")]
+pub mod scripting;
+
+#[cfg(feature = "svd")]
+pub mod svd;
 
 type Address = u32;
@@ -0,0 +1,159 @@
+use crate::{
+    i18n::{self, strings},
+    Theme,
+};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, Sparkline, Widget},
+};
+use std::borrow::Cow;
+
+/// What a line next to the plot shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Label,
+    Max,
+    Now,
+    Min,
+}
+
+/// A compact plot of a caller-fed time series (bytes changed per frame, heap usage, DMA
+/// transfers...) with min/max labels. Only the most recent samples that fit are shown.
+pub struct MemStatsView<'a> {
+    /// The samples, oldest first.
+    data: &'a [u64],
+
+    /// Label shown before the plot.
+    label: Option<Cow<'a, str>>,
+
+    /// Style of the plot, the theme's [`ascii`](Theme::ascii) one if unset.
+    style: Option<Style>,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> MemStatsView<'a> {
+    pub fn new(data: &'a [u64]) -> Self {
+        Self {
+            data,
+            label: None,
+            style: None,
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn label(self, label: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            label: Some(label.into()),
+            ..self
+        }
+    }
+
+    pub fn style(self, style: Style) -> Self {
+        Self {
+            style: Some(style),
+            ..self
+        }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+}
+
+impl<'a> Widget for MemStatsView<'a> {
+    fn render(mut self, area: Rect, buf: &mut Buffer) {
+        let area = self.wrap_in_block(area, buf);
+        if area.height == 0 || area.width == 0 {
+            return;
+        }
+
+        let min = self.data.iter().copied().min().unwrap_or(0);
+        let max = self.data.iter().copied().max().unwrap_or(0);
+        let last = self.data.last().copied();
+
+        let strings = strings();
+        let max_text = i18n::fill(&strings.stats_max, &[&max]);
+        let min_text = i18n::fill(&strings.stats_min, &[&min]);
+        let last_text = match last {
+            Some(last) => i18n::fill(&strings.stats_now, &[&last]),
+            None => i18n::fill(&strings.stats_now, &[&"--"]),
+        };
+        let label_width = [&max_text, &min_text, &last_text]
+            .iter()
+            .map(|text| text.chars().count())
+            .chain(self.label.as_ref().map(|label| label.chars().count()))
+            .max()
+            .unwrap_or(0) as u16
+            + 1;
+
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(label_width), Constraint::Min(1)].as_ref())
+            .split(area);
+        let labels = chunks[0];
+        let plot = chunks[1];
+
+        let mut lines = Vec::with_capacity(4);
+        if let Some(label) = self.label.take() {
+            lines.push((Role::Label, label, self.theme.header));
+        }
+        lines.push((Role::Max, max_text.into(), self.theme.info));
+        lines.push((Role::Now, last_text.into(), self.theme.address));
+        lines.push((Role::Min, min_text.into(), self.theme.info));
+
+        // with little room, prefer showing min/max over the rest
+        if (labels.height as usize) < lines.len() {
+            lines.retain(|(role, _, _)| matches!(role, Role::Max | Role::Min));
+        }
+
+        let count = lines.len();
+        for (index, (_, text, style)) in lines.into_iter().enumerate() {
+            // the minimum always sits at the bottom, next to the baseline
+            let y = if index + 1 == count {
+                labels.bottom() - 1
+            } else {
+                labels.y + index as u16
+            };
+            buf.set_stringn(labels.x, y, text, labels.width as usize, style);
+        }
+
+        // the sparkline multiplies values by its resolution, which overflows for huge ones
+        let resolution = plot.height as u64 * 8;
+        let scale = max / (u64::MAX / resolution.max(1)) + 1;
+        let visible = self.data.len().saturating_sub(plot.width as usize);
+        let data: Vec<_> = self.data[visible..]
+            .iter()
+            .map(|value| value / scale)
+            .collect();
+        let sparkline = Sparkline::default()
+            .data(&data)
+            .max((max / scale).max(1))
+            .style(self.style.unwrap_or(self.theme.ascii));
+        sparkline.render(plot, buf);
+    }
+}
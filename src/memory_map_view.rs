@@ -0,0 +1,219 @@
+use crate::{i18n::strings, keymap::Action, memory_view::Region, Address, Keymap, Theme};
+use crossterm::event::{KeyEvent, KeyEventKind};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, Row, StatefulWidget, Table, Widget},
+};
+
+/// Formats a size in bytes with a binary unit, e.g. `64K`.
+fn format_size(size: u64) -> String {
+    const UNITS: [&str; 5] = ["", "K", "M", "G", "T"];
+
+    let mut size = size;
+    let mut unit = 0;
+    while size >= 1024 && size & 1023 == 0 && unit + 1 < UNITS.len() {
+        size /= 1024;
+        unit += 1;
+    }
+
+    format!("{size}{}", UNITS[unit])
+}
+
+pub struct MemoryMapViewState {
+    selected: usize,
+    offset: usize,
+    page: usize,
+    len: usize,
+    selected_address: Option<Address>,
+}
+
+impl Default for MemoryMapViewState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryMapViewState {
+    pub fn new() -> Self {
+        Self {
+            selected: 0,
+            offset: 0,
+            page: 1,
+            len: 0,
+            selected_address: None,
+        }
+    }
+
+    /// Start of the selected region, as of the last render.
+    pub fn selected_address(&self) -> Option<Address> {
+        self.selected_address
+    }
+
+    /// Handles a key event with the [standard keymap](Keymap::standard).
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Address> {
+        self.handle_key_with(key, Keymap::standard())
+    }
+
+    /// Handles a key event, translated to an action by `keymap`. Returns the start of the selected
+    /// region when it is activated, so that the memory view can jump to it.
+    pub fn handle_key_with(&mut self, key: KeyEvent, keymap: &Keymap) -> Option<Address> {
+        if key.kind == KeyEventKind::Release {
+            return None;
+        }
+
+        let last = self.len.saturating_sub(1);
+        match keymap.action(key) {
+            Some(Action::Up) => self.selected = self.selected.saturating_sub(1),
+            Some(Action::Down) => self.selected = (self.selected + 1).min(last),
+            Some(Action::PageUp) => self.selected = self.selected.saturating_sub(self.page),
+            Some(Action::PageDown) => self.selected = (self.selected + self.page).min(last),
+            Some(Action::Home) => self.selected = 0,
+            Some(Action::End) => self.selected = last,
+            Some(Action::Activate) => return self.selected_address,
+            _ => (),
+        }
+
+        None
+    }
+}
+
+/// A list of mapped regions, e.g. from [`MemoryProvider::regions`].
+///
+/// [`MemoryProvider::regions`]: crate::memory_view::MemoryProvider::regions
+pub struct MemoryMapView<'a> {
+    /// The regions to list.
+    regions: &'a [Region],
+
+    /// Address to mark the containing region of, e.g. the memory view's pointer.
+    pointer: Option<Address>,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> MemoryMapView<'a> {
+    pub fn new(regions: &'a [Region]) -> Self {
+        Self {
+            regions,
+            pointer: None,
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn pointer(self, pointer: Address) -> Self {
+        Self {
+            pointer: Some(pointer),
+            ..self
+        }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+}
+
+impl<'a> StatefulWidget for MemoryMapView<'a> {
+    type State = MemoryMapViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = self.wrap_in_block(area, buf);
+        if area.height == 0 {
+            return;
+        }
+
+        // update state
+        let list_height = area.height as usize - 1;
+        state.len = self.regions.len();
+        state.selected = state.selected.min(state.len.saturating_sub(1));
+        state.page = list_height.max(1);
+        if state.selected < state.offset {
+            state.offset = state.selected;
+        } else if state.selected >= state.offset + state.page {
+            state.offset = state.selected + 1 - state.page;
+        }
+        state.selected_address = self.regions.get(state.selected).map(|region| region.start);
+
+        let strings = strings();
+        let header = Row::new([
+            &*strings.start,
+            &*strings.end,
+            &*strings.size,
+            &*strings.permissions,
+            &*strings.name,
+        ])
+        .style(self.theme.header);
+
+        let rows = self
+            .regions
+            .iter()
+            .enumerate()
+            .skip(state.offset)
+            .take(list_height)
+            .map(|(index, region)| {
+                let marker = match self.pointer {
+                    Some(pointer) if region.contains(pointer) => "▶",
+                    _ => " ",
+                };
+                let end = region.end().saturating_sub(1);
+
+                let row = Row::new([
+                    Line::from(vec![
+                        Span::styled(marker, Style::default().light_yellow()),
+                        Span::styled(format!("{:08X}", region.start), self.theme.address),
+                    ]),
+                    Line::from(Span::styled(format!("{end:08X}"), self.theme.address)),
+                    Line::from(Span::styled(
+                        format_size(region.size),
+                        Style::default().light_green(),
+                    )),
+                    Line::from(Span::styled(
+                        region.permissions.label(),
+                        Style::default().light_red(),
+                    )),
+                    Line::from(Span::styled(
+                        region.name.as_deref().unwrap_or(""),
+                        Style::default().light_blue(),
+                    )),
+                ]);
+
+                if index == state.selected {
+                    row.style(self.theme.selection)
+                } else {
+                    row
+                }
+            });
+
+        let constraints = [
+            Constraint::Length(9),
+            Constraint::Length(8),
+            Constraint::Length(6),
+            Constraint::Length(4),
+            Constraint::Min(1),
+        ];
+        let table = Table::new(rows).header(header).widths(&constraints);
+        Widget::render(table, area, buf);
+    }
+}
@@ -1,44 +1,343 @@
-use crate::Address;
-use itertools::Itertools;
+use crate::{
+    error::{render_error, Error},
+    i18n::{self, strings},
+    keymap::Action,
+    struct_view::{format_value, value_width, Field},
+    symbol::SymbolProvider,
+    Address, Keymap, Theme,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
     prelude::{Buffer, Rect, *},
-    widgets::{Block, Borders, Cell, Row, StatefulWidget, Table, Widget},
+    widgets::{block::Title, Block, Borders, Clear, StatefulWidget, Widget},
+};
+use std::{
+    fmt::Write,
+    time::{Duration, Instant},
 };
-use std::borrow::Cow;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Permissions {
+    pub read: bool,
+    pub write: bool,
+    pub execute: bool,
+}
+
+impl Permissions {
+    pub const READ_ONLY: Self = Self {
+        read: true,
+        write: false,
+        execute: false,
+    };
+
+    pub const READ_WRITE: Self = Self {
+        read: true,
+        write: true,
+        execute: false,
+    };
+
+    /// Permissions in the usual `rwx` notation.
+    pub fn label(self) -> String {
+        [
+            if self.read { 'r' } else { '-' },
+            if self.write { 'w' } else { '-' },
+            if self.execute { 'x' } else { '-' },
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+/// A mapped region of memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    pub start: Address,
+    /// Size of the region, in bytes.
+    pub size: u64,
+    pub permissions: Permissions,
+    pub name: Option<String>,
+}
+
+impl Region {
+    pub fn new(start: Address, size: u64) -> Self {
+        Self {
+            start,
+            size,
+            permissions: Permissions::READ_WRITE,
+            name: None,
+        }
+    }
+
+    pub fn permissions(self, permissions: Permissions) -> Self {
+        Self {
+            permissions,
+            ..self
+        }
+    }
+
+    pub fn name(self, name: impl Into<String>) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..self
+        }
+    }
+
+    /// Address right after the end of the region.
+    pub fn end(&self) -> u64 {
+        (self.start as u64).saturating_add(self.size)
+    }
+
+    pub fn contains(&self, address: Address) -> bool {
+        (self.start as u64..self.end()).contains(&(address as u64))
+    }
+}
 
 pub trait MemoryProvider {
     /// Reads values starting from `pointer` into the buffer.
     fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]);
+
+    /// Returns the regions of memory known to the provider, sorted by address. Empty if the
+    /// provider has no such information.
+    fn regions(&self) -> Vec<Region> {
+        Vec::new()
+    }
+
+    /// Whether the value at `address` has been modified but not committed yet. Modified values
+    /// are drawn in a distinct style.
+    fn is_modified(&self, _address: Address) -> bool {
+        false
+    }
+
+    /// Whether the value at `address` might be outdated, e.g. while it is fetched again in the
+    /// background. Stale values are drawn in a subtle style.
+    fn is_stale(&self, _address: Address) -> bool {
+        false
+    }
+
+    /// A counter that changes whenever the values or modification status of the provider
+    /// change, so that views can skip reading it again when it didn't. `None`, the default,
+    /// means values may change at any time.
+    fn generation(&self) -> Option<u64> {
+        None
+    }
+
+    /// Why the last reads failed, if they did, e.g. because the connection to the target was
+    /// lost. Views show it rather than just unmapped values.
+    fn error(&self) -> Option<Error> {
+        None
+    }
+}
+
+pub trait MemoryWriter {
+    /// Writes the values in the buffer starting at `pointer`. Returns whether all of them were
+    /// written.
+    fn write_from_buf(&mut self, pointer: Address, buf: &[u8]) -> bool;
+}
+
+/// Byte order used to interpret multi-byte values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Endianness {
+    #[default]
+    Little,
+    Big,
+}
+
+impl Endianness {
+    pub fn u16(self, bytes: [u8; 2]) -> u16 {
+        match self {
+            Self::Little => u16::from_le_bytes(bytes),
+            Self::Big => u16::from_be_bytes(bytes),
+        }
+    }
+
+    pub fn u32(self, bytes: [u8; 4]) -> u32 {
+        match self {
+            Self::Little => u32::from_le_bytes(bytes),
+            Self::Big => u32::from_be_bytes(bytes),
+        }
+    }
+
+    /// Name of the endianness, in English. Widgets show the one from their
+    /// [strings](crate::i18n) instead.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Little => "Little Endian",
+            Self::Big => "Big Endian",
+        }
+    }
+}
+
+/// What the address column shows once a base is marked, see [`MemoryViewState::mark_base`].
+/// Absolute addresses are always shown otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum AddressMode {
+    Absolute,
+    /// Signed offsets from the base, e.g. `FP-0x18`.
+    Relative,
+    /// Absolute addresses followed by offsets from the base.
+    #[default]
+    Both,
+}
+
+/// Checksum of the bytes of each row, shown in a column of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum RowChecksum {
+    /// Sum of the bytes, modulo 256.
+    Sum8,
+    /// CRC-8 with the polynomial `0x07`, as used by SMBus.
+    Crc8,
+}
+
+impl RowChecksum {
+    pub fn compute(self, bytes: &[u8]) -> u8 {
+        match self {
+            Self::Sum8 => bytes.iter().fold(0, |sum, byte| sum.wrapping_add(*byte)),
+            Self::Crc8 => bytes.iter().fold(0, |crc, byte| {
+                (0..8).fold(crc ^ byte, |crc, _| {
+                    if crc & 0x80 != 0 {
+                        (crc << 1) ^ 0x07
+                    } else {
+                        crc << 1
+                    }
+                })
+            }),
+        }
+    }
 }
 
 struct MemoryViewLayout {
     info_bar: Rect,
+    /// Labels of the fields above the records, in record mode.
+    header: Option<Rect>,
     address_column: Rect,
+    /// Width of the offsets from the base and whether absolute addresses are shown too, if
+    /// offsets are shown.
+    offsets: Option<(u16, bool)>,
     memory_table: Rect,
     ascii_table: Rect,
+    checksum_column: Option<Rect>,
 }
 
+/// State of a [`MemoryView`]. With the `serde` feature, only the pointer is (de)serialized: the
+/// rest is recomputed on the next render.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MemoryViewState {
     /// The memory address being pointed at.
     pub pointer: Address,
+    /// Whether values still visible after scrolling are kept instead of being read again, even
+    /// if the provider can't tell whether they changed. Meant for slow providers, together with
+    /// [`invalidate`](Self::invalidate) to refresh everything.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub incremental: bool,
 
+    #[cfg_attr(feature = "serde", serde(skip))]
     memory_buffer: Vec<Option<u8>>,
-    constraints_buffer: Vec<Constraint>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    text_buffer: String,
+    #[cfg_attr(feature = "serde", serde(skip))]
     beginning_bucket: Address,
+    #[cfg_attr(feature = "serde", serde(skip))]
     bytes_per_bucket: u16,
+    /// Start, length and provider generation of the values in `memory_buffer`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    last_read: Option<(Address, usize, Option<u64>)>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    goto: Option<GotoInput>,
+    /// Inputs that were jumped to, oldest first.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    goto_history: Vec<String>,
+    /// Label and address that offsets in the address column are relative to.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    base: Option<(String, Address)>,
+    /// Amount of ticks between reads of the whole provider, if limited.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    refresh_interval: Option<u32>,
+    /// Ticks since the whole provider was last read.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    ticks: u32,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    refreshed_at: Option<Instant>,
+    /// Address of the value the pointer follows.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    follow: Option<Address>,
+    /// Whether memory where the value under the pointer points is previewed.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    preview: bool,
+    /// Whether values equal to the one under the pointer are highlighted.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    highlight: bool,
+}
+
+/// Maximum amount of inputs kept in the goto history.
+const GOTO_HISTORY_LEN: usize = 64;
+
+/// The address being typed in the goto input.
+#[derive(Debug, Default)]
+struct GotoInput {
+    text: String,
+    /// Index of the history entry being shown, if browsing it.
+    history_index: Option<usize>,
+    /// Why the last submitted input couldn't be resolved.
+    error: Option<String>,
 }
 
 impl MemoryViewState {
     pub fn new(pointer: Address) -> Self {
         Self {
             pointer,
+            incremental: false,
             memory_buffer: Vec::new(),
-            constraints_buffer: Vec::new(),
+            text_buffer: String::new(),
             beginning_bucket: 0,
             bytes_per_bucket: 0,
+            last_read: None,
+            goto: None,
+            goto_history: Vec::new(),
+            base: None,
+            refresh_interval: None,
+            ticks: 0,
+            refreshed_at: None,
+            follow: None,
+            preview: false,
+            highlight: false,
         }
     }
 
+    /// Forces the next render to read the provider again, even if it reports no change.
+    pub fn invalidate(&mut self) {
+        self.last_read = None;
+    }
+
+    /// Amount of ticks between reads of the whole provider, if limited.
+    pub fn refresh_interval(&self) -> Option<u32> {
+        self.refresh_interval
+    }
+
+    /// Limits reads of the whole provider to once every `interval` [ticks](Self::tick): renders
+    /// in between only read rows scrolled into view. `None`, the default, reads it on every
+    /// render unless it reports no change.
+    pub fn set_refresh_interval(&mut self, interval: Option<u32>) {
+        self.refresh_interval = interval.map(|interval| interval.max(1));
+    }
+
+    /// Counts a tick towards the next refresh, see
+    /// [`set_refresh_interval`](Self::set_refresh_interval). Meant to be called from the run loop
+    /// of the app, e.g. in [`App::update`](crate::app::App::update).
+    pub fn tick(&mut self) {
+        self.ticks = self.ticks.saturating_add(1);
+    }
+
+    /// Time since the whole provider was last read, if it was.
+    pub fn since_refresh(&self) -> Option<Duration> {
+        self.refreshed_at.map(|refreshed_at| refreshed_at.elapsed())
+    }
+
     pub fn pointer_index(&self) -> usize {
         self.pointer.abs_diff(self.beginning_bucket) as usize
     }
@@ -46,6 +345,177 @@ impl MemoryViewState {
     pub fn bytes_per_bucket(&self) -> u16 {
         self.bytes_per_bucket
     }
+
+    /// Marks the address that the address column shows offsets from, e.g. the frame pointer
+    /// labelled `FP`. Frontends following a register mark it again whenever it changes.
+    pub fn mark_base(&mut self, label: impl Into<String>, address: Address) {
+        self.base = Some((label.into(), address));
+    }
+
+    pub fn clear_base(&mut self) {
+        self.base = None;
+    }
+
+    /// The label and address of the marked base, if any.
+    pub fn base(&self) -> Option<(&str, Address)> {
+        self.base
+            .as_ref()
+            .map(|(label, address)| (label.as_str(), *address))
+    }
+
+    /// Makes the pointer follow the 32-bit value at `address`, read with the endianness of the
+    /// view: on every render, the pointer is moved to where the value points, e.g. to keep up
+    /// with the write cursor of a ring buffer. Moving the pointer has no lasting effect until
+    /// following stops. The pointer stays put while the value can't be read.
+    pub fn set_follow(&mut self, address: Option<Address>) {
+        self.follow = address;
+    }
+
+    /// Address of the value the pointer follows, if any.
+    pub fn follow(&self) -> Option<Address> {
+        self.follow
+    }
+
+    /// Shows or hides a popup previewing memory where the 32-bit value under the pointer points,
+    /// when it looks like a pointer, without moving the view.
+    pub fn toggle_preview(&mut self) {
+        self.preview = !self.preview;
+    }
+
+    pub fn is_preview_open(&self) -> bool {
+        self.preview
+    }
+
+    /// Starts or stops highlighting every visible value equal to the one under the pointer, e.g.
+    /// to spot repeated magic values. See [`MemoryView::highlight_width`].
+    pub fn toggle_highlight(&mut self) {
+        self.highlight = !self.highlight;
+    }
+
+    pub fn is_highlighting(&self) -> bool {
+        self.highlight
+    }
+
+    /// Opens the goto input, shown in place of the first row of the info bar.
+    pub fn open_goto(&mut self) {
+        self.goto = Some(GotoInput::default());
+    }
+
+    pub fn is_goto_open(&self) -> bool {
+        self.goto.is_some()
+    }
+
+    /// Inputs that were jumped to, oldest first.
+    pub fn goto_history(&self) -> &[String] {
+        &self.goto_history
+    }
+
+    /// Handles a key event with the [standard keymap](Keymap::standard).
+    pub fn handle_key(&mut self, key: KeyEvent, symbols: Option<&dyn SymbolProvider>) -> bool {
+        self.handle_key_with(key, Keymap::standard(), symbols)
+    }
+
+    /// Handles a key event, translated to an action by `keymap`. [`Action::Toggle`] toggles the
+    /// [pointer preview](Self::toggle_preview), and [`Action::Goto`] opens the goto input, which
+    /// then takes every key until it is submitted or cancelled. Submitted inputs are
+    /// resolved as symbol names through `symbols`, if given, and otherwise as hex addresses.
+    /// Returns whether the event was consumed.
+    pub fn handle_key_with(
+        &mut self,
+        key: KeyEvent,
+        keymap: &Keymap,
+        symbols: Option<&dyn SymbolProvider>,
+    ) -> bool {
+        if key.kind == KeyEventKind::Release {
+            return false;
+        }
+
+        let Some(input) = &mut self.goto else {
+            match keymap.action(key) {
+                Some(Action::Goto) => self.open_goto(),
+                Some(Action::Toggle) => self.toggle_preview(),
+                _ => return false,
+            }
+
+            return true;
+        };
+
+        match key.code {
+            KeyCode::Esc => self.goto = None,
+            KeyCode::Enter => match resolve_address(&input.text, symbols) {
+                Ok(address) => {
+                    let text = input.text.trim().to_owned();
+                    self.goto_history.retain(|entry| *entry != text);
+                    self.goto_history.push(text);
+                    if self.goto_history.len() > GOTO_HISTORY_LEN {
+                        self.goto_history.remove(0);
+                    }
+
+                    self.pointer = address;
+                    self.goto = None;
+                }
+                Err(error) => input.error = Some(error),
+            },
+            KeyCode::Backspace => {
+                input.text.pop();
+                input.history_index = None;
+                input.error = None;
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                input.text.push(c);
+                input.history_index = None;
+                input.error = None;
+            }
+            KeyCode::Up if !self.goto_history.is_empty() => {
+                let index = match input.history_index {
+                    Some(index) => index.saturating_sub(1),
+                    None => self.goto_history.len() - 1,
+                };
+                input.history_index = Some(index);
+                input.text.clone_from(&self.goto_history[index]);
+                input.error = None;
+            }
+            KeyCode::Down => {
+                if let Some(index) = input.history_index {
+                    input.history_index =
+                        Some(index + 1).filter(|&index| index < self.goto_history.len());
+                    match input.history_index {
+                        Some(index) => input.text.clone_from(&self.goto_history[index]),
+                        None => input.text.clear(),
+                    }
+                    input.error = None;
+                }
+            }
+            _ => (),
+        }
+
+        true
+    }
+}
+
+/// Resolves the input of the goto prompt. Symbol names take precedence, so that hex-looking names
+/// such as `dead` can be jumped to: a `0x` prefix forces an address.
+fn resolve_address(text: &str, symbols: Option<&dyn SymbolProvider>) -> Result<Address, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(strings().no_address.to_string());
+    }
+
+    if let Some(address) = symbols.and_then(|symbols| symbols.lookup(text)) {
+        return Ok(address);
+    }
+
+    let digits = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))
+        .unwrap_or(text);
+    match Address::from_str_radix(digits, 16) {
+        Ok(address) => Ok(address),
+        Err(_) if symbols.is_some() => {
+            Err(i18n::fill(&strings().not_an_address_or_symbol, &[&text]))
+        }
+        Err(_) => Err(i18n::fill(&strings().not_an_address, &[&text])),
+    }
 }
 
 pub struct MemoryView<'a> {
@@ -54,6 +524,26 @@ pub struct MemoryView<'a> {
 
     /// Block to draw inside.
     block: Option<Block<'a>>,
+
+    /// Amount of bytes per row. As many as fit if `None`.
+    bytes_per_row: Option<u16>,
+
+    /// Byte order of the values in the info bar.
+    endianness: Endianness,
+
+    /// What the address column shows once a base is marked.
+    address_mode: AddressMode,
+
+    /// Checksum shown next to each row, if any.
+    checksum: Option<RowChecksum>,
+
+    /// Fields each row is decoded as, instead of showing its bytes.
+    record: Option<&'a [Field]>,
+
+    /// Amount of bytes compared when highlighting values equal to the one under the pointer.
+    highlight_width: u16,
+
+    theme: Theme,
 }
 
 impl<'a> MemoryView<'a> {
@@ -61,6 +551,67 @@ impl<'a> MemoryView<'a> {
         Self {
             memory_provider,
             block: None,
+            bytes_per_row: None,
+            endianness: Endianness::Little,
+            address_mode: AddressMode::Both,
+            checksum: None,
+            record: None,
+            highlight_width: 1,
+            theme: Theme::default(),
+        }
+    }
+
+    /// Shows a fixed amount of bytes per row, or as many as fit if the area is too narrow.
+    pub fn bytes_per_row(self, bytes_per_row: u16) -> Self {
+        Self {
+            bytes_per_row: Some(bytes_per_row.max(1)),
+            ..self
+        }
+    }
+
+    pub fn endianness(self, endianness: Endianness) -> Self {
+        Self { endianness, ..self }
+    }
+
+    pub fn address_mode(self, address_mode: AddressMode) -> Self {
+        Self {
+            address_mode,
+            ..self
+        }
+    }
+
+    /// Shows a checksum of each row after its characters, so that changed rows stand out.
+    pub fn checksum(self, checksum: RowChecksum) -> Self {
+        Self {
+            checksum: Some(checksum),
+            ..self
+        }
+    }
+
+    /// Decodes each row as a record of `fields`, shown in labelled columns instead of bytes, e.g.
+    /// to scan an array of structs. Rows are as long as the record, and aligned to it from the
+    /// [marked base](MemoryViewState::mark_base), or from address 0 if there is none.
+    pub fn record(self, fields: &'a [Field]) -> Self {
+        Self {
+            record: Some(fields),
+            ..self
+        }
+    }
+
+    /// Compares values of `width` bytes, aligned to it, when
+    /// [highlighting](MemoryViewState::toggle_highlight) values equal to the one under the
+    /// pointer, e.g. 4 for words. Bytes are compared by default.
+    pub fn highlight_width(self, width: u16) -> Self {
+        Self {
+            highlight_width: width.max(1),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
         }
     }
 
@@ -81,128 +632,327 @@ impl<'a> MemoryView<'a> {
         }
     }
 
-    fn layout(&mut self, area: Rect) -> MemoryViewLayout {
-        let main_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Min(1), Constraint::Length(4)].as_ref())
-            .split(area);
+    /// Width of the offsets from the base and whether absolute addresses are shown too, if a base
+    /// is marked and offsets are shown.
+    fn offset_width(&self, area: Rect, state: &MemoryViewState) -> Option<(u16, bool)> {
+        let (label, base) = state.base()?;
+        let absolute = match self.address_mode {
+            AddressMode::Absolute => return None,
+            AddressMode::Relative => false,
+            AddressMode::Both => true,
+        };
+
+        // no more bytes than cells are ever visible, so the farthest row is at most this far
+        let visible = area.width as u64 * area.height as u64;
+        let distance = state.pointer.abs_diff(base) as u64 + visible;
+        let digits = (64 - distance.leading_zeros()).div_ceil(4).clamp(2, 8) as u16;
+
+        Some((label.len() as u16 + 3 + digits, absolute))
+    }
+
+    fn layout(&mut self, area: Rect, state: &MemoryViewState) -> MemoryViewLayout {
+        let (header, main_area, info_bar) = match self.record {
+            Some(_) => {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints(
+                        [
+                            Constraint::Length(1),
+                            Constraint::Min(1),
+                            Constraint::Length(4),
+                        ]
+                        .as_ref(),
+                    )
+                    .split(area);
+                (Some(chunks[0]), chunks[1], chunks[2])
+            }
+            None => {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(1), Constraint::Length(4)].as_ref())
+                    .split(area);
+                (None, chunks[0], chunks[1])
+            }
+        };
 
+        // absolute addresses are 8 digits wide, centered with a cell of padding on each side
+        let offsets = self.offset_width(area, state);
+        let address_width = match offsets {
+            Some((offset_width, true)) => 11 + offset_width + 2,
+            Some((offset_width, false)) => offset_width + 3,
+            None => 11,
+        };
         let view_chunks = Layout::default()
             .direction(Direction::Horizontal)
             .constraints(
                 [
-                    Constraint::Length(11),
+                    Constraint::Length(address_width),
                     Constraint::Length(1),
                     Constraint::Min(8),
                 ]
                 .as_ref(),
             )
-            .split(main_chunks[0]);
+            .split(main_area);
 
-        let info_bar = main_chunks[1];
         let address_column = view_chunks[0];
 
-        let byte_count = (view_chunks[2].width - 1) / 4;
+        // two digits, a border and a cell of padding on each side
+        let (data_area, checksum_column) = match self.checksum {
+            Some(_) => {
+                let chunks = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Min(0), Constraint::Length(5)].as_ref())
+                    .split(view_chunks[2]);
+                (chunks[0], Some(chunks[1]))
+            }
+            None => (view_chunks[2], None),
+        };
+
+        // records take the place of both bytes and characters
+        if self.record.is_some() {
+            return MemoryViewLayout {
+                info_bar,
+                header: header.map(|header| Rect {
+                    x: data_area.x,
+                    width: data_area.width,
+                    ..header
+                }),
+                address_column,
+                offsets,
+                memory_table: data_area,
+                ascii_table: Rect::default(),
+                checksum_column,
+            };
+        }
+
+        let byte_count = data_area.width.saturating_sub(1) / 4;
+        let memory_constraint = match self.bytes_per_row {
+            Some(bytes_per_row) => Constraint::Length(bytes_per_row.min(byte_count.max(1)) * 3),
+            None => Constraint::Min(byte_count * 3),
+        };
         let data_chunks = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints(
-                [
-                    Constraint::Min(byte_count * 3),
-                    Constraint::Length(byte_count + 5),
-                ]
-                .as_ref(),
-            )
-            .split(view_chunks[2]);
+            .constraints([memory_constraint, Constraint::Length(byte_count + 5)].as_ref())
+            .split(data_area);
 
         let memory_table = data_chunks[0];
         let ascii_table = data_chunks[1];
 
         MemoryViewLayout {
             info_bar,
+            header,
             address_column,
+            offsets,
             memory_table,
             ascii_table,
+            checksum_column,
         }
     }
 
-    fn render_address_column(&mut self, area: Rect, buf: &mut Buffer, state: &MemoryViewState) {
-        let addresses = (0..area.height)
-            .map(|index| {
-                state
-                    .beginning_bucket
-                    .checked_add((state.bytes_per_bucket * index) as Address)
-            })
-            .map(|addr| {
-                let mut text = Text::from(
-                    addr.map(|x| (Cow::from(format!("{x:08X}"))))
-                        .unwrap_or(Cow::from("--------")),
+    /// Reads `len` values starting at the beginning bucket into the memory buffer. Values read by
+    /// the last render are reused when they're still valid, so that scrolling only reads the
+    /// newly exposed rows. Providers that report a generation are read again whenever it changes;
+    /// for the others, values are reused in incremental mode or between refreshes.
+    fn read_memory(&self, state: &mut MemoryViewState, len: usize) {
+        let start = state.beginning_bucket;
+        let generation = self.memory_provider.generation();
+        let refresh_due = match state.refresh_interval {
+            Some(interval) => state.ticks >= interval,
+            None => false,
+        };
+        let reusable = match state.last_read {
+            Some((last_start, last_len, last_generation))
+                if last_len == len
+                    && !refresh_due
+                    && match generation {
+                        Some(_) => last_generation == generation,
+                        None => state.incremental || state.refresh_interval.is_some(),
+                    } =>
+            {
+                Some(last_start)
+            }
+            _ => None,
+        };
+
+        state.last_read = Some((start, len, generation));
+        match reusable {
+            Some(last_start) if last_start == start => (),
+            // scrolled down: shift values up and read the rows below
+            Some(last_start) if start > last_start && ((start - last_start) as usize) < len => {
+                let delta = (start - last_start) as usize;
+                state.memory_buffer.copy_within(delta.., 0);
+                self.memory_provider.read_to_buf(
+                    start.wrapping_add((len - delta) as Address),
+                    &mut state.memory_buffer[len - delta..],
                 );
-                text.lines[0].alignment = Some(Alignment::Center);
-                Row::new([text]).style(Style::default().light_magenta())
-            });
+            }
+            // scrolled up: shift values down and read the rows above
+            Some(last_start) if start < last_start && ((last_start - start) as usize) < len => {
+                let delta = (last_start - start) as usize;
+                state.memory_buffer.copy_within(..len - delta, delta);
+                self.memory_provider
+                    .read_to_buf(start, &mut state.memory_buffer[..delta]);
+            }
+            _ => {
+                state.memory_buffer.clear();
+                state.memory_buffer.resize(len, None);
+                self.memory_provider
+                    .read_to_buf(start, &mut state.memory_buffer);
+                state.ticks = 0;
+                state.refreshed_at = Some(Instant::now());
+            }
+        }
+    }
+
+    /// The read values of the aligned value containing `address`, if all of them were read.
+    fn aligned_value<'s>(
+        &self,
+        state: &'s MemoryViewState,
+        address: Address,
+    ) -> Option<&'s [Option<u8>]> {
+        let width = self.highlight_width as Address;
+        let start = (address - address % width).checked_sub(state.beginning_bucket)? as usize;
+        let values = state.memory_buffer.get(start..start + width as usize)?;
+        values.iter().all(Option::is_some).then_some(values)
+    }
 
+    /// Whether the value containing `address` is highlighted as equal to the one under the
+    /// pointer.
+    fn is_occurrence(&self, state: &MemoryViewState, address: Address) -> bool {
+        state.highlight
+            && self
+                .aligned_value(state, state.pointer)
+                .is_some_and(|needle| self.aligned_value(state, address) == Some(needle))
+    }
+
+    fn render_address_column(
+        &mut self,
+        area: Rect,
+        buf: &mut Buffer,
+        state: &MemoryViewState,
+        offsets: Option<(u16, bool)>,
+    ) {
         let block = Block::new().borders(Borders::RIGHT);
         let inner_area = block.inner(area);
         block.render(area, buf);
 
-        let column_table = Table::new(addresses).widths(&[Constraint::Percentage(100)]);
-        Widget::render(column_table, inner_area, buf);
-    }
+        let absolute_width = match offsets {
+            Some((_, false)) => 0,
+            Some((_, true)) => 10.min(inner_area.width),
+            None => inner_area.width,
+        };
 
-    fn render_memory_table(&mut self, area: Rect, buf: &mut Buffer, state: &mut MemoryViewState) {
-        state
-            .constraints_buffer
-            .resize(state.bytes_per_bucket as usize, Constraint::Length(2));
+        let mut digits = [0; 8];
+        let mut offset = String::new();
+        for index in 0..inner_area.height {
+            let address = state
+                .beginning_bucket
+                .checked_add(state.bytes_per_bucket as Address * index as Address);
 
-        let chunks = state
-            .memory_buffer
-            .iter()
-            .enumerate()
-            .chunks(state.bytes_per_bucket as usize);
-
-        let buckets = chunks.into_iter().map(|bytes| {
-            let columns_iter = bytes.into_iter().map(|(i, byte)| {
-                let cell = Cell::from(
-                    byte.map(|x| Cow::from(format!("{x:02X}")))
-                        .unwrap_or(Cow::from("◦◦")),
+            let row = Rect::new(inner_area.x, inner_area.y + index, absolute_width, 1);
+            if row.width > 0 {
+                let text = match address {
+                    Some(address) => hex_address(address, &mut digits),
+                    None => "--------",
+                };
+                render_centered(buf, row, text, self.theme.address);
+            }
+
+            let (Some((offset_width, _)), Some((label, base))) = (offsets, state.base()) else {
+                continue;
+            };
+
+            offset.clear();
+            if let Some(address) = address {
+                let sign = if address < base { '-' } else { '+' };
+                let digits = (offset_width - label.len() as u16 - 3) as usize;
+                // writing to a string never fails
+                let _ = write!(
+                    offset,
+                    "{label}{sign}0x{:0digits$X}",
+                    address.abs_diff(base)
                 );
+            }
+
+            let row = Rect::new(row.right(), row.y, inner_area.width - absolute_width, 1);
+            render_centered(buf, row, &offset, self.theme.address);
+        }
+    }
+
+    fn render_memory_table(&mut self, area: Rect, buf: &mut Buffer, state: &MemoryViewState) {
+        let bytes_per_bucket = state.bytes_per_bucket as usize;
+        let rows = state
+            .memory_buffer
+            .chunks(bytes_per_bucket.max(1))
+            .take(area.height as usize);
 
-                let color = colorous::COOL.eval_rational(byte.unwrap_or(0) as usize, 256usize);
+        for (row, bytes) in rows.enumerate() {
+            let y = area.y + row as u16;
+            for (column, byte) in bytes.iter().enumerate() {
+                let i = row * bytes_per_bucket + column;
+                let address = state.beginning_bucket.wrapping_add(i as u32);
                 let style = {
-                    let style = Style::default().fg(Color::Rgb(color.r, color.g, color.b));
+                    let style = if self.memory_provider.is_modified(address) {
+                        self.theme.modified
+                    } else {
+                        self.theme.byte(byte.unwrap_or(0))
+                    };
 
-                    let style = if ((state.beginning_bucket.wrapping_add(i as u32)) / 4) % 2 == 0 {
+                    let style = if (address / 4).is_multiple_of(2) {
                         style.underlined()
                     } else {
                         style
                     };
 
+                    let style = if self.memory_provider.is_stale(address) {
+                        style.patch(self.theme.stale)
+                    } else {
+                        style
+                    };
+
+                    let style = if self.is_occurrence(state, address) {
+                        style.patch(self.theme.occurrence)
+                    } else {
+                        style
+                    };
+
                     if i == state.pointer_index() {
-                        style.bold().on_light_red()
+                        style.patch(self.theme.cursor)
                     } else {
                         style
                     }
                 };
-                cell.style(style)
-            });
-
-            Row::new(columns_iter)
-        });
 
-        let memory_table = Table::new(buckets).widths(&state.constraints_buffer);
-        Widget::render(memory_table, area, buf);
+                let text = byte.map(hex_byte).unwrap_or("◦◦");
+                let x = area.x + 3 * column as u16;
+                buf.set_style(Rect::new(x, y, 2, 1), style);
+                buf.set_string(x, y, text, style);
+            }
+        }
     }
 
     fn render_ascii_table(&mut self, area: Rect, buf: &mut Buffer, state: &MemoryViewState) {
-        let constraint = &[Constraint::Percentage(100)];
-        let chunks = state
+        let block = Block::new().borders(Borders::LEFT);
+        let inner_area = block.inner(area);
+        block.render(area, buf);
+
+        let rows = state
             .memory_buffer
-            .iter()
-            .chunks(state.bytes_per_bucket as usize);
+            .chunks((state.bytes_per_bucket as usize).max(1))
+            .take(inner_area.height as usize);
 
-        let buckets = chunks.into_iter().map(|bytes| {
-            let mut result = String::with_capacity(state.bytes_per_bucket as usize);
-            for byte in bytes {
+        for (index, bytes) in rows.enumerate() {
+            let row = Rect::new(
+                inner_area.x,
+                inner_area.y + index as u16,
+                inner_area.width,
+                1,
+            );
+            buf.set_style(row, self.theme.ascii);
+
+            let offset = (row.width / 2).saturating_sub(bytes.len() as u16 / 2);
+            let columns = (row.x + offset..row.right()).zip(bytes).enumerate();
+            for (column, (x, byte)) in columns {
                 let c = byte.unwrap_or(b' ') as char;
                 let c = if !c.is_ascii() {
                     '⸱'
@@ -216,128 +966,544 @@ impl<'a> MemoryView<'a> {
                     c
                 };
 
-                result.push(c);
+                let cell = buf.get_mut(x, row.y).set_char(c);
+                let i = index * state.bytes_per_bucket as usize + column;
+                if self.is_occurrence(state, state.beginning_bucket.wrapping_add(i as Address)) {
+                    cell.set_style(self.theme.occurrence);
+                }
+            }
+        }
+    }
+
+    fn render_records(
+        &mut self,
+        area: Rect,
+        header: Option<Rect>,
+        buf: &mut Buffer,
+        state: &MemoryViewState,
+        fields: &[Field],
+    ) {
+        // right aligned, a cell apart
+        let mut columns = Vec::with_capacity(fields.len());
+        let mut x = area.x;
+        let mut offset = 0;
+        for field in fields {
+            let width = value_width(&field.ty, field.format).max(field.name.len()) as u16;
+            if x + width > area.right() {
+                break;
             }
 
-            let mut text = Text::from(result);
-            text.lines[0].alignment = Some(Alignment::Center);
+            columns.push((x, width, offset));
+            x += width + 1;
+            offset += field.ty.size();
+        }
 
-            Row::new([text]).style(Style::default().light_blue())
-        });
+        if let Some(header) = header {
+            buf.set_style(header, self.theme.header);
+            for (field, (x, width, _)) in fields.iter().zip(&columns) {
+                let label = format!("{:>width$}", field.name, width = *width as usize);
+                buf.set_stringn(*x, header.y, label, *width as usize, self.theme.header);
+            }
+        }
+
+        let size = state.bytes_per_bucket as usize;
+        let rows = state
+            .memory_buffer
+            .chunks_exact(size.max(1))
+            .take(area.height as usize);
+
+        for (row, values) in rows.enumerate() {
+            let y = area.y + row as u16;
+            let Some(start) = state.beginning_bucket.checked_add((row * size) as Address) else {
+                break;
+            };
+
+            let record = ReadValues { start, values };
+            for (field, (x, width, offset)) in fields.iter().zip(&columns) {
+                let address = start.wrapping_add(*offset as Address);
+                let addresses = (0..field.ty.size() as Address).map(|i| address.wrapping_add(i));
+
+                let mut style = self.theme.info;
+                if addresses
+                    .clone()
+                    .any(|address| self.memory_provider.is_modified(address))
+                {
+                    style = self.theme.modified;
+                }
+                if addresses
+                    .clone()
+                    .any(|address| self.memory_provider.is_stale(address))
+                {
+                    style = style.patch(self.theme.stale);
+                }
+                if addresses.clone().any(|address| address == state.pointer) {
+                    style = style.patch(self.theme.cursor);
+                }
+
+                let value = format_value(&record, &field.ty, field.format, address);
+                let text = format!(
+                    "{:>width$}",
+                    value.as_deref().unwrap_or("--"),
+                    width = *width as usize
+                );
+                buf.set_stringn(*x, y, text, *width as usize, style);
+            }
+        }
+    }
 
+    /// Where the value under the pointer points, if it looks like a pointer: it isn't null, and
+    /// points inside of a known region or, if the provider knows none, to a readable value.
+    fn pointer_target(&self, state: &MemoryViewState) -> Option<Address> {
+        let values = state.memory_buffer.get(state.pointer_index()..)?;
+        let [Some(a), Some(b), Some(c), Some(d)] = values.get(..4)? else {
+            return None;
+        };
+
+        let target = self.endianness.u32([*a, *b, *c, *d]);
+        let regions = self.memory_provider.regions();
+        let plausible = if regions.is_empty() {
+            let mut value = [None];
+            self.memory_provider.read_to_buf(target, &mut value);
+            value[0].is_some()
+        } else {
+            regions.iter().any(|region| region.contains(target))
+        };
+
+        (target != 0 && plausible).then_some(target)
+    }
+
+    /// Renders the preview of memory at `target` right under the `anchor` cell, or above it if
+    /// there is no room below.
+    fn render_preview(&self, area: Rect, anchor: (u16, u16), buf: &mut Buffer, target: Address) {
+        // an address and the bytes of each row, inside of a border
+        let width = (8 + 1 + 3 * PREVIEW_BYTES_PER_ROW + 2).min(area.width);
+        let height = (PREVIEW_ROWS + 2).min(area.height);
+        let (x, y) = anchor;
+        let y = if y + 1 + height <= area.bottom() {
+            y + 1
+        } else {
+            y.saturating_sub(height).max(area.y)
+        };
+        let x = x.min(area.right() - width).max(area.x);
+        let popup_area = Rect::new(x, y, width, height);
+
+        Clear.render(popup_area, buf);
+        let block = Block::new()
+            .borders(Borders::ALL)
+            .border_style(self.theme.border)
+            .title(Span::styled(format!(" → {target:08X} "), self.theme.title));
+        let inner_area = block.inner(popup_area);
+        block.render(popup_area, buf);
+
+        let mut values = [None; (PREVIEW_ROWS * PREVIEW_BYTES_PER_ROW) as usize];
+        self.memory_provider.read_to_buf(target, &mut values);
+
+        let mut digits = [0; 8];
+        let rows = values.chunks(PREVIEW_BYTES_PER_ROW as usize);
+        for (row, bytes) in rows.take(inner_area.height as usize).enumerate() {
+            let Some(address) =
+                target.checked_add(row as Address * PREVIEW_BYTES_PER_ROW as Address)
+            else {
+                break;
+            };
+
+            let y = inner_area.y + row as u16;
+            let mut spans = vec![
+                Span::styled(
+                    hex_address(address, &mut digits).to_owned(),
+                    self.theme.address,
+                ),
+                Span::raw(" "),
+            ];
+            for byte in bytes {
+                let style = self.theme.byte(byte.unwrap_or(0));
+                spans.push(Span::raw(" "));
+                spans.push(Span::styled(byte.map(hex_byte).unwrap_or("◦◦"), style));
+            }
+
+            buf.set_line(inner_area.x, y, &Line::from(spans), inner_area.width);
+        }
+    }
+
+    fn render_checksum_column(
+        &mut self,
+        area: Rect,
+        buf: &mut Buffer,
+        state: &MemoryViewState,
+        checksum: RowChecksum,
+    ) {
         let block = Block::new().borders(Borders::LEFT);
         let inner_area = block.inner(area);
         block.render(area, buf);
 
-        let ascii_table = Table::new(buckets).widths(constraint.as_slice());
-        Widget::render(ascii_table, inner_area, buf);
+        let rows = state
+            .memory_buffer
+            .chunks((state.bytes_per_bucket as usize).max(1))
+            .take(inner_area.height as usize);
+
+        let mut bytes = Vec::new();
+        for (index, values) in rows.enumerate() {
+            let row = Rect::new(
+                inner_area.x,
+                inner_area.y + index as u16,
+                inner_area.width,
+                1,
+            );
+
+            // rows with unmapped values have no meaningful checksum
+            bytes.clear();
+            bytes.extend(values.iter().map_while(|value| *value));
+            let text = if bytes.len() == values.len() {
+                hex_byte(checksum.compute(&bytes))
+            } else {
+                "--"
+            };
+            render_centered(buf, row, text, self.theme.address);
+        }
     }
 
-    pub fn render_info_bar(&mut self, area: Rect, buf: &mut Buffer, state: &MemoryViewState) {
+    pub fn render_info_bar(&mut self, area: Rect, buf: &mut Buffer, state: &mut MemoryViewState) {
         let block = Block::new().borders(Borders::TOP);
+        let block = match self.memory_provider.error() {
+            Some(error) => block.title(Span::styled(format!(" {error} "), self.theme.alert)),
+            None => block,
+        };
+        let strings = strings();
+        let block = match state.follow {
+            Some(address) => block.title(Span::styled(
+                format!(" {} *{address:08X} ", strings.following),
+                self.theme.muted,
+            )),
+            None => block,
+        };
+        let block = match state.since_refresh() {
+            Some(elapsed) if state.refresh_interval.is_some() => {
+                let elapsed = elapsed.as_secs();
+                let text = if elapsed < 60 {
+                    i18n::fill(&strings.refreshed_seconds_ago, &[&elapsed])
+                } else {
+                    i18n::fill(&strings.refreshed_minutes_ago, &[&(elapsed / 60)])
+                };
+                let text = format!(" {text} ");
+                block.title(
+                    Title::from(Span::styled(text, self.theme.muted)).alignment(Alignment::Right),
+                )
+            }
+            _ => block,
+        };
         let inner_area = block.inner(area);
         block.render(area, buf);
 
-        let bytes = &state.memory_buffer[state.pointer_index()..state.pointer_index() + 4];
+        // the pointer may be past the values read, e.g. in a single row
+        let mut bytes = [None; 4];
+        if let Some(values) = state.memory_buffer.get(state.pointer_index()..) {
+            for (byte, value) in bytes.iter_mut().zip(values) {
+                *byte = *value;
+            }
+        }
 
-        let as_u8 = state.memory_buffer[state.pointer_index()].unwrap();
-        let as_i8 = as_u8 as i8;
+        let as_u8 = bytes[0];
+        let as_i8 = as_u8.map(|x| x as i8);
 
         let as_u16 = match bytes[..2] {
-            [Some(a), Some(b)] => Some(u16::from_le_bytes([a, b])),
+            [Some(a), Some(b)] => Some(self.endianness.u16([a, b])),
             _ => None,
         };
         let as_i16 = as_u16.map(|x| x as i16);
 
         let as_u32 = match bytes[..] {
-            [Some(a), Some(b), Some(c), Some(d)] => Some(u32::from_le_bytes([a, b, c, d])),
+            [Some(a), Some(b), Some(c), Some(d)] => Some(self.endianness.u32([a, b, c, d])),
             _ => None,
         };
         let as_i32 = as_u32.map(|x| x as i32);
 
-        let as_f32 = match bytes[..] {
-            [Some(a), Some(b), Some(c), Some(d)] => Some(f32::from_le_bytes([a, b, c, d])),
-            _ => None,
-        };
+        let as_f32 = as_u32.map(f32::from_bits);
 
-        let rows: [[Text; 3]; 3] = [
-            [
-                format!("u8: {as_u8:?}").into(),
-                if let Some(n) = as_u16 {
-                    format!("u16: {n:?}").into()
-                } else {
-                    "u16: --".into()
-                },
-                if let Some(n) = as_u32 {
-                    format!("u32: {n:?}").into()
-                } else {
-                    "u32: --".into()
-                },
-            ],
-            [
-                format!("i8: {as_i8:?}").into(),
-                if let Some(n) = as_i16 {
-                    format!("i16: {n:?}").into()
-                } else {
-                    "i16: --".into()
-                },
-                if let Some(n) = as_i32 {
-                    format!("i32: {n:?}").into()
-                } else {
-                    "i32: --".into()
-                },
-            ],
-            [
-                if let Some(n) = as_f32 {
-                    format!("f32: {n:?}").into()
-                } else {
-                    "f32: --".into()
-                },
-                format!("Selected: {:08X}", state.pointer).into(),
-                "Little Endian".into(),
-            ],
-        ];
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                [
+                    Constraint::Percentage(33),
+                    Constraint::Length(1),
+                    Constraint::Percentage(33),
+                    Constraint::Length(1),
+                    Constraint::Percentage(33),
+                ]
+                .as_ref(),
+            )
+            .split(Rect::new(inner_area.x, inner_area.y, inner_area.width, 1));
+
+        let text = &mut state.text_buffer;
+        for row in 0..inner_area.height.min(3) {
+            let y = inner_area.y + row;
+            buf.set_style(
+                Rect::new(inner_area.x, y, inner_area.width, 1),
+                self.theme.info,
+            );
+
+            for column in 0..3 {
+                text.clear();
+                // writing to a string never fails
+                let _ = match (row, column) {
+                    (0, 0) => write_optional(text, "u8", as_u8),
+                    (0, 1) => write_optional(text, "u16", as_u16),
+                    (0, 2) => write_optional(text, "u32", as_u32),
+                    (1, 0) => write_optional(text, "i8", as_i8),
+                    (1, 1) => write_optional(text, "i16", as_i16),
+                    (1, 2) => write_optional(text, "i32", as_i32),
+                    (2, 0) => write_optional(text, "f32", as_f32),
+                    (2, 1) => write!(text, "{}: {:08X}", strings.selected, state.pointer),
+                    _ => match self.endianness {
+                        Endianness::Little => write!(text, "{}", strings.little_endian),
+                        Endianness::Big => write!(text, "{}", strings.big_endian),
+                    },
+                };
+
+                let area = columns[column * 2];
+                if area.width > 0 {
+                    buf.set_stringn(area.x, y, &*text, area.width as usize, self.theme.info);
+                }
+            }
+        }
+
+        if let Some(input) = &state.goto {
+            let row = Rect::new(inner_area.x, inner_area.y, inner_area.width, 1);
+            self.render_goto_input(row.intersection(inner_area), buf, input);
+        }
+    }
 
-        let rows = rows
-            .into_iter()
-            .map(Row::new)
-            .map(|row| row.style(Style::default().light_green()));
+    fn render_goto_input(&self, area: Rect, buf: &mut Buffer, input: &GotoInput) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
 
-        let constraints = [
-            Constraint::Percentage(33),
-            Constraint::Percentage(33),
-            Constraint::Percentage(33),
+        buf.set_style(area, self.theme.info);
+        let mut spans = vec![
+            Span::styled(format!("{}: ", strings().go_to), self.theme.title),
+            Span::styled(input.text.as_str(), self.theme.info),
+            Span::styled(" ", self.theme.cursor),
         ];
+        if let Some(error) = &input.error {
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(error.as_str(), self.theme.alert));
+        }
 
-        let table = Table::new(rows).widths(&constraints);
-        Widget::render(table, inner_area, buf);
+        buf.set_line(area.x, area.y, &Line::from(spans), area.width);
     }
 }
 
+/// Amount of rows of the pointer preview.
+const PREVIEW_ROWS: u16 = 4;
+/// Amount of bytes per row of the pointer preview.
+const PREVIEW_BYTES_PER_ROW: u16 = 8;
+
+/// Values already read by a view, so that records are decoded without reading them again.
+struct ReadValues<'a> {
+    start: Address,
+    values: &'a [Option<u8>],
+}
+
+impl MemoryProvider for ReadValues<'_> {
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]) {
+        for (offset, value) in buf.iter_mut().enumerate() {
+            *value = pointer
+                .checked_add(offset as Address)
+                .and_then(|address| address.checked_sub(self.start))
+                .and_then(|index| self.values.get(index as usize))
+                .copied()
+                .flatten();
+        }
+    }
+}
+
+/// Two hexadecimal digits of every byte value, so that rendering doesn't need to format them.
+const HEX_TABLE: [u8; 512] = {
+    let digits = b"0123456789ABCDEF";
+    let mut table = [0; 512];
+    let mut i = 0;
+    while i < 256 {
+        table[2 * i] = digits[i >> 4];
+        table[2 * i + 1] = digits[i & 0xF];
+        i += 1;
+    }
+
+    table
+};
+
+const HEX: &str = match std::str::from_utf8(&HEX_TABLE) {
+    Ok(hex) => hex,
+    Err(_) => panic!("hex table is ascii"),
+};
+
+fn hex_byte(byte: u8) -> &'static str {
+    let index = 2 * byte as usize;
+    &HEX[index..index + 2]
+}
+
+pub(crate) fn hex_address(address: Address, digits: &mut [u8; 8]) -> &str {
+    for (pair, byte) in digits.chunks_mut(2).zip(address.to_be_bytes()) {
+        pair.copy_from_slice(hex_byte(byte).as_bytes());
+    }
+
+    std::str::from_utf8(digits).expect("hex digits are ascii")
+}
+
+fn write_optional<T: std::fmt::Debug>(
+    text: &mut String,
+    name: &str,
+    value: Option<T>,
+) -> std::fmt::Result {
+    match value {
+        Some(value) => write!(text, "{name}: {value:?}"),
+        None => write!(text, "{name}: --"),
+    }
+}
+
+/// Renders a single line of text centered in a row, like tables do with centered cells.
+pub(crate) fn render_centered(buf: &mut Buffer, row: Rect, text: &str, style: Style) {
+    buf.set_style(row, style);
+    if row.width == 0 {
+        return;
+    }
+
+    let offset = (row.width / 2).saturating_sub(text.len() as u16 / 2);
+    buf.set_stringn(
+        row.x + offset,
+        row.y,
+        text,
+        row.width.saturating_sub(offset) as usize,
+        style,
+    );
+}
+
 impl<'a> StatefulWidget for MemoryView<'a> {
     type State = MemoryViewState;
 
     fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
         let area = self.wrap_in_block(area, buf);
-        let layout = self.layout(area);
+
+        // before the layout, which depends on the pointer
+        if let Some(address) = state.follow {
+            let mut bytes = [None; 4];
+            self.memory_provider.read_to_buf(address, &mut bytes);
+            if let [Some(a), Some(b), Some(c), Some(d)] = bytes {
+                state.pointer = self.endianness.u32([a, b, c, d]);
+            }
+        }
+
+        let layout = self.layout(area, state);
+        if layout.memory_table.width < 3 || layout.memory_table.height == 0 {
+            let error = Error::AreaTooSmall {
+                width: area.width,
+                height: area.height,
+            };
+            render_error(&error, area, buf, &self.theme);
+            return;
+        }
 
         // update state
-        state.bytes_per_bucket = layout.memory_table.width / 3;
-        let pointed_bucket = state.pointer - state.pointer % state.bytes_per_bucket as Address;
-        state.beginning_bucket = pointed_bucket.saturating_sub(
-            (state.bytes_per_bucket * ((layout.address_column.height / 2) & !1)) as Address,
-        );
+        let pointed_bucket = match self.record {
+            Some(fields) => {
+                let size = fields.iter().map(|field| field.ty.size()).sum::<usize>();
+                state.bytes_per_bucket = size.clamp(1, u16::MAX as usize) as u16;
+
+                let origin = state.base().map_or(0, |(_, base)| base);
+                let misalignment = (state.pointer as i64 - origin as i64)
+                    .rem_euclid(state.bytes_per_bucket as i64);
+                state.pointer.saturating_sub(misalignment as Address)
+            }
+            None => {
+                state.bytes_per_bucket = layout.memory_table.width / 3;
+                state.pointer - state.pointer % state.bytes_per_bucket as Address
+            }
+        };
+        // whole rows, so that records stay aligned
+        let rows_above = (((layout.address_column.height / 2) & !1) as Address)
+            .min(pointed_bucket / state.bytes_per_bucket as Address);
+        state.beginning_bucket = pointed_bucket - rows_above * state.bytes_per_bucket as Address;
 
         let value_count = state.bytes_per_bucket as usize * area.height as usize;
-        state.memory_buffer.clear();
-        state.memory_buffer.resize(value_count, None);
-        self.memory_provider
-            .read_to_buf(state.beginning_bucket, &mut state.memory_buffer);
+        self.read_memory(state, value_count);
 
         // render!
-        self.render_address_column(layout.address_column, buf, state);
-        self.render_memory_table(layout.memory_table, buf, state);
-        self.render_ascii_table(layout.ascii_table, buf, state);
+        self.render_address_column(layout.address_column, buf, state, layout.offsets);
+        match self.record {
+            Some(fields) => {
+                self.render_records(layout.memory_table, layout.header, buf, state, fields)
+            }
+            None => {
+                self.render_memory_table(layout.memory_table, buf, state);
+                self.render_ascii_table(layout.ascii_table, buf, state);
+            }
+        }
+        if let (Some(area), Some(checksum)) = (layout.checksum_column, self.checksum) {
+            self.render_checksum_column(area, buf, state, checksum);
+        }
         self.render_info_bar(layout.info_bar, buf, state);
+
+        if state.preview {
+            if let Some(target) = self.pointer_target(state) {
+                let index = state.pointer_index();
+                let bytes_per_bucket = state.bytes_per_bucket.max(1) as usize;
+                let column = match self.record {
+                    Some(_) => 0,
+                    None => 3 * (index % bytes_per_bucket) as u16,
+                };
+                // the pointer may be past the rows shown, e.g. in a single row
+                let row = (index / bytes_per_bucket).min(layout.memory_table.height as usize - 1);
+                let anchor = (
+                    layout.memory_table.x + column,
+                    layout.memory_table.y + row as u16,
+                );
+                self.render_preview(area, anchor, buf, target);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        provider::{SliceProvider, VecProvider},
+        testing::render_stateful_to_string,
+    };
+
+    #[test]
+    fn renders_rows() {
+        let data: Vec<u8> = b"anton hex view, sixteen+".to_vec();
+        let provider = SliceProvider::new(0x1000, &data);
+        let mut state = MemoryViewState::new(0x1004);
+
+        let view = MemoryView::new(&provider);
+        let text = render_stateful_to_string(view, &mut state, 60, 7);
+        let expected = [
+            " 00000FFC │ ◦◦ ◦◦ ◦◦ ◦◦ 61 6E 74 6F 6E 20 68 │      anton h",
+            " 00001007 │ 65 78 20 76 69 65 77 2C 20 73 69 │  ex view, si",
+            " 00001012 │ 78 74 65 65 6E 2B ◦◦ ◦◦ ◦◦ ◦◦ ◦◦ │  xteen+",
+            "────────────────────────────────────────────────────────────",
+            "u8: 110              u16: 8302            u32: 1701322862",
+            "i8: 110              i16: 8302            i32: 1701322862",
+            "f32: 6.8511703e22    Selected: 00001004   Little Endian",
+        ];
+        assert_eq!(text, expected.join("\n"));
+    }
+
+    #[test]
+    fn reads_again_when_the_generation_changes() {
+        let mut provider = VecProvider::zeroed(0x1000, 32);
+        let mut state = MemoryViewState::new(0x1000);
+        // with a long refresh interval, only a change of generation causes a read
+        state.set_refresh_interval(Some(u32::MAX));
+
+        let render = |provider: &VecProvider, state: &mut MemoryViewState| {
+            render_stateful_to_string(MemoryView::new(provider), state, 60, 7)
+        };
+        let before = render(&provider, &mut state);
+        provider.data_mut()[0] = 0xAB;
+        let after = render(&provider, &mut state);
+
+        assert!(!before.contains("AB"));
+        assert!(after.contains("AB"));
     }
 }
@@ -0,0 +1,478 @@
+use crate::{
+    keymap::Action,
+    memory_view::{MemoryProvider, MemoryWriter},
+    Address, Keymap, Theme,
+};
+use crossterm::event::{KeyEvent, KeyEventKind};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, Row, StatefulWidget, Table, Widget},
+};
+use std::borrow::Cow;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegisterWidth {
+    W8,
+    W16,
+    W32,
+}
+
+impl RegisterWidth {
+    /// Size of the register, in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            Self::W8 => 1,
+            Self::W16 => 2,
+            Self::W32 => 4,
+        }
+    }
+
+    /// Size of the register, in bits.
+    pub fn bits(self) -> u32 {
+        self.size() as u32 * 8
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Access {
+    #[default]
+    ReadWrite,
+    ReadOnly,
+    WriteOnly,
+    /// Can be written once after reset.
+    WriteOnce,
+    /// Can be read, and written once after reset.
+    ReadWriteOnce,
+}
+
+impl Access {
+    pub fn is_readable(self) -> bool {
+        !matches!(self, Self::WriteOnly | Self::WriteOnce)
+    }
+
+    pub fn is_writable(self) -> bool {
+        !matches!(self, Self::ReadOnly)
+    }
+
+    /// A short label for the access, e.g. `RW`.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::ReadWrite => "RW",
+            Self::ReadOnly => "RO",
+            Self::WriteOnly => "WO",
+            Self::WriteOnce => "W1",
+            Self::ReadWriteOnce => "RW1",
+        }
+    }
+}
+
+/// A named value of a [`MmioField`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumeratedValue {
+    pub name: Cow<'static, str>,
+    pub value: u32,
+}
+
+/// A bit field inside of a [`MmioRegister`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MmioField {
+    pub name: Cow<'static, str>,
+    /// Index of the least significant bit of the field.
+    pub offset: u32,
+    /// Width of the field, in bits.
+    pub width: u32,
+    pub access: Access,
+    /// Names for the values this field can take.
+    pub enumerated_values: Vec<EnumeratedValue>,
+}
+
+impl MmioField {
+    pub fn new(name: impl Into<Cow<'static, str>>, offset: u32, width: u32) -> Self {
+        Self {
+            name: name.into(),
+            offset,
+            width,
+            access: Access::default(),
+            enumerated_values: Vec::new(),
+        }
+    }
+
+    pub fn access(self, access: Access) -> Self {
+        Self { access, ..self }
+    }
+
+    pub fn enumerated_value(mut self, name: impl Into<Cow<'static, str>>, value: u32) -> Self {
+        self.enumerated_values.push(EnumeratedValue {
+            name: name.into(),
+            value,
+        });
+        self
+    }
+
+    /// Name of the given value of this field, if it has one.
+    pub fn value_name(&self, value: u32) -> Option<&str> {
+        self.enumerated_values
+            .iter()
+            .find(|enumerated| enumerated.value == value)
+            .map(|enumerated| enumerated.name.as_ref())
+    }
+
+    fn mask(&self) -> u32 {
+        if self.width >= 32 {
+            u32::MAX
+        } else {
+            (1 << self.width) - 1
+        }
+    }
+
+    /// Extracts the value of this field from the value of its register.
+    pub fn extract(&self, register: u32) -> u32 {
+        register.checked_shr(self.offset).unwrap_or(0) & self.mask()
+    }
+
+    /// Replaces the value of this field inside the value of its register.
+    pub fn insert(&self, register: u32, value: u32) -> u32 {
+        let mask = self.mask().checked_shl(self.offset).unwrap_or(0);
+        (register & !mask) | (value.checked_shl(self.offset).unwrap_or(0) & mask)
+    }
+}
+
+/// A memory-mapped hardware register. Values are little endian.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MmioRegister {
+    pub address: Address,
+    pub name: Cow<'static, str>,
+    pub width: RegisterWidth,
+    pub access: Access,
+    pub fields: Vec<MmioField>,
+}
+
+impl MmioRegister {
+    pub fn new(address: Address, name: impl Into<Cow<'static, str>>, width: RegisterWidth) -> Self {
+        Self {
+            address,
+            name: name.into(),
+            width,
+            access: Access::default(),
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn access(self, access: Access) -> Self {
+        Self { access, ..self }
+    }
+
+    pub fn field(mut self, field: MmioField) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    /// Reads the current value of the register.
+    pub fn read(&self, provider: &dyn MemoryProvider) -> Option<u32> {
+        if !self.access.is_readable() {
+            return None;
+        }
+
+        let mut buf = [None; 4];
+        let buf = &mut buf[..self.width.size()];
+        provider.read_to_buf(self.address, buf);
+
+        let mut bytes = [0; 4];
+        for (byte, value) in bytes.iter_mut().zip(buf.iter()) {
+            *byte = (*value)?;
+        }
+
+        Some(u32::from_le_bytes(bytes))
+    }
+
+    /// Writes a new value to the register. Returns whether it was written.
+    pub fn write(&self, writer: &mut dyn MemoryWriter, value: u32) -> bool {
+        if !self.access.is_writable() {
+            return false;
+        }
+
+        let bytes = value.to_le_bytes();
+        writer.write_from_buf(self.address, &bytes[..self.width.size()])
+    }
+
+    /// The fields drawn under the register. Fields without bits, e.g. from a broken SVD file,
+    /// hold nothing to show.
+    fn shown_fields(&self) -> impl Iterator<Item = &MmioField> {
+        self.fields.iter().filter(|field| field.width > 0)
+    }
+}
+
+pub struct MmioViewState {
+    /// Index of the register under the cursor.
+    pub selected: usize,
+
+    offset: usize,
+    /// Amount of registers, as of the last render.
+    len: usize,
+    page: usize,
+    /// The register under the cursor and its value, as of the last render.
+    selected_register: Option<(MmioRegister, Option<u32>)>,
+}
+
+impl Default for MmioViewState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MmioViewState {
+    pub fn new() -> Self {
+        Self {
+            selected: 0,
+            offset: 0,
+            len: 0,
+            page: 1,
+            selected_register: None,
+        }
+    }
+
+    /// The register under the cursor, as of the last render.
+    pub fn selected_register(&self) -> Option<&MmioRegister> {
+        self.selected_register
+            .as_ref()
+            .map(|(register, _)| register)
+    }
+
+    /// Value of the register under the cursor, as of the last render.
+    pub fn selected_value(&self) -> Option<u32> {
+        self.selected_register
+            .as_ref()
+            .and_then(|(_, value)| *value)
+    }
+
+    /// Writes a new value to the register under the cursor. Returns whether it was written.
+    pub fn write_selected(&self, writer: &mut dyn MemoryWriter, value: u32) -> bool {
+        self.selected_register()
+            .is_some_and(|register| register.write(writer, value))
+    }
+
+    /// Writes a new value to a field of the register under the cursor, keeping the other fields
+    /// as they were last read. Returns whether it was written.
+    pub fn write_selected_field(
+        &self,
+        writer: &mut dyn MemoryWriter,
+        field: usize,
+        value: u32,
+    ) -> bool {
+        let Some((register, Some(current))) = &self.selected_register else {
+            return false;
+        };
+        let Some(field) = register.fields.get(field) else {
+            return false;
+        };
+
+        register.write(writer, field.insert(*current, value))
+    }
+
+    /// Handles a key event with the [standard keymap](Keymap::standard).
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        self.handle_key_with(key, Keymap::standard())
+    }
+
+    /// Handles a key event, translated to an action by `keymap`. Returns whether the event was
+    /// consumed.
+    pub fn handle_key_with(&mut self, key: KeyEvent, keymap: &Keymap) -> bool {
+        if key.kind == KeyEventKind::Release {
+            return false;
+        }
+
+        let last = self.len.saturating_sub(1);
+        match keymap.action(key) {
+            Some(Action::Up) => self.selected = self.selected.saturating_sub(1),
+            Some(Action::Down) => self.selected = (self.selected + 1).min(last),
+            Some(Action::PageUp) => self.selected = self.selected.saturating_sub(self.page),
+            Some(Action::PageDown) => self.selected = (self.selected + self.page).min(last),
+            Some(Action::Home) => self.selected = 0,
+            Some(Action::End) => self.selected = last,
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+/// A list of hardware registers with their current values decoded into fields.
+pub struct MmioView<'a> {
+    /// The memory provider.
+    memory_provider: &'a dyn MemoryProvider,
+
+    /// The registers to display.
+    registers: &'a [MmioRegister],
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> MmioView<'a> {
+    pub fn new(memory_provider: &'a dyn MemoryProvider, registers: &'a [MmioRegister]) -> Self {
+        Self {
+            memory_provider,
+            registers,
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+
+    fn register_rows<'r>(
+        &self,
+        register: &'r MmioRegister,
+        value: Option<u32>,
+        selected: bool,
+    ) -> Vec<Row<'r>> {
+        let digits = register.width.size() * 2;
+        let value_text = value
+            .map(|value| format!("0x{value:0digits$X}"))
+            .unwrap_or_else(|| format!("0x{}", "-".repeat(digits)));
+
+        let header = Row::new([
+            Line::from(Span::styled(
+                register.name.clone(),
+                Style::default().light_blue().bold(),
+            )),
+            Line::from(Span::styled(
+                format!("{:08X}", register.address),
+                self.theme.address,
+            )),
+            Line::from(Span::styled(value_text, Style::default().light_green())),
+        ]);
+        let header = if selected {
+            header.style(self.theme.selection)
+        } else {
+            header
+        };
+
+        let mut rows = vec![header];
+        for field in register.shown_fields() {
+            let bits = if field.width == 1 {
+                format!("[{}]", field.offset)
+            } else {
+                let last = field.offset.saturating_add(field.width - 1);
+                format!("[{last}:{}]", field.offset)
+            };
+            let field_value = value
+                .map(|value| {
+                    let value = field.extract(value);
+                    match field.value_name(value) {
+                        Some(name) => format!("{name} ({value})"),
+                        None if field.width == 1 => value.to_string(),
+                        None => format!("0x{value:X} ({value})"),
+                    }
+                })
+                .unwrap_or_else(|| "--".to_owned());
+
+            rows.push(Row::new([
+                Line::from(vec![
+                    Span::raw("  "),
+                    Span::styled(field.name.clone(), Style::default().white()),
+                ]),
+                Line::from(vec![
+                    Span::styled(bits, self.theme.muted),
+                    Span::raw(" "),
+                    Span::styled(field.access.label(), self.theme.muted),
+                ]),
+                Line::from(Span::styled(field_value, Style::default().light_yellow())),
+            ]));
+        }
+
+        rows
+    }
+}
+
+impl<'a> StatefulWidget for MmioView<'a> {
+    type State = MmioViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = self.wrap_in_block(area, buf);
+
+        // update state
+        state.len = self.registers.len();
+        state.selected = state.selected.min(state.len.saturating_sub(1));
+        state.offset = state.offset.min(state.selected);
+        state.selected_register = self.registers.get(state.selected).map(|register| {
+            let value = register.read(self.memory_provider);
+            (register.clone(), value)
+        });
+
+        // scroll until the selected register and all of its fields fit
+        let height = area.height as usize;
+        let row_count = |register: &MmioRegister| register.shown_fields().count() + 1;
+        if !self.registers.is_empty() {
+            while state.offset < state.selected {
+                let used: usize = self.registers[state.offset..=state.selected]
+                    .iter()
+                    .map(row_count)
+                    .sum();
+                if used <= height {
+                    break;
+                }
+                state.offset += 1;
+            }
+        }
+        state.page = self
+            .registers
+            .iter()
+            .skip(state.offset)
+            .scan(0, |used, register| {
+                *used += row_count(register);
+                (*used <= height).then_some(())
+            })
+            .count()
+            .max(1);
+
+        let rows = self
+            .registers
+            .iter()
+            .enumerate()
+            .skip(state.offset)
+            .flat_map(|(index, register)| {
+                // registers may have read side effects, so avoid reading the selected one twice
+                let selected = index == state.selected;
+                let value = if selected {
+                    state.selected_value()
+                } else {
+                    register.read(self.memory_provider)
+                };
+                self.register_rows(register, value, selected)
+            })
+            .take(height);
+
+        let constraints = [
+            Constraint::Percentage(40),
+            Constraint::Length(11),
+            Constraint::Min(10),
+        ];
+        let table = Table::new(rows).widths(&constraints);
+        Widget::render(table, area, buf);
+    }
+}
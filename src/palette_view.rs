@@ -0,0 +1,251 @@
+use crate::{keymap::Action, memory_view::MemoryProvider, Address, Keymap, Theme};
+use crossterm::event::{KeyEvent, KeyEventKind};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, StatefulWidget, Widget},
+};
+
+/// Encoding of a palette entry. 16-bit formats are little endian.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorFormat {
+    /// 5 bits per channel, red in the lowest bits (GBA, SNES, PS1).
+    #[default]
+    Rgb555,
+    /// 5 bits for red and blue, 6 for green, red in the highest bits.
+    Rgb565,
+    /// One byte per channel, in R, G, B order.
+    Rgb888,
+}
+
+impl ColorFormat {
+    /// Size of an entry, in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            Self::Rgb555 | Self::Rgb565 => 2,
+            Self::Rgb888 => 3,
+        }
+    }
+
+    /// Decodes an entry into its 8-bit channels and raw value.
+    pub fn decode(self, bytes: &[u8]) -> Option<(u8, u8, u8, u32)> {
+        // scales a channel of `bits` bits to 8 bits
+        let scale = |value: u16, bits: u32| ((value as u32 * 255) / ((1 << bits) - 1)) as u8;
+
+        match self {
+            Self::Rgb555 => {
+                let value = u16::from_le_bytes(bytes.get(..2)?.try_into().ok()?);
+                let r = scale(value & 0x1F, 5);
+                let g = scale((value >> 5) & 0x1F, 5);
+                let b = scale((value >> 10) & 0x1F, 5);
+                Some((r, g, b, value as u32))
+            }
+            Self::Rgb565 => {
+                let value = u16::from_le_bytes(bytes.get(..2)?.try_into().ok()?);
+                let r = scale(value >> 11, 5);
+                let g = scale((value >> 5) & 0x3F, 6);
+                let b = scale(value & 0x1F, 5);
+                Some((r, g, b, value as u32))
+            }
+            Self::Rgb888 => {
+                let [r, g, b] = bytes.get(..3)?.try_into().ok()?;
+                Some((r, g, b, u32::from_be_bytes([0, r, g, b])))
+            }
+        }
+    }
+}
+
+pub struct PaletteViewState {
+    /// Address of the first entry.
+    pub base: Address,
+    /// Index of the entry under the cursor.
+    pub cursor: usize,
+
+    offset: usize,
+    columns: usize,
+    visible_rows: usize,
+    len: usize,
+    entry_size: usize,
+    buffer: Vec<Option<u8>>,
+}
+
+impl PaletteViewState {
+    pub fn new(base: Address) -> Self {
+        Self {
+            base,
+            cursor: 0,
+            offset: 0,
+            columns: 1,
+            visible_rows: 1,
+            len: 0,
+            entry_size: 0,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Address of the entry under the cursor, as of the last render.
+    pub fn selected_address(&self) -> Address {
+        self.base
+            .wrapping_add((self.cursor * self.entry_size) as Address)
+    }
+
+    /// Handles a key event with the [standard keymap](Keymap::standard).
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        self.handle_key_with(key, Keymap::standard())
+    }
+
+    /// Handles a key event, translated to an action by `keymap`. Returns whether the event was
+    /// consumed.
+    pub fn handle_key_with(&mut self, key: KeyEvent, keymap: &Keymap) -> bool {
+        if key.kind == KeyEventKind::Release {
+            return false;
+        }
+
+        let last = self.len.saturating_sub(1);
+        let columns = self.columns;
+        match keymap.action(key) {
+            Some(Action::Left) => self.cursor = self.cursor.saturating_sub(1),
+            Some(Action::Right) => self.cursor = (self.cursor + 1).min(last),
+            Some(Action::Up) => self.cursor = self.cursor.saturating_sub(columns),
+            Some(Action::Down) => self.cursor = (self.cursor + columns).min(last),
+            Some(Action::Home) => self.cursor = 0,
+            Some(Action::End) => self.cursor = last,
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+/// A memory range decoded as color entries, shown as swatches.
+pub struct PaletteView<'a> {
+    /// The memory provider.
+    memory_provider: &'a dyn MemoryProvider,
+
+    /// Amount of entries in the palette.
+    len: usize,
+
+    format: ColorFormat,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> PaletteView<'a> {
+    pub fn new(memory_provider: &'a dyn MemoryProvider, len: usize) -> Self {
+        Self {
+            memory_provider,
+            len,
+            format: ColorFormat::default(),
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn format(self, format: ColorFormat) -> Self {
+        Self { format, ..self }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+}
+
+impl<'a> StatefulWidget for PaletteView<'a> {
+    type State = PaletteViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = self.wrap_in_block(area, buf);
+        if area.height == 0 {
+            return;
+        }
+
+        // "███ 0FF 7FFF  "
+        let value_digits = self.format.size() * 2;
+        let entry_width = 3 + 1 + 3 + 1 + value_digits as u16 + 2;
+
+        // update state
+        state.len = self.len;
+        state.entry_size = self.format.size();
+        state.cursor = state.cursor.min(self.len.saturating_sub(1));
+        state.columns = ((area.width / entry_width) as usize).max(1);
+        state.visible_rows = area.height as usize;
+
+        let cursor_row = state.cursor / state.columns;
+        if cursor_row < state.offset {
+            state.offset = cursor_row;
+        } else if cursor_row >= state.offset + state.visible_rows {
+            state.offset = cursor_row + 1 - state.visible_rows;
+        }
+
+        let first = state.offset * state.columns;
+        let count = (state.visible_rows * state.columns).min(self.len.saturating_sub(first));
+        state.buffer.clear();
+        state.buffer.resize(count * state.entry_size, None);
+        self.memory_provider.read_to_buf(
+            state
+                .base
+                .wrapping_add((first * state.entry_size) as Address),
+            &mut state.buffer,
+        );
+
+        // render!
+        for (index, bytes) in state.buffer.chunks(state.entry_size).enumerate() {
+            let entry = first + index;
+            let x = area.x + (index % state.columns) as u16 * entry_width;
+            let y = area.y + (index / state.columns) as u16;
+
+            let bytes: Option<Vec<u8>> = bytes.iter().copied().collect();
+            let decoded = bytes.and_then(|bytes| self.format.decode(&bytes));
+
+            let (swatch, value) = match decoded {
+                Some((r, g, b, value)) => (
+                    Span::styled("███", Style::default().fg(Color::Rgb(r, g, b))),
+                    Span::styled(
+                        format!("{value:0value_digits$X}"),
+                        Style::default().light_green(),
+                    ),
+                ),
+                None => (
+                    Span::styled("◦◦◦", self.theme.muted),
+                    Span::styled("-".repeat(value_digits), self.theme.muted),
+                ),
+            };
+
+            let index_style = if entry == state.cursor {
+                Style::default().black().on_light_yellow().bold()
+            } else {
+                self.theme.address
+            };
+            let line = Line::from(vec![
+                swatch,
+                Span::raw(" "),
+                Span::styled(format!("{entry:03X}"), index_style),
+                Span::raw(" "),
+                value,
+            ]);
+            buf.set_line(x, y, &line, entry_width.min(area.right() - x));
+        }
+    }
+}
@@ -0,0 +1,481 @@
+//! Custom panes that frontends can host without knowing about them.
+//!
+//! Third parties implement [`Pane`] and register a constructor under a name in a
+//! [`PaneRegistry`]. Frontends then build their [`PaneTabs`] or [`PaneLayout`] out of the
+//! registry, e.g. from names listed in a config file.
+
+use crate::{keymap::Action, session::Session, tabs::Tabs, Keymap, Theme};
+use crossterm::event::{Event, KeyEvent, KeyEventKind};
+use eyre::{bail, Result};
+use ratatui::{
+    prelude::{Buffer, Constraint, Direction, Layout, Rect},
+    text::Span,
+    widgets::{Block, Borders, Widget},
+};
+use std::borrow::Cow;
+
+/// What a pane is rendered with, besides its area.
+pub struct PaneContext<'a> {
+    pub theme: &'a Theme,
+    /// Whether the pane receives key events.
+    pub focused: bool,
+}
+
+pub trait Pane {
+    /// Shown in tab headers and borders.
+    fn title(&self) -> Cow<'_, str>;
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, context: &PaneContext);
+
+    /// Handles an event meant for the pane. Returns whether it was consumed.
+    fn handle_event(&mut self, _event: &Event) -> bool {
+        false
+    }
+
+    /// Called once every tick, e.g. to refresh values of a running target.
+    fn update(&mut self) {}
+}
+
+type Factory = Box<dyn Fn() -> Box<dyn Pane>>;
+
+/// Constructors of panes, by name.
+#[derive(Default)]
+pub struct PaneRegistry {
+    /// In registration order, so that frontends can list them consistently.
+    factories: Vec<(String, Factory)>,
+}
+
+impl PaneRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a constructor, replacing the one registered under the same name if any.
+    pub fn register<F>(&mut self, name: impl Into<String>, factory: F)
+    where
+        F: Fn() -> Box<dyn Pane> + 'static,
+    {
+        let name = name.into();
+        let factory = Box::new(factory);
+        match self.factories.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, existing)) => *existing = factory,
+            None => self.factories.push((name, factory)),
+        }
+    }
+
+    /// Names of the registered panes, in registration order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.factories.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.iter().any(|(n, _)| n == name)
+    }
+
+    /// Creates a new instance of the pane registered under `name`.
+    pub fn create(&self, name: &str) -> Option<Box<dyn Pane>> {
+        self.factories
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, factory)| factory())
+    }
+
+    fn create_or_bail(&self, name: &str) -> Result<Box<dyn Pane>> {
+        match self.create(name) {
+            Some(pane) => Ok(pane),
+            None => bail!("no pane named `{name}` is registered"),
+        }
+    }
+}
+
+fn key_action(event: &Event, keymap: &Keymap) -> Option<Action> {
+    match event {
+        Event::Key(key @ KeyEvent { kind, .. }) if *kind != KeyEventKind::Release => {
+            keymap.action(*key)
+        }
+        _ => None,
+    }
+}
+
+/// Panes shown one at a time, under a row of tabs.
+pub struct PaneTabs {
+    panes: Vec<Box<dyn Pane>>,
+    selected: usize,
+    theme: Theme,
+}
+
+impl Default for PaneTabs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PaneTabs {
+    pub fn new() -> Self {
+        Self {
+            panes: Vec::new(),
+            selected: 0,
+            theme: Theme::default(),
+        }
+    }
+
+    /// Creates a tab for each of the named panes, in order.
+    pub fn from_registry<'n>(
+        registry: &PaneRegistry,
+        names: impl IntoIterator<Item = &'n str>,
+    ) -> Result<Self> {
+        let mut tabs = Self::new();
+        for name in names {
+            tabs.push(registry.create_or_bail(name)?);
+        }
+
+        Ok(tabs)
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    pub fn push(&mut self, pane: Box<dyn Pane>) {
+        self.panes.push(pane);
+    }
+
+    pub fn len(&self) -> usize {
+        self.panes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.panes.is_empty()
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn select(&mut self, index: usize) {
+        self.selected = index.min(self.panes.len().saturating_sub(1));
+    }
+
+    pub fn selected_pane_mut(&mut self) -> Option<&mut (dyn Pane + 'static)> {
+        self.panes.get_mut(self.selected).map(|pane| &mut **pane)
+    }
+
+    /// Switches tabs on [`Action::NextTab`] and [`Action::PreviousTab`], and passes other events
+    /// to the selected pane. Returns whether the event was consumed.
+    pub fn handle_event(&mut self, event: &Event, keymap: &Keymap) -> bool {
+        let len = self.panes.len();
+        match key_action(event, keymap) {
+            Some(Action::NextTab) if len > 0 => self.selected = (self.selected + 1) % len,
+            Some(Action::PreviousTab) if len > 0 => {
+                self.selected = self.selected.checked_sub(1).unwrap_or(len - 1)
+            }
+            _ => {
+                return self
+                    .selected_pane_mut()
+                    .is_some_and(|pane| pane.handle_event(event))
+            }
+        }
+
+        true
+    }
+
+    /// Updates every pane, including hidden ones.
+    pub fn update(&mut self) {
+        for pane in &mut self.panes {
+            pane.update();
+        }
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        if area.height == 0 || self.panes.is_empty() {
+            return;
+        }
+
+        let titles: Vec<Cow<str>> = self
+            .panes
+            .iter()
+            .map(|pane| Cow::Owned(pane.title().into_owned()))
+            .collect();
+        let header = Rect { height: 1, ..area };
+        Tabs::new(&titles, self.selected)
+            .theme(&self.theme)
+            .render(header, buf);
+
+        let body = Rect {
+            y: area.y + 1,
+            height: area.height - 1,
+            ..area
+        };
+        let context = PaneContext {
+            theme: &self.theme,
+            focused: true,
+        };
+        self.panes[self.selected].render(body, buf, &context);
+    }
+}
+
+/// Smallest size a pane can be shrunk to, in cells: its borders and a row or column of content.
+const MIN_PANE_SIZE: u32 = 3;
+
+/// Panes shown side by side, each in a bordered block, one of them focused at a time.
+///
+/// Panes are laid out with their constraints until resized: from then on, they keep their
+/// relative sizes, which can be [saved](Self::save) in a session.
+pub struct PaneLayout {
+    direction: Direction,
+    panes: Vec<(Constraint, Box<dyn Pane>)>,
+    focused: usize,
+    /// Relative sizes of the panes, once resized.
+    ratios: Option<Vec<u32>>,
+    maximized: Option<usize>,
+    /// Sizes of the panes along the direction of the layout, as of the last render.
+    sizes: Vec<u16>,
+    theme: Theme,
+}
+
+impl PaneLayout {
+    pub fn new(direction: Direction) -> Self {
+        Self {
+            direction,
+            panes: Vec::new(),
+            focused: 0,
+            ratios: None,
+            maximized: None,
+            sizes: Vec::new(),
+            theme: Theme::default(),
+        }
+    }
+
+    /// Lays out the named panes with the given constraints, in order.
+    pub fn from_registry<'n>(
+        registry: &PaneRegistry,
+        direction: Direction,
+        panes: impl IntoIterator<Item = (&'n str, Constraint)>,
+    ) -> Result<Self> {
+        let mut layout = Self::new(direction);
+        for (name, constraint) in panes {
+            layout = layout.pane(constraint, registry.create_or_bail(name)?);
+        }
+
+        Ok(layout)
+    }
+
+    pub fn pane(mut self, constraint: Constraint, pane: Box<dyn Pane>) -> Self {
+        self.panes.push((constraint, pane));
+        self.ratios = None;
+        self
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    pub fn focused(&self) -> usize {
+        self.focused
+    }
+
+    /// Focuses a pane. If a pane is maximized, the newly focused one is maximized instead.
+    pub fn focus(&mut self, index: usize) {
+        self.focused = index.min(self.panes.len().saturating_sub(1));
+        if self.maximized.is_some() {
+            self.maximized = Some(self.focused);
+        }
+    }
+
+    /// Relative sizes of the panes, in order, if they were resized.
+    pub fn ratios(&self) -> Option<&[u32]> {
+        self.ratios.as_deref()
+    }
+
+    /// Lays out the panes with the given relative sizes rather than their constraints. Ignored
+    /// unless there is one per pane, and at least one isn't 0. Ratios adding up to more than
+    /// `u32::MAX`, e.g. from a tampered session, are ignored too.
+    pub fn set_ratios(&mut self, ratios: Vec<u32>) {
+        let sum = ratios
+            .iter()
+            .try_fold(0u32, |sum, &ratio| sum.checked_add(ratio));
+        if ratios.len() == self.panes.len() && sum.is_some_and(|sum| sum > 0) {
+            self.ratios = Some(ratios);
+        }
+    }
+
+    /// Lays out the panes with their constraints again.
+    pub fn reset_ratios(&mut self) {
+        self.ratios = None;
+    }
+
+    /// Index of the maximized pane, if any.
+    pub fn maximized(&self) -> Option<usize> {
+        self.maximized
+    }
+
+    /// Maximizes the focused pane, hiding the others, or shows them all again if a pane is
+    /// maximized.
+    pub fn toggle_maximize(&mut self) {
+        self.maximized = match self.maximized {
+            Some(_) => None,
+            None => (self.focused < self.panes.len()).then_some(self.focused),
+        };
+    }
+
+    /// Grows the focused pane by `amount` cells, or shrinks it if negative, at the expense of or
+    /// to the benefit of the others. Panes don't shrink below a few cells. Does nothing before the
+    /// first render, or while a pane is maximized.
+    pub fn resize(&mut self, amount: i32) {
+        if self.maximized.is_some() || self.sizes.len() != self.panes.len() || amount == 0 {
+            return;
+        }
+
+        let mut sizes: Vec<u32> = match &self.ratios {
+            // rendered sizes round the ratios, so that repeated resizes would drift
+            Some(ratios) if self.sizes.iter().all(|&size| size > 0) => {
+                let total = self.sizes.iter().map(|&size| size as u64).sum::<u64>();
+                let sum = ratios.iter().map(|&ratio| ratio as u64).sum::<u64>().max(1);
+                ratios
+                    .iter()
+                    .map(|&ratio| (ratio as u64 * total / sum) as u32)
+                    .collect()
+            }
+            _ => self.sizes.iter().map(|&size| size as u32).collect(),
+        };
+
+        // take from the largest other pane, or give to the next one (the previous for the last)
+        let focused = self.focused;
+        let other = if amount > 0 {
+            (0..sizes.len())
+                .filter(|&index| index != focused)
+                .max_by_key(|&index| sizes[index])
+        } else if focused + 1 < sizes.len() {
+            Some(focused + 1)
+        } else {
+            focused.checked_sub(1)
+        };
+        let Some(other) = other else {
+            return;
+        };
+
+        let (from, to) = if amount > 0 {
+            (other, focused)
+        } else {
+            (focused, other)
+        };
+        let amount = amount
+            .unsigned_abs()
+            .min(sizes[from].saturating_sub(MIN_PANE_SIZE));
+        sizes[from] -= amount;
+        sizes[to] += amount;
+        self.ratios = Some(sizes);
+    }
+
+    /// Records the relative sizes of the panes and the maximized one in `session`.
+    pub fn save(&self, session: &mut Session) {
+        session.pane_ratios = self.ratios.clone().unwrap_or_default();
+        session.maximized_pane = self.maximized;
+    }
+
+    /// Restores the relative sizes of the panes and the maximized one from `session`, as saved
+    /// by [`save`](Self::save) with the same panes.
+    pub fn restore(&mut self, session: &Session) {
+        self.ratios = None;
+        self.set_ratios(session.pane_ratios.clone());
+        self.maximized = session
+            .maximized_pane
+            .filter(|&index| index < self.panes.len());
+    }
+
+    /// Moves the focus on [`Action::FocusNext`] and [`Action::FocusPrevious`], resizes the
+    /// focused pane by a twentieth of the layout on [`Action::Grow`] and [`Action::Shrink`],
+    /// maximizes it on [`Action::Maximize`], and passes other events to the focused pane.
+    /// Returns whether the event was consumed.
+    pub fn handle_event(&mut self, event: &Event, keymap: &Keymap) -> bool {
+        let len = self.panes.len();
+        let step = (self.sizes.iter().map(|&size| size as i32).sum::<i32>() / 20).max(1);
+        match key_action(event, keymap) {
+            Some(Action::FocusNext) if len > 0 => self.focus((self.focused + 1) % len),
+            Some(Action::FocusPrevious) if len > 0 => {
+                self.focus(self.focused.checked_sub(1).unwrap_or(len - 1))
+            }
+            Some(Action::Grow) if len > 0 => self.resize(step),
+            Some(Action::Shrink) if len > 0 => self.resize(-step),
+            Some(Action::Maximize) if len > 0 => self.toggle_maximize(),
+            _ => {
+                return self
+                    .panes
+                    .get_mut(self.focused)
+                    .is_some_and(|(_, pane)| pane.handle_event(event))
+            }
+        }
+
+        true
+    }
+
+    pub fn update(&mut self) {
+        for (_, pane) in &mut self.panes {
+            pane.update();
+        }
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let constraints: Vec<_> = match &self.ratios {
+            Some(ratios) => {
+                let sum = ratios.iter().sum::<u32>().max(1);
+                ratios
+                    .iter()
+                    .map(|&ratio| Constraint::Ratio(ratio, sum))
+                    .collect()
+            }
+            None => self
+                .panes
+                .iter()
+                .map(|(constraint, _)| *constraint)
+                .collect(),
+        };
+        let areas = Layout::default()
+            .direction(self.direction)
+            .constraints(constraints)
+            .split(area);
+
+        // update state
+        self.sizes = areas
+            .iter()
+            .map(|area| match self.direction {
+                Direction::Horizontal => area.width,
+                Direction::Vertical => area.height,
+            })
+            .collect();
+
+        for (index, ((_, pane), pane_area)) in self.panes.iter_mut().zip(areas.iter()).enumerate() {
+            let area = match self.maximized {
+                Some(maximized) if maximized == index => area,
+                Some(_) => continue,
+                None => *pane_area,
+            };
+
+            let focused = index == self.focused;
+            let border = if focused {
+                self.theme.border_focused
+            } else {
+                self.theme.border
+            };
+
+            let block = Block::new()
+                .borders(Borders::ALL)
+                .border_style(border)
+                .title(Span::styled(pane.title().into_owned(), self.theme.title));
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+
+            let context = PaneContext {
+                theme: &self.theme,
+                focused,
+            };
+            pane.render(inner_area, buf, &context);
+        }
+    }
+}
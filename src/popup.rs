@@ -0,0 +1,25 @@
+use ratatui::prelude::{Buffer, Rect, *};
+
+/// Returns a rectangle of at most `width` by `height` cells centered inside `area`.
+pub(crate) fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Dims every cell inside `area`, so that whatever is drawn on top of it stands out.
+pub(crate) fn dim(area: Rect, buf: &mut Buffer) {
+    buf.set_style(
+        area,
+        Style::default()
+            .fg(Color::DarkGray)
+            .bg(Color::Reset)
+            .add_modifier(Modifier::DIM),
+    );
+}
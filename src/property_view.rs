@@ -0,0 +1,266 @@
+use crate::{keymap::Action, Keymap, Theme};
+use crossterm::event::{KeyEvent, KeyEventKind};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, Row, StatefulWidget, Table, Widget},
+};
+use std::{borrow::Cow, collections::HashMap};
+
+/// An item of a [`PropertyView`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Property<'a> {
+    /// A header grouping the entries that follow it.
+    Group(Cow<'a, str>),
+    Entry {
+        name: Cow<'a, str>,
+        value: Line<'a>,
+    },
+}
+
+impl<'a> Property<'a> {
+    pub fn group(name: impl Into<Cow<'a, str>>) -> Self {
+        Self::Group(name.into())
+    }
+
+    pub fn entry(name: impl Into<Cow<'a, str>>, value: impl Into<Line<'a>>) -> Self {
+        Self::Entry {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
+
+pub struct PropertyViewState {
+    /// Index of the item under the cursor.
+    pub selected: usize,
+
+    offset: usize,
+    page: usize,
+    /// Amount of items, as of the last render.
+    len: usize,
+    /// Name of the entry under the cursor, as of the last render.
+    selected_name: Option<String>,
+
+    /// Values changes are highlighted against, keyed by group and name.
+    baseline: HashMap<(String, String), String>,
+    /// Whether the baseline should be retaken on the next render.
+    retake_baseline: bool,
+}
+
+impl Default for PropertyViewState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PropertyViewState {
+    pub fn new() -> Self {
+        Self {
+            selected: 0,
+            offset: 0,
+            page: 1,
+            len: 0,
+            selected_name: None,
+            baseline: HashMap::new(),
+            retake_baseline: true,
+        }
+    }
+
+    /// Name of the entry under the cursor, as of the last render.
+    pub fn selected_name(&self) -> Option<&str> {
+        self.selected_name.as_deref()
+    }
+
+    /// Makes the values of the next render the ones changes are highlighted against. Call this
+    /// whenever the inspected state advances, e.g. after stepping an emulator.
+    pub fn mark_baseline(&mut self) {
+        self.retake_baseline = true;
+    }
+
+    /// Handles a key event with the [standard keymap](Keymap::standard).
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        self.handle_key_with(key, Keymap::standard())
+    }
+
+    /// Handles a key event, translated to an action by `keymap`. Returns whether the event was
+    /// consumed.
+    pub fn handle_key_with(&mut self, key: KeyEvent, keymap: &Keymap) -> bool {
+        if key.kind == KeyEventKind::Release {
+            return false;
+        }
+
+        let last = self.len.saturating_sub(1);
+        match keymap.action(key) {
+            Some(Action::Up) => self.selected = self.selected.saturating_sub(1),
+            Some(Action::Down) => self.selected = (self.selected + 1).min(last),
+            Some(Action::PageUp) => self.selected = self.selected.saturating_sub(self.page),
+            Some(Action::PageDown) => self.selected = (self.selected + self.page).min(last),
+            Some(Action::Home) => self.selected = 0,
+            Some(Action::End) => self.selected = last,
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+/// An ordered list of named values with optional group headers, highlighting the values that
+/// changed since the last baseline.
+pub struct PropertyView<'a> {
+    properties: &'a [Property<'a>],
+
+    /// Style of values that changed.
+    changed_style: Style,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> PropertyView<'a> {
+    pub fn new(properties: &'a [Property<'a>]) -> Self {
+        Self {
+            properties,
+            changed_style: Style::default().black().on_light_yellow(),
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn changed_style(self, changed_style: Style) -> Self {
+        Self {
+            changed_style,
+            ..self
+        }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+
+    fn line_text(line: &Line) -> String {
+        line.spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+}
+
+impl<'a> StatefulWidget for PropertyView<'a> {
+    type State = PropertyViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = self.wrap_in_block(area, buf);
+
+        // update state
+        state.len = self.properties.len();
+        state.selected = state.selected.min(state.len.saturating_sub(1));
+        state.page = (area.height as usize).max(1);
+        if state.selected < state.offset {
+            state.offset = state.selected;
+        } else if state.selected >= state.offset + state.page {
+            state.offset = state.selected + 1 - state.page;
+        }
+        state.selected_name = match self.properties.get(state.selected) {
+            Some(Property::Entry { name, .. }) => Some(name.to_string()),
+            _ => None,
+        };
+
+        // compare every value against the baseline, since changes may be scrolled out of view
+        let mut group = "";
+        let mut changed = Vec::with_capacity(self.properties.len());
+        let mut values = HashMap::new();
+        for property in self.properties {
+            match property {
+                Property::Group(name) => {
+                    group = name;
+                    changed.push(false);
+                }
+                Property::Entry { name, value } => {
+                    let key = (group.to_owned(), name.to_string());
+                    let text = Self::line_text(value);
+                    changed.push(
+                        !state.retake_baseline
+                            && state.baseline.get(&key).is_some_and(|old| *old != text),
+                    );
+                    values.insert(key, text);
+                }
+            }
+        }
+        if state.retake_baseline {
+            state.baseline = values;
+            state.retake_baseline = false;
+        }
+
+        let name_width = self
+            .properties
+            .iter()
+            .map(|property| match property {
+                Property::Entry { name, .. } => name.chars().count() + 2,
+                Property::Group(name) => name.chars().count(),
+            })
+            .max()
+            .unwrap_or(0) as u16;
+
+        let rows = self
+            .properties
+            .iter()
+            .zip(changed)
+            .enumerate()
+            .skip(state.offset)
+            .take(state.page)
+            .map(|(index, (property, changed))| {
+                let row = match property {
+                    Property::Group(name) => Row::new([Line::from(Span::styled(
+                        name.clone(),
+                        self.theme.header.underlined(),
+                    ))]),
+                    Property::Entry { name, value } => {
+                        let mut value = value.clone();
+                        if changed {
+                            value.patch_style(self.changed_style);
+                        }
+
+                        Row::new([
+                            Line::from(vec![
+                                Span::raw("  "),
+                                Span::styled(name.clone(), Style::default().light_blue()),
+                            ]),
+                            value,
+                        ])
+                    }
+                };
+
+                if index == state.selected {
+                    row.style(self.theme.selection)
+                } else {
+                    row
+                }
+            });
+
+        let constraints = [Constraint::Length(name_width), Constraint::Min(1)];
+        let table = Table::new(rows).widths(&constraints);
+        Widget::render(table, area, buf);
+    }
+}
@@ -0,0 +1,336 @@
+use crate::{
+    instruction_view::{InstructionProvider, INSTRUCTION_SIZE},
+    memory_view::{MemoryProvider, MemoryWriter, Region},
+    Address,
+};
+use std::{
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError, TryLockError},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Default time after which fetched values are fetched again.
+const DEFAULT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A read for the worker: start address and amount of values.
+type Request = (Address, usize);
+
+struct WorkerState<T> {
+    queued: Option<Request>,
+    in_flight: Option<Request>,
+    /// Start of the last completed fetch, its values and when it completed.
+    start: Address,
+    values: Vec<Option<T>>,
+    fetched_at: Option<Instant>,
+    /// Bumped on invalidation, so that fetches started before it are discarded.
+    generation: u64,
+    shutdown: bool,
+}
+
+struct Shared<T> {
+    state: Mutex<WorkerState<T>>,
+    wake: Condvar,
+}
+
+/// Runs reads on a worker thread and serves the values of the last completed one.
+struct Fetcher<T> {
+    shared: Arc<Shared<T>>,
+    /// Distance between the addresses of consecutive values.
+    stride: u64,
+    interval: Duration,
+}
+
+impl<T> Fetcher<T>
+where
+    T: Clone + Send + 'static,
+{
+    fn spawn<F>(stride: usize, interval: Duration, mut read: F) -> Self
+    where
+        F: FnMut(Address, &mut [Option<T>]) + Send + 'static,
+    {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(WorkerState {
+                queued: None,
+                in_flight: None,
+                start: 0,
+                values: Vec::new(),
+                fetched_at: None,
+                generation: 0,
+                shutdown: false,
+            }),
+            wake: Condvar::new(),
+        });
+
+        let worker = shared.clone();
+        thread::Builder::new()
+            .name("anton-fetch".to_owned())
+            .spawn(move || loop {
+                let (start, len, generation) = {
+                    let mut state = worker.state.lock().unwrap_or_else(PoisonError::into_inner);
+                    while state.queued.is_none() && !state.shutdown {
+                        state = worker
+                            .wake
+                            .wait(state)
+                            .unwrap_or_else(PoisonError::into_inner);
+                    }
+
+                    if state.shutdown {
+                        return;
+                    }
+
+                    state.in_flight = state.queued.take();
+                    let (start, len) = state.in_flight.unwrap();
+                    (start, len, state.generation)
+                };
+
+                let mut values = Vec::new();
+                values.resize_with(len, || None);
+                read(start, &mut values);
+
+                let mut state = worker.state.lock().unwrap_or_else(PoisonError::into_inner);
+                state.in_flight = None;
+                if state.generation != generation {
+                    // invalidated while reading: the values might predate a write
+                    continue;
+                }
+
+                state.start = start;
+                state.values = values;
+                state.fetched_at = Some(Instant::now());
+            })
+            .expect("failed to spawn the fetch worker");
+
+        Self {
+            shared,
+            stride: stride.max(1) as u64,
+            interval,
+        }
+    }
+
+    /// Fills the buffer with the fetched values, and asks the worker for the requested ones
+    /// unless they were fetched recently or are being fetched already.
+    fn read(&self, pointer: Address, buf: &mut [Option<T>]) {
+        let mut state = self.state();
+        for (index, value) in buf.iter_mut().enumerate() {
+            let address = pointer as u64 + index as u64 * self.stride;
+            *value = address
+                .checked_sub(state.start as u64)
+                .filter(|offset| offset % self.stride == 0)
+                .and_then(|offset| state.values.get((offset / self.stride) as usize))
+                .cloned()
+                .flatten();
+        }
+
+        let request = (pointer, buf.len());
+        let fresh = state
+            .fetched_at
+            .is_some_and(|time| time.elapsed() < self.interval);
+        let fetched = fresh && (state.start, state.values.len()) == request;
+        let pending = state.in_flight == Some(request) || state.queued == Some(request);
+        if !fetched && !pending {
+            state.queued = Some(request);
+            self.shared.wake.notify_one();
+        }
+    }
+
+    /// Whether the value at `address` is being fetched.
+    fn is_stale(&self, address: Address) -> bool {
+        let state = self.state();
+        [state.queued, state.in_flight]
+            .into_iter()
+            .flatten()
+            .any(|(start, len)| {
+                (address as u64)
+                    .checked_sub(start as u64)
+                    .is_some_and(|offset| offset < len as u64 * self.stride)
+            })
+    }
+
+    /// Drops the fetched values, so that the next read asks for them again. The fetch in
+    /// progress, if any, is discarded once it completes.
+    fn invalidate(&self) {
+        let mut state = self.state();
+        state.fetched_at = None;
+        state.in_flight = None;
+        state.generation = state.generation.wrapping_add(1);
+    }
+
+    /// Locks the state shared with the worker. A panic while it was locked, e.g. in a read of
+    /// the underlying provider, leaves it consistent, so poisoning is ignored.
+    fn state(&self) -> MutexGuard<'_, WorkerState<T>> {
+        self.shared
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Drop for Fetcher<T> {
+    fn drop(&mut self) {
+        // the worker isn't joined: it might be stuck in a slow read, and exits right after it
+        self.shared
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .shutdown = true;
+        self.shared.wake.notify_one();
+    }
+}
+
+/// A decorator that reads the underlying provider on a worker thread, so that slow providers
+/// don't block rendering.
+///
+/// Reads return the values of the last completed fetch right away, and ask the worker for the
+/// requested values if they are missing or older than the refresh interval. Values being fetched
+/// are reported as stale, which views draw with [`Theme::stale`](crate::Theme::stale).
+///
+/// Values may change at any time, so views read them on every frame: wrap the underlying
+/// provider in a [`ThrottledProvider`](super::ThrottledProvider) to avoid fetching a whole
+/// window again after scrolling. To stage writes, wrap this provider in an
+/// [`EditBuffer`](super::EditBuffer) rather than the other way around.
+pub struct BackgroundProvider<P> {
+    inner: Arc<Mutex<P>>,
+    fetcher: Fetcher<u8>,
+    regions: Mutex<Vec<Region>>,
+}
+
+impl<P> BackgroundProvider<P>
+where
+    P: MemoryProvider + Send + 'static,
+{
+    /// Spawns a worker reading `inner`, with a refresh interval of 500ms.
+    pub fn new(inner: P) -> Self {
+        Self::with_interval(inner, DEFAULT_INTERVAL)
+    }
+
+    /// Spawns a worker reading `inner`, fetching values again once older than `interval`.
+    pub fn with_interval(inner: P, interval: Duration) -> Self {
+        let regions = inner.regions();
+        let inner = Arc::new(Mutex::new(inner));
+
+        let provider = inner.clone();
+        let fetcher = Fetcher::spawn(1, interval, move |pointer, buf| {
+            provider
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .read_to_buf(pointer, buf)
+        });
+
+        Self {
+            inner,
+            fetcher,
+            regions: Mutex::new(regions),
+        }
+    }
+}
+
+impl<P> BackgroundProvider<P> {
+    pub fn interval(&self) -> Duration {
+        self.fetcher.interval
+    }
+
+    /// Runs `f` with the underlying provider, waiting for the read in progress if any. The
+    /// fetched values are dropped afterwards.
+    pub fn with_inner<R>(&self, f: impl FnOnce(&mut P) -> R) -> R {
+        let result = f(&mut self.inner.lock().unwrap_or_else(PoisonError::into_inner));
+        self.fetcher.invalidate();
+        result
+    }
+
+    /// Drops the fetched values, so that they are fetched again on the next read.
+    pub fn invalidate(&self) {
+        self.fetcher.invalidate();
+    }
+}
+
+impl<P> MemoryProvider for BackgroundProvider<P>
+where
+    P: MemoryProvider,
+{
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]) {
+        self.fetcher.read(pointer, buf);
+    }
+
+    /// The regions of the underlying provider, or the last known ones while it is busy.
+    fn regions(&self) -> Vec<Region> {
+        let mut regions = self.regions.lock().unwrap_or_else(PoisonError::into_inner);
+        let inner = match self.inner.try_lock() {
+            Ok(inner) => Some(inner),
+            Err(TryLockError::Poisoned(poisoned)) => Some(poisoned.into_inner()),
+            Err(TryLockError::WouldBlock) => None,
+        };
+        if let Some(inner) = inner {
+            *regions = inner.regions();
+        }
+
+        regions.clone()
+    }
+
+    fn is_stale(&self, address: Address) -> bool {
+        self.fetcher.is_stale(address)
+    }
+}
+
+impl<P> MemoryWriter for BackgroundProvider<P>
+where
+    P: MemoryWriter,
+{
+    /// Writes to the underlying provider, waiting for the read in progress if any.
+    fn write_from_buf(&mut self, pointer: Address, buf: &[u8]) -> bool {
+        let written = self
+            .inner
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .write_from_buf(pointer, buf);
+        self.fetcher.invalidate();
+        written
+    }
+}
+
+/// Like [`BackgroundProvider`], for instructions.
+pub struct BackgroundInstructionProvider<I> {
+    fetcher: Fetcher<I>,
+}
+
+impl<I> BackgroundInstructionProvider<I>
+where
+    I: Clone + Send + 'static,
+{
+    /// Spawns a worker reading `inner`, with a refresh interval of 500ms.
+    pub fn new<P>(inner: P) -> Self
+    where
+        P: InstructionProvider<I> + Send + 'static,
+    {
+        Self::with_interval(inner, DEFAULT_INTERVAL)
+    }
+
+    /// Spawns a worker reading `inner`, fetching instructions again once older than `interval`.
+    pub fn with_interval<P>(inner: P, interval: Duration) -> Self
+    where
+        P: InstructionProvider<I> + Send + 'static,
+    {
+        let fetcher = Fetcher::spawn(INSTRUCTION_SIZE as usize, interval, move |pointer, buf| {
+            inner.read_to_buf(pointer, buf)
+        });
+
+        Self { fetcher }
+    }
+
+    /// Drops the fetched instructions, so that they are fetched again on the next read.
+    pub fn invalidate(&self) {
+        self.fetcher.invalidate();
+    }
+}
+
+impl<I> InstructionProvider<I> for BackgroundInstructionProvider<I>
+where
+    I: Clone + Send + 'static,
+{
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<I>]) {
+        self.fetcher.read(pointer, buf);
+    }
+
+    fn is_stale(&self, address: Address) -> bool {
+        self.fetcher.is_stale(address)
+    }
+}
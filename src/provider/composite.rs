@@ -0,0 +1,335 @@
+use crate::{
+    memory_view::{MemoryProvider, MemoryWriter, Permissions, Region},
+    Address,
+};
+use eyre::{ensure, Result};
+
+/// Something that can be both read from and written to.
+pub trait MemoryDevice: MemoryProvider + MemoryWriter {}
+
+impl<T: MemoryProvider + MemoryWriter> MemoryDevice for T {}
+
+enum Child<'a> {
+    ReadOnly(Box<dyn MemoryProvider + 'a>),
+    ReadWrite(Box<dyn MemoryDevice + 'a>),
+}
+
+impl<'a> Child<'a> {
+    fn provider(&self) -> &dyn MemoryProvider {
+        match self {
+            Self::ReadOnly(provider) => provider.as_ref(),
+            Self::ReadWrite(device) => device.as_ref(),
+        }
+    }
+}
+
+/// A range of addresses routed to a child provider.
+pub struct CompositeMapping<'a> {
+    start: Address,
+    size: u64,
+    /// Size of the child's window. Accesses past it wrap around, mirroring the child.
+    mirror: u64,
+    name: Option<String>,
+    permissions: Permissions,
+    child: Child<'a>,
+}
+
+impl<'a> CompositeMapping<'a> {
+    pub fn name(&mut self, name: impl Into<String>) -> &mut Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Only exposes the first `size` bytes of the child, repeating them across the mapping.
+    pub fn mirror(&mut self, size: u64) -> &mut Self {
+        self.mirror = size.clamp(1, self.size);
+        self
+    }
+
+    /// Overrides the permissions reported for the mapping.
+    pub fn permissions(&mut self, permissions: Permissions) -> &mut Self {
+        self.permissions = permissions;
+        self
+    }
+
+    fn end(&self) -> u64 {
+        self.start as u64 + self.size
+    }
+
+    /// Address inside of the child, and how many bytes can be accessed from there without
+    /// reaching the end of the mapping or of a mirror.
+    fn translate(&self, address: u64) -> (Address, u64) {
+        let offset = (address - self.start as u64) % self.mirror;
+        let run = (self.mirror - offset).min(self.end() - address);
+        (offset as Address, run)
+    }
+}
+
+/// A provider routing address ranges to child providers, e.g. a ROM file here, a RAM buffer there
+/// and an MMIO callback elsewhere. Children are addressed relative to the start of their mapping.
+#[derive(Default)]
+pub struct CompositeProvider<'a> {
+    /// Non-overlapping mappings, sorted by address.
+    mappings: Vec<CompositeMapping<'a>>,
+}
+
+impl<'a> CompositeProvider<'a> {
+    pub fn new() -> Self {
+        Self {
+            mappings: Vec::new(),
+        }
+    }
+
+    /// Maps a read-only child at `start..start + size`. Fails if the range overlaps another
+    /// mapping.
+    pub fn map(
+        &mut self,
+        start: Address,
+        size: u64,
+        provider: impl MemoryProvider + 'a,
+    ) -> Result<&mut CompositeMapping<'a>> {
+        self.insert(
+            start,
+            size,
+            Permissions::READ_ONLY,
+            Child::ReadOnly(Box::new(provider)),
+        )
+    }
+
+    /// Maps a writable child at `start..start + size`. Fails if the range overlaps another
+    /// mapping.
+    pub fn map_writable(
+        &mut self,
+        start: Address,
+        size: u64,
+        device: impl MemoryDevice + 'a,
+    ) -> Result<&mut CompositeMapping<'a>> {
+        self.insert(
+            start,
+            size,
+            Permissions::READ_WRITE,
+            Child::ReadWrite(Box::new(device)),
+        )
+    }
+
+    fn insert(
+        &mut self,
+        start: Address,
+        size: u64,
+        permissions: Permissions,
+        child: Child<'a>,
+    ) -> Result<&mut CompositeMapping<'a>> {
+        ensure!(size > 0, "empty mapping at {start:08X}");
+        let end = start as u64 + size;
+        ensure!(
+            end <= Address::MAX as u64 + 1,
+            "mapping at {start:08X} goes past the end of the address space"
+        );
+
+        let index = self
+            .mappings
+            .partition_point(|mapping| mapping.start < start);
+        let overlapping = [index.checked_sub(1), Some(index)]
+            .into_iter()
+            .flatten()
+            .filter_map(|index| self.mappings.get(index))
+            .find(|mapping| (mapping.start as u64) < end && (start as u64) < mapping.end());
+        if let Some(other) = overlapping {
+            let name = other.name.as_deref().unwrap_or("unnamed");
+            eyre::bail!(
+                "mapping {start:08X}..{end:08X} overlaps {name} at {:08X}..{:08X}",
+                other.start,
+                other.end()
+            );
+        }
+
+        self.mappings.insert(
+            index,
+            CompositeMapping {
+                start,
+                size,
+                mirror: size,
+                name: None,
+                permissions,
+                child,
+            },
+        );
+        Ok(&mut self.mappings[index])
+    }
+
+    /// Removes the mapping starting at `start`. Returns whether there was one.
+    pub fn unmap(&mut self, start: Address) -> bool {
+        match self
+            .mappings
+            .binary_search_by_key(&start, |mapping| mapping.start)
+        {
+            Ok(index) => {
+                self.mappings.remove(index);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Index of the mapping containing `address`, or of the next one after it.
+    fn mapping_index(&self, address: u64) -> Result<usize, usize> {
+        let index = self
+            .mappings
+            .partition_point(|mapping| mapping.end() <= address);
+        match self.mappings.get(index) {
+            Some(mapping) if mapping.start as u64 <= address => Ok(index),
+            _ => Err(index),
+        }
+    }
+}
+
+impl<'a> MemoryProvider for CompositeProvider<'a> {
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]) {
+        let mut offset = 0;
+        while offset < buf.len() {
+            let address = pointer as u64 + offset as u64;
+            let remaining = (buf.len() - offset) as u64;
+
+            let run = match self.mapping_index(address) {
+                Ok(index) => {
+                    let mapping = &self.mappings[index];
+                    let (child_address, run) = mapping.translate(address);
+                    let run = run.min(remaining) as usize;
+                    mapping
+                        .child
+                        .provider()
+                        .read_to_buf(child_address, &mut buf[offset..offset + run]);
+                    run
+                }
+                Err(next) => {
+                    let gap = self
+                        .mappings
+                        .get(next)
+                        .map_or(remaining, |mapping| mapping.start as u64 - address);
+                    let run = gap.min(remaining) as usize;
+                    buf[offset..offset + run].fill(None);
+                    run
+                }
+            };
+
+            offset += run;
+        }
+    }
+
+    fn is_modified(&self, address: Address) -> bool {
+        self.mapping_index(address as u64).is_ok_and(|index| {
+            let mapping = &self.mappings[index];
+            let (child_address, _) = mapping.translate(address as u64);
+            mapping.child.provider().is_modified(child_address)
+        })
+    }
+
+    fn is_stale(&self, address: Address) -> bool {
+        self.mapping_index(address as u64).is_ok_and(|index| {
+            let mapping = &self.mappings[index];
+            let (child_address, _) = mapping.translate(address as u64);
+            mapping.child.provider().is_stale(child_address)
+        })
+    }
+
+    fn regions(&self) -> Vec<Region> {
+        self.mappings
+            .iter()
+            .map(|mapping| {
+                let region =
+                    Region::new(mapping.start, mapping.size).permissions(mapping.permissions);
+                match &mapping.name {
+                    Some(name) => region.name(name.clone()),
+                    None => region,
+                }
+            })
+            .collect()
+    }
+}
+
+impl<'a> MemoryWriter for CompositeProvider<'a> {
+    fn write_from_buf(&mut self, pointer: Address, buf: &[u8]) -> bool {
+        let mut all_written = true;
+        let mut offset = 0;
+        while offset < buf.len() {
+            let address = pointer as u64 + offset as u64;
+            let remaining = (buf.len() - offset) as u64;
+
+            match self.mapping_index(address) {
+                Ok(index) => {
+                    let mapping = &mut self.mappings[index];
+                    let (child_address, run) = mapping.translate(address);
+                    let run = run.min(remaining) as usize;
+                    all_written &= match &mut mapping.child {
+                        Child::ReadWrite(device) => {
+                            device.write_from_buf(child_address, &buf[offset..offset + run])
+                        }
+                        Child::ReadOnly(_) => false,
+                    };
+                    offset += run;
+                }
+                Err(next) => {
+                    let gap = self
+                        .mappings
+                        .get(next)
+                        .map_or(remaining, |mapping| mapping.start as u64 - address);
+                    all_written = false;
+                    offset += gap.min(remaining) as usize;
+                }
+            }
+        }
+
+        all_written
+    }
+}
+
+/// A provider backed by callbacks, e.g. to emulate memory-mapped registers. Accesses are made one
+/// byte at a time.
+pub struct CallbackProvider<'a> {
+    read: Box<dyn Fn(Address) -> Option<u8> + 'a>,
+    write: Option<Box<dyn FnMut(Address, u8) -> bool + 'a>>,
+}
+
+impl<'a> CallbackProvider<'a> {
+    pub fn new(read: impl Fn(Address) -> Option<u8> + 'a) -> Self {
+        Self {
+            read: Box::new(read),
+            write: None,
+        }
+    }
+
+    /// Handles writes. Without it, every write fails.
+    pub fn on_write(self, write: impl FnMut(Address, u8) -> bool + 'a) -> Self {
+        Self {
+            write: Some(Box::new(write)),
+            ..self
+        }
+    }
+}
+
+impl<'a> MemoryProvider for CallbackProvider<'a> {
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]) {
+        for (offset, value) in buf.iter_mut().enumerate() {
+            *value = pointer
+                .checked_add(offset as Address)
+                .and_then(|address| (self.read)(address));
+        }
+    }
+}
+
+impl<'a> MemoryWriter for CallbackProvider<'a> {
+    fn write_from_buf(&mut self, pointer: Address, buf: &[u8]) -> bool {
+        let Some(write) = &mut self.write else {
+            return false;
+        };
+
+        let mut all_written = true;
+        for (offset, value) in buf.iter().enumerate() {
+            all_written &= pointer
+                .checked_add(offset as Address)
+                .is_some_and(|address| write(address, *value));
+        }
+
+        all_written
+    }
+}
@@ -0,0 +1,396 @@
+use crate::{
+    memory_view::{MemoryProvider, Permissions, Region},
+    register_view::{RegisterProvider, RegisterValue},
+    Address,
+};
+use eyre::{bail, ensure, eyre, Context, Result};
+use std::path::Path;
+
+const PT_LOAD: u32 = 1;
+const PT_NOTE: u32 = 4;
+const ET_CORE: u16 = 4;
+const NT_PRSTATUS: u32 = 1;
+/// Note listing the files mapped into the process.
+const NT_FILE: u32 = 0x4649_4C45;
+
+const EM_386: u16 = 3;
+const EM_ARM: u16 = 40;
+const EM_X86_64: u16 = 62;
+const EM_AARCH64: u16 = 183;
+
+/// Names of the general registers in `NT_PRSTATUS`, in order, for the supported architectures.
+fn register_names(machine: u16) -> Option<&'static [&'static str]> {
+    Some(match machine {
+        EM_X86_64 => &[
+            "r15", "r14", "r13", "r12", "rbp", "rbx", "r11", "r10", "r9", "r8", "rax", "rcx",
+            "rdx", "rsi", "rdi", "orig_rax", "rip", "cs", "eflags", "rsp", "ss", "fs_base",
+            "gs_base", "ds", "es", "fs", "gs",
+        ],
+        EM_386 => &[
+            "ebx", "ecx", "edx", "esi", "edi", "ebp", "eax", "ds", "es", "fs", "gs", "orig_eax",
+            "eip", "cs", "eflags", "esp", "ss",
+        ],
+        EM_AARCH64 => &[
+            "x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7", "x8", "x9", "x10", "x11", "x12", "x13",
+            "x14", "x15", "x16", "x17", "x18", "x19", "x20", "x21", "x22", "x23", "x24", "x25",
+            "x26", "x27", "x28", "x29", "x30", "sp", "pc", "pstate",
+        ],
+        EM_ARM => &[
+            "r0", "r1", "r2", "r3", "r4", "r5", "r6", "r7", "r8", "r9", "r10", "r11", "r12", "sp",
+            "lr", "pc", "cpsr", "orig_r0",
+        ],
+        _ => return None,
+    })
+}
+
+/// Reads integers out of an ELF file, honoring its class and endianness.
+struct Reader<'a> {
+    data: &'a [u8],
+    is_64: bool,
+    big_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes<const N: usize>(&self, offset: u64) -> Result<[u8; N]> {
+        let start = usize::try_from(offset)?;
+        start
+            .checked_add(N)
+            .and_then(|end| self.data.get(start..end))
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| eyre!("truncated file (reading {N} bytes at {offset:#X})"))
+    }
+
+    fn u16(&self, offset: u64) -> Result<u16> {
+        let bytes = self.bytes(offset)?;
+        Ok(if self.big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    }
+
+    fn u32(&self, offset: u64) -> Result<u32> {
+        let bytes = self.bytes(offset)?;
+        Ok(if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+
+    fn u64(&self, offset: u64) -> Result<u64> {
+        let bytes = self.bytes(offset)?;
+        Ok(if self.big_endian {
+            u64::from_be_bytes(bytes)
+        } else {
+            u64::from_le_bytes(bytes)
+        })
+    }
+
+    /// Reads a word: 8 bytes on 64-bit files, 4 otherwise.
+    fn word(&self, offset: u64) -> Result<u64> {
+        if self.is_64 {
+            self.u64(offset)
+        } else {
+            self.u32(offset).map(u64::from)
+        }
+    }
+
+    fn word_size(&self) -> u64 {
+        if self.is_64 {
+            8
+        } else {
+            4
+        }
+    }
+}
+
+/// A `PT_LOAD` segment.
+#[derive(Debug, Clone)]
+struct Segment {
+    address: u64,
+    /// Offset of the segment's data in the file.
+    offset: usize,
+    /// Amount of bytes present in the file. The rest of the segment wasn't dumped.
+    file_size: u64,
+    memory_size: u64,
+    permissions: Permissions,
+}
+
+/// A provider over an ELF core dump. Loaded segments are mapped at their virtual addresses, and
+/// the registers of each thread are taken from the `NT_PRSTATUS` notes.
+///
+/// Addresses are 32 bits wide, so the provider exposes a 4 GiB window of the dumped address space
+/// starting at [`base`](Self::base).
+pub struct CoreDumpProvider {
+    data: Vec<u8>,
+    base: u64,
+    segments: Vec<Segment>,
+    /// Files mapped into the process, as `(start, end, path)`.
+    files: Vec<(u64, u64, String)>,
+    /// General registers of each thread.
+    threads: Vec<Vec<RegisterValue>>,
+    /// Index of the thread whose registers are exposed.
+    thread: usize,
+}
+
+impl CoreDumpProvider {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).wrap_err_with(|| format!("reading {}", path.display()))?;
+        Self::from_bytes(data)
+    }
+
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self> {
+        ensure!(data.starts_with(b"\x7FELF"), "not an ELF file");
+        let reader = Reader {
+            data: &data,
+            is_64: data.get(4) == Some(&2),
+            big_endian: data.get(5) == Some(&2),
+        };
+
+        ensure!(reader.u16(16)? == ET_CORE, "not a core dump");
+        let machine = reader.u16(18)?;
+        let (phoff, phentsize, phnum) = if reader.is_64 {
+            (reader.u64(32)?, reader.u16(54)?, reader.u16(56)?)
+        } else {
+            (reader.u32(28)? as u64, reader.u16(42)?, reader.u16(44)?)
+        };
+
+        let mut segments = Vec::new();
+        let mut notes = Vec::new();
+        for index in 0..phnum as u64 {
+            let header = phoff
+                .checked_add(index * phentsize as u64)
+                .ok_or_else(|| eyre!("program header {index} is out of range"))?;
+            let kind = reader.u32(header)?;
+            let (flags, offset, address, file_size, memory_size) = if reader.is_64 {
+                (
+                    reader.u32(header + 4)?,
+                    reader.u64(header + 8)?,
+                    reader.u64(header + 16)?,
+                    reader.u64(header + 32)?,
+                    reader.u64(header + 40)?,
+                )
+            } else {
+                (
+                    reader.u32(header + 24)?,
+                    reader.u32(header + 4)? as u64,
+                    reader.u32(header + 8)? as u64,
+                    reader.u32(header + 16)? as u64,
+                    reader.u32(header + 20)? as u64,
+                )
+            };
+
+            match kind {
+                PT_LOAD if address.checked_add(memory_size).is_none() => {
+                    bail!("segment at {address:#X} overflows the address space")
+                }
+                PT_LOAD => segments.push(Segment {
+                    address,
+                    offset: usize::try_from(offset)?,
+                    // truncated core files are common, so only trust what is really there
+                    file_size: file_size
+                        .min((data.len() as u64).saturating_sub(offset))
+                        .min(memory_size),
+                    memory_size,
+                    permissions: Permissions {
+                        read: flags & 4 != 0,
+                        write: flags & 2 != 0,
+                        execute: flags & 1 != 0,
+                    },
+                }),
+                PT_NOTE => notes.push((offset, file_size)),
+                _ => (),
+            }
+        }
+        segments.sort_by_key(|segment| segment.address);
+
+        let mut files = Vec::new();
+        let mut threads = Vec::new();
+        for (offset, size) in notes {
+            Self::parse_notes(&reader, machine, offset, size, &mut files, &mut threads)?;
+        }
+
+        Ok(Self {
+            data,
+            base: 0,
+            segments,
+            files,
+            threads,
+            thread: 0,
+        })
+    }
+
+    fn parse_notes(
+        reader: &Reader,
+        machine: u16,
+        offset: u64,
+        size: u64,
+        files: &mut Vec<(u64, u64, String)>,
+        threads: &mut Vec<Vec<RegisterValue>>,
+    ) -> Result<()> {
+        // sizes are read as 32-bit values, so aligning them can't overflow
+        let align = |value: u64| (value + 3) & !3;
+        let out_of_range = || eyre!("note at {offset:#X} is out of range");
+
+        let end = offset.checked_add(size).ok_or_else(out_of_range)?;
+        let mut current = offset;
+        while end.saturating_sub(current) >= 12 {
+            let name_size = reader.u32(current)? as u64;
+            let desc_size = reader.u32(current + 4)? as u64;
+            let kind = reader.u32(current + 8)?;
+            let desc = (current + 12)
+                .checked_add(align(name_size))
+                .ok_or_else(out_of_range)?;
+            current = desc
+                .checked_add(align(desc_size))
+                .ok_or_else(out_of_range)?;
+
+            match kind {
+                NT_PRSTATUS => {
+                    let Some(names) = register_names(machine) else {
+                        continue;
+                    };
+
+                    // offset of pr_reg in struct elf_prstatus
+                    let registers = desc
+                        .checked_add(if reader.is_64 { 112 } else { 72 })
+                        .ok_or_else(out_of_range)?;
+                    let word = reader.word_size();
+                    let values = names
+                        .iter()
+                        .enumerate()
+                        .map(|(index, name)| {
+                            let register = registers
+                                .checked_add(index as u64 * word)
+                                .ok_or_else(out_of_range)?;
+                            Ok(RegisterValue {
+                                name: (*name).into(),
+                                bits: word as u32 * 8,
+                                value: Some(reader.word(register)?),
+                            })
+                        })
+                        .collect::<Result<_>>()?;
+                    threads.push(values);
+                }
+                NT_FILE => {
+                    let word = reader.word_size();
+                    let count = reader.word(desc)?;
+                    let entries = desc.checked_add(2 * word).ok_or_else(out_of_range)?;
+                    let mut names = count
+                        .checked_mul(3 * word)
+                        .and_then(|len| entries.checked_add(len))
+                        .ok_or_else(out_of_range)?;
+                    for index in 0..count {
+                        // before the names, which don't overflow
+                        let entry = entries + index * 3 * word;
+                        let start = reader.word(entry)?;
+                        let end = reader.word(entry + word)?;
+
+                        let name_start = usize::try_from(names)?;
+                        let name_end = reader
+                            .data
+                            .get(name_start..)
+                            .ok_or_else(|| eyre!("truncated file (reading NT_FILE names)"))?
+                            .iter()
+                            .position(|byte| *byte == 0)
+                            .map(|len| name_start + len)
+                            .ok_or_else(|| eyre!("unterminated name in NT_FILE note"))?;
+                        let name = String::from_utf8_lossy(&reader.data[name_start..name_end]);
+                        names = name_end as u64 + 1;
+
+                        files.push((start, end, name.into_owned()));
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Moves the window into the dumped address space so that address `0` maps to `base`.
+    pub fn with_base(self, base: u64) -> Self {
+        Self { base, ..self }
+    }
+
+    pub fn base(&self) -> u64 {
+        self.base
+    }
+
+    /// Amount of threads with registers in the dump.
+    pub fn thread_count(&self) -> usize {
+        self.threads.len()
+    }
+
+    /// Selects the thread whose registers are exposed.
+    pub fn select_thread(&mut self, thread: usize) -> Result<()> {
+        if thread >= self.threads.len() {
+            bail!("no thread {thread} in core dump");
+        }
+
+        self.thread = thread;
+        Ok(())
+    }
+
+    fn file_name(&self, address: u64) -> Option<&str> {
+        self.files
+            .iter()
+            .find(|(start, end, _)| (*start..*end).contains(&address))
+            .map(|(_, _, name)| name.as_str())
+    }
+}
+
+impl MemoryProvider for CoreDumpProvider {
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]) {
+        let start = self.base + pointer as u64;
+        for (offset, value) in buf.iter_mut().enumerate() {
+            let address = start + offset as u64;
+            let index = self
+                .segments
+                .partition_point(|segment| segment.address <= address);
+
+            *value = index
+                .checked_sub(1)
+                .map(|index| &self.segments[index])
+                .and_then(|segment| {
+                    let within = address - segment.address;
+                    (within < segment.file_size)
+                        .then(|| self.data.get(segment.offset + within as usize).copied())
+                        .flatten()
+                });
+        }
+    }
+
+    fn regions(&self) -> Vec<Region> {
+        let window = self.base..self.base + Address::MAX as u64 + 1;
+        self.segments
+            .iter()
+            .filter_map(|segment| {
+                let end = (segment.address + segment.memory_size).min(window.end);
+                let start = segment.address.max(window.start);
+                if start >= end {
+                    return None;
+                }
+
+                let region = Region::new((start - self.base) as Address, end - start)
+                    .permissions(segment.permissions);
+                Some(match self.file_name(segment.address) {
+                    Some(name) => region.name(name),
+                    None => region,
+                })
+            })
+            .collect()
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(0)
+    }
+}
+
+impl RegisterProvider for CoreDumpProvider {
+    fn registers(&self) -> Vec<RegisterValue> {
+        self.threads.get(self.thread).cloned().unwrap_or_default()
+    }
+}
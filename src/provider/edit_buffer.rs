@@ -0,0 +1,197 @@
+use crate::{
+    memory_view::{MemoryProvider, MemoryWriter, Region},
+    search::parse_pattern,
+    Address, Error,
+};
+use std::{collections::BTreeMap, ops::RangeInclusive};
+
+/// A staging layer over a provider. Writes are recorded instead of being applied, and reads see
+/// them overlaid on the underlying values until they are committed or rolled back.
+#[derive(Debug, Clone, Default)]
+pub struct EditBuffer<W> {
+    inner: W,
+    pending: BTreeMap<Address, u8>,
+    /// Bumped whenever `pending` changes.
+    edits: u64,
+}
+
+impl<W> EditBuffer<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            pending: BTreeMap::new(),
+            edits: 0,
+        }
+    }
+
+    pub fn inner(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Returns the underlying provider, discarding pending writes.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Whether there are pending writes.
+    pub fn is_dirty(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Amount of bytes with pending writes.
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// A counter bumped whenever the pending writes change. Unlike the
+    /// [generation](MemoryProvider::generation), it is known even if the underlying provider
+    /// can't tell when its values change, e.g. to
+    /// [invalidate](crate::memory_view::MemoryViewState::invalidate) views that only read it every
+    /// so often.
+    pub fn edits(&self) -> u64 {
+        self.edits
+    }
+
+    /// Pending writes, as `(address, value)`, sorted by address.
+    pub fn pending(&self) -> impl Iterator<Item = (Address, u8)> + '_ {
+        self.pending
+            .iter()
+            .map(|(address, value)| (*address, *value))
+    }
+
+    /// Discards every pending write.
+    pub fn rollback(&mut self) {
+        self.pending.clear();
+        self.edits += 1;
+    }
+
+    /// Discards the pending writes inside of `start..=end`.
+    pub fn rollback_range(&mut self, start: Address, end: Address) {
+        let addresses: Vec<_> = self.pending.range(start..=end).map(|(a, _)| *a).collect();
+        for address in addresses {
+            self.pending.remove(&address);
+        }
+
+        self.edits += 1;
+    }
+
+    /// Stages writes repeating `pattern` over `range`, e.g. `&[0]` to zero it. The last
+    /// repetition is cut short if the range isn't a multiple of the pattern in length. Does
+    /// nothing if the pattern is empty.
+    pub fn fill_range(&mut self, range: RangeInclusive<Address>, pattern: &[u8]) {
+        if pattern.is_empty() || range.is_empty() {
+            return;
+        }
+
+        for (address, value) in range.zip(pattern.iter().cycle()) {
+            self.pending.insert(address, *value);
+        }
+
+        self.edits += 1;
+    }
+
+    /// Stages the bytes of `text` starting at `address`. `text` is parsed like
+    /// [search queries](parse_pattern): hex bytes optionally separated by whitespace, e.g.
+    /// `DE AD BE EF`, or text wrapped in double quotes. Returns the amount of bytes staged, which
+    /// is less than given if they run past the end of the address space, or `None` if `text`
+    /// can't be parsed.
+    pub fn paste_hex(&mut self, address: Address, text: &str) -> Option<usize> {
+        let bytes = parse_pattern(text)?;
+        let room = u64::from(Address::MAX - address) + 1;
+        let len = (bytes.len() as u64).min(room) as usize;
+
+        self.write_from_buf(address, &bytes[..len]);
+        Some(len)
+    }
+
+    /// Pending writes grouped into runs of contiguous addresses.
+    fn runs(&self) -> Vec<(Address, Vec<u8>)> {
+        let mut runs: Vec<(Address, Vec<u8>)> = Vec::new();
+        for (address, value) in &self.pending {
+            match runs.last_mut() {
+                Some((start, values))
+                    if start.checked_add(values.len() as Address) == Some(*address) =>
+                {
+                    values.push(*value)
+                }
+                _ => runs.push((*address, vec![*value])),
+            }
+        }
+
+        runs
+    }
+}
+
+impl<W: MemoryWriter> EditBuffer<W> {
+    /// Applies every pending write to the underlying provider. Writes that fail stay pending.
+    /// Returns whether all of them succeeded.
+    pub fn commit(&mut self) -> bool {
+        let mut all_written = true;
+        for (start, values) in self.runs() {
+            if self.inner.write_from_buf(start, &values) {
+                let end = start + (values.len() - 1) as Address;
+                self.rollback_range(start, end);
+            } else {
+                all_written = false;
+            }
+        }
+
+        all_written
+    }
+}
+
+impl<W: MemoryProvider> MemoryProvider for EditBuffer<W> {
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]) {
+        self.inner.read_to_buf(pointer, buf);
+        if buf.is_empty() {
+            return;
+        }
+
+        let end = pointer.saturating_add((buf.len() - 1) as Address);
+        for (address, value) in self.pending.range(pointer..=end) {
+            buf[(address - pointer) as usize] = Some(*value);
+        }
+    }
+
+    fn regions(&self) -> Vec<Region> {
+        self.inner.regions()
+    }
+
+    fn is_modified(&self, address: Address) -> bool {
+        self.pending.contains_key(&address)
+    }
+
+    fn is_stale(&self, address: Address) -> bool {
+        self.inner.is_stale(address)
+    }
+
+    fn generation(&self) -> Option<u64> {
+        // both counters only grow, so their sum changes whenever either does
+        self.inner
+            .generation()
+            .map(|generation| generation.wrapping_add(self.edits))
+    }
+
+    fn error(&self) -> Option<Error> {
+        self.inner.error()
+    }
+}
+
+impl<W> MemoryWriter for EditBuffer<W> {
+    /// Stages the write. Always succeeds.
+    fn write_from_buf(&mut self, pointer: Address, buf: &[u8]) -> bool {
+        for (offset, value) in buf.iter().enumerate() {
+            let Some(address) = pointer.checked_add(offset as Address) else {
+                break;
+            };
+            self.pending.insert(address, *value);
+        }
+
+        self.edits += 1;
+        true
+    }
+}
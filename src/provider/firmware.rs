@@ -0,0 +1,403 @@
+use crate::{
+    memory_view::{MemoryProvider, MemoryWriter, Region},
+    Address,
+};
+use eyre::{bail, ensure, eyre, Context, Result};
+use std::{collections::BTreeMap, fmt::Write as _, path::Path};
+
+/// Amount of data bytes per record when emitting files.
+const RECORD_LEN: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FirmwareFormat {
+    /// Intel HEX.
+    IntelHex,
+    /// Motorola S-record.
+    Srec,
+}
+
+fn parse_hex_bytes(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() & 1 != 0 {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+fn push_hex(line: &mut String, bytes: &[u8]) {
+    for byte in bytes {
+        let _ = write!(line, "{byte:02X}");
+    }
+}
+
+/// A provider over a firmware image in Intel HEX or Motorola S-record format. Only the addresses
+/// the file specifies are mapped, everything else reads as `None`.
+#[derive(Debug, Clone, Default)]
+pub struct FirmwareProvider {
+    /// Contiguous blocks of data, keyed by their start address. Blocks never overlap or touch.
+    blocks: BTreeMap<Address, Vec<u8>>,
+    /// Start (entry point) address, if the file specifies one.
+    start_address: Option<u32>,
+}
+
+impl FirmwareProvider {
+    /// Opens a file, detecting its format from its contents.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("reading {}", path.display()))?;
+
+        match Self::detect(&text) {
+            Some(FirmwareFormat::IntelHex) => Self::parse_ihex(&text),
+            Some(FirmwareFormat::Srec) => Self::parse_srec(&text),
+            None => bail!("{} is neither Intel HEX nor S-record", path.display()),
+        }
+    }
+
+    /// Detects the format of a file from its first record.
+    pub fn detect(text: &str) -> Option<FirmwareFormat> {
+        match text.trim_start().as_bytes().first()? {
+            b':' => Some(FirmwareFormat::IntelHex),
+            b'S' => Some(FirmwareFormat::Srec),
+            _ => None,
+        }
+    }
+
+    pub fn parse_ihex(text: &str) -> Result<Self> {
+        let mut image = Self::default();
+        // upper bits of addresses, from extended segment and linear address records
+        let mut offset = 0u32;
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let record = line
+                .strip_prefix(':')
+                .and_then(parse_hex_bytes)
+                .filter(|record| record.len() >= 5 && record.len() == record[0] as usize + 5)
+                .ok_or_else(|| eyre!("line {}: malformed record", number + 1))?;
+            ensure!(
+                record.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) == 0,
+                "line {}: bad checksum",
+                number + 1
+            );
+
+            let address = u16::from_be_bytes([record[1], record[2]]) as u32;
+            let data = &record[4..record.len() - 1];
+            let word = || -> Result<u32> {
+                ensure!(data.len() >= 2, "line {}: short record", number + 1);
+                Ok(u16::from_be_bytes([data[0], data[1]]) as u32)
+            };
+
+            match record[3] {
+                0x00 => image.insert(offset.wrapping_add(address), data),
+                0x01 => break,
+                0x02 => offset = word()? << 4,
+                0x04 => offset = word()? << 16,
+                0x03 | 0x05 => {
+                    ensure!(data.len() == 4, "line {}: short record", number + 1);
+                    let value = u32::from_be_bytes([data[0], data[1], data[2], data[3]]);
+                    // start segment addresses are CS:IP pairs
+                    image.start_address = Some(if record[3] == 0x03 {
+                        ((value >> 16) << 4) + (value & 0xFFFF)
+                    } else {
+                        value
+                    });
+                }
+                kind => bail!("line {}: unknown record type {kind:02X}", number + 1),
+            }
+        }
+
+        Ok(image)
+    }
+
+    pub fn parse_srec(text: &str) -> Result<Self> {
+        let mut image = Self::default();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (kind, record) = line
+                .strip_prefix('S')
+                .and_then(|rest| Some((rest.chars().next()?, parse_hex_bytes(rest.get(1..)?)?)))
+                .filter(|(_, record)| record.len() >= 2 && record.len() == record[0] as usize + 1)
+                .ok_or_else(|| eyre!("line {}: malformed record", number + 1))?;
+            ensure!(
+                record.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) == 0xFF,
+                "line {}: bad checksum",
+                number + 1
+            );
+
+            let address_len = match kind {
+                '0' | '1' | '5' | '9' => 2,
+                '2' | '6' | '8' => 3,
+                '3' | '7' => 4,
+                kind => bail!("line {}: unknown record type S{kind}", number + 1),
+            };
+            let body = &record[1..record.len() - 1];
+            ensure!(
+                body.len() >= address_len,
+                "line {}: short record",
+                number + 1
+            );
+
+            let (address, data) = body.split_at(address_len);
+            let address = address
+                .iter()
+                .fold(0u32, |address, byte| address << 8 | *byte as u32);
+
+            match kind {
+                '1' | '2' | '3' => image.insert(address, data),
+                '7' | '8' | '9' => image.start_address = Some(address),
+                _ => (),
+            }
+        }
+
+        Ok(image)
+    }
+
+    /// Start (entry point) address specified by the file, if any.
+    pub fn start_address(&self) -> Option<u32> {
+        self.start_address
+    }
+
+    /// Writes data at `address`, mapping it if it wasn't.
+    pub fn insert(&mut self, address: Address, data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+
+        let start = address as u64;
+        let end = start + data.len() as u64;
+
+        // merge with every block that overlaps or touches the new data
+        let touching: Vec<(Address, Vec<u8>)> = self
+            .blocks
+            .range(..=end.min(Address::MAX as u64) as Address)
+            .filter(|(block, bytes)| **block as u64 + bytes.len() as u64 >= start)
+            .map(|(block, _)| *block)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .filter_map(|block| Some((block, self.blocks.remove(&block)?)))
+            .collect();
+
+        let merged_start = touching
+            .first()
+            .map_or(start, |(block, _)| start.min(*block as u64));
+        let merged_end = touching.last().map_or(end, |(block, bytes)| {
+            end.max(*block as u64 + bytes.len() as u64)
+        });
+
+        let mut merged = vec![0; (merged_end - merged_start) as usize];
+        for (block, bytes) in &touching {
+            let offset = (*block as u64 - merged_start) as usize;
+            merged[offset..offset + bytes.len()].copy_from_slice(bytes);
+        }
+        let offset = (start - merged_start) as usize;
+        merged[offset..offset + data.len()].copy_from_slice(data);
+
+        self.blocks.insert(merged_start as Address, merged);
+    }
+
+    /// The block containing `address`, along with the offset of `address` inside of it.
+    fn block_at(&self, address: u64) -> Option<(Address, usize)> {
+        let (start, bytes) = self
+            .blocks
+            .range(..=address.min(Address::MAX as u64) as Address)
+            .next_back()?;
+        let offset = (address - *start as u64) as usize;
+        (offset < bytes.len()).then_some((*start, offset))
+    }
+
+    /// Emits the image in the given format.
+    pub fn emit(&self, format: FirmwareFormat) -> String {
+        match format {
+            FirmwareFormat::IntelHex => self.to_ihex(),
+            FirmwareFormat::Srec => self.to_srec(),
+        }
+    }
+
+    /// Data records of the blocks, stopping at the end of the address space.
+    fn records(&self) -> impl Iterator<Item = (u32, &[u8])> {
+        self.blocks.iter().flat_map(|(start, bytes)| {
+            bytes
+                .chunks(RECORD_LEN)
+                .enumerate()
+                .map_while(move |(index, chunk)| {
+                    let offset = u32::try_from(index * RECORD_LEN).ok()?;
+                    Some((start.checked_add(offset)?, chunk))
+                })
+        })
+    }
+
+    pub fn to_ihex(&self) -> String {
+        fn record(out: &mut String, kind: u8, address: u16, data: &[u8]) {
+            let mut bytes = vec![data.len() as u8];
+            bytes.extend(address.to_be_bytes());
+            bytes.push(kind);
+            bytes.extend_from_slice(data);
+            let checksum = bytes
+                .iter()
+                .fold(0u8, |sum, byte| sum.wrapping_add(*byte))
+                .wrapping_neg();
+            bytes.push(checksum);
+
+            out.push(':');
+            push_hex(out, &bytes);
+            out.push('\n');
+        }
+
+        let mut out = String::new();
+        let mut upper = 0;
+        for (address, mut data) in self.records() {
+            let mut address = address;
+            while !data.is_empty() {
+                if address >> 16 != upper {
+                    upper = address >> 16;
+                    record(&mut out, 0x04, 0, &(upper as u16).to_be_bytes());
+                }
+
+                // records can't cross 64 KiB boundaries
+                let room = 0x1_0000 - (address & 0xFFFF) as usize;
+                let (now, rest) = data.split_at(data.len().min(room));
+                record(&mut out, 0x00, address as u16, now);
+                address = address.wrapping_add(now.len() as u32);
+                data = rest;
+            }
+        }
+        if let Some(start) = self.start_address {
+            record(&mut out, 0x05, 0, &start.to_be_bytes());
+        }
+        record(&mut out, 0x01, 0, &[]);
+
+        out
+    }
+
+    pub fn to_srec(&self) -> String {
+        fn record(out: &mut String, kind: char, address: u32, data: &[u8]) {
+            let mut bytes = vec![(data.len() + 5) as u8];
+            bytes.extend(address.to_be_bytes());
+            bytes.extend_from_slice(data);
+            let checksum = !bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+            bytes.push(checksum);
+
+            out.push('S');
+            out.push(kind);
+            push_hex(out, &bytes);
+            out.push('\n');
+        }
+
+        let mut out = String::new();
+        for (address, data) in self.records() {
+            record(&mut out, '3', address, data);
+        }
+        record(&mut out, '7', self.start_address.unwrap_or(0), &[]);
+
+        out
+    }
+}
+
+impl MemoryProvider for FirmwareProvider {
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]) {
+        for (offset, value) in buf.iter_mut().enumerate() {
+            *value = self
+                .block_at(pointer as u64 + offset as u64)
+                .and_then(|(start, offset)| self.blocks[&start].get(offset).copied());
+        }
+    }
+
+    fn regions(&self) -> Vec<Region> {
+        self.blocks
+            .iter()
+            .map(|(start, bytes)| Region::new(*start, bytes.len() as u64))
+            .collect()
+    }
+}
+
+impl MemoryWriter for FirmwareProvider {
+    /// Writes over mapped data. Fails, without writing anything, if any byte isn't mapped.
+    fn write_from_buf(&mut self, pointer: Address, buf: &[u8]) -> bool {
+        let mapped =
+            (0..buf.len() as u64).all(|offset| self.block_at(pointer as u64 + offset).is_some());
+        if !mapped {
+            return false;
+        }
+
+        self.insert(pointer, buf);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image() -> FirmwareProvider {
+        let mut image = FirmwareProvider::default();
+        // crosses a 64 KiB boundary, which Intel HEX records can't
+        image.insert(0x0000_FFF8, &(0..40).collect::<Vec<u8>>());
+        image.insert(0x0800_0000, &[0xDE, 0xAD, 0xBE, 0xEF]);
+        image.start_address = Some(0x0800_0000);
+        image
+    }
+
+    #[test]
+    fn ihex_round_trips() {
+        let image = image();
+        let parsed = FirmwareProvider::parse_ihex(&image.to_ihex()).unwrap();
+
+        assert_eq!(parsed.blocks, image.blocks);
+        assert_eq!(parsed.start_address(), image.start_address());
+    }
+
+    #[test]
+    fn srec_round_trips() {
+        let image = image();
+        let parsed = FirmwareProvider::parse_srec(&image.to_srec()).unwrap();
+
+        assert_eq!(parsed.blocks, image.blocks);
+        assert_eq!(parsed.start_address(), image.start_address());
+    }
+
+    #[test]
+    fn parses_ihex() {
+        let text = ":10010000214601360121470136007EFE09D2190140\n:00000001FF\n";
+        let image = FirmwareProvider::parse_ihex(text).unwrap();
+
+        let mut buf = [None; 4];
+        image.read_to_buf(0x00FF, &mut buf);
+        assert_eq!(buf, [None, Some(0x21), Some(0x46), Some(0x01)]);
+        assert!(FirmwareProvider::parse_ihex(&text.replace("40\n", "41\n")).is_err());
+    }
+
+    #[test]
+    fn parses_srec() {
+        let text = "S00F000068656C6C6F202020202000003C\n\
+                    S11F00007C0802A6900100049421FFF07C6C1B787C8C23783C6000003863000026\n\
+                    S9030000FC\n";
+        let image = FirmwareProvider::parse_srec(text).unwrap();
+
+        let mut buf = [None; 4];
+        image.read_to_buf(0x0000, &mut buf);
+        assert_eq!(buf, [Some(0x7C), Some(0x08), Some(0x02), Some(0xA6)]);
+        assert_eq!(image.start_address(), Some(0));
+    }
+
+    #[test]
+    fn records_stop_at_the_end_of_the_address_space() {
+        let mut image = FirmwareProvider::default();
+        image.insert(Address::MAX - 15, &[0xAA; 32]);
+
+        let records: Vec<_> = image.records().map(|(address, _)| address).collect();
+        assert_eq!(records, [Address::MAX - 15]);
+    }
+}
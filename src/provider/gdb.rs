@@ -0,0 +1,837 @@
+use crate::{
+    execution::{ExecutionControl, StopReason},
+    memory_view::{MemoryProvider, MemoryWriter, Permissions, Region},
+    register_view::{RegisterProvider, RegisterValue},
+    Address, Error,
+};
+use eyre::{bail, eyre, Context, Result};
+use std::{
+    fmt::Write as _,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
+
+/// Default size of memory read and write requests, in bytes.
+const DEFAULT_CHUNK: usize = 0x200;
+/// Size below which failed reads aren't split any further, in bytes.
+const MIN_SPLIT_CHUNK: usize = 16;
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Whether a packet is console output (`O` followed by hex) rather than a reply.
+fn is_console_output(packet: &str) -> bool {
+    packet.len() > 1 && packet.starts_with('O') && packet != "OK"
+}
+
+/// Parses a stop reply packet.
+fn parse_stop_reply(reply: &str) -> Option<StopReason> {
+    let code = u8::from_str_radix(reply.get(1..3)?, 16).ok()?;
+    match reply.as_bytes().first()? {
+        b'S' | b'T' => Some(StopReason::Signal(code)),
+        b'W' => Some(StopReason::Exited(code)),
+        b'X' => Some(StopReason::Terminated(code)),
+        _ => None,
+    }
+}
+
+fn checksum(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte))
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() & 1 != 0 {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+fn encode_hex(data: &[u8]) -> String {
+    data.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+/// An entry of a target's memory map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryArea {
+    /// Absolute address of the area.
+    pub start: u64,
+    pub length: u64,
+    /// Type of memory: `ram`, `rom` or `flash`.
+    pub kind: String,
+}
+
+impl MemoryArea {
+    pub fn permissions(&self) -> Permissions {
+        if self.kind == "ram" {
+            Permissions::READ_WRITE
+        } else {
+            Permissions::READ_ONLY
+        }
+    }
+}
+
+/// Gets the value of an attribute of an XML element, given the text of its start tag.
+fn attribute<'a>(element: &'a str, name: &str) -> Option<&'a str> {
+    let pattern = format!("{name}=\"");
+    let (index, _) = element.match_indices(&pattern).find(|(index, _)| {
+        element[..*index]
+            .chars()
+            .next_back()
+            .filter(|c| !c.is_whitespace())
+            .is_none()
+    })?;
+
+    let start = index + pattern.len();
+    Some(&element[start..start + element[start..].find('"')?])
+}
+
+fn number_attribute(element: &str, name: &str) -> Option<u64> {
+    let value = attribute(element, name)?;
+    match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+/// Start tags of every element with the given name.
+fn elements<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let tag = format!("<{name} ");
+    xml.split(tag.as_str())
+        .skip(1)
+        .filter_map(|element| Some(&element[..element.find('>')?]))
+        .map(|element| element.strip_suffix('/').unwrap_or(element))
+        .collect()
+}
+
+/// A register, as described by the target description.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisterInfo {
+    pub name: String,
+    /// Width of the register, in bits.
+    pub bits: u32,
+    /// Number of the register in the protocol.
+    pub number: u32,
+}
+
+struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Connection {
+    fn open(address: &str) -> Result<Self> {
+        let socket = address
+            .to_socket_addrs()
+            .wrap_err_with(|| format!("resolving {address}"))?
+            .next()
+            .ok_or_else(|| eyre!("{address} didn't resolve to any address"))?;
+        let stream = TcpStream::connect_timeout(&socket, TIMEOUT)
+            .wrap_err_with(|| format!("connecting to {address}"))?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_nodelay(true)?;
+
+        Ok(Self {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+        })
+    }
+
+    /// Whether data can be read without blocking.
+    fn has_data(&mut self) -> Result<bool> {
+        if !self.reader.buffer().is_empty() {
+            return Ok(true);
+        }
+
+        self.writer.set_nonblocking(true)?;
+        let result = self.reader.fill_buf().map(|data| data.is_empty());
+        self.writer.set_nonblocking(false)?;
+
+        match result {
+            Ok(true) => bail!("connection closed by the stub"),
+            Ok(false) => Ok(true),
+            Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn read_byte(&mut self) -> Result<u8> {
+        let mut byte = [0];
+        self.reader.read_exact(&mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn send(&mut self, payload: &str) -> Result<()> {
+        let packet = format!("${payload}#{:02x}", checksum(payload.as_bytes()));
+        for _ in 0..3 {
+            self.writer.write_all(packet.as_bytes())?;
+            loop {
+                match self.read_byte()? {
+                    b'+' => return Ok(()),
+                    b'-' => break,
+                    // stray bytes from a previous exchange
+                    _ => continue,
+                }
+            }
+        }
+
+        bail!("packet rejected by the stub")
+    }
+
+    fn receive(&mut self) -> Result<String> {
+        loop {
+            while self.read_byte()? != b'$' {}
+
+            let mut raw = Vec::new();
+            self.reader.read_until(b'#', &mut raw)?;
+            raw.pop();
+            let mut sum = [0; 2];
+            self.reader.read_exact(&mut sum)?;
+
+            let expected = std::str::from_utf8(&sum)
+                .ok()
+                .and_then(|sum| u8::from_str_radix(sum, 16).ok());
+            if expected != Some(checksum(&raw)) {
+                self.writer.write_all(b"-")?;
+                continue;
+            }
+            self.writer.write_all(b"+")?;
+
+            // undo escaping and run-length encoding
+            let mut data = Vec::with_capacity(raw.len());
+            let mut bytes = raw.into_iter();
+            while let Some(byte) = bytes.next() {
+                match byte {
+                    b'}' => data.push(bytes.next().unwrap_or(0) ^ 0x20),
+                    b'*' => {
+                        let count = bytes.next().unwrap_or(29).saturating_sub(29);
+                        let last = data.last().copied().unwrap_or(b'0');
+                        data.resize(data.len() + count as usize, last);
+                    }
+                    _ => data.push(byte),
+                }
+            }
+
+            return String::from_utf8(data).wrap_err("non UTF-8 reply");
+        }
+    }
+}
+
+/// A client for the GDB remote serial protocol over TCP. Reconnects on the next request after the
+/// connection is lost.
+pub struct GdbClient {
+    address: String,
+    connection: Mutex<Option<Connection>>,
+    /// Maximum size of memory requests, in bytes.
+    chunk: usize,
+}
+
+impl GdbClient {
+    /// Connects to a stub listening at `address`, e.g. `localhost:1234`.
+    pub fn connect(address: impl Into<String>) -> Result<Self> {
+        let mut client = Self {
+            address: address.into(),
+            connection: Mutex::new(None),
+            chunk: DEFAULT_CHUNK,
+        };
+
+        let supported = client.request("qSupported:xmlRegisters=i386")?;
+        let packet_size = supported
+            .split(';')
+            .find_map(|feature| feature.strip_prefix("PacketSize="))
+            .and_then(|size| usize::from_str_radix(size, 16).ok());
+        if let Some(size) = packet_size {
+            // memory is sent as hex, with some room for the packet framing
+            client.chunk = (size.saturating_sub(8) / 2).clamp(1, 0x4000);
+        }
+
+        Ok(client)
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Whether the client currently holds a connection to the stub.
+    pub fn is_connected(&self) -> bool {
+        self.connection
+            .lock()
+            .is_ok_and(|connection| connection.is_some())
+    }
+
+    /// Sends a packet and waits for the reply, reconnecting first if the connection was lost.
+    /// Packets are never sent twice, as some aren't idempotent (e.g. `s` or `M`): if the request
+    /// fails once sent, e.g. because the reply timed out, the connection is dropped and the error
+    /// returned.
+    pub fn request(&self, payload: &str) -> Result<String> {
+        let mut connection = self
+            .connection
+            .lock()
+            .map_err(|_| eyre!("connection lock poisoned"))?;
+
+        // the stub may have closed the connection since the last request
+        if connection
+            .as_mut()
+            .is_some_and(|current| current.has_data().is_err())
+        {
+            *connection = None;
+        }
+
+        let current = match connection.as_mut() {
+            Some(current) => current,
+            None => connection.insert(Connection::open(&self.address)?),
+        };
+
+        let result = current.send(payload).and_then(|_| current.receive());
+        if result.is_err() {
+            *connection = None;
+        }
+
+        result
+    }
+
+    /// Reads target memory. Returns `None` if the stub reported an error.
+    pub fn read_memory(&self, address: u64, len: usize) -> Result<Option<Vec<u8>>> {
+        let reply = self.request(&format!("m{address:x},{len:x}"))?;
+        if reply.starts_with('E') || reply.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(decode_hex(&reply))
+    }
+
+    /// Writes target memory. Returns whether the stub accepted the write.
+    pub fn write_memory(&self, address: u64, data: &[u8]) -> Result<bool> {
+        let reply = self.request(&format!(
+            "M{address:x},{:x}:{}",
+            data.len(),
+            encode_hex(data)
+        ))?;
+
+        Ok(reply == "OK")
+    }
+
+    /// Reads a whole `qXfer` object. Returns `None` if the stub doesn't support it.
+    pub fn read_xfer(&self, object: &str, annex: &str) -> Result<Option<String>> {
+        let mut data = String::new();
+        loop {
+            let reply = self.request(&format!(
+                "qXfer:{object}:read:{annex}:{:x},{:x}",
+                data.len(),
+                self.chunk * 2
+            ))?;
+
+            match reply.as_bytes().first() {
+                Some(b'm') => data.push_str(&reply[1..]),
+                Some(b'l') => {
+                    data.push_str(&reply[1..]);
+                    return Ok(Some(data));
+                }
+                _ => return Ok(None),
+            }
+        }
+    }
+
+    /// The target memory map, from `qXfer:memory-map:read`. Empty if the stub doesn't provide
+    /// one.
+    pub fn memory_map(&self) -> Result<Vec<MemoryArea>> {
+        let Some(xml) = self.read_xfer("memory-map", "")? else {
+            return Ok(Vec::new());
+        };
+
+        Ok(elements(&xml, "memory")
+            .into_iter()
+            .filter_map(|element| {
+                Some(MemoryArea {
+                    start: number_attribute(element, "start")?,
+                    length: number_attribute(element, "length")?,
+                    kind: attribute(element, "type").unwrap_or("ram").to_owned(),
+                })
+            })
+            .collect())
+    }
+
+    /// The registers of the target, from the target description (`target.xml` and the files it
+    /// includes), sorted by number. Empty if the stub doesn't provide one.
+    pub fn register_layout(&self) -> Result<Vec<RegisterInfo>> {
+        let mut registers = Vec::new();
+        let mut pending = vec!["target.xml".to_owned()];
+        let mut next_number = 0;
+        while let Some(annex) = pending.pop() {
+            let Some(xml) = self.read_xfer("features", &annex)? else {
+                continue;
+            };
+
+            // included files are read in order, so push them reversed
+            let includes: Vec<_> = elements(&xml, "xi:include")
+                .into_iter()
+                .filter_map(|element| attribute(element, "href"))
+                .map(str::to_owned)
+                .collect();
+            pending.extend(includes.into_iter().rev());
+
+            for element in elements(&xml, "reg") {
+                let (Some(name), Some(bits)) = (
+                    attribute(element, "name"),
+                    number_attribute(element, "bitsize"),
+                ) else {
+                    continue;
+                };
+                let number = number_attribute(element, "regnum").unwrap_or(next_number) as u32;
+                next_number = number as u64 + 1;
+
+                registers.push(RegisterInfo {
+                    name: name.to_owned(),
+                    bits: bits as u32,
+                    number,
+                });
+            }
+        }
+
+        registers.sort_by_key(|register| register.number);
+        Ok(registers)
+    }
+
+    /// Reads every general register with the `g` packet, in the layout given. Registers the stub
+    /// didn't send, or sent as unavailable, are `None`. Values are little endian.
+    pub fn read_registers(&self, layout: &[RegisterInfo]) -> Result<Vec<Option<Vec<u8>>>> {
+        let reply = self.request("g")?;
+        if reply.starts_with('E') {
+            bail!("stub failed to read registers ({reply})");
+        }
+
+        let mut offset = 0;
+        Ok(layout
+            .iter()
+            .map(|register| {
+                let len = register.bits as usize / 4;
+                let hex = reply.get(offset..offset + len);
+                offset += len;
+                hex.and_then(decode_hex)
+            })
+            .collect())
+    }
+
+    /// Sends a packet without waiting for a reply, e.g. to resume the target.
+    pub fn send(&self, payload: &str) -> Result<()> {
+        self.with_connection(|connection| connection.send(payload))
+    }
+
+    /// Interrupts a running target. Its stop reply has to be waited for.
+    pub fn interrupt(&self) -> Result<()> {
+        self.with_connection(|connection| Ok(connection.writer.write_all(&[0x03])?))
+    }
+
+    /// Waits for a stop reply, skipping console output.
+    pub fn wait_stop(&self) -> Result<String> {
+        self.with_connection(|connection| loop {
+            let reply = connection.receive()?;
+            if !is_console_output(&reply) {
+                return Ok(reply);
+            }
+        })
+    }
+
+    /// Checks, without blocking, for a stop reply.
+    pub fn poll_stop(&self) -> Result<Option<String>> {
+        self.with_connection(|connection| {
+            while connection.has_data()? {
+                let reply = connection.receive()?;
+                if !is_console_output(&reply) {
+                    return Ok(Some(reply));
+                }
+            }
+
+            Ok(None)
+        })
+    }
+
+    fn with_connection<T>(&self, f: impl FnOnce(&mut Connection) -> Result<T>) -> Result<T> {
+        let mut connection = self
+            .connection
+            .lock()
+            .map_err(|_| eyre!("connection lock poisoned"))?;
+        let current = connection
+            .as_mut()
+            .ok_or_else(|| eyre!("not connected to {}", self.address))?;
+
+        let result = f(current);
+        if result.is_err() {
+            *connection = None;
+        }
+
+        result
+    }
+}
+
+/// A provider over the memory of a target behind a GDB stub (gdbserver, QEMU's `-s`, OpenOCD...).
+///
+/// Addresses are 32 bits wide, so the provider exposes a 4 GiB window of the target's address
+/// space starting at [`base`](Self::base).
+pub struct GdbProvider {
+    client: GdbClient,
+    base: u64,
+    /// The memory map, fetched once on connection.
+    regions: Vec<Region>,
+    /// The register layout, fetched once on connection.
+    layout: Vec<RegisterInfo>,
+
+    breakpoints: Vec<Address>,
+    /// Architecture specific kind of software breakpoints, usually their size in bytes.
+    breakpoint_kind: u32,
+    running: bool,
+    /// The program counter as of the last register read, which every stop triggers. Cached, as
+    /// views ask for it on every render.
+    program_counter: Mutex<Option<Address>>,
+    /// Why the last read failed, if it did.
+    error: Mutex<Option<Error>>,
+}
+
+impl GdbProvider {
+    /// Connects to a stub listening at `address`, e.g. `localhost:1234`.
+    pub fn connect(address: impl Into<String>) -> Result<Self> {
+        Self::from_client(GdbClient::connect(address)?, 0)
+    }
+
+    /// Wraps an existing client, mapping address `0` to `base` in the target.
+    pub fn from_client(client: GdbClient, base: u64) -> Result<Self> {
+        let layout = client.register_layout()?;
+        let mut provider = Self {
+            client,
+            base,
+            regions: Vec::new(),
+            layout,
+            breakpoints: Vec::new(),
+            breakpoint_kind: 1,
+            running: false,
+            program_counter: Mutex::new(None),
+            error: Mutex::new(None),
+        };
+        provider.refresh_regions()?;
+        provider.refresh_program_counter();
+
+        Ok(provider)
+    }
+
+    /// Moves the window into the target's address space so that address `0` maps to `base`.
+    pub fn with_base(mut self, base: u64) -> Result<Self> {
+        self.base = base;
+        self.refresh_regions()?;
+
+        Ok(self)
+    }
+
+    /// Sets the kind of software breakpoints, as expected by the `Z0` packet (e.g. 1 on x86, 2
+    /// or 4 on ARM). Defaults to 1.
+    pub fn with_breakpoint_kind(self, breakpoint_kind: u32) -> Self {
+        Self {
+            breakpoint_kind,
+            ..self
+        }
+    }
+
+    pub fn base(&self) -> u64 {
+        self.base
+    }
+
+    /// The register layout of the target.
+    pub fn register_layout(&self) -> &[RegisterInfo] {
+        &self.layout
+    }
+
+    pub fn client(&self) -> &GdbClient {
+        &self.client
+    }
+
+    /// Fetches the memory map from the stub again.
+    pub fn refresh_regions(&mut self) -> Result<()> {
+        let window = self.base..self.base + Address::MAX as u64 + 1;
+        self.regions = self
+            .client
+            .memory_map()?
+            .into_iter()
+            .filter_map(|area| {
+                // areas running past the end of the address space are bogus
+                let end = area.start.checked_add(area.length)?.min(window.end);
+                let start = area.start.max(window.start);
+                (start < end).then(|| {
+                    Region::new((start - self.base) as Address, end - start)
+                        .permissions(area.permissions())
+                        .name(area.kind)
+                })
+            })
+            .collect();
+
+        Ok(())
+    }
+}
+
+impl GdbProvider {
+    /// Reads a chunk of memory. Stubs fail whole requests that touch unmapped memory, so requests
+    /// failed by the stub are split in halves until they are small enough. Requests that didn't
+    /// get through are not retried, as the connection is likely gone.
+    fn read_chunk(&self, address: u64, buf: &mut [Option<u8>]) -> Result<()> {
+        match self.client.read_memory(address, buf.len()) {
+            Ok(Some(data)) => {
+                for (offset, value) in buf.iter_mut().enumerate() {
+                    *value = data.get(offset).copied();
+                }
+            }
+            Ok(None) if buf.len() > MIN_SPLIT_CHUNK => {
+                let (left, right) = buf.split_at_mut(buf.len() / 2);
+                let middle = address + left.len() as u64;
+                if let Err(err) = self.read_chunk(address, left) {
+                    right.fill(None);
+                    return Err(err);
+                }
+                self.read_chunk(middle, right)?;
+            }
+            Ok(None) => buf.fill(None),
+            Err(err) => {
+                buf.fill(None);
+                return Err(err);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl GdbProvider {
+    /// Translates a stop reply, recognizing stops on breakpoints.
+    fn stop_reason(&mut self, reply: &str) -> Result<StopReason> {
+        self.running = false;
+        let reason =
+            parse_stop_reply(reply).ok_or_else(|| eyre!("unexpected stop reply ({reply})"))?;
+
+        // SIGTRAP
+        let pc = self.refresh_program_counter();
+        if reason == StopReason::Signal(5) {
+            if let Some(pc) = pc {
+                if self.breakpoints.contains(&pc) {
+                    return Ok(StopReason::Breakpoint(pc));
+                }
+            }
+        }
+
+        Ok(reason)
+    }
+
+    /// Reads the registers again, which updates the cached program counter, and returns it.
+    fn refresh_program_counter(&self) -> Option<Address> {
+        self.registers();
+        self.program_counter()
+    }
+
+    fn breakpoint_packet(&self, kind: char, address: Address) -> Result<()> {
+        let reply = self.client.request(&format!(
+            "{kind}0,{:x},{:x}",
+            self.base + address as u64,
+            self.breakpoint_kind
+        ))?;
+        if reply != "OK" {
+            bail!("stub rejected breakpoint at {address:08X} ({reply})");
+        }
+
+        Ok(())
+    }
+}
+
+impl ExecutionControl for GdbProvider {
+    fn resume(&mut self) -> Result<()> {
+        self.client.send("c")?;
+        self.running = true;
+        *self
+            .program_counter
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = None;
+        Ok(())
+    }
+
+    fn halt(&mut self) -> Result<StopReason> {
+        if !self.running {
+            bail!("target isn't running");
+        }
+
+        self.client.interrupt()?;
+        let reply = self.client.wait_stop()?;
+        self.stop_reason(&reply)
+    }
+
+    fn step(&mut self) -> Result<StopReason> {
+        if self.running {
+            bail!("target is running");
+        }
+
+        let reply = self.client.request("s")?;
+        let reply = if is_console_output(&reply) {
+            self.client.wait_stop()?
+        } else {
+            reply
+        };
+        self.stop_reason(&reply)
+    }
+
+    fn poll_stop(&mut self) -> Result<Option<StopReason>> {
+        if !self.running {
+            return Ok(None);
+        }
+
+        match self.client.poll_stop()? {
+            Some(reply) => self.stop_reason(&reply).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn is_running(&self) -> bool {
+        self.running
+    }
+
+    fn set_breakpoint(&mut self, address: Address) -> Result<()> {
+        if !self.breakpoints.contains(&address) {
+            self.breakpoint_packet('Z', address)?;
+            self.breakpoints.push(address);
+        }
+
+        Ok(())
+    }
+
+    fn remove_breakpoint(&mut self, address: Address) -> Result<()> {
+        if let Some(index) = self.breakpoints.iter().position(|b| *b == address) {
+            self.breakpoint_packet('z', address)?;
+            self.breakpoints.remove(index);
+        }
+
+        Ok(())
+    }
+
+    fn breakpoints(&self) -> Vec<Address> {
+        self.breakpoints.clone()
+    }
+
+    fn program_counter(&self) -> Option<Address> {
+        *self
+            .program_counter
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl RegisterProvider for GdbProvider {
+    fn registers(&self) -> Vec<RegisterValue> {
+        // the stub doesn't answer while the target runs
+        let values = if self.running {
+            None
+        } else {
+            self.client.read_registers(&self.layout).ok()
+        };
+
+        let registers: Vec<_> = self
+            .layout
+            .iter()
+            .enumerate()
+            .filter(|(_, register)| register.bits <= 64)
+            .map(|(index, register)| {
+                let value = values
+                    .as_ref()
+                    .and_then(|values| values.get(index)?.as_ref())
+                    .map(|bytes| {
+                        let mut value = [0; 8];
+                        value[..bytes.len().min(8)].copy_from_slice(&bytes[..bytes.len().min(8)]);
+                        u64::from_le_bytes(value)
+                    });
+
+                RegisterValue {
+                    name: register.name.clone().into(),
+                    bits: register.bits,
+                    value,
+                }
+            })
+            .collect();
+
+        let program_counter = registers
+            .iter()
+            .find(|register| matches!(register.name.as_ref(), "pc" | "rip" | "eip"))
+            .and_then(|register| register.value?.checked_sub(self.base))
+            .and_then(|pc| Address::try_from(pc).ok());
+        *self
+            .program_counter
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = program_counter;
+
+        registers
+    }
+}
+
+impl MemoryProvider for GdbProvider {
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]) {
+        // the stub doesn't answer while the target runs
+        if self.running {
+            buf.fill(None);
+            return;
+        }
+
+        let start = self.base + pointer as u64;
+        let mut failure = None;
+        for (index, chunk) in buf.chunks_mut(self.client.chunk).enumerate() {
+            let address = start + (index * self.client.chunk) as u64;
+            if failure.is_some() {
+                chunk.fill(None);
+                continue;
+            }
+
+            // don't bother the stub with chunks outside of its memory map
+            let chunk_start = address - self.base;
+            let chunk_end = chunk_start + chunk.len() as u64;
+            let mapped = self.regions.is_empty()
+                || self
+                    .regions
+                    .iter()
+                    .any(|region| (region.start as u64) < chunk_end && chunk_start < region.end());
+            if mapped {
+                failure = self.read_chunk(address, chunk).err();
+            } else {
+                chunk.fill(None);
+            }
+        }
+
+        *self.error.lock().unwrap_or_else(PoisonError::into_inner) = failure.map(Error::provider);
+    }
+
+    fn regions(&self) -> Vec<Region> {
+        self.regions.clone()
+    }
+
+    fn error(&self) -> Option<Error> {
+        if self.running {
+            return Some(Error::Provider("target is running".to_owned()));
+        }
+
+        self.error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl MemoryWriter for GdbProvider {
+    fn write_from_buf(&mut self, pointer: Address, buf: &[u8]) -> bool {
+        if self.running {
+            return false;
+        }
+
+        let start = self.base + pointer as u64;
+        buf.chunks(self.client.chunk)
+            .enumerate()
+            .all(|(index, chunk)| {
+                let address = start + (index * self.client.chunk) as u64;
+                self.client.write_memory(address, chunk).unwrap_or(false)
+            })
+    }
+}
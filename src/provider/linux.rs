@@ -0,0 +1,193 @@
+use crate::{
+    memory_view::{MemoryProvider, MemoryWriter, Permissions, Region},
+    Address,
+};
+use eyre::{Context, Result};
+use std::{
+    fs::{File, OpenOptions},
+    os::unix::fs::FileExt,
+};
+
+const PAGE_SIZE: u64 = 4096;
+
+/// A provider over the memory of a live process, read through `/proc/<pid>/mem` or, if that isn't
+/// accessible, `process_vm_readv`.
+///
+/// Addresses are 32 bits wide, so the provider exposes a 4 GiB window of the process' address
+/// space starting at [`base`](Self::base).
+pub struct LinuxProcessProvider {
+    pid: u32,
+    base: u64,
+    mem: Option<File>,
+}
+
+impl LinuxProcessProvider {
+    /// Attaches to the process with the given pid. Requires ptrace access to it.
+    pub fn attach(pid: u32) -> Result<Self> {
+        let path = format!("/proc/{pid}/mem");
+        let mem = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&path)
+            .or_else(|_| File::open(&path))
+            .ok();
+
+        let provider = Self { pid, base: 0, mem };
+        if provider.mem.is_none() {
+            // make sure the process exists and that process_vm_readv is usable instead
+            std::fs::metadata(format!("/proc/{pid}"))
+                .wrap_err_with(|| format!("no process with pid {pid}"))?;
+        }
+
+        Ok(provider)
+    }
+
+    /// Moves the window into the process' address space so that address `0` maps to `base`.
+    pub fn with_base(self, base: u64) -> Self {
+        Self { base, ..self }
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    pub fn base(&self) -> u64 {
+        self.base
+    }
+
+    /// Every mapping of the process, as listed in `/proc/<pid>/maps`. Addresses are absolute.
+    pub fn maps(&self) -> Result<Vec<Mapping>> {
+        let path = format!("/proc/{}/maps", self.pid);
+        let maps = std::fs::read_to_string(&path).wrap_err_with(|| format!("reading {path}"))?;
+
+        Ok(maps.lines().filter_map(Mapping::parse).collect())
+    }
+
+    fn read_vm(&self, address: u64, buf: &mut [u8]) -> usize {
+        let local = libc::iovec {
+            iov_base: buf.as_mut_ptr().cast(),
+            iov_len: buf.len(),
+        };
+        let remote = libc::iovec {
+            iov_base: address as *mut libc::c_void,
+            iov_len: buf.len(),
+        };
+
+        // SAFETY: the local iovec points to `buf`, which is valid for writes of its length. the
+        // remote one is only interpreted by the kernel, in the address space of the target.
+        let read =
+            unsafe { libc::process_vm_readv(self.pid as libc::pid_t, &local, 1, &remote, 1, 0) };
+        read.max(0) as usize
+    }
+
+    /// Reads as much as possible starting at `address`. Returns the amount of bytes read.
+    fn read_raw(&self, address: u64, buf: &mut [u8]) -> usize {
+        match &self.mem {
+            Some(mem) => mem.read_at(buf, address).unwrap_or(0),
+            None => self.read_vm(address, buf),
+        }
+    }
+}
+
+impl MemoryProvider for LinuxProcessProvider {
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]) {
+        let start = self.base + pointer as u64;
+        let mut bytes = vec![0; buf.len()];
+
+        // reads stop at the first unmapped page, so retry page by page past it
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let address = start + offset as u64;
+            let read = self.read_raw(address, &mut bytes[offset..]);
+            for (value, byte) in buf[offset..offset + read].iter_mut().zip(&bytes[offset..]) {
+                *value = Some(*byte);
+            }
+            offset += read;
+
+            // skip the rest of the page that failed
+            let address = start + offset as u64;
+            let skip = (PAGE_SIZE - address % PAGE_SIZE) as usize;
+            let end = (offset + skip).min(bytes.len());
+            if offset < end {
+                buf[offset..end].fill(None);
+            }
+            offset = end;
+        }
+    }
+
+    fn regions(&self) -> Vec<Region> {
+        let window = self.base..self.base + (Address::MAX as u64 + 1);
+        let Ok(maps) = self.maps() else {
+            return Vec::new();
+        };
+
+        maps.into_iter()
+            .filter_map(|mapping| {
+                let start = mapping.start.max(window.start);
+                let end = mapping.end.min(window.end);
+                if start >= end {
+                    return None;
+                }
+
+                let region = Region::new((start - self.base) as Address, end - start)
+                    .permissions(mapping.permissions);
+                Some(match mapping.path {
+                    Some(path) => region.name(path),
+                    None => region,
+                })
+            })
+            .collect()
+    }
+}
+
+impl MemoryWriter for LinuxProcessProvider {
+    fn write_from_buf(&mut self, pointer: Address, buf: &[u8]) -> bool {
+        let Some(mem) = &self.mem else {
+            return false;
+        };
+
+        mem.write_all_at(buf, self.base + pointer as u64).is_ok()
+    }
+}
+
+/// A line of `/proc/<pid>/maps`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mapping {
+    pub start: u64,
+    pub end: u64,
+    pub permissions: Permissions,
+    /// Whether the mapping is shared rather than private.
+    pub shared: bool,
+    /// Backing file or pseudo-path (`[heap]`, `[stack]`...), if any.
+    pub path: Option<String>,
+}
+
+impl Mapping {
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace();
+        let (start, end) = fields.next()?.split_once('-')?;
+        let permissions = fields.next()?.as_bytes();
+        // offset, device and inode
+        let path = fields.nth(3).map(|_| {
+            // paths may contain spaces, so take everything after the inode
+            let mut rest = line;
+            for _ in 0..5 {
+                rest = rest.trim_start();
+                rest = &rest[rest.find(char::is_whitespace).unwrap_or(rest.len())..];
+            }
+            rest.trim().to_owned()
+        });
+
+        Some(Self {
+            start: u64::from_str_radix(start, 16).ok()?,
+            end: u64::from_str_radix(end, 16).ok()?,
+            permissions: Permissions {
+                read: permissions.first() == Some(&b'r'),
+                write: permissions.get(1) == Some(&b'w'),
+                execute: permissions.get(2) == Some(&b'x'),
+            },
+            shared: permissions.get(3) == Some(&b's'),
+            path: path.filter(|path| !path.is_empty()),
+        })
+    }
+}
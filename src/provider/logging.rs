@@ -0,0 +1,267 @@
+use crate::{
+    io_log_view::{AccessKind, IoAccess, IoLog},
+    memory_view::{MemoryProvider, MemoryWriter, Region},
+    Address, Error,
+};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+/// Default amount of accesses kept in an [`AccessLog`].
+const DEFAULT_CAPACITY: usize = 4096;
+/// Default size of the heatmap buckets, in bytes.
+const DEFAULT_GRANULARITY: u32 = 256;
+
+/// An access made through a [`LoggingProvider`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoggedAccess {
+    /// Order of the access, counting from the creation of the log.
+    pub sequence: u64,
+    pub address: Address,
+    pub kind: AccessKind,
+    /// Amount of bytes accessed.
+    pub len: usize,
+    /// Up to the first 8 bytes accessed, little-endian. Unmapped bytes count as zero.
+    pub value: u64,
+}
+
+impl LoggedAccess {
+    /// Converts the access into an entry of an [`IoLog`]. Accesses longer than 8 bytes are
+    /// truncated to their first 8 bytes.
+    pub fn io_access(&self) -> IoAccess {
+        IoAccess {
+            timestamp: self.sequence,
+            address: self.address,
+            kind: self.kind,
+            value: self.value,
+            size: self.len.min(8) as u8,
+        }
+    }
+}
+
+/// Totals of the accesses made through a [`LoggingProvider`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AccessStats {
+    pub reads: u64,
+    pub read_bytes: u64,
+    pub writes: u64,
+    pub write_bytes: u64,
+}
+
+/// A record of accesses: the most recent ones, totals and a heatmap of how many times each part
+/// of memory was read.
+#[derive(Debug, Clone)]
+pub struct AccessLog {
+    entries: VecDeque<LoggedAccess>,
+    capacity: usize,
+    sequence: u64,
+    stats: AccessStats,
+    granularity: u32,
+    /// Amount of bytes read per bucket, by bucket start.
+    heat: BTreeMap<Address, u64>,
+}
+
+impl Default for AccessLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl AccessLog {
+    /// Creates a log which keeps up to `capacity` of the most recent accesses.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity: capacity.max(1),
+            sequence: 0,
+            stats: AccessStats::default(),
+            granularity: DEFAULT_GRANULARITY,
+            heat: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the size of the heatmap buckets, in bytes. Clears the heatmap.
+    pub fn with_granularity(self, granularity: u32) -> Self {
+        Self {
+            granularity: granularity.max(1),
+            heat: BTreeMap::new(),
+            ..self
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The most recent accesses, oldest first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &LoggedAccess> {
+        self.entries.iter()
+    }
+
+    pub fn stats(&self) -> AccessStats {
+        self.stats
+    }
+
+    pub fn granularity(&self) -> u32 {
+        self.granularity
+    }
+
+    /// Amount of bytes read per bucket of [`granularity`](Self::granularity) bytes, as
+    /// `(bucket start, bytes read)`, sorted by address. Buckets which were never read are
+    /// skipped.
+    pub fn heatmap(&self) -> impl Iterator<Item = (Address, u64)> + '_ {
+        self.heat.iter().map(|(start, count)| (*start, *count))
+    }
+
+    /// Forgets every access, including the totals and the heatmap.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.stats = AccessStats::default();
+        self.heat.clear();
+    }
+
+    /// Moves the recorded accesses into an [`IoLog`], so that they can be shown by an
+    /// `IoLogView`. Totals and the heatmap are kept.
+    pub fn drain_into(&mut self, io_log: &mut IoLog) {
+        for access in self.entries.drain(..) {
+            io_log.push(access.io_access());
+        }
+    }
+
+    fn record(&mut self, address: Address, kind: AccessKind, len: usize, value: u64) {
+        if len == 0 {
+            return;
+        }
+
+        match kind {
+            AccessKind::Read => {
+                self.stats.reads += 1;
+                self.stats.read_bytes += len as u64;
+
+                let end = address as u64 + len as u64;
+                let granularity = self.granularity as u64;
+                let mut bucket = address as u64 - address as u64 % granularity;
+                while bucket < end {
+                    let covered = end.min(bucket + granularity) - (address as u64).max(bucket);
+                    *self.heat.entry(bucket as Address).or_default() += covered;
+                    bucket += granularity;
+                }
+            }
+            AccessKind::Write => {
+                self.stats.writes += 1;
+                self.stats.write_bytes += len as u64;
+            }
+        }
+
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(LoggedAccess {
+            sequence: self.sequence,
+            address,
+            kind,
+            len,
+            value,
+        });
+        self.sequence += 1;
+    }
+}
+
+/// Packs up to the first 8 bytes into a little-endian value.
+fn pack(bytes: impl Iterator<Item = u8>) -> u64 {
+    bytes.take(8).enumerate().fold(0, |value, (index, byte)| {
+        value | (byte as u64) << (index * 8)
+    })
+}
+
+/// A decorator that records every access made to the underlying provider into a shared
+/// [`AccessLog`]. Useful to find out how much a given layout actually reads, or where.
+#[derive(Debug)]
+pub struct LoggingProvider<P> {
+    inner: P,
+    log: Arc<Mutex<AccessLog>>,
+}
+
+impl<P> LoggingProvider<P> {
+    pub fn new(inner: P) -> Self {
+        Self::with_log(inner, Arc::new(Mutex::new(AccessLog::default())))
+    }
+
+    /// Creates a provider which records into an existing log, e.g. one shared with other
+    /// providers.
+    pub fn with_log(inner: P, log: Arc<Mutex<AccessLog>>) -> Self {
+        Self { inner, log }
+    }
+
+    /// A handle to the log accesses are recorded into.
+    pub fn log(&self) -> Arc<Mutex<AccessLog>> {
+        self.log.clone()
+    }
+
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+
+    fn record(&self, address: Address, kind: AccessKind, len: usize, value: u64) {
+        if let Ok(mut log) = self.log.lock() {
+            log.record(address, kind, len, value);
+        }
+    }
+}
+
+impl<P> MemoryProvider for LoggingProvider<P>
+where
+    P: MemoryProvider,
+{
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]) {
+        self.inner.read_to_buf(pointer, buf);
+
+        let value = pack(buf.iter().map(|value| value.unwrap_or(0)));
+        self.record(pointer, AccessKind::Read, buf.len(), value);
+    }
+
+    fn regions(&self) -> Vec<Region> {
+        self.inner.regions()
+    }
+
+    fn is_modified(&self, address: Address) -> bool {
+        self.inner.is_modified(address)
+    }
+
+    fn is_stale(&self, address: Address) -> bool {
+        self.inner.is_stale(address)
+    }
+
+    fn generation(&self) -> Option<u64> {
+        self.inner.generation()
+    }
+
+    fn error(&self) -> Option<Error> {
+        self.inner.error()
+    }
+}
+
+impl<P> MemoryWriter for LoggingProvider<P>
+where
+    P: MemoryWriter,
+{
+    fn write_from_buf(&mut self, pointer: Address, buf: &[u8]) -> bool {
+        let value = pack(buf.iter().copied());
+        self.record(pointer, AccessKind::Write, buf.len(), value);
+
+        self.inner.write_from_buf(pointer, buf)
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{
+    memory_view::{MemoryProvider, MemoryWriter, Permissions, Region},
+    Address,
+};
+use eyre::{eyre, Result};
+use mach2::{
+    kern_return::KERN_SUCCESS,
+    mach_port::mach_port_deallocate,
+    port::{mach_port_name_t, MACH_PORT_NULL},
+    traps::{mach_task_self, task_for_pid},
+    vm::{mach_vm_read_overwrite, mach_vm_region, mach_vm_write},
+    vm_prot::{VM_PROT_EXECUTE, VM_PROT_READ, VM_PROT_WRITE},
+    vm_region::{vm_region_basic_info_64, vm_region_info_t, VM_REGION_BASIC_INFO_64},
+    vm_types::{mach_vm_address_t, mach_vm_size_t},
+};
+
+const PAGE_SIZE: u64 = 4096;
+
+/// A provider over the memory of a live process, read through the Mach VM API.
+///
+/// Addresses are 32 bits wide, so the provider exposes a 4 GiB window of the process' address
+/// space starting at [`base`](Self::base).
+pub struct MachProcessProvider {
+    pid: u32,
+    base: u64,
+    task: mach_port_name_t,
+}
+
+impl MachProcessProvider {
+    /// Attaches to the process with the given pid. Requires the `task_for_pid` entitlement or
+    /// root privileges.
+    pub fn attach(pid: u32) -> Result<Self> {
+        let mut task = MACH_PORT_NULL;
+
+        // SAFETY: `task` is valid for writes
+        let result = unsafe { task_for_pid(mach_task_self(), pid as libc::c_int, &mut task) };
+        if result != KERN_SUCCESS {
+            return Err(eyre!(
+                "task_for_pid failed for process {pid} (kern_return_t {result})"
+            ));
+        }
+
+        Ok(Self { pid, base: 0, task })
+    }
+
+    /// Moves the window into the process' address space so that address `0` maps to `base`.
+    pub fn with_base(self, base: u64) -> Self {
+        Self { base, ..self }
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    pub fn base(&self) -> u64 {
+        self.base
+    }
+
+    /// Reads the whole range starting at `address`. Returns whether it succeeded.
+    fn read_raw(&self, address: u64, buf: &mut [u8]) -> bool {
+        let mut read: mach_vm_size_t = 0;
+
+        // SAFETY: `buf` is valid for writes of its length
+        let result = unsafe {
+            mach_vm_read_overwrite(
+                self.task,
+                address,
+                buf.len() as mach_vm_size_t,
+                buf.as_mut_ptr() as mach_vm_address_t,
+                &mut read,
+            )
+        };
+
+        result == KERN_SUCCESS && read as usize == buf.len()
+    }
+
+    /// Finds the first region at or after `address`. Returns its start, size and protection.
+    fn region(&self, address: u64) -> Option<(u64, u64, Permissions)> {
+        let mut start: mach_vm_address_t = address;
+        let mut size: mach_vm_size_t = 0;
+        let mut info = vm_region_basic_info_64::default();
+        let mut count = vm_region_basic_info_64::count();
+        let mut object_name = MACH_PORT_NULL;
+
+        // SAFETY: every out pointer is valid for writes, and `count` matches `info`
+        let result = unsafe {
+            mach_vm_region(
+                self.task,
+                &mut start,
+                &mut size,
+                VM_REGION_BASIC_INFO_64,
+                (&mut info as *mut vm_region_basic_info_64) as vm_region_info_t,
+                &mut count,
+                &mut object_name,
+            )
+        };
+        if result != KERN_SUCCESS {
+            return None;
+        }
+
+        let permissions = Permissions {
+            read: info.protection & VM_PROT_READ != 0,
+            write: info.protection & VM_PROT_WRITE != 0,
+            execute: info.protection & VM_PROT_EXECUTE != 0,
+        };
+        Some((start, size, permissions))
+    }
+}
+
+impl Drop for MachProcessProvider {
+    fn drop(&mut self) {
+        // SAFETY: the task port right is owned by the provider
+        unsafe {
+            mach_port_deallocate(mach_task_self(), self.task);
+        }
+    }
+}
+
+impl MemoryProvider for MachProcessProvider {
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]) {
+        let start = self.base + pointer as u64;
+        let mut bytes = vec![0; buf.len()];
+        if self.read_raw(start, &mut bytes) {
+            for (value, byte) in buf.iter_mut().zip(bytes) {
+                *value = Some(byte);
+            }
+            return;
+        }
+
+        // reads of ranges containing unmapped pages fail as a whole, so retry page by page
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let address = start + offset as u64;
+            let end = (offset + (PAGE_SIZE - address % PAGE_SIZE) as usize).min(bytes.len());
+            let ok = self.read_raw(address, &mut bytes[offset..end]);
+            for (value, byte) in buf[offset..end].iter_mut().zip(&bytes[offset..end]) {
+                *value = ok.then_some(*byte);
+            }
+            offset = end;
+        }
+    }
+
+    fn regions(&self) -> Vec<Region> {
+        let window_end = self.base + Address::MAX as u64 + 1;
+
+        let mut regions = Vec::new();
+        let mut address = self.base;
+        while address < window_end {
+            let Some((start, size, permissions)) = self.region(address) else {
+                break;
+            };
+
+            let end = (start + size).min(window_end);
+            let start = start.max(self.base);
+            if end <= address {
+                break;
+            }
+
+            if start < end {
+                regions.push(
+                    Region::new((start - self.base) as Address, end - start)
+                        .permissions(permissions),
+                );
+            }
+
+            address = end;
+        }
+
+        regions
+    }
+}
+
+impl MemoryWriter for MachProcessProvider {
+    fn write_from_buf(&mut self, pointer: Address, buf: &[u8]) -> bool {
+        // SAFETY: `buf` is valid for reads of its length
+        let result = unsafe {
+            mach_vm_write(
+                self.task,
+                self.base + pointer as u64,
+                buf.as_ptr() as _,
+                buf.len() as _,
+            )
+        };
+
+        result == KERN_SUCCESS
+    }
+}
@@ -0,0 +1,87 @@
+//! Ready-made [`MemoryProvider`](crate::memory_view::MemoryProvider) implementations.
+
+mod background;
+mod composite;
+mod edit_buffer;
+mod firmware;
+mod logging;
+mod slice;
+mod snapshot;
+mod throttled;
+
+#[cfg(feature = "coredump")]
+mod coredump;
+
+#[cfg(feature = "gdb-rsp")]
+mod gdb;
+
+#[cfg(all(feature = "process-linux", target_os = "linux"))]
+mod linux;
+
+#[cfg(all(feature = "process-macos", target_os = "macos"))]
+mod macos;
+
+#[cfg(feature = "object")]
+mod object;
+
+#[cfg(feature = "probe-rs")]
+mod probe_rs;
+
+#[cfg(feature = "qemu")]
+mod qemu;
+
+#[cfg(feature = "remote")]
+mod remote;
+
+#[cfg(all(feature = "shared-memory", any(unix, windows)))]
+mod shared_memory;
+
+#[cfg(feature = "wasm")]
+mod wasm;
+
+#[cfg(all(feature = "process-windows", windows))]
+mod windows;
+
+pub use background::{BackgroundInstructionProvider, BackgroundProvider};
+pub use composite::{CallbackProvider, CompositeMapping, CompositeProvider, MemoryDevice};
+pub use edit_buffer::EditBuffer;
+pub use firmware::{FirmwareFormat, FirmwareProvider};
+pub use logging::{AccessLog, AccessStats, LoggedAccess, LoggingProvider};
+pub use slice::{SliceProvider, VecProvider};
+pub use snapshot::{SnapshotMetadata, SnapshotProvider};
+pub use throttled::ThrottledProvider;
+
+pub(crate) use snapshot::{read_array, read_string, write_string};
+
+#[cfg(feature = "coredump")]
+pub use coredump::CoreDumpProvider;
+
+#[cfg(feature = "gdb-rsp")]
+pub use gdb::{GdbClient, GdbProvider, MemoryArea, RegisterInfo};
+
+#[cfg(all(feature = "process-linux", target_os = "linux"))]
+pub use linux::{LinuxProcessProvider, Mapping};
+
+#[cfg(all(feature = "process-macos", target_os = "macos"))]
+pub use macos::MachProcessProvider;
+
+#[cfg(feature = "object")]
+pub use self::object::ObjectProvider;
+
+#[cfg(feature = "probe-rs")]
+pub use self::probe_rs::ProbeRsProvider;
+
+#[cfg(feature = "qemu")]
+pub use qemu::{AddressSpace, QemuMonitorProvider, QemuProtocol};
+
+#[cfg(feature = "remote")]
+pub use remote::RemoteProvider;
+
+#[cfg(all(feature = "shared-memory", any(unix, windows)))]
+pub use shared_memory::{SharedMemoryProvider, SharedMemoryPublisher};
+
+#[cfg(feature = "wasm")]
+pub use wasm::WasmMemoryProvider;
+
+#[cfg(all(feature = "process-windows", windows))]
+pub use windows::WindowsProcessProvider;
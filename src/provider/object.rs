@@ -0,0 +1,238 @@
+use crate::{
+    memory_view::{MemoryProvider, Permissions, Region},
+    symbol::{Symbol, SymbolProvider, SymbolTable},
+    Address,
+};
+use eyre::{Context, Result};
+use object::{Object, ObjectSection, ObjectSymbol, SectionKind, SymbolKind};
+use std::path::Path;
+
+/// A section mapped at its virtual address.
+#[derive(Debug, Clone)]
+struct Section {
+    address: u64,
+    size: u64,
+    name: String,
+    permissions: Permissions,
+    /// Contents of the section. Empty for sections without data in the file, e.g. `.bss`, which
+    /// read as zeroes.
+    data: Vec<u8>,
+}
+
+impl Section {
+    fn end(&self) -> u64 {
+        self.address + self.size
+    }
+}
+
+fn permissions(kind: SectionKind) -> Option<Permissions> {
+    Some(match kind {
+        SectionKind::Text => Permissions {
+            read: true,
+            write: false,
+            execute: true,
+        },
+        // .tbss takes no room in the address space, only in each thread's TLS block
+        SectionKind::Data | SectionKind::UninitializedData | SectionKind::Tls => {
+            Permissions::READ_WRITE
+        }
+        SectionKind::ReadOnlyData
+        | SectionKind::ReadOnlyDataWithRel
+        | SectionKind::ReadOnlyString => Permissions::READ_ONLY,
+        _ => return None,
+    })
+}
+
+/// A provider over an executable or object file (ELF, PE, Mach-O...). Allocated sections are
+/// mapped at their virtual addresses and named after the section, and the symbol table is
+/// exposed through [`SymbolProvider`].
+///
+/// Addresses are 32 bits wide, so the provider exposes a 4 GiB window of the file's address space
+/// starting at [`base`](Self::base).
+pub struct ObjectProvider {
+    base: u64,
+    /// Mapped sections, sorted by address.
+    sections: Vec<Section>,
+    /// Every defined symbol, with absolute addresses.
+    all_symbols: Vec<(u64, String)>,
+    /// The symbols inside of the window.
+    symbols: SymbolTable,
+    entry: u64,
+}
+
+impl ObjectProvider {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).wrap_err_with(|| format!("reading {}", path.display()))?;
+        Self::parse(&data)
+    }
+
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let file = object::File::parse(data).wrap_err("parsing object file")?;
+
+        let mut sections = Vec::new();
+        for section in file.sections() {
+            let Some(permissions) = permissions(section.kind()) else {
+                continue;
+            };
+            if section.size() == 0 {
+                continue;
+            }
+
+            let data = match section.kind() {
+                SectionKind::UninitializedData => Vec::new(),
+                _ => section
+                    .uncompressed_data()
+                    .map(|data| data.into_owned())
+                    .unwrap_or_default(),
+            };
+            sections.push(Section {
+                address: section.address(),
+                size: section.size(),
+                name: section.name().unwrap_or("").to_owned(),
+                permissions,
+                data,
+            });
+        }
+        sections.sort_by_key(|section| section.address);
+
+        let all_symbols = file
+            .symbols()
+            .filter(|symbol| {
+                symbol.is_definition()
+                    && matches!(symbol.kind(), SymbolKind::Text | SymbolKind::Data)
+            })
+            .filter_map(|symbol| Some((symbol.address(), symbol.name().ok()?.to_owned())))
+            .filter(|(_, name)| !name.is_empty())
+            .collect();
+
+        let mut provider = Self {
+            base: 0,
+            sections,
+            all_symbols,
+            symbols: SymbolTable::new(),
+            entry: file.entry(),
+        };
+        provider.rebuild_symbols();
+
+        Ok(provider)
+    }
+
+    /// Moves the window into the file's address space so that address `0` maps to `base`.
+    pub fn with_base(mut self, base: u64) -> Self {
+        self.base = base;
+        self.rebuild_symbols();
+        self
+    }
+
+    pub fn base(&self) -> u64 {
+        self.base
+    }
+
+    /// Address of the entry point, relative to the base.
+    pub fn entry(&self) -> Option<Address> {
+        self.to_window(self.entry)
+    }
+
+    /// Address of the section with the given name, relative to the base.
+    pub fn section_address(&self, name: &str) -> Option<Address> {
+        self.sections
+            .iter()
+            .find(|section| section.name == name)
+            .and_then(|section| self.to_window(section.address))
+    }
+
+    fn to_window(&self, address: u64) -> Option<Address> {
+        address
+            .checked_sub(self.base)
+            .and_then(|address| Address::try_from(address).ok())
+    }
+
+    fn rebuild_symbols(&mut self) {
+        self.symbols = self
+            .all_symbols
+            .iter()
+            .filter_map(|(address, name)| Some(Symbol::new(self.to_window(*address)?, name)))
+            .collect();
+    }
+}
+
+impl MemoryProvider for ObjectProvider {
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]) {
+        let start = self.base + pointer as u64;
+        for (offset, value) in buf.iter_mut().enumerate() {
+            let address = start + offset as u64;
+            let index = self
+                .sections
+                .partition_point(|section| section.address <= address);
+
+            *value = index
+                .checked_sub(1)
+                .map(|index| &self.sections[index])
+                .filter(|section| address < section.end())
+                .map(|section| {
+                    let within = (address - section.address) as usize;
+                    section.data.get(within).copied().unwrap_or(0)
+                });
+        }
+    }
+
+    fn regions(&self) -> Vec<Region> {
+        let window = self.base..self.base + Address::MAX as u64 + 1;
+        self.sections
+            .iter()
+            .filter_map(|section| {
+                let end = section.end().min(window.end);
+                let start = section.address.max(window.start);
+                (start < end).then(|| {
+                    Region::new((start - self.base) as Address, end - start)
+                        .permissions(section.permissions)
+                        .name(section.name.clone())
+                })
+            })
+            .collect()
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(0)
+    }
+}
+
+impl SymbolProvider for ObjectProvider {
+    fn symbols(&self) -> Vec<Symbol> {
+        self.symbols.symbols()
+    }
+
+    fn symbol_at(&self, address: Address) -> Option<Symbol> {
+        self.symbols.symbol_at(address)
+    }
+
+    fn lookup(&self, name: &str) -> Option<Address> {
+        self.symbols.lookup(name)
+    }
+
+    /// Symbols are only changed in memory: the file is left untouched.
+    fn is_mutable(&self) -> bool {
+        true
+    }
+
+    fn rename(&mut self, address: Address, name: &str) -> Result<()> {
+        self.symbols.rename(address, name)?;
+
+        // every symbol at the address, so that the new name survives moving the window
+        let absolute = self.base + address as u64;
+        for (_, symbol) in self.all_symbols.iter_mut().filter(|(a, _)| *a == absolute) {
+            *symbol = name.to_owned();
+        }
+
+        Ok(())
+    }
+
+    fn add_label(&mut self, address: Address, name: &str) -> Result<()> {
+        self.symbols.add_label(address, name)?;
+        self.all_symbols
+            .push((self.base + address as u64, name.to_owned()));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{
+    memory_view::{MemoryProvider, MemoryWriter, Permissions, Region},
+    register_view::{RegisterProvider, RegisterValue},
+    Address,
+};
+use eyre::{eyre, Result};
+use probe_rs::{config::MemoryRegion, MemoryInterface, Session};
+use std::{
+    borrow::Cow,
+    ops::Range,
+    sync::{Mutex, PoisonError},
+};
+
+/// Size of the chunks reads are split into, so that a failing chunk doesn't fail the whole read.
+const CHUNK: usize = 0x400;
+
+/// A provider for the memory and core registers of a target attached through a debug probe.
+pub struct ProbeRsProvider {
+    session: Mutex<Session>,
+    core: usize,
+    base: u64,
+    /// Mapped ranges of the target, relative to `base`.
+    regions: Vec<Region>,
+}
+
+impl ProbeRsProvider {
+    /// Attaches to a target through the first available probe. `chip` is the name of the target
+    /// chip, as known to probe-rs (e.g. `STM32F411RETx`).
+    pub fn attach(chip: &str) -> Result<Self> {
+        let session = Session::auto_attach(chip, probe_rs::Permissions::default())
+            .map_err(|e| eyre!("attaching to {chip}: {e}"))?;
+
+        Ok(Self::from_session(session))
+    }
+
+    /// Uses an already established session, e.g. one attached with a specific probe.
+    pub fn from_session(session: Session) -> Self {
+        let mut provider = Self {
+            session: Mutex::new(session),
+            core: 0,
+            base: 0,
+            regions: Vec::new(),
+        };
+        provider.update_regions();
+
+        provider
+    }
+
+    /// Selects the core whose view of memory and registers is used.
+    pub fn with_core(self, core: usize) -> Self {
+        Self { core, ..self }
+    }
+
+    /// Sets the target address shown at address zero.
+    pub fn with_base(mut self, base: u64) -> Self {
+        self.base = base;
+        self.update_regions();
+
+        self
+    }
+
+    fn update_regions(&mut self) {
+        let window = self.base..self.base + (1 << Address::BITS);
+        let session = self
+            .session
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        let mut regions: Vec<_> = session
+            .target()
+            .memory_map
+            .iter()
+            .filter_map(|region| {
+                let (range, name, permissions) = match region {
+                    MemoryRegion::Ram(ram) => (
+                        &ram.range,
+                        &ram.name,
+                        Permissions {
+                            read: true,
+                            write: true,
+                            execute: true,
+                        },
+                    ),
+                    MemoryRegion::Nvm(nvm) => (
+                        &nvm.range,
+                        &nvm.name,
+                        Permissions {
+                            read: true,
+                            write: false,
+                            execute: true,
+                        },
+                    ),
+                    MemoryRegion::Generic(generic) => {
+                        (&generic.range, &generic.name, Permissions::READ_WRITE)
+                    }
+                };
+
+                let start = range.start.max(window.start);
+                let end = range.end.min(window.end);
+                if start >= end {
+                    return None;
+                }
+
+                let region = Region::new((start - self.base) as Address, end - start)
+                    .permissions(permissions);
+                Some(match name {
+                    Some(name) => region.name(name.clone()),
+                    None => region,
+                })
+            })
+            .collect();
+        regions.sort_by_key(|region| region.start);
+
+        self.regions = regions;
+    }
+
+    /// Whether a range relative to `base` lies entirely outside of the target's memory map.
+    fn is_unmapped(&self, range: Range<u64>) -> bool {
+        !self.regions.is_empty()
+            && !self
+                .regions
+                .iter()
+                .any(|region| (region.start as u64) < range.end && range.start < region.end())
+    }
+}
+
+impl MemoryProvider for ProbeRsProvider {
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]) {
+        buf.fill(None);
+
+        let mut session = self.session.lock().unwrap_or_else(PoisonError::into_inner);
+        let Ok(mut core) = session.core(self.core) else {
+            return;
+        };
+
+        let mut data = vec![0; CHUNK];
+        for (index, chunk) in buf.chunks_mut(CHUNK).enumerate() {
+            let start = pointer as u64 + (index * CHUNK) as u64;
+            if self.is_unmapped(start..start + chunk.len() as u64) {
+                continue;
+            }
+
+            let data = &mut data[..chunk.len()];
+            if core.read_8(self.base + start, data).is_ok() {
+                for (value, byte) in chunk.iter_mut().zip(data.iter()) {
+                    *value = Some(*byte);
+                }
+            }
+        }
+    }
+
+    fn regions(&self) -> Vec<Region> {
+        self.regions.clone()
+    }
+}
+
+impl MemoryWriter for ProbeRsProvider {
+    fn write_from_buf(&mut self, pointer: Address, buf: &[u8]) -> bool {
+        let base = self.base;
+        let session = self
+            .session
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        let Ok(mut core) = session.core(self.core) else {
+            return false;
+        };
+
+        core.write_8(base + pointer as u64, buf).is_ok()
+    }
+}
+
+impl RegisterProvider for ProbeRsProvider {
+    fn registers(&self) -> Vec<RegisterValue> {
+        let mut session = self.session.lock().unwrap_or_else(PoisonError::into_inner);
+        let Ok(mut core) = session.core(self.core) else {
+            return Vec::new();
+        };
+
+        // collect the layout first, since reading a register borrows the core mutably
+        let layout: Vec<_> = core
+            .registers()
+            .core_registers()
+            .map(|register| {
+                (
+                    register.name().to_owned(),
+                    register.size_in_bits() as u32,
+                    register.id(),
+                )
+            })
+            .collect();
+
+        layout
+            .into_iter()
+            .map(|(name, bits, id)| RegisterValue {
+                name: Cow::Owned(name),
+                bits,
+                value: core.read_core_reg::<u64>(id).ok(),
+            })
+            .collect()
+    }
+}
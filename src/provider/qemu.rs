@@ -0,0 +1,474 @@
+use crate::{
+    memory_view::{MemoryProvider, Permissions, Region},
+    Address, Error,
+};
+use eyre::{bail, eyre, Context, Result};
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
+
+/// Size of memory dump commands, in bytes.
+const CHUNK: usize = 0x100;
+/// Size below which incomplete dumps aren't split any further, in bytes.
+const MIN_SPLIT_CHUNK: usize = 16;
+const TIMEOUT: Duration = Duration::from_secs(2);
+const HMP_PROMPT: &[u8] = b"(qemu) ";
+
+/// Protocol spoken by a QEMU monitor socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QemuProtocol {
+    /// The human monitor, e.g. `-monitor tcp:localhost:4444,server,nowait`.
+    Hmp,
+    /// The machine protocol, e.g. `-qmp tcp:localhost:4444,server,nowait`. Memory is still read
+    /// through human monitor commands.
+    Qmp,
+}
+
+/// Which address space memory is read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AddressSpace {
+    /// Guest physical memory.
+    #[default]
+    Physical,
+    /// Guest virtual memory, as seen by the current CPU.
+    Virtual,
+}
+
+trait Stream: Read + Write + Send {}
+
+impl<T: Read + Write + Send> Stream for T {}
+
+/// Opens a new connection to a monitor.
+type Connect = Box<dyn Fn() -> Result<Box<dyn Stream>> + Send + Sync>;
+
+/// Escapes a string for use inside of a JSON string literal.
+fn escape_json(text: &str) -> String {
+    text.chars().fold(String::new(), |mut escaped, c| {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+        escaped
+    })
+}
+
+/// Gets the value of a string member of a JSON object, given its text.
+fn json_string(json: &str, name: &str) -> Option<String> {
+    let key = format!("\"{name}\"");
+    let rest = json[json.find(&key)? + key.len()..].trim_start();
+    let rest = rest.strip_prefix(':')?.trim_start().strip_prefix('"')?;
+
+    let mut value = String::new();
+    let mut chars = rest.chars();
+    loop {
+        match chars.next()? {
+            '"' => return Some(value),
+            '\\' => match chars.next()? {
+                'n' => value.push('\n'),
+                'r' => value.push('\r'),
+                't' => value.push('\t'),
+                'b' => value.push('\u{8}'),
+                'f' => value.push('\u{c}'),
+                'u' => {
+                    let code: String = chars.by_ref().take(4).collect();
+                    let code = u32::from_str_radix(&code, 16).ok()?;
+                    value.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                }
+                c => value.push(c),
+            },
+            c => value.push(c),
+        }
+    }
+}
+
+/// Parses the output of a `/Nxb` memory dump into `(address, value)` pairs.
+fn parse_dump(output: &str) -> impl Iterator<Item = (u64, u8)> + '_ {
+    output.lines().flat_map(|line| {
+        // drop anything before a carriage return, like the echo of the command
+        let line = line.rsplit('\r').next().unwrap_or(line).trim();
+        let parsed = line.split_once(':').and_then(|(address, values)| {
+            let address = u64::from_str_radix(address.trim(), 16).ok()?;
+            let values = values
+                .split_whitespace()
+                .map(|value| u8::from_str_radix(value.strip_prefix("0x")?, 16).ok())
+                .collect::<Option<Vec<_>>>()?;
+
+            Some((address, values))
+        });
+
+        parsed.into_iter().flat_map(|(address, values)| {
+            values
+                .into_iter()
+                .enumerate()
+                .map(move |(offset, value)| (address + offset as u64, value))
+        })
+    })
+}
+
+/// Parses the regions of the first flat view in the output of `info mtree -f`.
+fn parse_mtree(output: &str) -> Vec<(u64, u64, Permissions, String)> {
+    let mut regions = Vec::new();
+    let mut views = 0;
+    for line in output.lines() {
+        let line = line.trim();
+        if line.starts_with("FlatView") {
+            views += 1;
+            if views > 1 {
+                break;
+            }
+
+            continue;
+        }
+
+        // e.g. `0000000000000000-000000000009ffff (prio 0, ram): pc.ram`
+        let parsed = (|| {
+            let (range, rest) = line.split_once(' ')?;
+            let (start, end) = range.split_once('-')?;
+            let start = u64::from_str_radix(start, 16).ok()?;
+            let end = u64::from_str_radix(end, 16).ok()?;
+
+            let kind = rest.strip_prefix('(')?.split_once(')')?.0;
+            let kind = kind.split_once(", ")?.1;
+            let permissions = match kind {
+                "ram" => Permissions::READ_WRITE,
+                "rom" | "romd" => Permissions::READ_ONLY,
+                _ => Permissions::default(),
+            };
+            let name = rest.split_once("): ")?.1;
+            let name = name.split_once(" @").map_or(name, |(name, _)| name);
+
+            Some((
+                start,
+                end.checked_sub(start)? + 1,
+                permissions,
+                name.to_owned(),
+            ))
+        })();
+
+        regions.extend(parsed);
+    }
+
+    regions
+}
+
+struct Monitor {
+    stream: BufReader<Box<dyn Stream>>,
+    protocol: QemuProtocol,
+}
+
+impl Monitor {
+    fn new(stream: Box<dyn Stream>, protocol: QemuProtocol) -> Result<Self> {
+        let mut monitor = Self {
+            stream: BufReader::new(stream),
+            protocol,
+        };
+
+        match protocol {
+            QemuProtocol::Hmp => {
+                monitor.read_prompt()?;
+            }
+            QemuProtocol::Qmp => {
+                let greeting = monitor.read_line()?;
+                if !greeting.contains("\"QMP\"") {
+                    bail!("not a QMP socket");
+                }
+
+                monitor.send_line("{\"execute\": \"qmp_capabilities\"}")?;
+                monitor.read_reply()?;
+            }
+        }
+
+        Ok(monitor)
+    }
+
+    fn send_line(&mut self, line: &str) -> Result<()> {
+        let stream = self.stream.get_mut();
+        stream.write_all(line.as_bytes())?;
+        stream.write_all(b"\n")?;
+        stream.flush()?;
+
+        Ok(())
+    }
+
+    fn read_line(&mut self) -> Result<String> {
+        let mut line = String::new();
+        if self.stream.read_line(&mut line)? == 0 {
+            bail!("connection closed by QEMU");
+        }
+
+        Ok(line)
+    }
+
+    /// Reads human monitor output up to the next prompt.
+    fn read_prompt(&mut self) -> Result<String> {
+        let mut output = Vec::new();
+        while !output.ends_with(HMP_PROMPT) {
+            let data = self.stream.fill_buf()?;
+            if data.is_empty() {
+                bail!("connection closed by QEMU");
+            }
+
+            let len = data.len();
+            output.extend_from_slice(data);
+            self.stream.consume(len);
+        }
+
+        output.truncate(output.len() - HMP_PROMPT.len());
+        Ok(String::from_utf8_lossy(&output).into_owned())
+    }
+
+    /// Reads QMP messages until a reply, skipping events. Returns the reply text.
+    fn read_reply(&mut self) -> Result<String> {
+        loop {
+            let line = self.read_line()?;
+            if line.contains("\"return\"") {
+                return Ok(line);
+            }
+
+            if line.contains("\"error\"") {
+                let description = json_string(&line, "desc").unwrap_or(line);
+                bail!("QEMU error: {}", description.trim());
+            }
+        }
+    }
+
+    /// Runs a human monitor command and returns its output.
+    fn command(&mut self, command: &str) -> Result<String> {
+        match self.protocol {
+            QemuProtocol::Hmp => {
+                self.send_line(command)?;
+                self.read_prompt()
+            }
+            QemuProtocol::Qmp => {
+                self.send_line(&format!(
+                    "{{\"execute\": \"human-monitor-command\", \"arguments\": \
+                     {{\"command-line\": \"{}\"}}}}",
+                    escape_json(command)
+                ))?;
+
+                let reply = self.read_reply()?;
+                json_string(&reply, "return").ok_or_else(|| eyre!("malformed QMP reply"))
+            }
+        }
+    }
+}
+
+/// A provider for the memory of a QEMU guest, read through its monitor socket. Useful when no
+/// gdbstub is configured. QEMU's monitor has no way to write memory, so this provider is
+/// read-only.
+pub struct QemuMonitorProvider {
+    connect: Connect,
+    protocol: QemuProtocol,
+    /// `None` after a command failed, e.g. timed out, since its output could still arrive and be
+    /// taken for the next command's: the next command connects again.
+    monitor: Mutex<Option<Monitor>>,
+    space: AddressSpace,
+    base: u64,
+    regions: Vec<Region>,
+    /// Why the last read failed, if it did.
+    error: Mutex<Option<Error>>,
+}
+
+impl QemuMonitorProvider {
+    /// Connects to a monitor listening on a TCP socket at `address`, e.g. `localhost:4444`.
+    pub fn connect(address: impl ToSocketAddrs, protocol: QemuProtocol) -> Result<Self> {
+        let socket = address
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| eyre!("address didn't resolve to any address"))?;
+        let connect = move || -> Result<Box<dyn Stream>> {
+            let stream = TcpStream::connect_timeout(&socket, TIMEOUT)
+                .wrap_err_with(|| format!("connecting to {socket}"))?;
+            stream.set_read_timeout(Some(TIMEOUT))?;
+            stream.set_nodelay(true)?;
+
+            Ok(Box::new(stream))
+        };
+
+        Self::from_connect(Box::new(connect), protocol)
+    }
+
+    /// Connects to a monitor listening on a unix socket at `path`.
+    #[cfg(unix)]
+    pub fn connect_unix(path: impl AsRef<std::path::Path>, protocol: QemuProtocol) -> Result<Self> {
+        let path = path.as_ref().to_owned();
+        let connect = move || -> Result<Box<dyn Stream>> {
+            let stream = std::os::unix::net::UnixStream::connect(&path)
+                .wrap_err_with(|| format!("connecting to {}", path.display()))?;
+            stream.set_read_timeout(Some(TIMEOUT))?;
+
+            Ok(Box::new(stream))
+        };
+
+        Self::from_connect(Box::new(connect), protocol)
+    }
+
+    fn from_connect(connect: Connect, protocol: QemuProtocol) -> Result<Self> {
+        let monitor = Monitor::new(connect()?, protocol)?;
+        let mut provider = Self {
+            connect,
+            protocol,
+            monitor: Mutex::new(Some(monitor)),
+            space: AddressSpace::default(),
+            base: 0,
+            regions: Vec::new(),
+            error: Mutex::new(None),
+        };
+        provider.update_regions();
+
+        Ok(provider)
+    }
+
+    /// Selects the address space memory is read from.
+    pub fn with_address_space(mut self, space: AddressSpace) -> Self {
+        self.space = space;
+        self.update_regions();
+
+        self
+    }
+
+    /// Sets the guest address shown at address zero.
+    pub fn with_base(mut self, base: u64) -> Self {
+        self.base = base;
+        self.update_regions();
+
+        self
+    }
+
+    /// Runs a human monitor command and returns its output. Connects to the monitor again if
+    /// the last command failed.
+    pub fn command(&self, command: &str) -> Result<String> {
+        let mut slot = self.monitor.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut monitor = match slot.take() {
+            Some(monitor) => monitor,
+            None => Monitor::new((self.connect)()?, self.protocol)?,
+        };
+
+        let output = monitor.command(command)?;
+        *slot = Some(monitor);
+
+        Ok(output)
+    }
+
+    /// Updates the regions from the physical memory tree. Virtual memory has no regions, since
+    /// its layout depends on the guest.
+    fn update_regions(&mut self) {
+        self.regions.clear();
+        if self.space != AddressSpace::Physical {
+            return;
+        }
+
+        let Ok(output) = self.command("info mtree -f") else {
+            return;
+        };
+
+        let window = self.base..self.base + (1 << Address::BITS);
+        for (start, size, permissions, name) in parse_mtree(&output) {
+            let start_clipped = start.max(window.start);
+            let end = (start + size).min(window.end);
+            if start_clipped >= end {
+                continue;
+            }
+
+            self.regions.push(
+                Region::new((start_clipped - self.base) as Address, end - start_clipped)
+                    .permissions(permissions)
+                    .name(name),
+            );
+        }
+        self.regions.sort_by_key(|region| region.start);
+    }
+
+    /// Dumps `len` bytes at the absolute guest address `address`. Values QEMU reported an error
+    /// for, e.g. because they're in an unmapped page, are missing.
+    fn dump(&self, address: u64, len: usize) -> Result<HashMap<u64, u8>> {
+        let command = match self.space {
+            AddressSpace::Physical => "xp",
+            AddressSpace::Virtual => "x",
+        };
+
+        let output = self.command(&format!("{command} /{len}xb {address:#x}"))?;
+        Ok(parse_dump(&output)
+            .filter(|(a, _)| (address..address + len as u64).contains(a))
+            .collect())
+    }
+
+    /// Reads into `buf` from the absolute guest address `address`, splitting dumps QEMU
+    /// couldn't complete so that unreadable pages don't hide the readable ones around them.
+    /// Dumps that failed altogether, e.g. because the monitor didn't answer, aren't split, as
+    /// their halves would likely fail too.
+    fn read_split(&self, address: u64, buf: &mut [Option<u8>]) -> Result<()> {
+        let values = match self.dump(address, buf.len()) {
+            Ok(values) => values,
+            Err(error) => {
+                buf.fill(None);
+                return Err(error);
+            }
+        };
+
+        if values.len() < buf.len() && buf.len() > MIN_SPLIT_CHUNK {
+            let (head, tail) = buf.split_at_mut(buf.len() / 2);
+            let middle = address + head.len() as u64;
+            if let Err(error) = self.read_split(address, head) {
+                tail.fill(None);
+                return Err(error);
+            }
+
+            return self.read_split(middle, tail);
+        }
+
+        for (offset, value) in buf.iter_mut().enumerate() {
+            *value = values.get(&(address + offset as u64)).copied();
+        }
+
+        Ok(())
+    }
+}
+
+impl MemoryProvider for QemuMonitorProvider {
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]) {
+        buf.fill(None);
+
+        let mut failure = None;
+        for (index, chunk) in buf.chunks_mut(CHUNK).enumerate() {
+            let start = pointer as u64 + (index * CHUNK) as u64;
+            let end = start + chunk.len() as u64;
+
+            // skip chunks outside of the physical memory map
+            let mapped = self.regions.is_empty()
+                || self
+                    .regions
+                    .iter()
+                    .any(|region| (region.start as u64) < end && start < region.end());
+            if mapped {
+                // stop at the first failure rather than waiting for each chunk to time out
+                failure = self.read_split(self.base + start, chunk).err();
+                if failure.is_some() {
+                    break;
+                }
+            }
+        }
+
+        *self.error.lock().unwrap_or_else(PoisonError::into_inner) = failure.map(Error::provider);
+    }
+
+    fn regions(&self) -> Vec<Region> {
+        self.regions.clone()
+    }
+
+    fn error(&self) -> Option<Error> {
+        self.error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{
+    memory_view::{MemoryProvider, MemoryWriter, Region},
+    remote::{self, Request},
+    Address, Error,
+};
+use eyre::{Context, Result};
+use std::{
+    io::ErrorKind,
+    net::{SocketAddr, TcpStream, ToSocketAddrs},
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A provider for memory exposed by [`remote::serve`] over a socket.
+#[derive(Debug)]
+pub struct RemoteProvider {
+    /// Where the remote target listens, to reconnect to.
+    addresses: Vec<SocketAddr>,
+    /// `None` once a request failed midway, until the next one reconnects.
+    stream: Mutex<Option<TcpStream>>,
+    /// The regions of the target, fetched on first use.
+    regions: Mutex<Option<Vec<Region>>>,
+    /// Why the last read failed, if it did.
+    error: Mutex<Option<Error>>,
+}
+
+fn open(addresses: &[SocketAddr]) -> Result<TcpStream> {
+    let stream = TcpStream::connect(addresses).wrap_err("connecting to remote target")?;
+    stream.set_nodelay(true)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+
+    Ok(stream)
+}
+
+/// Whether the server closed the connection, as it does with clients left idle, or sent
+/// something nobody asked for.
+fn is_closed(stream: &TcpStream) -> bool {
+    if stream.set_nonblocking(true).is_err() {
+        return true;
+    }
+
+    let open = matches!(stream.peek(&mut [0]), Err(err) if err.kind() == ErrorKind::WouldBlock);
+    stream.set_nonblocking(false).is_err() || !open
+}
+
+impl RemoteProvider {
+    pub fn connect(address: impl ToSocketAddrs) -> Result<Self> {
+        let addresses: Vec<_> = address
+            .to_socket_addrs()
+            .wrap_err("resolving remote target")?
+            .collect();
+        let stream = open(&addresses)?;
+
+        Ok(Self {
+            addresses,
+            stream: Mutex::new(Some(stream)),
+            regions: Mutex::new(None),
+            error: Mutex::new(None),
+        })
+    }
+
+    /// Drops the cached regions, so that they are fetched again the next time they are needed.
+    pub fn invalidate_regions(&self) {
+        *self.regions.lock().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+
+    /// Sends a request and waits for its reply, returning the reply body. Reconnects first if
+    /// the previous request failed, or if the server closed the connection since.
+    fn request(&self, request: &Request) -> Result<Vec<u8>> {
+        let mut stream = self.stream.lock().unwrap_or_else(PoisonError::into_inner);
+        if stream.as_ref().is_some_and(is_closed) {
+            *stream = None;
+        }
+
+        let current = match stream.as_mut() {
+            Some(current) => current,
+            None => stream.insert(open(&self.addresses)?),
+        };
+
+        let reply = remote::write_message(&mut *current, &request.encode()).and_then(|()| {
+            remote::read_message(&mut *current)?
+                .ok_or_else(|| eyre::eyre!("connection closed by remote target"))
+        });
+        let reply = match reply {
+            Ok(reply) => reply,
+            Err(err) => {
+                // a late reply would be taken for the one of the next request
+                *stream = None;
+                return Err(err);
+            }
+        };
+
+        Ok(remote::reply_body(&reply)?.to_vec())
+    }
+
+    /// Reads values starting from `address` into the buffer.
+    pub fn read(&self, address: Address, buf: &mut [Option<u8>]) -> Result<()> {
+        let mut address = address;
+        for chunk in buf.chunks_mut(remote::MAX_READ) {
+            let body = self.request(&Request::Read {
+                address,
+                len: chunk.len() as u32,
+            })?;
+            remote::decode_values(&body, chunk)?;
+
+            address = address.wrapping_add(chunk.len() as Address);
+        }
+
+        Ok(())
+    }
+
+    /// Writes the values in the buffer starting at `address`. Returns whether all of them were
+    /// written.
+    pub fn write(&self, address: Address, data: &[u8]) -> Result<bool> {
+        let body = self.request(&Request::Write {
+            address,
+            data: data.to_vec(),
+        })?;
+
+        Ok(body.first().is_some_and(|written| *written != 0))
+    }
+
+    pub fn memory_regions(&self) -> Result<Vec<Region>> {
+        remote::decode_regions(&self.request(&Request::Regions)?)
+    }
+}
+
+impl MemoryProvider for RemoteProvider {
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]) {
+        let result = self.read(pointer, buf);
+        if result.is_err() {
+            buf.fill(None);
+        }
+
+        *self.error.lock().unwrap_or_else(PoisonError::into_inner) =
+            result.err().map(Error::provider);
+    }
+
+    /// The regions of the remote target, fetched once and cached until
+    /// [invalidated](Self::invalidate_regions).
+    fn regions(&self) -> Vec<Region> {
+        let mut regions = self.regions.lock().unwrap_or_else(PoisonError::into_inner);
+        if regions.is_none() {
+            *regions = self.memory_regions().ok();
+        }
+
+        regions.clone().unwrap_or_default()
+    }
+
+    fn error(&self) -> Option<Error> {
+        self.error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl MemoryWriter for RemoteProvider {
+    fn write_from_buf(&mut self, pointer: Address, buf: &[u8]) -> bool {
+        self.write(pointer, buf).unwrap_or(false)
+    }
+}
@@ -0,0 +1,413 @@
+use crate::{
+    memory_view::{MemoryProvider, MemoryWriter, Permissions, Region},
+    Address,
+};
+use eyre::{ensure, Result};
+
+/// Identifies segments created by a [`SharedMemoryPublisher`].
+const MAGIC: &[u8; 8] = b"ANTNSHM1";
+/// Size of the segment header: the magic, the base address and the length of the data.
+const HEADER_SIZE: usize = 16;
+
+#[cfg(unix)]
+mod sys {
+    use eyre::{bail, Result};
+    use std::{ffi::CString, io};
+
+    /// A mapped POSIX shared memory object.
+    pub struct Segment {
+        name: CString,
+        fd: libc::c_int,
+        ptr: *mut u8,
+        len: usize,
+        owner: bool,
+    }
+
+    impl Segment {
+        fn name(name: &str) -> Result<CString> {
+            let name = if name.starts_with('/') {
+                name.to_owned()
+            } else {
+                format!("/{name}")
+            };
+
+            Ok(CString::new(name)?)
+        }
+
+        fn map(name: CString, fd: libc::c_int, len: usize, owner: bool) -> Result<Self> {
+            // SAFETY: `fd` is a valid shared memory object of at least `len` bytes, and a null
+            // address lets the kernel choose where to map it.
+            let ptr = unsafe {
+                libc::mmap(
+                    std::ptr::null_mut(),
+                    len,
+                    libc::PROT_READ | libc::PROT_WRITE,
+                    libc::MAP_SHARED,
+                    fd,
+                    0,
+                )
+            };
+
+            if ptr == libc::MAP_FAILED {
+                let error = io::Error::last_os_error();
+                // SAFETY: `fd` is a valid descriptor owned by us.
+                unsafe {
+                    libc::close(fd);
+                    if owner {
+                        libc::shm_unlink(name.as_ptr());
+                    }
+                }
+
+                bail!("mapping shared memory: {error}");
+            }
+
+            Ok(Self {
+                name,
+                fd,
+                ptr: ptr.cast(),
+                len,
+                owner,
+            })
+        }
+
+        pub fn create(name: &str, len: usize) -> Result<Self> {
+            let name = Self::name(name)?;
+
+            // SAFETY: `name` is a valid nul terminated string.
+            let fd = unsafe {
+                libc::shm_open(
+                    name.as_ptr(),
+                    libc::O_CREAT | libc::O_EXCL | libc::O_RDWR,
+                    0o600,
+                )
+            };
+            if fd < 0 {
+                bail!("creating shared memory: {}", io::Error::last_os_error());
+            }
+
+            // SAFETY: `fd` is a valid descriptor owned by us.
+            if unsafe { libc::ftruncate(fd, len as libc::off_t) } != 0 {
+                let error = io::Error::last_os_error();
+                // SAFETY: as above, and `name` refers to the object we just created.
+                unsafe {
+                    libc::close(fd);
+                    libc::shm_unlink(name.as_ptr());
+                }
+
+                bail!("resizing shared memory: {error}");
+            }
+
+            Self::map(name, fd, len, true)
+        }
+
+        pub fn open(name: &str) -> Result<Self> {
+            let name = Self::name(name)?;
+
+            // SAFETY: `name` is a valid nul terminated string.
+            let fd = unsafe { libc::shm_open(name.as_ptr(), libc::O_RDWR, 0) };
+            if fd < 0 {
+                bail!("opening shared memory: {}", io::Error::last_os_error());
+            }
+
+            let mut stat = std::mem::MaybeUninit::<libc::stat>::uninit();
+            // SAFETY: `fd` is a valid descriptor and `stat` is valid for writes.
+            if unsafe { libc::fstat(fd, stat.as_mut_ptr()) } != 0 {
+                let error = io::Error::last_os_error();
+                // SAFETY: `fd` is a valid descriptor owned by us.
+                unsafe { libc::close(fd) };
+
+                bail!("querying shared memory: {error}");
+            }
+
+            // SAFETY: fstat succeeded, so `stat` is initialized.
+            let len = unsafe { stat.assume_init() }.st_size as usize;
+            Self::map(name, fd, len, false)
+        }
+
+        pub fn as_ptr(&self) -> *mut u8 {
+            self.ptr
+        }
+
+        pub fn len(&self) -> usize {
+            self.len
+        }
+    }
+
+    impl Drop for Segment {
+        fn drop(&mut self) {
+            // SAFETY: `ptr` and `len` describe a mapping we own, and `fd` a descriptor we own.
+            unsafe {
+                libc::munmap(self.ptr.cast(), self.len);
+                libc::close(self.fd);
+                if self.owner {
+                    libc::shm_unlink(self.name.as_ptr());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+mod sys {
+    use eyre::{bail, Result};
+    use std::{io, os::windows::ffi::OsStrExt};
+    use windows_sys::Win32::{
+        Foundation::{CloseHandle, HANDLE, INVALID_HANDLE_VALUE},
+        System::Memory::{
+            CreateFileMappingW, MapViewOfFile, OpenFileMappingW, UnmapViewOfFile, VirtualQuery,
+            FILE_MAP_ALL_ACCESS, MEMORY_BASIC_INFORMATION, MEMORY_MAPPED_VIEW_ADDRESS,
+            PAGE_READWRITE,
+        },
+    };
+
+    /// A mapped view of a named file mapping backed by the paging file.
+    pub struct Segment {
+        handle: HANDLE,
+        view: MEMORY_MAPPED_VIEW_ADDRESS,
+        len: usize,
+    }
+
+    impl Segment {
+        fn name(name: &str) -> Vec<u16> {
+            std::ffi::OsStr::new(name)
+                .encode_wide()
+                .chain(std::iter::once(0))
+                .collect()
+        }
+
+        fn map(handle: HANDLE, len: usize) -> Result<Self> {
+            // SAFETY: `handle` is a valid file mapping handle owned by us.
+            let view = unsafe { MapViewOfFile(handle, FILE_MAP_ALL_ACCESS, 0, 0, len) };
+            if view.Value.is_null() {
+                let error = io::Error::last_os_error();
+                // SAFETY: as above.
+                unsafe { CloseHandle(handle) };
+
+                bail!("mapping shared memory: {error}");
+            }
+
+            Ok(Self { handle, view, len })
+        }
+
+        pub fn create(name: &str, len: usize) -> Result<Self> {
+            let name = Self::name(name);
+
+            // SAFETY: `name` is a valid nul terminated wide string, and an invalid file handle
+            // asks for a mapping backed by the paging file.
+            let handle = unsafe {
+                CreateFileMappingW(
+                    INVALID_HANDLE_VALUE,
+                    std::ptr::null(),
+                    PAGE_READWRITE,
+                    (len as u64 >> 32) as u32,
+                    len as u32,
+                    name.as_ptr(),
+                )
+            };
+            if handle == 0 {
+                bail!("creating shared memory: {}", io::Error::last_os_error());
+            }
+
+            Self::map(handle, len)
+        }
+
+        pub fn open(name: &str) -> Result<Self> {
+            let name = Self::name(name);
+
+            // SAFETY: `name` is a valid nul terminated wide string.
+            let handle = unsafe { OpenFileMappingW(FILE_MAP_ALL_ACCESS, 0, name.as_ptr()) };
+            if handle == 0 {
+                bail!("opening shared memory: {}", io::Error::last_os_error());
+            }
+
+            // map the whole object, then find out how large the view is
+            let mut segment = Self::map(handle, 0)?;
+            let mut info = std::mem::MaybeUninit::<MEMORY_BASIC_INFORMATION>::uninit();
+            // SAFETY: the view address is valid, and `info` is valid for writes of its size.
+            let written = unsafe {
+                VirtualQuery(
+                    segment.view.Value,
+                    info.as_mut_ptr(),
+                    std::mem::size_of::<MEMORY_BASIC_INFORMATION>(),
+                )
+            };
+            if written == 0 {
+                bail!("querying shared memory: {}", io::Error::last_os_error());
+            }
+
+            // SAFETY: VirtualQuery succeeded, so `info` is initialized.
+            segment.len = unsafe { info.assume_init() }.RegionSize;
+            Ok(segment)
+        }
+
+        pub fn as_ptr(&self) -> *mut u8 {
+            self.view.Value.cast()
+        }
+
+        pub fn len(&self) -> usize {
+            self.len
+        }
+    }
+
+    impl Drop for Segment {
+        fn drop(&mut self) {
+            // SAFETY: the view and the handle are owned by us.
+            unsafe {
+                UnmapViewOfFile(self.view);
+                CloseHandle(self.handle);
+            }
+        }
+    }
+}
+
+// SAFETY: the segment is a plain mapping of memory, which can be accessed from any thread.
+unsafe impl Send for sys::Segment {}
+
+/// Publishes memory through a named shared memory segment, so that a [`SharedMemoryProvider`] in
+/// another process can inspect it without any copying.
+///
+/// The segment starts with a small header describing the base address and the length of the
+/// data, followed by the data itself. An emulator can use [`as_mut_slice`](Self::as_mut_slice)
+/// directly as its RAM. The segment is removed when the publisher is dropped.
+pub struct SharedMemoryPublisher {
+    segment: sys::Segment,
+}
+
+impl SharedMemoryPublisher {
+    /// Creates a segment called `name` holding `len` bytes, shown starting at `base`. Fails if a
+    /// segment with the same name already exists.
+    pub fn create(name: &str, base: Address, len: u32) -> Result<Self> {
+        let segment = sys::Segment::create(name, HEADER_SIZE + len as usize)?;
+
+        let mut header = [0; HEADER_SIZE];
+        header[..8].copy_from_slice(MAGIC);
+        header[8..12].copy_from_slice(&base.to_le_bytes());
+        header[12..].copy_from_slice(&len.to_le_bytes());
+        // SAFETY: the segment is at least `HEADER_SIZE` bytes long.
+        unsafe { std::ptr::copy_nonoverlapping(header.as_ptr(), segment.as_ptr(), HEADER_SIZE) };
+
+        Ok(Self { segment })
+    }
+
+    pub fn len(&self) -> usize {
+        self.segment.len() - HEADER_SIZE
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The published data. Readers in other processes see changes as soon as they are made, and
+    /// might also write to it.
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        // SAFETY: the data follows the header, and the segment lives as long as `self`.
+        unsafe {
+            std::slice::from_raw_parts_mut(self.segment.as_ptr().add(HEADER_SIZE), self.len())
+        }
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.as_mut_slice().as_mut_ptr()
+    }
+}
+
+/// A provider for memory published by a [`SharedMemoryPublisher`], possibly in another process.
+pub struct SharedMemoryProvider {
+    segment: sys::Segment,
+    base: Address,
+    len: usize,
+}
+
+impl SharedMemoryProvider {
+    /// Opens the segment called `name`.
+    pub fn open(name: &str) -> Result<Self> {
+        let segment = sys::Segment::open(name)?;
+        ensure!(
+            segment.len() >= HEADER_SIZE,
+            "shared memory segment too small"
+        );
+
+        let mut header = [0; HEADER_SIZE];
+        // SAFETY: the segment is at least `HEADER_SIZE` bytes long.
+        unsafe {
+            std::ptr::copy_nonoverlapping(segment.as_ptr(), header.as_mut_ptr(), HEADER_SIZE)
+        };
+        ensure!(&header[..8] == MAGIC, "not an anton shared memory segment");
+
+        let base = u32::from_le_bytes(header[8..12].try_into().unwrap());
+        let len = u32::from_le_bytes(header[12..].try_into().unwrap()) as usize;
+        ensure!(
+            HEADER_SIZE + len <= segment.len(),
+            "shared memory segment is truncated"
+        );
+
+        Ok(Self { segment, base, len })
+    }
+
+    pub fn base(&self) -> Address {
+        self.base
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Range of offsets into the data overlapping `len` bytes starting at `pointer`, along with
+    /// the offset of that range into the buffer.
+    fn overlap(&self, pointer: Address, len: usize) -> Option<(usize, usize, usize)> {
+        let start = (pointer as u64).max(self.base as u64);
+        let end = (pointer as u64 + len as u64).min(self.base as u64 + self.len as u64);
+        (start < end).then(|| {
+            (
+                (start - pointer as u64) as usize,
+                (start - self.base as u64) as usize,
+                (end - start) as usize,
+            )
+        })
+    }
+
+    fn data(&self) -> *mut u8 {
+        // SAFETY: the data follows the header.
+        unsafe { self.segment.as_ptr().add(HEADER_SIZE) }
+    }
+}
+
+impl MemoryProvider for SharedMemoryProvider {
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]) {
+        buf.fill(None);
+        let Some((buf_offset, offset, len)) = self.overlap(pointer, buf.len()) else {
+            return;
+        };
+
+        for (index, value) in buf[buf_offset..buf_offset + len].iter_mut().enumerate() {
+            // SAFETY: `overlap` keeps the offset inside of the data. volatile, since another
+            // process might be writing to it concurrently.
+            *value = Some(unsafe { self.data().add(offset + index).read_volatile() });
+        }
+    }
+
+    fn regions(&self) -> Vec<Region> {
+        vec![Region::new(self.base, self.len as u64)
+            .permissions(Permissions::READ_WRITE)
+            .name("shared memory")]
+    }
+}
+
+impl MemoryWriter for SharedMemoryProvider {
+    fn write_from_buf(&mut self, pointer: Address, buf: &[u8]) -> bool {
+        let Some((buf_offset, offset, len)) = self.overlap(pointer, buf.len()) else {
+            return buf.is_empty();
+        };
+
+        for (index, byte) in buf[buf_offset..buf_offset + len].iter().enumerate() {
+            // SAFETY: as in `read_to_buf`.
+            unsafe { self.data().add(offset + index).write_volatile(*byte) };
+        }
+
+        len == buf.len()
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{
+    memory_view::{MemoryProvider, MemoryWriter, Permissions, Region},
+    Address,
+};
+
+/// Copies the part of `data`, mapped at `base`, that overlaps `pointer..pointer + buf.len()` into
+/// the buffer. Everything else is set to `None`.
+fn read_mapped(data: &[u8], base: Address, pointer: Address, buf: &mut [Option<u8>]) {
+    for (offset, value) in buf.iter_mut().enumerate() {
+        *value = (pointer as u64 + offset as u64)
+            .checked_sub(base as u64)
+            .and_then(|index| data.get(index as usize))
+            .copied();
+    }
+}
+
+/// A read-only provider over a byte slice mapped at a base address.
+#[derive(Debug, Clone, Copy)]
+pub struct SliceProvider<'a> {
+    base: Address,
+    data: &'a [u8],
+}
+
+impl<'a> SliceProvider<'a> {
+    pub fn new(base: Address, data: &'a [u8]) -> Self {
+        Self { base, data }
+    }
+
+    pub fn base(&self) -> Address {
+        self.base
+    }
+
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+impl<'a> MemoryProvider for SliceProvider<'a> {
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]) {
+        read_mapped(self.data, self.base, pointer, buf);
+    }
+
+    fn regions(&self) -> Vec<Region> {
+        vec![Region::new(self.base, self.data.len() as u64).permissions(Permissions::READ_ONLY)]
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(0)
+    }
+}
+
+/// A writable provider over an owned buffer mapped at a base address.
+#[derive(Debug, Clone, Default)]
+pub struct VecProvider {
+    base: Address,
+    data: Vec<u8>,
+    generation: u64,
+}
+
+impl VecProvider {
+    pub fn new(base: Address, data: Vec<u8>) -> Self {
+        Self {
+            base,
+            data,
+            generation: 0,
+        }
+    }
+
+    /// A zero-filled buffer of `size` bytes.
+    pub fn zeroed(base: Address, size: usize) -> Self {
+        Self::new(base, vec![0; size])
+    }
+
+    pub fn base(&self) -> Address {
+        self.base
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        self.generation += 1;
+        &mut self.data
+    }
+
+    pub fn into_inner(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+impl MemoryProvider for VecProvider {
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]) {
+        read_mapped(&self.data, self.base, pointer, buf);
+    }
+
+    fn regions(&self) -> Vec<Region> {
+        vec![Region::new(self.base, self.data.len() as u64)]
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(self.generation)
+    }
+}
+
+impl MemoryWriter for VecProvider {
+    fn write_from_buf(&mut self, pointer: Address, buf: &[u8]) -> bool {
+        let Some(start) = pointer.checked_sub(self.base) else {
+            return false;
+        };
+        let start = start as usize;
+        let Some(target) = self.data.get_mut(start..start + buf.len()) else {
+            return false;
+        };
+
+        target.copy_from_slice(buf);
+        self.generation += 1;
+        true
+    }
+}
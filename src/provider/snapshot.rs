@@ -0,0 +1,539 @@
+use crate::{
+    memory_view::{MemoryProvider, Permissions, Region},
+    Address,
+};
+use eyre::{bail, ensure, Context, Result};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    ops::Range,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+const MAGIC: &[u8; 8] = b"ANTNSNAP";
+const VERSION: u8 = 2;
+/// Version of the snapshots written before the metadata and regions were added, which only hold
+/// the blocks.
+const VERSION_BLOCKS_ONLY: u8 = 1;
+/// Flag set when the blocks are zstd-compressed.
+const FLAG_COMPRESSED: u8 = 1;
+
+pub(crate) fn write_string(mut writer: impl Write, text: &str) -> Result<()> {
+    let text = &text.as_bytes()[..text.len().min(u16::MAX as usize)];
+    writer.write_all(&(text.len() as u16).to_le_bytes())?;
+    writer.write_all(text)?;
+
+    Ok(())
+}
+
+pub(crate) fn read_array<const N: usize>(mut reader: impl Read) -> Result<[u8; N]> {
+    let mut array = [0; N];
+    reader.read_exact(&mut array)?;
+
+    Ok(array)
+}
+
+/// Reads `len` bytes. The buffer grows as they are read rather than being allocated up front, so
+/// that a corrupt length fails once the data runs out instead of allocating that much.
+pub(crate) fn read_vec(reader: impl Read, len: usize) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    reader.take(len as u64).read_to_end(&mut data)?;
+    ensure!(data.len() == len, "unexpected end of data");
+
+    Ok(data)
+}
+
+pub(crate) fn read_string(mut reader: impl Read) -> Result<String> {
+    let len = u16::from_le_bytes(read_array(&mut reader)?);
+    let mut text = vec![0; len as usize];
+    reader.read_exact(&mut text)?;
+
+    Ok(String::from_utf8(text)?)
+}
+
+/// Information about where and when a snapshot was taken.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SnapshotMetadata {
+    /// Name of the target the snapshot was taken from.
+    pub target: Option<String>,
+    /// When the snapshot was taken.
+    pub timestamp: Option<SystemTime>,
+}
+
+/// A captured range of memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Block {
+    start: Address,
+    values: Vec<Option<u8>>,
+}
+
+impl Block {
+    fn end(&self) -> u64 {
+        self.start as u64 + self.values.len() as u64
+    }
+}
+
+/// An immutable copy of ranges of another provider, taken at some point in time. Useful to diff
+/// against the live memory later on, e.g. with a `HexDiffView`, or to save to disk.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SnapshotProvider {
+    metadata: SnapshotMetadata,
+    /// Regions of the captured provider overlapping the captured ranges.
+    regions: Vec<Region>,
+    /// Captured ranges, sorted by address and non-overlapping.
+    blocks: Vec<Block>,
+}
+
+impl SnapshotProvider {
+    /// Copies the given ranges out of `provider`, along with the regions overlapping them.
+    /// Overlapping ranges are captured once. Ranges can reach the last byte of the address space,
+    /// and are clipped to it.
+    pub fn capture(provider: &dyn MemoryProvider, ranges: &[Range<u64>]) -> Self {
+        let mut ranges: Vec<_> = ranges
+            .iter()
+            .map(|range| range.start..range.end.min(Address::MAX as u64 + 1))
+            .filter(|range| !range.is_empty())
+            .collect();
+        ranges.sort_by_key(|range| range.start);
+
+        // merge overlapping and touching ranges
+        let mut merged: Vec<Range<u64>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+
+        let regions = provider
+            .regions()
+            .into_iter()
+            .filter(|region| {
+                merged
+                    .iter()
+                    .any(|range| (region.start as u64) < range.end && range.start < region.end())
+            })
+            .collect();
+
+        let blocks = merged
+            .into_iter()
+            .map(|range| {
+                let mut values = vec![None; (range.end - range.start) as usize];
+                provider.read_to_buf(range.start as Address, &mut values);
+                Block {
+                    start: range.start as Address,
+                    values,
+                }
+            })
+            .collect();
+
+        Self {
+            metadata: SnapshotMetadata {
+                target: None,
+                timestamp: Some(SystemTime::now()),
+            },
+            regions,
+            blocks,
+        }
+    }
+
+    /// Sets the name of the target the snapshot was taken from.
+    pub fn with_target(mut self, target: impl Into<String>) -> Self {
+        self.metadata.target = Some(target.into());
+        self
+    }
+
+    pub fn metadata(&self) -> &SnapshotMetadata {
+        &self.metadata
+    }
+
+    /// The captured ranges.
+    pub fn ranges(&self) -> Vec<Range<u64>> {
+        self.blocks
+            .iter()
+            .map(|block| block.start as u64..block.end())
+            .collect()
+    }
+
+    /// Ranges of captured addresses whose value differs in `other`.
+    pub fn changed_ranges(&self, other: &dyn MemoryProvider) -> Vec<Range<u64>> {
+        let mut changed: Vec<Range<u64>> = Vec::new();
+        let mut current = Vec::new();
+        for block in &self.blocks {
+            current.resize(block.values.len(), None);
+            other.read_to_buf(block.start, &mut current);
+
+            for (offset, (old, new)) in block.values.iter().zip(&current).enumerate() {
+                if old == new {
+                    continue;
+                }
+
+                let address = block.start as u64 + offset as u64;
+                match changed.last_mut() {
+                    Some(last) if last.end == address => last.end += 1,
+                    _ => changed.push(address..address + 1),
+                }
+            }
+        }
+
+        changed
+    }
+
+    /// Saves the snapshot to a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        self.save_with(path.as_ref(), false)
+    }
+
+    /// Saves the snapshot to a file, compressing its contents with zstd.
+    #[cfg(feature = "compressed-snapshot")]
+    pub fn save_compressed(&self, path: impl AsRef<Path>) -> Result<()> {
+        self.save_with(path.as_ref(), true)
+    }
+
+    fn save_with(&self, path: &Path, compressed: bool) -> Result<()> {
+        let file = File::create(path).wrap_err_with(|| format!("creating {}", path.display()))?;
+
+        let mut writer = BufWriter::new(file);
+        self.write_with(&mut writer, compressed)?;
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Loads a snapshot saved with [`save`](Self::save), or with `save_compressed` if the
+    /// `compressed-snapshot` feature is enabled.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).wrap_err_with(|| format!("opening {}", path.display()))?;
+
+        Self::read_from(BufReader::new(file))
+            .wrap_err_with(|| format!("loading snapshot {}", path.display()))
+    }
+
+    /// Serializes the snapshot.
+    ///
+    /// The header holds the metadata and the regions. It is followed by the blocks, each stored as
+    /// its start and length, its values (zero when unmapped) and a bitmap of which values are
+    /// mapped.
+    pub fn write_to(&self, writer: impl Write) -> Result<()> {
+        self.write_with(writer, false)
+    }
+
+    /// Serializes the snapshot like [`write_to`](Self::write_to), compressing the blocks with
+    /// zstd.
+    #[cfg(feature = "compressed-snapshot")]
+    pub fn write_compressed_to(&self, writer: impl Write) -> Result<()> {
+        self.write_with(writer, true)
+    }
+
+    fn write_with(&self, mut writer: impl Write, compressed: bool) -> Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION, if compressed { FLAG_COMPRESSED } else { 0 }])?;
+
+        // metadata
+        write_string(
+            &mut writer,
+            self.metadata.target.as_deref().unwrap_or_default(),
+        )?;
+        let timestamp = self
+            .metadata
+            .timestamp
+            .and_then(|timestamp| timestamp.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .unwrap_or_default();
+        writer.write_all(&timestamp.as_secs().to_le_bytes())?;
+        writer.write_all(&timestamp.subsec_nanos().to_le_bytes())?;
+
+        // regions
+        writer.write_all(&(self.regions.len() as u32).to_le_bytes())?;
+        for region in &self.regions {
+            let permissions = region.permissions.read as u8
+                | (region.permissions.write as u8) << 1
+                | (region.permissions.execute as u8) << 2;
+
+            writer.write_all(&region.start.to_le_bytes())?;
+            writer.write_all(&region.size.to_le_bytes())?;
+            writer.write_all(&[permissions])?;
+            write_string(&mut writer, region.name.as_deref().unwrap_or_default())?;
+        }
+
+        if compressed {
+            #[cfg(feature = "compressed-snapshot")]
+            {
+                let mut encoder =
+                    zstd::stream::write::Encoder::new(writer, zstd::DEFAULT_COMPRESSION_LEVEL)?;
+                self.write_blocks(&mut encoder)?;
+                encoder.finish()?;
+            }
+        } else {
+            self.write_blocks(writer)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_blocks(&self, mut writer: impl Write) -> Result<()> {
+        writer.write_all(&(self.blocks.len() as u32).to_le_bytes())?;
+        for block in &self.blocks {
+            writer.write_all(&block.start.to_le_bytes())?;
+            writer.write_all(&(block.values.len() as u32).to_le_bytes())?;
+
+            let data: Vec<u8> = block.values.iter().map(|v| v.unwrap_or(0)).collect();
+            writer.write_all(&data)?;
+
+            let mut mapped = vec![0u8; block.values.len().div_ceil(8)];
+            for (index, value) in block.values.iter().enumerate() {
+                if value.is_some() {
+                    mapped[index / 8] |= 1 << (index % 8);
+                }
+            }
+            writer.write_all(&mapped)?;
+        }
+
+        Ok(())
+    }
+
+    /// Deserializes a snapshot written with [`write_to`](Self::write_to), or with
+    /// `write_compressed_to` if the `compressed-snapshot` feature is enabled. Snapshots of the
+    /// first version, which only hold the blocks, are read without metadata or regions.
+    pub fn read_from(mut reader: impl Read) -> Result<Self> {
+        let magic: [u8; 8] = read_array(&mut reader)?;
+        ensure!(&magic == MAGIC, "not a snapshot");
+
+        let [version] = read_array(&mut reader)?;
+        if version == VERSION_BLOCKS_ONLY {
+            return Ok(Self {
+                metadata: SnapshotMetadata::default(),
+                regions: Vec::new(),
+                blocks: Self::read_blocks(reader)?,
+            });
+        }
+        ensure!(version == VERSION, "unsupported snapshot version {version}");
+        let [flags] = read_array(&mut reader)?;
+
+        // metadata
+        let target = read_string(&mut reader)?;
+        let secs = u64::from_le_bytes(read_array(&mut reader)?);
+        let nanos = u32::from_le_bytes(read_array(&mut reader)?);
+        ensure!(nanos < 1_000_000_000, "snapshot timestamp out of range");
+        let timestamp = if secs != 0 || nanos != 0 {
+            let since_epoch = Duration::new(secs, nanos);
+            match SystemTime::UNIX_EPOCH.checked_add(since_epoch) {
+                Some(timestamp) => Some(timestamp),
+                None => bail!("snapshot timestamp out of range"),
+            }
+        } else {
+            None
+        };
+        let metadata = SnapshotMetadata {
+            target: (!target.is_empty()).then_some(target),
+            timestamp,
+        };
+
+        // regions
+        let count = u32::from_le_bytes(read_array(&mut reader)?);
+        let mut regions = Vec::new();
+        for _ in 0..count {
+            let start = u32::from_le_bytes(read_array(&mut reader)?);
+            let size = u64::from_le_bytes(read_array(&mut reader)?);
+            let [permissions] = read_array(&mut reader)?;
+            let name = read_string(&mut reader)?;
+
+            let region = Region::new(start, size).permissions(Permissions {
+                read: permissions & 1 != 0,
+                write: permissions & 2 != 0,
+                execute: permissions & 4 != 0,
+            });
+            regions.push(if name.is_empty() {
+                region
+            } else {
+                region.name(name)
+            });
+        }
+
+        let blocks = if flags & FLAG_COMPRESSED != 0 {
+            #[cfg(feature = "compressed-snapshot")]
+            {
+                Self::read_blocks(zstd::stream::read::Decoder::new(reader)?)?
+            }
+
+            #[cfg(not(feature = "compressed-snapshot"))]
+            bail!("compressed snapshots require the `compressed-snapshot` feature")
+        } else {
+            Self::read_blocks(reader)?
+        };
+
+        Ok(Self {
+            metadata,
+            regions,
+            blocks,
+        })
+    }
+
+    fn read_blocks(mut reader: impl Read) -> Result<Vec<Block>> {
+        let count = u32::from_le_bytes(read_array(&mut reader)?);
+
+        let mut blocks: Vec<Block> = Vec::new();
+        for _ in 0..count {
+            let start = u32::from_le_bytes(read_array(&mut reader)?);
+            let len = u32::from_le_bytes(read_array(&mut reader)?) as usize;
+            if blocks.last().is_some_and(|last| last.end() > start as u64) {
+                bail!("snapshot blocks overlap or are out of order");
+            }
+
+            let data = read_vec(&mut reader, len)?;
+            let mapped = read_vec(&mut reader, len.div_ceil(8))?;
+
+            blocks.push(Block {
+                start,
+                values: data
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, value)| {
+                        (mapped[index / 8] & (1 << (index % 8)) != 0).then_some(value)
+                    })
+                    .collect(),
+            });
+        }
+
+        Ok(blocks)
+    }
+}
+
+impl MemoryProvider for SnapshotProvider {
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]) {
+        for (offset, value) in buf.iter_mut().enumerate() {
+            let address = pointer as u64 + offset as u64;
+            let index = self
+                .blocks
+                .partition_point(|block| block.start as u64 <= address);
+
+            *value = index
+                .checked_sub(1)
+                .and_then(|index| {
+                    let block = &self.blocks[index];
+                    block
+                        .values
+                        .get((address - block.start as u64) as usize)
+                        .copied()
+                })
+                .flatten();
+        }
+    }
+
+    /// The regions of the captured provider, or the captured ranges if it had none.
+    fn regions(&self) -> Vec<Region> {
+        if !self.regions.is_empty() {
+            return self
+                .regions
+                .iter()
+                .map(|region| region.clone().permissions(Permissions::READ_ONLY))
+                .collect();
+        }
+
+        self.blocks
+            .iter()
+            .map(|block| {
+                Region::new(block.start, block.values.len() as u64)
+                    .permissions(Permissions::READ_ONLY)
+                    .name("snapshot")
+            })
+            .collect()
+    }
+
+    fn generation(&self) -> Option<u64> {
+        Some(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::VecProvider;
+
+    fn snapshot() -> SnapshotProvider {
+        let provider = VecProvider::new(0x1000, (0..0x40).collect());
+        // the second range runs past the end of the provider, which reads as unmapped
+        SnapshotProvider::capture(&provider, &[0x1000..0x1010, 0x1030..0x1048]).with_target("board")
+    }
+
+    #[test]
+    fn round_trips() {
+        let snapshot = snapshot();
+        let mut bytes = Vec::new();
+        snapshot.write_to(&mut bytes).unwrap();
+
+        assert_eq!(bytes[MAGIC.len()], VERSION);
+        assert_eq!(
+            SnapshotProvider::read_from(bytes.as_slice()).unwrap(),
+            snapshot
+        );
+    }
+
+    #[cfg(feature = "compressed-snapshot")]
+    #[test]
+    fn round_trips_compressed() {
+        let snapshot = snapshot();
+        let mut bytes = Vec::new();
+        snapshot.write_compressed_to(&mut bytes).unwrap();
+
+        assert_eq!(
+            SnapshotProvider::read_from(bytes.as_slice()).unwrap(),
+            snapshot
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend([VERSION + 1, 0]);
+
+        assert!(SnapshotProvider::read_from(bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    fn rejects_out_of_range_timestamps() {
+        let read = |secs: u64, nanos: u32| {
+            let mut bytes = MAGIC.to_vec();
+            bytes.extend([VERSION, 0]);
+            write_string(&mut bytes, "board").unwrap();
+            bytes.extend(secs.to_le_bytes());
+            bytes.extend(nanos.to_le_bytes());
+            // no regions nor blocks
+            bytes.extend([0; 8]);
+
+            SnapshotProvider::read_from(bytes.as_slice())
+        };
+
+        assert!(read(1, 999_999_999).is_ok());
+        assert!(read(0, 1_000_000_000).is_err());
+        assert!(read(u64::MAX, 999_999_999).is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_blocks() {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION_BLOCKS_ONLY);
+        // a single block claiming the largest length, with no data
+        bytes.extend(1u32.to_le_bytes());
+        bytes.extend(0u32.to_le_bytes());
+        bytes.extend(u32::MAX.to_le_bytes());
+
+        assert!(SnapshotProvider::read_from(bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    fn reads_blocks_only_version() {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION_BLOCKS_ONLY);
+        snapshot().write_blocks(&mut bytes).unwrap();
+
+        let read = SnapshotProvider::read_from(bytes.as_slice()).unwrap();
+        assert_eq!(read.metadata(), &SnapshotMetadata::default());
+        assert_eq!(read.ranges(), snapshot().ranges());
+
+        let mut buf = [None; 4];
+        read.read_to_buf(0x103E, &mut buf);
+        assert_eq!(buf, [Some(0x3E), Some(0x3F), None, None]);
+    }
+}
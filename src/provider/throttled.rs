@@ -0,0 +1,229 @@
+use crate::{
+    memory_view::{MemoryProvider, MemoryWriter, Region},
+    Address, Error,
+};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// Size of the chunks data is cached in, in bytes.
+const CHUNK_SIZE: u64 = 256;
+/// Default maximum amount of cached chunks.
+const DEFAULT_CAPACITY: usize = 1024;
+
+#[derive(Debug, Default)]
+struct Cache {
+    /// Cached chunks, by chunk start, along with the time they were read.
+    chunks: HashMap<u64, (Instant, Vec<Option<u8>>)>,
+    regions: Option<(Instant, Vec<Region>)>,
+}
+
+/// A decorator that limits how often the underlying provider is queried. Data is read in chunks,
+/// and each chunk is served from a cache until it is older than the configured interval.
+///
+/// Useful for providers that are expensive to query, like other processes or remote targets,
+/// which would otherwise be read on every frame.
+#[derive(Debug)]
+pub struct ThrottledProvider<P> {
+    inner: P,
+    interval: Duration,
+    capacity: usize,
+    cache: RefCell<Cache>,
+}
+
+impl<P> ThrottledProvider<P> {
+    /// Creates a new throttled provider which queries `inner` at most once per `interval` for any
+    /// given range.
+    pub fn new(inner: P, interval: Duration) -> Self {
+        Self {
+            inner,
+            interval,
+            capacity: DEFAULT_CAPACITY,
+            cache: RefCell::new(Cache::default()),
+        }
+    }
+
+    /// Sets the maximum amount of cached chunks. Once exceeded, the oldest chunks are dropped at
+    /// the end of the read, so that a read larger than the cache still gets every chunk it
+    /// fetched.
+    pub fn with_capacity(self, capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            ..self
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// Returns the underlying provider. Since it might be modified through the returned
+    /// reference, the cache is dropped.
+    pub fn inner_mut(&mut self) -> &mut P {
+        self.invalidate();
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+
+    /// Drops every cached value, so that the next reads query the underlying provider.
+    pub fn invalidate(&mut self) {
+        let cache = self.cache.get_mut();
+        cache.chunks.clear();
+        cache.regions = None;
+    }
+
+    /// Drops the cached values of the chunks overlapping `len` bytes starting at `pointer`.
+    fn invalidate_range(&mut self, pointer: Address, len: usize) {
+        if len == 0 {
+            return;
+        }
+
+        let start = pointer as u64 / CHUNK_SIZE;
+        let end = (pointer as u64 + len as u64 - 1) / CHUNK_SIZE;
+        let chunks = &mut self.cache.get_mut().chunks;
+        for chunk in start..=end {
+            chunks.remove(&(chunk * CHUNK_SIZE));
+        }
+    }
+
+    fn is_fresh(&self, time: Instant, now: Instant) -> bool {
+        now.duration_since(time) < self.interval
+    }
+}
+
+impl<P> ThrottledProvider<P>
+where
+    P: MemoryProvider,
+{
+    /// Reads the chunks starting at `first` (inclusive) up to `last` (inclusive) from the
+    /// underlying provider in a single query, and caches them.
+    fn fetch(&self, cache: &mut Cache, first: u64, last: u64, now: Instant) {
+        let start = first * CHUNK_SIZE;
+        let end = ((last + 1) * CHUNK_SIZE).min(1 << Address::BITS);
+        let mut values = vec![None; (end - start) as usize];
+        self.inner.read_to_buf(start as Address, &mut values);
+
+        for (index, chunk) in values.chunks(CHUNK_SIZE as usize).enumerate() {
+            let chunk_start = start + index as u64 * CHUNK_SIZE;
+            cache.chunks.insert(chunk_start, (now, chunk.to_vec()));
+        }
+    }
+
+    /// Drops the oldest chunks when over capacity.
+    fn evict(&self, cache: &mut Cache) {
+        if cache.chunks.len() <= self.capacity {
+            return;
+        }
+
+        let mut times: Vec<_> = cache
+            .chunks
+            .iter()
+            .map(|(start, (time, _))| (*time, *start))
+            .collect();
+        times.sort_unstable();
+
+        let excess = cache.chunks.len() - self.capacity;
+        for (_, start) in times.into_iter().take(excess) {
+            cache.chunks.remove(&start);
+        }
+    }
+}
+
+impl<P> MemoryProvider for ThrottledProvider<P>
+where
+    P: MemoryProvider,
+{
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]) {
+        if buf.is_empty() {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut cache = self.cache.borrow_mut();
+
+        // refresh stale chunks, coalescing consecutive ones into a single read
+        let first = pointer as u64 / CHUNK_SIZE;
+        let last = (pointer as u64 + buf.len() as u64 - 1).min(Address::MAX as u64) / CHUNK_SIZE;
+        let mut stale_start = None;
+        for chunk in first..=last {
+            let fresh = cache
+                .chunks
+                .get(&(chunk * CHUNK_SIZE))
+                .is_some_and(|(time, _)| self.is_fresh(*time, now));
+
+            match (fresh, stale_start) {
+                (false, None) => stale_start = Some(chunk),
+                (true, Some(start)) => {
+                    self.fetch(&mut cache, start, chunk - 1, now);
+                    stale_start = None;
+                }
+                _ => (),
+            }
+        }
+        if let Some(start) = stale_start {
+            self.fetch(&mut cache, start, last, now);
+        }
+
+        for (offset, value) in buf.iter_mut().enumerate() {
+            let address = pointer as u64 + offset as u64;
+            let chunk_start = address - address % CHUNK_SIZE;
+            *value = cache
+                .chunks
+                .get(&chunk_start)
+                .and_then(|(_, values)| values.get((address - chunk_start) as usize).copied())
+                .flatten();
+        }
+
+        self.evict(&mut cache);
+    }
+
+    fn regions(&self) -> Vec<Region> {
+        let now = Instant::now();
+        let mut cache = self.cache.borrow_mut();
+        match &cache.regions {
+            Some((time, regions)) if self.is_fresh(*time, now) => regions.clone(),
+            _ => {
+                let regions = self.inner.regions();
+                cache.regions = Some((now, regions.clone()));
+                regions
+            }
+        }
+    }
+
+    fn is_modified(&self, address: Address) -> bool {
+        self.inner.is_modified(address)
+    }
+
+    fn is_stale(&self, address: Address) -> bool {
+        self.inner.is_stale(address)
+    }
+
+    fn error(&self) -> Option<Error> {
+        self.inner.error()
+    }
+}
+
+impl<P> MemoryWriter for ThrottledProvider<P>
+where
+    P: MemoryWriter,
+{
+    fn write_from_buf(&mut self, pointer: Address, buf: &[u8]) -> bool {
+        let written = self.inner.write_from_buf(pointer, buf);
+        self.invalidate_range(pointer, buf.len());
+
+        written
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{
+    memory_view::{MemoryProvider, MemoryWriter, Permissions, Region},
+    Address,
+};
+use wasmtime::{AsContext, AsContextMut, Instance, Memory};
+
+/// Size of a WebAssembly page, in bytes.
+const PAGE_SIZE: u64 = 0x10000;
+
+/// A provider for the linear memory of a WebAssembly instance.
+///
+/// `S` is anything that gives access to the store the memory belongs to, like a `&Store<T>`, a
+/// `&mut Store<T>` or a `&mut Caller<'_, T>`. Writing requires mutable access. Bounds are checked
+/// against the current size of the memory on every access, so growth is picked up immediately.
+pub struct WasmMemoryProvider<S> {
+    store: S,
+    memory: Memory,
+}
+
+impl<S> WasmMemoryProvider<S>
+where
+    S: AsContext,
+{
+    pub fn new(store: S, memory: Memory) -> Self {
+        Self { store, memory }
+    }
+
+    pub fn memory(&self) -> Memory {
+        self.memory
+    }
+
+    pub fn into_store(self) -> S {
+        self.store
+    }
+
+    /// Current size of the memory, in bytes.
+    pub fn len(&self) -> u64 {
+        self.memory.data_size(&self.store) as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Maximum size the memory can grow to, in bytes, if it is limited.
+    pub fn max_len(&self) -> Option<u64> {
+        self.memory
+            .ty(&self.store)
+            .maximum()
+            .map(|pages| pages.saturating_mul(PAGE_SIZE))
+    }
+}
+
+impl<S> WasmMemoryProvider<S>
+where
+    S: AsContextMut,
+{
+    /// Creates a provider for the memory exported by `instance` as `name`. Returns `None` if
+    /// there's no such memory.
+    pub fn exported(mut store: S, instance: &Instance, name: &str) -> Option<Self> {
+        let memory = instance.get_memory(&mut store, name)?;
+        Some(Self::new(store, memory))
+    }
+}
+
+impl<S> MemoryProvider for WasmMemoryProvider<S>
+where
+    S: AsContext,
+{
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]) {
+        let data = self.memory.data(&self.store);
+        let start = (pointer as usize).min(data.len());
+        let available = &data[start..(start + buf.len()).min(data.len())];
+
+        let (mapped, unmapped) = buf.split_at_mut(available.len());
+        for (value, byte) in mapped.iter_mut().zip(available) {
+            *value = Some(*byte);
+        }
+        unmapped.fill(None);
+    }
+
+    fn regions(&self) -> Vec<Region> {
+        let len = self.len().min(1 << Address::BITS);
+        if len == 0 {
+            return Vec::new();
+        }
+
+        vec![Region::new(0, len)
+            .permissions(Permissions::READ_WRITE)
+            .name("linear memory")]
+    }
+}
+
+impl<S> MemoryWriter for WasmMemoryProvider<S>
+where
+    S: AsContextMut,
+{
+    fn write_from_buf(&mut self, pointer: Address, buf: &[u8]) -> bool {
+        self.memory
+            .write(&mut self.store, pointer as usize, buf)
+            .is_ok()
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{
+    memory_view::{MemoryProvider, MemoryWriter, Permissions, Region},
+    Address,
+};
+use eyre::{eyre, Result};
+use std::{ffi::c_void, mem::MaybeUninit};
+use windows_sys::Win32::{
+    Foundation::{CloseHandle, HANDLE},
+    System::{
+        Diagnostics::Debug::{ReadProcessMemory, WriteProcessMemory},
+        Memory::{
+            VirtualQueryEx, MEMORY_BASIC_INFORMATION, MEM_COMMIT, PAGE_EXECUTE, PAGE_EXECUTE_READ,
+            PAGE_EXECUTE_READWRITE, PAGE_EXECUTE_WRITECOPY, PAGE_GUARD, PAGE_NOACCESS,
+            PAGE_PROTECTION_FLAGS, PAGE_READONLY, PAGE_READWRITE, PAGE_WRITECOPY,
+        },
+        Threading::{
+            OpenProcess, PROCESS_QUERY_INFORMATION, PROCESS_VM_OPERATION, PROCESS_VM_READ,
+            PROCESS_VM_WRITE,
+        },
+    },
+};
+
+const PAGE_SIZE: u64 = 4096;
+
+fn permissions(protect: PAGE_PROTECTION_FLAGS) -> Permissions {
+    if protect & (PAGE_GUARD | PAGE_NOACCESS) != 0 {
+        return Permissions::default();
+    }
+
+    let read = PAGE_READONLY
+        | PAGE_READWRITE
+        | PAGE_WRITECOPY
+        | PAGE_EXECUTE_READ
+        | PAGE_EXECUTE_READWRITE
+        | PAGE_EXECUTE_WRITECOPY;
+    let write = PAGE_READWRITE | PAGE_WRITECOPY | PAGE_EXECUTE_READWRITE | PAGE_EXECUTE_WRITECOPY;
+    let execute =
+        PAGE_EXECUTE | PAGE_EXECUTE_READ | PAGE_EXECUTE_READWRITE | PAGE_EXECUTE_WRITECOPY;
+
+    Permissions {
+        read: protect & read != 0,
+        write: protect & write != 0,
+        execute: protect & execute != 0,
+    }
+}
+
+/// A provider over the memory of a live process, read through `ReadProcessMemory`.
+///
+/// Addresses are 32 bits wide, so the provider exposes a 4 GiB window of the process' address
+/// space starting at [`base`](Self::base).
+pub struct WindowsProcessProvider {
+    pid: u32,
+    base: u64,
+    handle: HANDLE,
+}
+
+impl WindowsProcessProvider {
+    /// Attaches to the process with the given pid, asking for write access if possible.
+    pub fn attach(pid: u32) -> Result<Self> {
+        let read = PROCESS_VM_READ | PROCESS_QUERY_INFORMATION;
+
+        // SAFETY: OpenProcess has no preconditions, failure is reported through a null handle
+        let handle = unsafe {
+            match OpenProcess(read | PROCESS_VM_WRITE | PROCESS_VM_OPERATION, 0, pid) {
+                0 => OpenProcess(read, 0, pid),
+                handle => handle,
+            }
+        };
+        if handle == 0 {
+            return Err(eyre!(
+                "failed to open process {pid}: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+
+        Ok(Self {
+            pid,
+            base: 0,
+            handle,
+        })
+    }
+
+    /// Moves the window into the process' address space so that address `0` maps to `base`.
+    pub fn with_base(self, base: u64) -> Self {
+        Self { base, ..self }
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    pub fn base(&self) -> u64 {
+        self.base
+    }
+
+    /// Queries the region containing `address`.
+    fn query(&self, address: u64) -> Option<MEMORY_BASIC_INFORMATION> {
+        let mut info = MaybeUninit::<MEMORY_BASIC_INFORMATION>::uninit();
+
+        // SAFETY: `info` is valid for writes of the length passed
+        let written = unsafe {
+            VirtualQueryEx(
+                self.handle,
+                address as *const c_void,
+                info.as_mut_ptr(),
+                std::mem::size_of::<MEMORY_BASIC_INFORMATION>(),
+            )
+        };
+
+        // SAFETY: a non-zero return means the structure was filled in
+        (written != 0).then(|| unsafe { info.assume_init() })
+    }
+
+    /// Reads as much as possible starting at `address`. Returns the amount of bytes read.
+    fn read_raw(&self, address: u64, buf: &mut [u8]) -> usize {
+        let mut read = 0;
+
+        // SAFETY: `buf` is valid for writes of its length
+        unsafe {
+            ReadProcessMemory(
+                self.handle,
+                address as *const c_void,
+                buf.as_mut_ptr().cast(),
+                buf.len(),
+                &mut read,
+            );
+        }
+
+        read
+    }
+}
+
+impl Drop for WindowsProcessProvider {
+    fn drop(&mut self) {
+        // SAFETY: the handle is owned by the provider and valid until now
+        unsafe {
+            CloseHandle(self.handle);
+        }
+    }
+}
+
+impl MemoryProvider for WindowsProcessProvider {
+    fn read_to_buf(&self, pointer: Address, buf: &mut [Option<u8>]) {
+        let start = self.base + pointer as u64;
+        let mut bytes = vec![0; buf.len()];
+        if self.read_raw(start, &mut bytes) == bytes.len() {
+            for (value, byte) in buf.iter_mut().zip(bytes) {
+                *value = Some(byte);
+            }
+            return;
+        }
+
+        // reads of ranges containing unmapped pages fail as a whole, so retry page by page
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let address = start + offset as u64;
+            let end = (offset + (PAGE_SIZE - address % PAGE_SIZE) as usize).min(bytes.len());
+            let read = self.read_raw(address, &mut bytes[offset..end]);
+            for (index, value) in buf[offset..end].iter_mut().enumerate() {
+                *value = (index < read).then_some(bytes[offset + index]);
+            }
+            offset = end;
+        }
+    }
+
+    fn regions(&self) -> Vec<Region> {
+        let window_end = self.base + Address::MAX as u64 + 1;
+
+        let mut regions = Vec::new();
+        let mut address = self.base;
+        while address < window_end {
+            let Some(info) = self.query(address) else {
+                break;
+            };
+
+            let start = (info.BaseAddress as u64).max(self.base);
+            let end = (info.BaseAddress as u64 + info.RegionSize as u64).min(window_end);
+            if end <= address {
+                break;
+            }
+
+            if info.State == MEM_COMMIT && start < end {
+                regions.push(
+                    Region::new((start - self.base) as Address, end - start)
+                        .permissions(permissions(info.Protect)),
+                );
+            }
+
+            address = end;
+        }
+
+        regions
+    }
+}
+
+impl MemoryWriter for WindowsProcessProvider {
+    fn write_from_buf(&mut self, pointer: Address, buf: &[u8]) -> bool {
+        let mut written = 0;
+
+        // SAFETY: `buf` is valid for reads of its length
+        let ok = unsafe {
+            WriteProcessMemory(
+                self.handle,
+                (self.base + pointer as u64) as *const c_void,
+                buf.as_ptr().cast(),
+                buf.len(),
+                &mut written,
+            )
+        };
+
+        ok != 0 && written == buf.len()
+    }
+}
@@ -0,0 +1,413 @@
+//! Recordings of sessions: the terminal events an application received, along with snapshots of
+//! the memory it showed, so that a bug report can come with a session to replay rather than a
+//! video of it.
+//!
+//! Recordings are made and played back by the [`Runner`](crate::app::Runner) of the `app`
+//! feature, but can be read and driven by hand too, e.g. to replay them through widgets in a test.
+
+use crate::{
+    keymap::KeyChord,
+    provider::{read_array, read_string, write_string, SnapshotProvider},
+};
+use crossterm::event::{
+    Event, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use eyre::{bail, ensure, Context, Result};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, ErrorKind, Read, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+const MAGIC: &[u8; 8] = b"ANTNREC\0";
+const VERSION: u8 = 1;
+
+const TAG_KEY: u8 = 0;
+const TAG_MOUSE: u8 = 1;
+const TAG_RESIZE: u8 = 2;
+const TAG_TICK: u8 = 3;
+const TAG_SNAPSHOT: u8 = 4;
+
+/// Something that happened during a recorded session.
+#[derive(Debug)]
+pub enum Entry {
+    /// A key, mouse or resize event. Other events aren't recorded.
+    Event(Event),
+    /// A tick of the run loop, i.e. a call to [`App::update`](crate::app::App::update).
+    Tick,
+    /// The memory the application showed at that point.
+    Snapshot(SnapshotProvider),
+}
+
+/// Writes a recording as a session goes.
+///
+/// Entries are buffered, so the recording is only complete once [finished](Self::finish).
+pub struct Recorder {
+    writer: Box<dyn Write>,
+    start: Instant,
+    /// Amount of ticks between snapshots, if any are taken.
+    snapshot_interval: Option<u32>,
+    ticks: u32,
+}
+
+impl Recorder {
+    /// Records to a new file at `path`, replacing any existing one.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::create(path).wrap_err_with(|| format!("creating {}", path.display()))?;
+
+        Self::new(BufWriter::new(file))
+    }
+
+    /// Records to `writer`, starting the clock of the recording.
+    pub fn new(mut writer: impl Write + 'static) -> Result<Self> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+
+        Ok(Self {
+            writer: Box::new(writer),
+            start: Instant::now(),
+            snapshot_interval: Some(4),
+            ticks: 0,
+        })
+    }
+
+    /// How many ticks go by between snapshots taken by the [`Runner`](crate::app::Runner), or
+    /// `None` to only take one when recording starts. Defaults to 4, i.e. a second with the
+    /// default tick rate.
+    pub fn snapshot_interval(self, snapshot_interval: Option<u32>) -> Self {
+        Self {
+            snapshot_interval: snapshot_interval.map(|interval| interval.max(1)),
+            ..self
+        }
+    }
+
+    /// Records an event. Events other than keys, mouse events and resizes are ignored, as are
+    /// keys that have no [`KeyChord`] equivalent.
+    pub fn record_event(&mut self, event: &Event) -> Result<()> {
+        match *event {
+            Event::Key(key) => {
+                let chord = KeyChord::from(key);
+                if chord.to_string().parse::<KeyChord>().ok() != Some(chord) {
+                    return Ok(());
+                }
+
+                self.write_header(TAG_KEY)?;
+                write_string(&mut self.writer, &chord.to_string())?;
+                self.writer
+                    .write_all(&[key.modifiers.bits(), encode_key_kind(key.kind)])?;
+            }
+            Event::Mouse(mouse) => {
+                self.write_header(TAG_MOUSE)?;
+                let (kind, button) = encode_mouse_kind(mouse.kind);
+                self.writer
+                    .write_all(&[kind, button, mouse.modifiers.bits()])?;
+                self.writer.write_all(&mouse.column.to_le_bytes())?;
+                self.writer.write_all(&mouse.row.to_le_bytes())?;
+            }
+            Event::Resize(width, height) => {
+                self.write_header(TAG_RESIZE)?;
+                self.writer.write_all(&width.to_le_bytes())?;
+                self.writer.write_all(&height.to_le_bytes())?;
+            }
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    /// Records a tick. Returns whether a snapshot is due, as of the
+    /// [interval](Self::snapshot_interval).
+    pub fn record_tick(&mut self) -> Result<bool> {
+        self.write_header(TAG_TICK)?;
+        self.ticks += 1;
+
+        Ok(self
+            .snapshot_interval
+            .is_some_and(|interval| self.ticks.is_multiple_of(interval)))
+    }
+
+    /// Records a snapshot of the memory shown.
+    pub fn record_snapshot(&mut self, snapshot: &SnapshotProvider) -> Result<()> {
+        let mut bytes = Vec::new();
+        snapshot.write_to(&mut bytes)?;
+
+        self.write_header(TAG_SNAPSHOT)?;
+        self.writer.write_all(&(bytes.len() as u64).to_le_bytes())?;
+        self.writer.write_all(&bytes)?;
+
+        Ok(())
+    }
+
+    /// Flushes the recording.
+    pub fn finish(mut self) -> Result<()> {
+        self.writer.flush()?;
+
+        Ok(())
+    }
+
+    fn write_header(&mut self, tag: u8) -> Result<()> {
+        let elapsed = self.start.elapsed().as_millis() as u64;
+        self.writer.write_all(&[tag])?;
+        self.writer.write_all(&elapsed.to_le_bytes())?;
+
+        Ok(())
+    }
+}
+
+/// A recording made with a [`Recorder`].
+#[derive(Debug)]
+pub struct Recording {
+    /// Each entry, along with the time since the start of the recording.
+    entries: Vec<(Duration, Entry)>,
+}
+
+impl Recording {
+    /// Loads a recording saved by a [`Recorder`] made with [`create`](Recorder::create).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).wrap_err_with(|| format!("opening {}", path.display()))?;
+
+        Self::read_from(BufReader::new(file))
+            .wrap_err_with(|| format!("loading recording {}", path.display()))
+    }
+
+    /// Reads a recording. A recording cut short, e.g. because the application crashed before it
+    /// was finished, is read up to its last complete entry.
+    pub fn read_from(mut reader: impl Read) -> Result<Self> {
+        let magic: [u8; 8] = read_array(&mut reader)?;
+        ensure!(&magic == MAGIC, "not a recording");
+
+        let [version] = read_array(&mut reader)?;
+        ensure!(
+            version == VERSION,
+            "unsupported recording version {version}"
+        );
+
+        let mut entries = Vec::new();
+        loop {
+            match Self::read_entry(&mut reader) {
+                Ok(entry) => entries.push(entry),
+                Err(error)
+                    if error
+                        .downcast_ref::<std::io::Error>()
+                        .is_some_and(|error| error.kind() == ErrorKind::UnexpectedEof) =>
+                {
+                    break
+                }
+                Err(error) => return Err(error.wrap_err(format!("entry {}", entries.len()))),
+            }
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// The entries of the recording, along with their time since its start, in order.
+    pub fn entries(&self) -> &[(Duration, Entry)] {
+        &self.entries
+    }
+
+    /// Length of the recording, i.e. the time of its last entry.
+    pub fn duration(&self) -> Duration {
+        self.entries
+            .last()
+            .map_or(Duration::ZERO, |(time, _)| *time)
+    }
+
+    fn read_entry(mut reader: impl Read) -> Result<(Duration, Entry)> {
+        let [tag] = read_array(&mut reader)?;
+        let elapsed = Duration::from_millis(u64::from_le_bytes(read_array(&mut reader)?));
+
+        let entry = match tag {
+            TAG_KEY => {
+                let chord = read_string(&mut reader)?;
+                let chord: KeyChord = match chord.parse() {
+                    Ok(chord) => chord,
+                    Err(error) => bail!("invalid key `{chord}`: {error}"),
+                };
+                let [modifiers, kind] = read_array(&mut reader)?;
+
+                Entry::Event(Event::Key(KeyEvent::new_with_kind(
+                    chord.code,
+                    KeyModifiers::from_bits_truncate(modifiers),
+                    decode_key_kind(kind)?,
+                )))
+            }
+            TAG_MOUSE => {
+                let [kind, button, modifiers] = read_array(&mut reader)?;
+                let column = u16::from_le_bytes(read_array(&mut reader)?);
+                let row = u16::from_le_bytes(read_array(&mut reader)?);
+
+                Entry::Event(Event::Mouse(MouseEvent {
+                    kind: decode_mouse_kind(kind, button)?,
+                    column,
+                    row,
+                    modifiers: KeyModifiers::from_bits_truncate(modifiers),
+                }))
+            }
+            TAG_RESIZE => {
+                let width = u16::from_le_bytes(read_array(&mut reader)?);
+                let height = u16::from_le_bytes(read_array(&mut reader)?);
+
+                Entry::Event(Event::Resize(width, height))
+            }
+            TAG_TICK => Entry::Tick,
+            TAG_SNAPSHOT => {
+                let len = u64::from_le_bytes(read_array(&mut reader)?);
+                let mut bytes = Vec::new();
+                (&mut reader).take(len).read_to_end(&mut bytes)?;
+                if (bytes.len() as u64) < len {
+                    return Err(std::io::Error::from(ErrorKind::UnexpectedEof).into());
+                }
+
+                Entry::Snapshot(SnapshotProvider::read_from(bytes.as_slice())?)
+            }
+            tag => bail!("unknown entry {tag}"),
+        };
+
+        Ok((elapsed, entry))
+    }
+}
+
+fn encode_key_kind(kind: KeyEventKind) -> u8 {
+    match kind {
+        KeyEventKind::Press => 0,
+        KeyEventKind::Repeat => 1,
+        KeyEventKind::Release => 2,
+    }
+}
+
+fn decode_key_kind(kind: u8) -> Result<KeyEventKind> {
+    Ok(match kind {
+        0 => KeyEventKind::Press,
+        1 => KeyEventKind::Repeat,
+        2 => KeyEventKind::Release,
+        kind => bail!("unknown key event kind {kind}"),
+    })
+}
+
+fn encode_mouse_kind(kind: MouseEventKind) -> (u8, u8) {
+    let button = |button| match button {
+        MouseButton::Left => 0,
+        MouseButton::Right => 1,
+        MouseButton::Middle => 2,
+    };
+
+    match kind {
+        MouseEventKind::Down(b) => (0, button(b)),
+        MouseEventKind::Up(b) => (1, button(b)),
+        MouseEventKind::Drag(b) => (2, button(b)),
+        MouseEventKind::Moved => (3, 0),
+        MouseEventKind::ScrollDown => (4, 0),
+        MouseEventKind::ScrollUp => (5, 0),
+        MouseEventKind::ScrollLeft => (6, 0),
+        MouseEventKind::ScrollRight => (7, 0),
+    }
+}
+
+fn decode_mouse_kind(kind: u8, button: u8) -> Result<MouseEventKind> {
+    let button = match button {
+        0 => MouseButton::Left,
+        1 => MouseButton::Right,
+        2 => MouseButton::Middle,
+        button => bail!("unknown mouse button {button}"),
+    };
+
+    Ok(match kind {
+        0 => MouseEventKind::Down(button),
+        1 => MouseEventKind::Up(button),
+        2 => MouseEventKind::Drag(button),
+        3 => MouseEventKind::Moved,
+        4 => MouseEventKind::ScrollDown,
+        5 => MouseEventKind::ScrollUp,
+        6 => MouseEventKind::ScrollLeft,
+        7 => MouseEventKind::ScrollRight,
+        kind => bail!("unknown mouse event kind {kind}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::VecProvider;
+    use crossterm::event::KeyCode;
+    use std::{cell::RefCell, io, rc::Rc};
+
+    /// A writer whose bytes can still be read once the recorder owning it is finished.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn record(key: Event, mouse: Event, snapshot: &SnapshotProvider) -> Vec<u8> {
+        let buffer = SharedBuffer::default();
+        let mut recorder = Recorder::new(buffer.clone())
+            .unwrap()
+            .snapshot_interval(Some(2));
+
+        recorder.record_event(&key).unwrap();
+        recorder.record_event(&mouse).unwrap();
+        recorder.record_event(&Event::Resize(80, 24)).unwrap();
+        recorder.record_event(&Event::FocusGained).unwrap();
+        assert!(!recorder.record_tick().unwrap());
+        assert!(recorder.record_tick().unwrap());
+        recorder.record_snapshot(snapshot).unwrap();
+        recorder.finish().unwrap();
+
+        buffer.0.take()
+    }
+
+    #[test]
+    fn round_trips() {
+        let key = Event::Key(KeyEvent::new(KeyCode::Char('g'), KeyModifiers::CONTROL));
+        let mouse = Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 3,
+            row: 7,
+            modifiers: KeyModifiers::ALT,
+        });
+        let provider = VecProvider::new(0x100, vec![1, 2, 3, 4]);
+        let snapshot = SnapshotProvider::capture(&provider, std::slice::from_ref(&(0x100..0x104)));
+
+        let bytes = record(key.clone(), mouse.clone(), &snapshot);
+        let recording = Recording::read_from(bytes.as_slice()).unwrap();
+        let entries: Vec<_> = recording.entries().iter().map(|(_, entry)| entry).collect();
+        assert!(matches!(
+            entries.as_slice(),
+            [
+                Entry::Event(read_key),
+                Entry::Event(read_mouse),
+                Entry::Event(Event::Resize(80, 24)),
+                Entry::Tick,
+                Entry::Tick,
+                Entry::Snapshot(read_snapshot),
+            ] if *read_key == key && *read_mouse == mouse && *read_snapshot == snapshot
+        ));
+    }
+
+    #[test]
+    fn reads_truncated_recordings() {
+        let key = Event::Key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        let mouse = Event::Mouse(MouseEvent {
+            kind: MouseEventKind::ScrollUp,
+            column: 0,
+            row: 0,
+            modifiers: KeyModifiers::NONE,
+        });
+        let snapshot = SnapshotProvider::default();
+
+        let bytes = record(key, mouse, &snapshot);
+        let recording = Recording::read_from(&bytes[..bytes.len() - 1]).unwrap();
+        assert_eq!(recording.entries().len(), 5);
+        assert!(matches!(recording.entries().last(), Some((_, Entry::Tick))));
+    }
+}
@@ -0,0 +1,339 @@
+use crate::{i18n::strings, keymap::Action, memory_view::Region, Address, Keymap, Theme};
+use crossterm::event::{KeyEvent, KeyEventKind, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, StatefulWidget, Widget},
+};
+use std::ops::Range;
+
+/// What a region of the address space holds, which decides its color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegionKind {
+    Rom,
+    Ram,
+    /// Memory-mapped peripherals.
+    Mmio,
+    Unmapped,
+}
+
+impl RegionKind {
+    pub const ALL: [Self; 4] = [Self::Rom, Self::Ram, Self::Mmio, Self::Unmapped];
+
+    /// Guesses the kind of a region: regions named like peripherals (`mmio`, `io`, `periph`...)
+    /// are MMIO, and the others are RAM if writable, or ROM otherwise.
+    pub fn guess(region: &Region) -> Self {
+        let name = region.name.as_deref().unwrap_or("").to_ascii_lowercase();
+        let is_mmio = name
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .any(|word| {
+                matches!(
+                    word,
+                    "io" | "mmio" | "periph" | "peripheral" | "peripherals"
+                )
+            });
+
+        if is_mmio {
+            Self::Mmio
+        } else if region.permissions.write {
+            Self::Ram
+        } else {
+            Self::Rom
+        }
+    }
+
+    /// Name of the kind, from the [strings](crate::i18n) of the widgets.
+    pub fn label(self) -> String {
+        let strings = strings();
+        match self {
+            Self::Rom => strings.rom.to_string(),
+            Self::Ram => strings.ram.to_string(),
+            Self::Mmio => strings.mmio.to_string(),
+            Self::Unmapped => strings.unmapped.to_string(),
+        }
+    }
+
+    pub(crate) fn style(self, theme: &Theme) -> Style {
+        match self {
+            Self::Rom => theme.rom,
+            Self::Ram => theme.ram,
+            Self::Mmio => theme.mmio,
+            Self::Unmapped => theme.unmapped,
+        }
+    }
+
+    pub(crate) fn symbol(self) -> &'static str {
+        match self {
+            Self::Unmapped => "·",
+            _ => "█",
+        }
+    }
+}
+
+pub struct RegionBarViewState {
+    /// Index of the selected cell of the bar, if any.
+    selected: Option<u16>,
+    /// Index of the region drawn in each cell, if any, and the address jumped to when activating
+    /// it, as of the last render.
+    cells: Vec<(Option<usize>, Address)>,
+    /// Area of the bar, as of the last render.
+    bar: Rect,
+}
+
+impl Default for RegionBarViewState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegionBarViewState {
+    pub fn new() -> Self {
+        Self {
+            selected: None,
+            cells: Vec::new(),
+            bar: Rect::default(),
+        }
+    }
+
+    /// Address the selected cell jumps to, as of the last render: the start of the part of the
+    /// region drawn in it, or of the cell if it is unmapped.
+    pub fn selected_address(&self) -> Option<Address> {
+        self.selected
+            .and_then(|selected| self.cells.get(selected as usize))
+            .map(|(_, address)| *address)
+    }
+
+    /// Handles a key event with the [standard keymap](Keymap::standard).
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Address> {
+        self.handle_key_with(key, Keymap::standard())
+    }
+
+    /// Handles a key event, translated to an action by `keymap`. Left and right move the
+    /// selection by a cell, and up and down to the start of the previous or next segment, i.e.
+    /// run of cells showing the same region or unmapped memory. Returns the
+    /// [selected address](Self::selected_address) when the selection is activated, so that the
+    /// memory view can jump to it.
+    pub fn handle_key_with(&mut self, key: KeyEvent, keymap: &Keymap) -> Option<Address> {
+        if key.kind == KeyEventKind::Release || self.cells.is_empty() {
+            return None;
+        }
+
+        let last = self.cells.len() as u16 - 1;
+        let selected = self.selected.unwrap_or(0).min(last);
+        let segment = |index: u16| self.cells[index as usize].0;
+        let segment_start = |index: u16| {
+            (0..=index)
+                .rev()
+                .take_while(|&i| segment(i) == segment(index))
+                .last()
+                .unwrap_or(index)
+        };
+        self.selected = Some(match keymap.action(key) {
+            Some(Action::Left) => selected.saturating_sub(1),
+            Some(Action::Right) => (selected + 1).min(last),
+            Some(Action::Up) => segment_start(segment_start(selected).saturating_sub(1)),
+            Some(Action::Down) => (selected..=last)
+                .find(|&i| segment(i) != segment(selected))
+                .unwrap_or(selected),
+            Some(Action::Home) => 0,
+            Some(Action::End) => last,
+            Some(Action::Activate) => return self.selected_address(),
+            _ => return None,
+        });
+
+        None
+    }
+
+    /// Selects the cell that was clicked. Returns its [address](Self::selected_address), so that
+    /// the memory view can jump to it.
+    pub fn handle_mouse(&mut self, mouse: MouseEvent) -> Option<Address> {
+        let MouseEventKind::Down(MouseButton::Left) = mouse.kind else {
+            return None;
+        };
+        if !(self.bar.left()..self.bar.right()).contains(&mouse.column)
+            || !(self.bar.top()..self.bar.bottom()).contains(&mouse.row)
+        {
+            return None;
+        }
+
+        self.selected = Some(mouse.column - self.bar.x);
+        self.selected_address()
+    }
+}
+
+/// The address space, or a part of it, drawn as a single bar with regions colored by
+/// [kind](RegionKind), e.g. next to a [`MemoryMapView`](crate::memory_map_view::MemoryMapView).
+///
+/// The bar takes the first row, or the second one if there is room for a marker of the pointer
+/// above it. A third row shows the bounds of the range.
+pub struct RegionBarView<'a> {
+    /// The regions to draw.
+    regions: &'a [Region],
+
+    /// Range of addresses covered by the bar.
+    range: Range<u64>,
+
+    /// Decides the kind of each region.
+    classify: &'a dyn Fn(&Region) -> RegionKind,
+
+    /// Address to mark, e.g. the memory view's pointer.
+    pointer: Option<Address>,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> RegionBarView<'a> {
+    pub fn new(regions: &'a [Region]) -> Self {
+        Self {
+            regions,
+            range: 0..Address::MAX as u64 + 1,
+            classify: &RegionKind::guess,
+            pointer: None,
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    /// Covers only `range` rather than the whole address space, e.g. to zoom into the regions of
+    /// a process.
+    pub fn range(self, range: Range<Address>) -> Self {
+        Self {
+            range: range.start as u64..range.end as u64,
+            ..self
+        }
+    }
+
+    /// Decides the kind of each region with `classify` rather than [`RegionKind::guess`].
+    pub fn classify(self, classify: &'a dyn Fn(&Region) -> RegionKind) -> Self {
+        Self { classify, ..self }
+    }
+
+    pub fn pointer(self, pointer: Address) -> Self {
+        Self {
+            pointer: Some(pointer),
+            ..self
+        }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+
+    /// Start of the range covered by each of `width` cells, followed by the end of the last one.
+    fn cell_bounds(&self, width: u16) -> Vec<u64> {
+        let len = self.range.end.saturating_sub(self.range.start);
+        (0..=width as u64)
+            .map(|index| self.range.start + (len as u128 * index as u128 / width as u128) as u64)
+            .collect()
+    }
+
+    /// The index of the region drawn in the cell covering `range`, if any, along with the address
+    /// the cell jumps to. Regions taking up most of the cell win, but any region wins over
+    /// unmapped memory so that small regions remain visible.
+    fn cell(&self, range: Range<u64>) -> (Option<usize>, u64) {
+        let overlap = |region: &Region| {
+            let start = (region.start as u64).max(range.start);
+            let end = region.end().min(range.end);
+            (start < end).then(|| (end - start, start))
+        };
+
+        self.regions
+            .iter()
+            .enumerate()
+            .filter_map(|(index, region)| Some((index, overlap(region)?)))
+            .max_by_key(|(_, (len, start))| (*len, std::cmp::Reverse(*start)))
+            .map_or((None, range.start), |(index, (_, start))| {
+                (Some(index), start)
+            })
+    }
+}
+
+impl<'a> StatefulWidget for RegionBarView<'a> {
+    type State = RegionBarViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = self.wrap_in_block(area, buf);
+        if area.width == 0 || area.height == 0 || self.range.is_empty() {
+            state.cells.clear();
+            return;
+        }
+
+        let marker_row = (area.height >= 2).then_some(area.y);
+        let bar = Rect {
+            y: area.y + marker_row.is_some() as u16,
+            height: 1,
+            ..area
+        };
+
+        // update state
+        let bounds = self.cell_bounds(bar.width);
+        let cells: Vec<_> = bounds
+            .windows(2)
+            .map(|bounds| self.cell(bounds[0]..bounds[1].max(bounds[0] + 1)))
+            .collect();
+        state.bar = bar;
+        state.cells = cells
+            .iter()
+            .map(|(region, address)| (*region, *address as Address))
+            .collect();
+        state.selected = state.selected.map(|selected| selected.min(bar.width - 1));
+
+        let pointer_cell = self
+            .pointer
+            .map(|pointer| pointer as u64)
+            .filter(|pointer| self.range.contains(pointer))
+            .map(|pointer| bounds.partition_point(|&start| start <= pointer) as u16 - 1);
+        for (index, (region, _)) in cells.iter().enumerate() {
+            let index = index as u16;
+            let kind = region.map_or(RegionKind::Unmapped, |region| {
+                (self.classify)(&self.regions[region])
+            });
+            let mut style = kind.style(&self.theme);
+            if state.selected == Some(index) {
+                style = style.patch(self.theme.selection);
+            }
+            if pointer_cell == Some(index) && marker_row.is_none() {
+                style = style.patch(self.theme.cursor);
+            }
+
+            buf.set_string(bar.x + index, bar.y, kind.symbol(), style);
+        }
+
+        if let (Some(row), Some(cell)) = (marker_row, pointer_cell) {
+            buf.set_string(area.x + cell, row, "▼", self.theme.cursor);
+        }
+
+        if area.height >= 3 {
+            let row = bar.y + 1;
+            let start = format!("{:08X}", self.range.start);
+            let end = format!("{:08X}", self.range.end - 1);
+            buf.set_stringn(area.x, row, &start, area.width as usize, self.theme.address);
+            if area.width as usize > start.len() + end.len() {
+                let x = area.right() - end.len() as u16;
+                buf.set_string(x, row, &end, self.theme.address);
+            }
+        }
+    }
+}
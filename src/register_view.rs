@@ -0,0 +1,241 @@
+use crate::{keymap::Action, Keymap, Theme};
+use crossterm::event::{KeyEvent, KeyEventKind};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, StatefulWidget, Widget},
+};
+use std::{borrow::Cow, collections::HashMap};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisterValue {
+    pub name: Cow<'static, str>,
+    /// Width of the register, in bits.
+    pub bits: u32,
+    /// Value of the register. `None` if unavailable.
+    pub value: Option<u64>,
+}
+
+pub trait RegisterProvider {
+    /// Returns the current value of every register, in display order.
+    fn registers(&self) -> Vec<RegisterValue>;
+}
+
+impl RegisterProvider for [RegisterValue] {
+    fn registers(&self) -> Vec<RegisterValue> {
+        self.to_vec()
+    }
+}
+
+impl RegisterProvider for Vec<RegisterValue> {
+    fn registers(&self) -> Vec<RegisterValue> {
+        self.clone()
+    }
+}
+
+pub struct RegisterViewState {
+    /// Index of the register under the cursor.
+    pub selected: usize,
+
+    /// Amount of registers and columns, as of the last render.
+    len: usize,
+    columns: usize,
+    /// Name and value of the register under the cursor, as of the last render.
+    selected_register: Option<RegisterValue>,
+
+    /// Values changes are highlighted against.
+    baseline: HashMap<Cow<'static, str>, Option<u64>>,
+    retake_baseline: bool,
+}
+
+impl Default for RegisterViewState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegisterViewState {
+    pub fn new() -> Self {
+        Self {
+            selected: 0,
+            len: 0,
+            columns: 1,
+            selected_register: None,
+            baseline: HashMap::new(),
+            retake_baseline: true,
+        }
+    }
+
+    /// The register under the cursor, as of the last render.
+    pub fn selected_register(&self) -> Option<&RegisterValue> {
+        self.selected_register.as_ref()
+    }
+
+    /// Makes the values of the next render the ones changes are highlighted against. Call this
+    /// whenever the target stops.
+    pub fn mark_baseline(&mut self) {
+        self.retake_baseline = true;
+    }
+
+    /// Handles a key event with the [standard keymap](Keymap::standard).
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        self.handle_key_with(key, Keymap::standard())
+    }
+
+    /// Handles a key event, translated to an action by `keymap`. Returns whether the event was
+    /// consumed.
+    pub fn handle_key_with(&mut self, key: KeyEvent, keymap: &Keymap) -> bool {
+        if key.kind == KeyEventKind::Release {
+            return false;
+        }
+
+        let last = self.len.saturating_sub(1);
+        match keymap.action(key) {
+            Some(Action::Left) => self.selected = self.selected.saturating_sub(1),
+            Some(Action::Right) => self.selected = (self.selected + 1).min(last),
+            Some(Action::Up) => self.selected = self.selected.saturating_sub(self.columns),
+            Some(Action::Down) => self.selected = (self.selected + self.columns).min(last),
+            Some(Action::Home) => self.selected = 0,
+            Some(Action::End) => self.selected = last,
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+/// A grid of registers and their values, highlighting the ones that changed since the last
+/// baseline.
+pub struct RegisterView<'a> {
+    register_provider: &'a dyn RegisterProvider,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> RegisterView<'a> {
+    pub fn new(register_provider: &'a dyn RegisterProvider) -> Self {
+        Self {
+            register_provider,
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+
+    fn value_text(register: &RegisterValue) -> String {
+        let digits = (register.bits.clamp(8, 64) as usize).div_ceil(4);
+        match register.value {
+            Some(value) => format!("{value:0digits$X}"),
+            None => "-".repeat(digits),
+        }
+    }
+}
+
+impl<'a> StatefulWidget for RegisterView<'a> {
+    type State = RegisterViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = self.wrap_in_block(area, buf);
+        let registers = self.register_provider.registers();
+
+        // update state
+        state.len = registers.len();
+        state.selected = state.selected.min(state.len.saturating_sub(1));
+        state.selected_register = registers.get(state.selected).cloned();
+
+        let changed: Vec<bool> = registers
+            .iter()
+            .map(|register| {
+                !state.retake_baseline
+                    && state
+                        .baseline
+                        .get(&register.name)
+                        .is_some_and(|old| *old != register.value)
+            })
+            .collect();
+        if state.retake_baseline {
+            state.baseline = registers
+                .iter()
+                .map(|register| (register.name.clone(), register.value))
+                .collect();
+            state.retake_baseline = false;
+        }
+
+        let name_width = registers
+            .iter()
+            .map(|register| register.name.chars().count())
+            .max()
+            .unwrap_or(0);
+        let value_width = registers
+            .iter()
+            .map(|register| Self::value_text(register).len())
+            .max()
+            .unwrap_or(0);
+        let cell_width = (name_width + value_width + 3) as u16;
+        state.columns = (area.width / cell_width.max(1)).max(1) as usize;
+
+        // scroll by whole rows so that the selected register stays visible
+        let height = area.height.max(1) as usize;
+        let selected_row = state.selected / state.columns;
+        let first_row = selected_row.saturating_sub(height - 1);
+
+        for (index, (register, changed)) in registers
+            .iter()
+            .zip(changed)
+            .enumerate()
+            .skip(first_row * state.columns)
+        {
+            let row = index / state.columns - first_row;
+            if row >= area.height as usize {
+                break;
+            }
+
+            let x = area.x + (index % state.columns) as u16 * cell_width;
+            let y = area.y + row as u16;
+            let value_style = if changed {
+                self.theme.alert
+            } else if register.value.is_none() {
+                self.theme.muted
+            } else {
+                Style::default().light_green()
+            };
+            let name_style = if index == state.selected {
+                Style::default().light_blue().patch(self.theme.selection)
+            } else {
+                Style::default().light_blue()
+            };
+
+            let line = Line::from(vec![
+                Span::styled(format!("{:>name_width$}", register.name), name_style),
+                Span::raw(" "),
+                Span::styled(Self::value_text(register), value_style),
+            ]);
+            buf.set_line(x, y, &line, cell_width.min(area.right() - x));
+        }
+    }
+}
@@ -0,0 +1,315 @@
+//! A small protocol to expose memory over a socket, so that a frontend can inspect a target
+//! running in another process or on another machine.
+//!
+//! Every message is a little-endian `u32` length followed by that many bytes of payload. The
+//! first byte of a request payload is its opcode:
+//!
+//! - `0x01` read: address (`u32`) and length (`u32`). Replied with the values, followed by a
+//!   bitmap of which of them are mapped.
+//! - `0x02` write: address (`u32`) followed by the values. Replied with a single byte, non-zero
+//!   if every value was written.
+//! - `0x03` regions: replied with the amount of regions (`u32`), each as its start (`u32`), size
+//!   (`u64`), permissions (`u8`, `rwx` as bits 0 to 2) and name (`u16` length and UTF-8 bytes).
+//!
+//! Replies start with a status byte: zero on success, otherwise the rest is an UTF-8 error
+//! message.
+
+use crate::{
+    memory_view::{MemoryProvider, Permissions, Region},
+    provider::MemoryDevice,
+    Address,
+};
+use eyre::{bail, ensure, eyre, Result};
+use std::{
+    io::{ErrorKind, Read, Write},
+    net::TcpListener,
+    time::Duration,
+};
+
+/// Maximum size of a message payload, in bytes.
+const MAX_MESSAGE: usize = 16 << 20;
+/// Maximum length of a single read request, in bytes.
+pub(crate) const MAX_READ: usize = 1 << 20;
+/// Time after which a client that sent nothing is dropped, so that others get their turn.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+const OP_READ: u8 = 0x01;
+const OP_WRITE: u8 = 0x02;
+const OP_REGIONS: u8 = 0x03;
+
+const STATUS_OK: u8 = 0;
+const STATUS_ERROR: u8 = 1;
+
+/// Reads a message. Returns `None` if the stream was closed before it started.
+pub(crate) fn read_message(mut reader: impl Read) -> Result<Option<Vec<u8>>> {
+    let mut len = [0; 4];
+    match reader.read_exact(&mut len) {
+        Ok(()) => (),
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+
+    let len = u32::from_le_bytes(len) as usize;
+    ensure!(len <= MAX_MESSAGE, "message too large ({len} bytes)");
+
+    let mut payload = vec![0; len];
+    reader.read_exact(&mut payload)?;
+
+    Ok(Some(payload))
+}
+
+pub(crate) fn write_message(mut writer: impl Write, payload: &[u8]) -> Result<()> {
+    ensure!(payload.len() <= MAX_MESSAGE, "message too large");
+    writer.write_all(&(payload.len() as u32).to_le_bytes())?;
+    writer.write_all(payload)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// A cursor over a message payload.
+struct Cursor<'a>(&'a [u8]);
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        ensure!(self.0.len() >= len, "truncated message");
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn rest(&mut self) -> &'a [u8] {
+        std::mem::take(&mut self.0)
+    }
+}
+
+/// A request sent by a client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Request {
+    Read { address: Address, len: u32 },
+    Write { address: Address, data: Vec<u8> },
+    Regions,
+}
+
+impl Request {
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut payload = Vec::new();
+        match self {
+            Self::Read { address, len } => {
+                payload.push(OP_READ);
+                payload.extend_from_slice(&address.to_le_bytes());
+                payload.extend_from_slice(&len.to_le_bytes());
+            }
+            Self::Write { address, data } => {
+                payload.push(OP_WRITE);
+                payload.extend_from_slice(&address.to_le_bytes());
+                payload.extend_from_slice(data);
+            }
+            Self::Regions => payload.push(OP_REGIONS),
+        }
+
+        payload
+    }
+
+    fn decode(payload: &[u8]) -> Result<Self> {
+        let mut cursor = Cursor(payload);
+        Ok(match cursor.u8()? {
+            OP_READ => Self::Read {
+                address: cursor.u32()?,
+                len: cursor.u32()?,
+            },
+            OP_WRITE => Self::Write {
+                address: cursor.u32()?,
+                data: cursor.rest().to_vec(),
+            },
+            OP_REGIONS => Self::Regions,
+            op => bail!("unknown opcode {op:#04X}"),
+        })
+    }
+}
+
+/// Splits a reply into its body, or fails with the error message it carries.
+pub(crate) fn reply_body(payload: &[u8]) -> Result<&[u8]> {
+    let mut cursor = Cursor(payload);
+    match cursor.u8()? {
+        STATUS_OK => Ok(cursor.rest()),
+        _ => Err(eyre!(
+            "remote error: {}",
+            String::from_utf8_lossy(cursor.rest())
+        )),
+    }
+}
+
+pub(crate) fn encode_values(values: &[Option<u8>], payload: &mut Vec<u8>) {
+    payload.extend(values.iter().map(|value| value.unwrap_or(0)));
+
+    let mut mapped = vec![0u8; values.len().div_ceil(8)];
+    for (index, value) in values.iter().enumerate() {
+        if value.is_some() {
+            mapped[index / 8] |= 1 << (index % 8);
+        }
+    }
+    payload.extend_from_slice(&mapped);
+}
+
+pub(crate) fn decode_values(body: &[u8], buf: &mut [Option<u8>]) -> Result<()> {
+    let mut cursor = Cursor(body);
+    let data = cursor.take(buf.len())?;
+    let mapped = cursor.take(buf.len().div_ceil(8))?;
+
+    for (index, value) in buf.iter_mut().enumerate() {
+        *value = (mapped[index / 8] & (1 << (index % 8)) != 0).then_some(data[index]);
+    }
+
+    Ok(())
+}
+
+fn encode_regions(regions: &[Region], payload: &mut Vec<u8>) {
+    payload.extend_from_slice(&(regions.len() as u32).to_le_bytes());
+    for region in regions {
+        let permissions = region.permissions.read as u8
+            | (region.permissions.write as u8) << 1
+            | (region.permissions.execute as u8) << 2;
+        let name = region.name.as_deref().unwrap_or_default();
+        let name = &name.as_bytes()[..name.len().min(u16::MAX as usize)];
+
+        payload.extend_from_slice(&region.start.to_le_bytes());
+        payload.extend_from_slice(&region.size.to_le_bytes());
+        payload.push(permissions);
+        payload.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        payload.extend_from_slice(name);
+    }
+}
+
+pub(crate) fn decode_regions(body: &[u8]) -> Result<Vec<Region>> {
+    let mut cursor = Cursor(body);
+    let count = cursor.u32()?;
+
+    let mut regions = Vec::new();
+    for _ in 0..count {
+        let start = cursor.u32()?;
+        let size = cursor.u64()?;
+        let permissions = cursor.u8()?;
+        let name_len = cursor.u16()? as usize;
+        let name = String::from_utf8_lossy(cursor.take(name_len)?);
+
+        let region = Region::new(start, size).permissions(Permissions {
+            read: permissions & 1 != 0,
+            write: permissions & 2 != 0,
+            execute: permissions & 4 != 0,
+        });
+        regions.push(if name.is_empty() {
+            region
+        } else {
+            region.name(name)
+        });
+    }
+
+    Ok(regions)
+}
+
+enum Target<'a> {
+    ReadOnly(&'a dyn MemoryProvider),
+    ReadWrite(&'a mut dyn MemoryDevice),
+}
+
+impl<'a> Target<'a> {
+    fn provider(&self) -> &dyn MemoryProvider {
+        match self {
+            Self::ReadOnly(provider) => *provider,
+            Self::ReadWrite(device) => *device,
+        }
+    }
+
+    /// Handles a request, returning the reply payload.
+    fn handle(&mut self, payload: &[u8]) -> Vec<u8> {
+        let request = match Request::decode(payload) {
+            Ok(request) => request,
+            Err(e) => return error_reply(&e.to_string()),
+        };
+
+        let mut reply = vec![STATUS_OK];
+        match request {
+            Request::Read { address, len } => {
+                if len as usize > MAX_READ {
+                    return error_reply("read too large");
+                }
+
+                let mut values = vec![None; len as usize];
+                self.provider().read_to_buf(address, &mut values);
+                encode_values(&values, &mut reply);
+            }
+            Request::Write { address, data } => match self {
+                Self::ReadOnly(_) => return error_reply("target is read-only"),
+                Self::ReadWrite(device) => {
+                    reply.push(device.write_from_buf(address, &data) as u8);
+                }
+            },
+            Request::Regions => encode_regions(&self.provider().regions(), &mut reply),
+        }
+
+        reply
+    }
+
+    fn serve_connection(&mut self, mut stream: impl Read + Write) -> Result<()> {
+        while let Some(payload) = read_message(&mut stream)? {
+            let reply = self.handle(&payload);
+            write_message(&mut stream, &reply)?;
+        }
+
+        Ok(())
+    }
+
+    fn serve(&mut self, listener: &TcpListener) -> Result<()> {
+        for stream in listener.incoming() {
+            let stream = stream?;
+            stream.set_nodelay(true)?;
+            stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+
+            // a misbehaving client shouldn't take the server down
+            let _ = self.serve_connection(stream);
+        }
+
+        Ok(())
+    }
+}
+
+fn error_reply(message: &str) -> Vec<u8> {
+    let mut reply = vec![STATUS_ERROR];
+    reply.extend_from_slice(message.as_bytes());
+    reply
+}
+
+/// Serves the memory of `device` to the clients connecting to `listener`, one at a time, until
+/// accepting a connection fails. Clients idle for more than 5 seconds are dropped, so that an idle
+/// or stalled client doesn't block the others.
+pub fn serve(device: &mut dyn MemoryDevice, listener: &TcpListener) -> Result<()> {
+    Target::ReadWrite(device).serve(listener)
+}
+
+/// Like [`serve`], but for providers which can't be written to. Write requests are rejected.
+pub fn serve_read_only(provider: &dyn MemoryProvider, listener: &TcpListener) -> Result<()> {
+    Target::ReadOnly(provider).serve(listener)
+}
+
+/// Serves the memory of `device` over an already established connection, until it is closed.
+pub fn serve_connection(device: &mut dyn MemoryDevice, stream: impl Read + Write) -> Result<()> {
+    Target::ReadWrite(device).serve_connection(stream)
+}
@@ -0,0 +1,279 @@
+//! [Rhai](https://rhai.rs) scripts that read and write memory, interpret the selected bytes and
+//! bind commands to keys.
+//!
+//! Scripts are loaded into a [`ScriptHost`], which runs their top level once. From there, they
+//! can call:
+//!
+//! - `read_u8(address)`, `read_u16(address)`, `read_u32(address)`: little endian values, or `()`
+//!   if any byte is unmapped.
+//! - `read(address, len)`: an array of bytes, with `()` for unmapped ones.
+//! - `write(address, bytes)`: writes a blob or an array of bytes. Returns whether it succeeded.
+//!   Fails if the host is [read-only](ScriptHost::read_only).
+//! - `bind_key(chord, function)`: runs `function(pointer)` when the chord is pressed, e.g.
+//!   `bind_key("ctrl+r", "follow")`. If it returns an address, the view jumps there.
+//! - `add_interpreter(name, function)`: shows `function(pointer)` in the info bar under `name`.
+//!
+//! `print` and `debug` output is collected rather than written to the terminal, see
+//! [`ScriptHost::take_output`].
+
+use crate::{
+    keymap::KeyChord,
+    memory_view::{MemoryProvider, MemoryWriter},
+    Address,
+};
+use crossterm::event::{KeyEvent, KeyEventKind};
+use eyre::{eyre, Result, WrapErr};
+use rhai::{Array, Blob, Dynamic, Engine, EvalAltResult, Scope, AST, INT};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    path::Path,
+    rc::Rc,
+};
+
+/// Operations a single script call may run before it is aborted, so that an endless loop can't
+/// freeze the application.
+const MAX_OPERATIONS: u64 = 10_000_000;
+
+/// Memory that scripts can read and write.
+pub trait ScriptMemory: MemoryProvider + MemoryWriter {}
+
+impl<T: MemoryProvider + MemoryWriter> ScriptMemory for T {}
+
+/// Memory shared between the application and its scripts. The application must not hold a borrow
+/// of it while calling into the [`ScriptHost`].
+pub type SharedMemory = Rc<RefCell<dyn ScriptMemory>>;
+
+/// What scripts registered while being loaded.
+#[derive(Default)]
+struct Registry {
+    /// Function names, by chord.
+    bindings: HashMap<KeyChord, String>,
+    /// Names and function names, in registration order.
+    interpreters: Vec<(String, String)>,
+    output: Vec<String>,
+}
+
+pub struct ScriptHost {
+    engine: Engine,
+    /// Every loaded script, merged.
+    ast: AST,
+    registry: Rc<RefCell<Registry>>,
+    /// Whether `write` is allowed, shared with its closures.
+    writable: Rc<Cell<bool>>,
+}
+
+fn to_address(value: INT) -> Result<Address, Box<EvalAltResult>> {
+    Address::try_from(value).map_err(|_| format!("{value:#X} is not a valid address").into())
+}
+
+fn read_le(memory: &SharedMemory, address: INT, len: usize) -> Result<Dynamic, Box<EvalAltResult>> {
+    let mut buf = [None; 4];
+    memory.borrow().read_to_buf(to_address(address)?, &mut buf[..len]);
+
+    let value = buf[..len]
+        .iter()
+        .rev()
+        .try_fold(0, |value, byte| byte.map(|byte| value << 8 | byte as INT));
+    Ok(value.map_or(Dynamic::UNIT, Dynamic::from))
+}
+
+impl ScriptHost {
+    pub fn new(memory: SharedMemory) -> Self {
+        Self::with_access(memory, true)
+    }
+
+    /// A host whose scripts can read memory but not write it: `write` raises an error instead.
+    pub fn read_only(memory: SharedMemory) -> Self {
+        Self::with_access(memory, false)
+    }
+
+    fn with_access(memory: SharedMemory, writable: bool) -> Self {
+        let writable = Rc::new(Cell::new(writable));
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+
+        let registry = Rc::new(RefCell::new(Registry::default()));
+
+        let output = registry.clone();
+        engine.on_print(move |text| output.borrow_mut().output.push(text.to_owned()));
+        let output = registry.clone();
+        engine.on_debug(move |text, _, _| output.borrow_mut().output.push(text.to_owned()));
+
+        for (name, len) in [("read_u8", 1), ("read_u16", 2), ("read_u32", 4)] {
+            let memory = memory.clone();
+            engine.register_fn(name, move |address: INT| read_le(&memory, address, len));
+        }
+
+        let shared = memory.clone();
+        engine.register_fn(
+            "read",
+            move |address: INT, len: INT| -> Result<Array, Box<EvalAltResult>> {
+                let len = usize::try_from(len).map_err(|_| format!("invalid length {len}"))?;
+                let mut buf = vec![None; len];
+                shared.borrow().read_to_buf(to_address(address)?, &mut buf);
+
+                Ok(buf
+                    .into_iter()
+                    .map(|byte| byte.map_or(Dynamic::UNIT, |byte| Dynamic::from(byte as INT)))
+                    .collect())
+            },
+        );
+
+        let shared = memory.clone();
+        let allowed = writable.clone();
+        engine.register_fn(
+            "write",
+            move |address: INT, bytes: Blob| -> Result<bool, Box<EvalAltResult>> {
+                if !allowed.get() {
+                    return Err("memory is read-only".into());
+                }
+
+                Ok(shared
+                    .borrow_mut()
+                    .write_from_buf(to_address(address)?, &bytes))
+            },
+        );
+
+        let shared = memory;
+        let allowed = writable.clone();
+        engine.register_fn(
+            "write",
+            move |address: INT, bytes: Array| -> Result<bool, Box<EvalAltResult>> {
+                if !allowed.get() {
+                    return Err("memory is read-only".into());
+                }
+
+                let bytes = bytes
+                    .into_iter()
+                    .map(|byte| byte.as_int().ok().and_then(|byte| u8::try_from(byte).ok()))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or("bytes must be integers from 0 to 255")?;
+
+                Ok(shared
+                    .borrow_mut()
+                    .write_from_buf(to_address(address)?, &bytes))
+            },
+        );
+
+        let bindings = registry.clone();
+        engine.register_fn(
+            "bind_key",
+            move |chord: &str, function: &str| -> Result<(), Box<EvalAltResult>> {
+                let chord = chord.parse::<KeyChord>()?;
+                bindings
+                    .borrow_mut()
+                    .bindings
+                    .insert(chord, function.to_owned());
+                Ok(())
+            },
+        );
+
+        let interpreters = registry.clone();
+        engine.register_fn("add_interpreter", move |name: &str, function: &str| {
+            interpreters
+                .borrow_mut()
+                .interpreters
+                .push((name.to_owned(), function.to_owned()));
+        });
+
+        Self {
+            engine,
+            ast: AST::empty(),
+            registry,
+            writable,
+        }
+    }
+
+    /// Allows or forbids scripts to write memory from now on, e.g. while a session is played
+    /// back.
+    pub fn set_writable(&self, writable: bool) {
+        self.writable.set(writable);
+    }
+
+    /// Compiles a script and runs its top level. Its functions are available to the scripts
+    /// loaded afterwards, and replace the ones with the same name loaded before.
+    pub fn load(&mut self, source: &str) -> Result<()> {
+        let ast = self
+            .engine
+            .compile(source)
+            .map_err(|error| eyre!("{error}"))?;
+        self.engine
+            .run_ast(&ast)
+            .map_err(|error| eyre!("{error}"))?;
+
+        self.ast += ast.clone_functions_only();
+        Ok(())
+    }
+
+    pub fn load_file(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("failed to read {}", path.display()))?;
+
+        self.load(&source)
+            .wrap_err_with(|| format!("in script {}", path.display()))
+    }
+
+    fn call(&self, function: &str, pointer: Address) -> Result<Dynamic> {
+        self.engine
+            .call_fn(&mut Scope::new(), &self.ast, function, (pointer as INT,))
+            .map_err(|error| eyre!("{function}: {error}"))
+    }
+
+    /// Runs the command bound to a key event, if any. Returns the address it asked to jump to.
+    pub fn handle_key(&self, key: KeyEvent, pointer: Address) -> Option<Result<Option<Address>>> {
+        if key.kind == KeyEventKind::Release {
+            return None;
+        }
+
+        let function = self
+            .registry
+            .borrow()
+            .bindings
+            .get(&KeyChord::from(key))
+            .cloned()?;
+
+        let result = self.call(&function, pointer).and_then(|value| {
+            if value.is_unit() {
+                return Ok(None);
+            }
+
+            let address = value
+                .as_int()
+                .ok()
+                .and_then(|value| Address::try_from(value).ok())
+                .ok_or_else(|| eyre!("{function}: returned {value}, which isn't an address"))?;
+            Ok(Some(address))
+        });
+
+        Some(result)
+    }
+
+    /// The output of every interpreter for the bytes at `pointer`, as `(name, text)`. Failing
+    /// interpreters show their error instead.
+    pub fn interpret(&self, pointer: Address) -> Vec<(String, String)> {
+        let interpreters = self.registry.borrow().interpreters.clone();
+        interpreters
+            .into_iter()
+            .map(|(name, function)| {
+                let text = match self.call(&function, pointer) {
+                    Ok(value) if value.is_unit() => "--".to_owned(),
+                    Ok(value) => value.to_string(),
+                    Err(error) => error.to_string(),
+                };
+                (name, text)
+            })
+            .collect()
+    }
+
+    /// Whether any interpreter was added.
+    pub fn has_interpreters(&self) -> bool {
+        !self.registry.borrow().interpreters.is_empty()
+    }
+
+    /// Takes the text printed by scripts since the last call.
+    pub fn take_output(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.registry.borrow_mut().output)
+    }
+}
@@ -0,0 +1,534 @@
+use crate::{
+    instruction_view::{InstructionDisplay, InstructionProvider, INSTRUCTION_SIZE},
+    memory_view::MemoryProvider,
+    Address,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use std::{
+    mem,
+    ops::Range,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    thread,
+};
+
+/// How many bytes are read from a provider at once while searching.
+const CHUNK_SIZE: usize = 4096;
+/// How many bytes a [`BackgroundSearch`] goes through between progress updates and checks for
+/// cancellation.
+const BATCH_SIZE: Address = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchDirection {
+    #[default]
+    Forward,
+    Backward,
+}
+
+impl SearchDirection {
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Forward => Self::Backward,
+            Self::Backward => Self::Forward,
+        }
+    }
+}
+
+/// Events produced by [`SearchState::handle_key`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchEvent {
+    /// The query was edited. Incremental searches should be re-run.
+    QueryChanged,
+    /// The user asked to jump to the next match in the current direction.
+    Next,
+    /// The user asked to jump to the next match in the opposite direction.
+    Previous,
+    /// The search direction was toggled.
+    DirectionChanged,
+    /// The [background search](SearchState::search_memory_in_background) in progress was
+    /// cancelled.
+    Cancelled,
+    /// The search bar was closed.
+    Closed,
+}
+
+/// Parses a search query into a byte pattern.
+///
+/// Queries wrapped in double quotes are searched as text; anything else is parsed as hex
+/// bytes, optionally separated by whitespace (e.g. `DE AD BE EF` or `deadbeef`).
+pub fn parse_pattern(query: &str) -> Option<Vec<u8>> {
+    let query = query.trim();
+    if let Some(text) = query.strip_prefix('"') {
+        let text = text.strip_suffix('"').unwrap_or(text);
+        return (!text.is_empty()).then(|| text.as_bytes().to_vec());
+    }
+
+    let digits: Vec<u8> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_digit(16).map(|d| d as u8))
+        .collect::<Option<_>>()?;
+
+    if digits.is_empty() || digits.len() % 2 == 1 {
+        return None;
+    }
+
+    Some(
+        digits
+            .chunks(2)
+            .map(|pair| pair[0] << 4 | pair[1])
+            .collect(),
+    )
+}
+
+/// Finds every occurrence of `pattern` inside `range`, up to `limit` matches. The range is wide
+/// enough to reach the last byte of the address space, and is clipped to it. Bytes the provider
+/// can't read never match.
+pub fn find_in_memory(
+    provider: &dyn MemoryProvider,
+    range: Range<u64>,
+    pattern: &[u8],
+    limit: usize,
+) -> Vec<Address> {
+    let mut matches = Vec::new();
+    let end = range.end.min(Address::MAX as u64 + 1);
+    if pattern.is_empty() || range.start >= end {
+        return matches;
+    }
+
+    let mut buf = vec![None; CHUNK_SIZE + pattern.len() - 1];
+    let mut start = range.start;
+    while start < end && matches.len() < limit {
+        let remaining = (end - start) as usize;
+        let len = (CHUNK_SIZE + pattern.len() - 1).min(remaining);
+
+        let buf = &mut buf[..len];
+        buf.fill(None);
+        provider.read_to_buf(start as Address, buf);
+
+        let found = buf
+            .windows(pattern.len())
+            .take(CHUNK_SIZE)
+            .enumerate()
+            .filter(|(_, window)| {
+                window
+                    .iter()
+                    .zip(pattern)
+                    .all(|(byte, expected)| *byte == Some(*expected))
+            })
+            .map(|(offset, _)| (start + offset as u64) as Address);
+        matches.extend(found.take(limit - matches.len()));
+
+        start += CHUNK_SIZE as u64;
+    }
+
+    matches
+}
+
+/// Finds every instruction inside `range` whose displayed text contains `query`, ignoring
+/// case, up to `limit` matches.
+pub fn find_in_instructions<I>(
+    provider: &dyn InstructionProvider<I>,
+    range: Range<Address>,
+    query: &str,
+    limit: usize,
+) -> Vec<Address>
+where
+    I: InstructionDisplay + Clone,
+{
+    let mut matches = Vec::new();
+    if query.is_empty() || range.is_empty() {
+        return matches;
+    }
+
+    let query = query.to_lowercase();
+    let mut buf = vec![None; CHUNK_SIZE / INSTRUCTION_SIZE as usize];
+    let mut start = range.start;
+    while start < range.end && matches.len() < limit {
+        let remaining = ((range.end - start) / INSTRUCTION_SIZE) as usize;
+        let len = buf.len().min(remaining.max(1));
+
+        let buf = &mut buf[..len];
+        buf.fill(None);
+        provider.read_to_buf(start, buf);
+
+        for (index, instruction) in buf.iter().enumerate() {
+            let Some(instruction) = instruction else {
+                continue;
+            };
+
+            let text: String = instruction
+                .instruction_display()
+                .spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect();
+
+            if text.to_lowercase().contains(&query) {
+                matches.push(start + index as Address * INSTRUCTION_SIZE);
+                if matches.len() >= limit {
+                    break;
+                }
+            }
+        }
+
+        let Some(next) = start.checked_add(len as Address * INSTRUCTION_SIZE) else {
+            break;
+        };
+        start = next;
+    }
+
+    matches
+}
+
+/// Shared between a [`BackgroundSearch`] and its worker.
+#[derive(Default)]
+struct Progress {
+    /// Matches found since they were last taken.
+    found: Mutex<Vec<Address>>,
+    /// Amount of bytes gone through.
+    scanned: AtomicU64,
+    cancelled: AtomicBool,
+    done: AtomicBool,
+}
+
+/// A search of memory running on a worker thread, so that large ranges can be searched without
+/// blocking the UI. Matches are streamed as they are found, and can be
+/// [taken](Self::take_matches) at any time.
+///
+/// The search is cancelled when dropped.
+pub struct BackgroundSearch {
+    progress: Arc<Progress>,
+    /// Amount of bytes to go through.
+    total: u64,
+}
+
+impl BackgroundSearch {
+    /// Spawns a worker searching `ranges` of `provider` for `pattern`, in order. Bytes the
+    /// provider can't read never match.
+    pub fn spawn<P>(provider: P, ranges: Vec<Range<Address>>, pattern: Vec<u8>) -> Self
+    where
+        P: MemoryProvider + Send + 'static,
+    {
+        let total = ranges
+            .iter()
+            .map(|range| range.end.saturating_sub(range.start) as u64)
+            .sum();
+        let progress = Arc::new(Progress::default());
+
+        let worker = progress.clone();
+        thread::Builder::new()
+            .name("anton-search".to_owned())
+            .spawn(move || {
+                Self::work(&provider, &ranges, &pattern, &worker);
+                worker.done.store(true, Ordering::Release);
+            })
+            .expect("failed to spawn the search worker");
+
+        Self { progress, total }
+    }
+
+    fn work(
+        provider: &dyn MemoryProvider,
+        ranges: &[Range<Address>],
+        pattern: &[u8],
+        progress: &Progress,
+    ) {
+        for range in ranges {
+            let mut start = range.start;
+            while start < range.end {
+                if progress.cancelled.load(Ordering::Relaxed) {
+                    return;
+                }
+
+                // matches starting in this batch may end in the next one
+                let end = start.saturating_add(BATCH_SIZE).min(range.end);
+                let overlap = end
+                    .saturating_add(pattern.len().saturating_sub(1) as Address)
+                    .min(range.end);
+                let found =
+                    find_in_memory(provider, start as u64..overlap as u64, pattern, usize::MAX);
+                progress
+                    .found
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .extend(found.into_iter().filter(|&address| address < end));
+                progress
+                    .scanned
+                    .fetch_add((end - start) as u64, Ordering::Relaxed);
+
+                start = end;
+            }
+        }
+    }
+
+    /// Amount of bytes gone through so far.
+    pub fn scanned(&self) -> u64 {
+        self.progress.scanned.load(Ordering::Relaxed)
+    }
+
+    /// Amount of bytes to go through.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Fraction of the ranges gone through so far, from 0 to 1.
+    pub fn ratio(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.scanned() as f64 / self.total as f64
+        }
+    }
+
+    /// Whether the worker is done, either because it went through every range or because it was
+    /// cancelled. Matches might still have to be taken.
+    pub fn is_done(&self) -> bool {
+        self.progress.done.load(Ordering::Acquire)
+    }
+
+    /// Asks the worker to stop, which it does once done with the batch in progress.
+    pub fn cancel(&self) {
+        self.progress.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// The matches found since the last call, in ascending order within each range.
+    pub fn take_matches(&self) -> Vec<Address> {
+        mem::take(
+            &mut *self
+                .progress
+                .found
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner),
+        )
+    }
+}
+
+impl Drop for BackgroundSearch {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+pub struct SearchState {
+    query: String,
+    direction: SearchDirection,
+
+    /// Addresses of the matches found, in ascending order.
+    matches: Vec<Address>,
+    /// Index into `matches` of the current match.
+    current: Option<usize>,
+    /// The search still adding to `matches`, if any.
+    background: Option<BackgroundSearch>,
+}
+
+impl Default for SearchState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SearchState {
+    pub fn new() -> Self {
+        Self {
+            query: String::new(),
+            direction: SearchDirection::Forward,
+            matches: Vec::new(),
+            current: None,
+            background: None,
+        }
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn set_query(&mut self, query: impl Into<String>) {
+        self.query = query.into();
+    }
+
+    pub fn direction(&self) -> SearchDirection {
+        self.direction
+    }
+
+    pub fn set_direction(&mut self, direction: SearchDirection) {
+        self.direction = direction;
+    }
+
+    pub fn matches(&self) -> &[Address] {
+        &self.matches
+    }
+
+    /// Index of the current match, if any.
+    pub fn current(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// Replaces the set of matches, cancelling the background search in progress if any. They
+    /// are sorted and deduplicated.
+    pub fn set_matches(&mut self, mut matches: Vec<Address>) {
+        matches.sort_unstable();
+        matches.dedup();
+
+        self.matches = matches;
+        self.current = None;
+        self.background = None;
+    }
+
+    /// Runs the query as a byte pattern over `range` of a memory provider.
+    pub fn search_memory(&mut self, provider: &dyn MemoryProvider, range: Range<Address>) {
+        let matches = parse_pattern(&self.query)
+            .map(|pattern| {
+                find_in_memory(
+                    provider,
+                    range.start as u64..range.end as u64,
+                    &pattern,
+                    usize::MAX,
+                )
+            })
+            .unwrap_or_default();
+        self.set_matches(matches);
+    }
+
+    /// Runs the query as a byte pattern over `ranges` of a memory provider on a worker thread,
+    /// replacing the current matches. Matches are added as they are found, on each call to
+    /// [`poll`](Self::poll), while the bar shows the progress of the search.
+    pub fn search_memory_in_background<P>(&mut self, provider: P, ranges: Vec<Range<Address>>)
+    where
+        P: MemoryProvider + Send + 'static,
+    {
+        self.set_matches(Vec::new());
+        if let Some(pattern) = parse_pattern(&self.query) {
+            self.background = Some(BackgroundSearch::spawn(provider, ranges, pattern));
+        }
+    }
+
+    /// Adds the matches found by the background search since the last call, keeping the current
+    /// match selected. Returns whether any were added. Should be called regularly, e.g. on every
+    /// tick, while [searching](Self::is_searching).
+    pub fn poll(&mut self) -> bool {
+        let Some(background) = &self.background else {
+            return false;
+        };
+
+        // checked before taking the matches, so that none are found in between
+        let done = background.is_done();
+        let found = background.take_matches();
+        if done {
+            self.background = None;
+        }
+        if found.is_empty() {
+            return false;
+        }
+
+        let current = self.current.map(|index| self.matches[index]);
+        self.matches.extend(found);
+        self.matches.sort_unstable();
+        self.matches.dedup();
+        self.current = current.and_then(|address| self.matches.binary_search(&address).ok());
+
+        true
+    }
+
+    /// Whether a background search is in progress.
+    pub fn is_searching(&self) -> bool {
+        self.background.is_some()
+    }
+
+    /// Fraction of the ranges the background search in progress has gone through, if any.
+    pub fn progress(&self) -> Option<f64> {
+        self.background.as_ref().map(BackgroundSearch::ratio)
+    }
+
+    /// Stops the background search in progress, if any, keeping the matches found so far.
+    pub fn cancel(&mut self) {
+        self.poll();
+        self.background = None;
+    }
+
+    /// Runs the query as text over `range` of an instruction provider.
+    pub fn search_instructions<I>(
+        &mut self,
+        provider: &dyn InstructionProvider<I>,
+        range: Range<Address>,
+    ) where
+        I: InstructionDisplay + Clone,
+    {
+        let matches = find_in_instructions(provider, range, &self.query, usize::MAX);
+        self.set_matches(matches);
+    }
+
+    fn step(&mut self, pointer: Address, direction: SearchDirection) -> Option<Address> {
+        if self.matches.is_empty() {
+            self.current = None;
+            return None;
+        }
+
+        let index = match direction {
+            SearchDirection::Forward => {
+                let index = self.matches.partition_point(|&addr| addr <= pointer);
+                if index == self.matches.len() {
+                    0
+                } else {
+                    index
+                }
+            }
+            SearchDirection::Backward => {
+                let index = self.matches.partition_point(|&addr| addr < pointer);
+                index.checked_sub(1).unwrap_or(self.matches.len() - 1)
+            }
+        };
+
+        self.current = Some(index);
+        Some(self.matches[index])
+    }
+
+    /// Selects the first match after `pointer` in the search direction, wrapping around, and
+    /// returns its address.
+    pub fn next_match(&mut self, pointer: Address) -> Option<Address> {
+        self.step(pointer, self.direction)
+    }
+
+    /// Selects the first match after `pointer` against the search direction, wrapping around,
+    /// and returns its address.
+    pub fn previous_match(&mut self, pointer: Address) -> Option<Address> {
+        self.step(pointer, self.direction.toggled())
+    }
+
+    /// Handles a key event while the search bar is being edited. Ctrl+C cancels the background
+    /// search in progress.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<SearchEvent> {
+        if key.kind == KeyEventKind::Release {
+            return None;
+        }
+
+        let event = match key.code {
+            KeyCode::Char('c')
+                if key.modifiers.contains(KeyModifiers::CONTROL) && self.is_searching() =>
+            {
+                self.cancel();
+                SearchEvent::Cancelled
+            }
+            KeyCode::Esc => SearchEvent::Closed,
+            KeyCode::Enter | KeyCode::Down => SearchEvent::Next,
+            KeyCode::Up => SearchEvent::Previous,
+            KeyCode::Tab => {
+                self.direction = self.direction.toggled();
+                SearchEvent::DirectionChanged
+            }
+            KeyCode::Backspace => {
+                self.query.pop()?;
+                SearchEvent::QueryChanged
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.query.push(c);
+                SearchEvent::QueryChanged
+            }
+            _ => return None,
+        };
+
+        Some(event)
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{
+    i18n::{self, strings},
+    search::{SearchDirection, SearchState},
+    Theme,
+};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, StatefulWidget, Widget},
+};
+use std::borrow::Cow;
+
+/// An incremental search input line, with a match counter and direction indicator, along with the
+/// progress of the [background search](SearchState::search_memory_in_background) if any.
+pub struct SearchBar<'a> {
+    /// Text shown before the query.
+    prompt: Cow<'a, str>,
+
+    /// Whether the query is being edited, in which case a cursor is drawn.
+    focused: bool,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> Default for SearchBar<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> SearchBar<'a> {
+    pub fn new() -> Self {
+        Self {
+            prompt: Cow::from("/"),
+            focused: true,
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn prompt(self, prompt: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            prompt: prompt.into(),
+            ..self
+        }
+    }
+
+    pub fn focused(self, focused: bool) -> Self {
+        Self { focused, ..self }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+}
+
+impl<'a> StatefulWidget for SearchBar<'a> {
+    type State = SearchState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = self.wrap_in_block(area, buf);
+        if area.height == 0 || area.width == 0 {
+            return;
+        }
+
+        let direction = match state.direction() {
+            SearchDirection::Forward => "↓",
+            SearchDirection::Backward => "↑",
+        };
+        let strings = strings();
+        let count = match (state.current(), state.matches().len()) {
+            (_, 0) if state.query().is_empty() => String::new(),
+            (_, 0) if state.is_searching() => String::new(),
+            (_, 0) => strings.no_matches.to_string(),
+            (Some(current), total) => i18n::fill(&strings.match_of, &[&(current + 1), &total]),
+            (None, total) => i18n::fill(&strings.matches, &[&total]),
+        };
+        let count_style = if state.matches().is_empty() {
+            self.theme.alert
+        } else {
+            self.theme.info
+        };
+        let progress = match state.progress() {
+            Some(progress) => {
+                let percent = format!("{:.0}", progress * 100.0);
+                format!("{} (^C) ", i18n::fill(&strings.searching, &[&percent]))
+            }
+            None => String::new(),
+        };
+
+        let status = Line::from(vec![
+            Span::styled(progress, self.theme.muted),
+            Span::styled(count, count_style),
+            Span::raw(" "),
+            Span::styled(direction, self.theme.address),
+        ]);
+        let status_width = (status.width() as u16).min(area.width);
+        let status_x = area.right() - status_width;
+        buf.set_line(status_x, area.y, &status, status_width);
+
+        let input_width = area.width.saturating_sub(status_width + 1);
+        let input = Line::from(vec![
+            Span::styled(self.prompt.clone(), self.theme.title),
+            Span::styled(state.query(), self.theme.info),
+        ]);
+        let (cursor_x, _) = buf.set_line(area.x, area.y, &input, input_width);
+
+        if self.focused && cursor_x < area.x + input_width {
+            buf.get_mut(cursor_x, area.y)
+                .set_style(Style::default().add_modifier(Modifier::REVERSED));
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Annotations and layout that outlive a run of an application.
+//!
+//! With the `serde` feature, a [`Session`] can be saved in any serde format along with the
+//! states of the views (e.g. [`MemoryViewState`](crate::memory_view::MemoryViewState)) and
+//! restored on the next launch.
+
+use crate::{execution::ExecutionControl, Address};
+use eyre::Result;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A named address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bookmark {
+    pub name: String,
+    pub address: Address,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Session {
+    /// Bookmarks, in the order they were added.
+    pub bookmarks: Vec<Bookmark>,
+    /// Comments, by address.
+    pub comments: BTreeMap<Address, String>,
+    /// Addresses of breakpoints.
+    pub breakpoints: BTreeSet<Address>,
+    /// Index of the selected tab.
+    pub selected_tab: usize,
+    /// Name of the focused panel, as defined by the application.
+    pub focused_panel: Option<String>,
+    /// Relative sizes of the panes of the application's layout, in order, once resized. See
+    /// [`PaneLayout::save`](crate::pane::PaneLayout::save).
+    pub pane_ratios: Vec<u32>,
+    /// Index of the maximized pane, if any.
+    pub maximized_pane: Option<usize>,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bookmarks an address, replacing the bookmark with the same name if any.
+    pub fn bookmark(&mut self, name: impl Into<String>, address: Address) {
+        let name = name.into();
+        match self
+            .bookmarks
+            .iter_mut()
+            .find(|bookmark| bookmark.name == name)
+        {
+            Some(bookmark) => bookmark.address = address,
+            None => self.bookmarks.push(Bookmark { name, address }),
+        }
+    }
+
+    /// Removes a bookmark. Returns its address.
+    pub fn remove_bookmark(&mut self, name: &str) -> Option<Address> {
+        let index = self
+            .bookmarks
+            .iter()
+            .position(|bookmark| bookmark.name == name)?;
+        Some(self.bookmarks.remove(index).address)
+    }
+
+    /// Address of the bookmark with the given name.
+    pub fn bookmark_address(&self, name: &str) -> Option<Address> {
+        self.bookmarks
+            .iter()
+            .find(|bookmark| bookmark.name == name)
+            .map(|bookmark| bookmark.address)
+    }
+
+    /// Comments an address. An empty comment removes the existing one.
+    pub fn set_comment(&mut self, address: Address, comment: impl Into<String>) {
+        let comment = comment.into();
+        if comment.is_empty() {
+            self.comments.remove(&address);
+        } else {
+            self.comments.insert(address, comment);
+        }
+    }
+
+    pub fn comment(&self, address: Address) -> Option<&str> {
+        self.comments.get(&address).map(String::as_str)
+    }
+
+    /// Toggles the breakpoint at an address. Returns whether it is now set.
+    pub fn toggle_breakpoint(&mut self, address: Address) -> bool {
+        if self.breakpoints.remove(&address) {
+            false
+        } else {
+            self.breakpoints.insert(address)
+        }
+    }
+
+    /// Records the breakpoints currently set on a target, replacing the recorded ones.
+    pub fn capture_breakpoints(&mut self, execution: &dyn ExecutionControl) {
+        self.breakpoints = execution.breakpoints().into_iter().collect();
+    }
+
+    /// Sets the recorded breakpoints on a target, e.g. after attaching to it.
+    pub fn restore_breakpoints(&self, execution: &mut dyn ExecutionControl) -> Result<()> {
+        let set: BTreeSet<_> = execution.breakpoints().into_iter().collect();
+        for &address in self.breakpoints.difference(&set) {
+            execution.set_breakpoint(address)?;
+        }
+
+        Ok(())
+    }
+}
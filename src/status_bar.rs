@@ -0,0 +1,161 @@
+use crate::Theme;
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, Widget},
+};
+
+/// A single-line bar with left, center and right segments, meant to sit under the main panes
+/// (mode indicator, target state, current address, FPS...).
+pub struct StatusBar<'a> {
+    /// Left-aligned segment.
+    left: Line<'a>,
+    /// Centered segment.
+    center: Line<'a>,
+    /// Right-aligned segment.
+    right: Line<'a>,
+
+    left_style: Style,
+    center_style: Style,
+    right_style: Style,
+
+    /// Style of the whole bar.
+    style: Style,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+}
+
+impl<'a> Default for StatusBar<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> StatusBar<'a> {
+    pub fn new() -> Self {
+        Self {
+            left: Line::default(),
+            center: Line::default(),
+            right: Line::default(),
+            left_style: Style::default(),
+            center_style: Style::default(),
+            right_style: Style::default(),
+            style: Style::default(),
+            block: None,
+        }
+        .theme(&Theme::default())
+    }
+
+    pub fn left(self, left: impl Into<Line<'a>>) -> Self {
+        Self {
+            left: left.into(),
+            ..self
+        }
+    }
+
+    pub fn center(self, center: impl Into<Line<'a>>) -> Self {
+        Self {
+            center: center.into(),
+            ..self
+        }
+    }
+
+    pub fn right(self, right: impl Into<Line<'a>>) -> Self {
+        Self {
+            right: right.into(),
+            ..self
+        }
+    }
+
+    pub fn left_style(self, left_style: Style) -> Self {
+        Self { left_style, ..self }
+    }
+
+    pub fn center_style(self, center_style: Style) -> Self {
+        Self {
+            center_style,
+            ..self
+        }
+    }
+
+    pub fn right_style(self, right_style: Style) -> Self {
+        Self {
+            right_style,
+            ..self
+        }
+    }
+
+    pub fn style(self, style: Style) -> Self {
+        Self { style, ..self }
+    }
+
+    /// Sets the styles of the bar and of its left and right segments from the theme.
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            left_style: theme.status_accent,
+            right_style: theme.address,
+            style: theme.status_bar,
+            ..self
+        }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+
+    /// Renders `line` padded by one cell on each side, starting at `x`. Returns the width used.
+    fn render_segment(line: &Line, style: Style, x: u16, area: Rect, buf: &mut Buffer) -> u16 {
+        let width = (line.width() as u16 + 2).min(area.right().saturating_sub(x));
+        if width == 0 {
+            return 0;
+        }
+
+        let segment_area = Rect::new(x, area.y, width, 1);
+        buf.set_style(segment_area, style);
+        buf.set_line(x + 1, area.y, line, width.saturating_sub(1));
+
+        width
+    }
+}
+
+impl<'a> Widget for StatusBar<'a> {
+    fn render(mut self, area: Rect, buf: &mut Buffer) {
+        let area = self.wrap_in_block(area, buf);
+        if area.height == 0 {
+            return;
+        }
+
+        let area = Rect { height: 1, ..area };
+        buf.set_style(area, self.style);
+
+        // the center is drawn first so that the side segments take precedence when space is short
+        let center_width = self.center.width() as u16 + 2;
+        let center_x = area.x + area.width.saturating_sub(center_width) / 2;
+        if self.center.width() > 0 {
+            Self::render_segment(&self.center, self.center_style, center_x, area, buf);
+        }
+
+        let right_width = (self.right.width() as u16 + 2).min(area.width);
+        if self.right.width() > 0 {
+            let right_x = area.right() - right_width;
+            Self::render_segment(&self.right, self.right_style, right_x, area, buf);
+        }
+
+        if self.left.width() > 0 {
+            Self::render_segment(&self.left, self.left_style, area.x, area, buf);
+        }
+    }
+}
@@ -0,0 +1,303 @@
+use crate::{keymap::Action, memory_view::MemoryProvider, Address, Keymap, Theme};
+use crossterm::event::{KeyEvent, KeyEventKind};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, Row, StatefulWidget, Table, Widget},
+};
+use std::ops::Range;
+
+/// How many bytes are read from a provider at once while scanning.
+const CHUNK_SIZE: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringEncoding {
+    Ascii,
+    Utf16Le,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanOptions {
+    /// Minimum amount of characters in a string.
+    pub min_len: usize,
+    pub ascii: bool,
+    pub utf16: bool,
+    /// Maximum amount of strings to find.
+    pub limit: usize,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            min_len: 4,
+            ascii: true,
+            utf16: true,
+            limit: usize::MAX,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoundString {
+    pub address: Address,
+    pub encoding: StringEncoding,
+    pub text: String,
+}
+
+fn is_printable(byte: u8) -> bool {
+    byte.is_ascii_graphic() || byte == b' ' || byte == b'\t'
+}
+
+/// A run of printable characters being accumulated.
+#[derive(Default)]
+struct Run {
+    start: Address,
+    text: String,
+}
+
+impl Run {
+    fn push(&mut self, address: Address, c: char) {
+        if self.text.is_empty() {
+            self.start = address;
+        }
+        self.text.push(c);
+    }
+
+    fn finish(
+        &mut self,
+        encoding: StringEncoding,
+        options: &ScanOptions,
+        out: &mut Vec<FoundString>,
+    ) {
+        if self.text.len() >= options.min_len && out.len() < options.limit {
+            out.push(FoundString {
+                address: self.start,
+                encoding,
+                text: std::mem::take(&mut self.text),
+            });
+        }
+        self.text.clear();
+    }
+}
+
+/// Scans `range` for runs of printable characters. Unreadable bytes end a run. Results are
+/// sorted by address.
+pub fn scan_strings(
+    provider: &dyn MemoryProvider,
+    range: Range<Address>,
+    options: &ScanOptions,
+) -> Vec<FoundString> {
+    let mut found = Vec::new();
+    let mut ascii = Run::default();
+    // UTF-16 code units can start at even or odd addresses, so track a run for each
+    let mut utf16 = [Run::default(), Run::default()];
+    let mut previous: Option<u8> = None;
+
+    let mut buf = vec![None; CHUNK_SIZE];
+    let mut start = range.start;
+    while start < range.end && found.len() < options.limit {
+        let len = ((range.end - start) as usize).min(CHUNK_SIZE);
+        let buf = &mut buf[..len];
+        buf.fill(None);
+        provider.read_to_buf(start, buf);
+
+        for (offset, byte) in buf.iter().enumerate() {
+            let address = start + offset as Address;
+
+            if options.ascii {
+                match byte {
+                    Some(byte) if is_printable(*byte) => ascii.push(address, *byte as char),
+                    _ => ascii.finish(StringEncoding::Ascii, options, &mut found),
+                }
+            }
+
+            if options.utf16 && address > range.start {
+                // the unit starting at the previous byte
+                let unit_address = address - 1;
+                let run = &mut utf16[(unit_address % 2) as usize];
+                match (previous, byte) {
+                    (Some(low), Some(0)) if is_printable(low) => {
+                        run.push(unit_address, low as char)
+                    }
+                    _ => run.finish(StringEncoding::Utf16Le, options, &mut found),
+                }
+            }
+
+            previous = *byte;
+        }
+
+        start += len as Address;
+    }
+
+    ascii.finish(StringEncoding::Ascii, options, &mut found);
+    for run in &mut utf16 {
+        run.finish(StringEncoding::Utf16Le, options, &mut found);
+    }
+
+    found.sort_by_key(|string| string.address);
+    found.truncate(options.limit);
+    found
+}
+
+pub struct StringsViewState {
+    selected: usize,
+    offset: usize,
+    page: usize,
+    len: usize,
+    selected_address: Option<Address>,
+}
+
+impl Default for StringsViewState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StringsViewState {
+    pub fn new() -> Self {
+        Self {
+            selected: 0,
+            offset: 0,
+            page: 1,
+            len: 0,
+            selected_address: None,
+        }
+    }
+
+    /// Address of the selected string, as of the last render.
+    pub fn selected_address(&self) -> Option<Address> {
+        self.selected_address
+    }
+
+    /// Handles a key event with the [standard keymap](Keymap::standard).
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Address> {
+        self.handle_key_with(key, Keymap::standard())
+    }
+
+    /// Handles a key event, translated to an action by `keymap`. Returns the address of the
+    /// selected string when it is activated, so that the memory view can jump to it.
+    pub fn handle_key_with(&mut self, key: KeyEvent, keymap: &Keymap) -> Option<Address> {
+        if key.kind == KeyEventKind::Release {
+            return None;
+        }
+
+        let last = self.len.saturating_sub(1);
+        match keymap.action(key) {
+            Some(Action::Up) => self.selected = self.selected.saturating_sub(1),
+            Some(Action::Down) => self.selected = (self.selected + 1).min(last),
+            Some(Action::PageUp) => self.selected = self.selected.saturating_sub(self.page),
+            Some(Action::PageDown) => self.selected = (self.selected + self.page).min(last),
+            Some(Action::Home) => self.selected = 0,
+            Some(Action::End) => self.selected = last,
+            Some(Action::Activate) => return self.selected_address,
+            _ => (),
+        }
+
+        None
+    }
+}
+
+/// A list of strings found by [`scan_strings`].
+pub struct StringsView<'a> {
+    /// The strings to list.
+    strings: &'a [FoundString],
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> StringsView<'a> {
+    pub fn new(strings: &'a [FoundString]) -> Self {
+        Self {
+            strings,
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+}
+
+impl<'a> StatefulWidget for StringsView<'a> {
+    type State = StringsViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = self.wrap_in_block(area, buf);
+
+        // update state
+        state.len = self.strings.len();
+        state.selected = state.selected.min(state.len.saturating_sub(1));
+        state.page = (area.height as usize).max(1);
+        if state.selected < state.offset {
+            state.offset = state.selected;
+        } else if state.selected >= state.offset + state.page {
+            state.offset = state.selected + 1 - state.page;
+        }
+        state.selected_address = self
+            .strings
+            .get(state.selected)
+            .map(|string| string.address);
+
+        let rows = self
+            .strings
+            .iter()
+            .enumerate()
+            .skip(state.offset)
+            .take(state.page)
+            .map(|(index, string)| {
+                let encoding = match string.encoding {
+                    StringEncoding::Ascii => "A",
+                    StringEncoding::Utf16Le => "U",
+                };
+
+                let row = Row::new([
+                    Line::from(Span::styled(
+                        format!("{:08X}", string.address),
+                        self.theme.address,
+                    )),
+                    Line::from(Span::styled(encoding, self.theme.muted)),
+                    Line::from(Span::styled(
+                        string.text.as_str(),
+                        Style::default().light_blue(),
+                    )),
+                ]);
+
+                if index == state.selected {
+                    row.style(self.theme.selection)
+                } else {
+                    row
+                }
+            });
+
+        let constraints = [
+            Constraint::Length(8),
+            Constraint::Length(1),
+            Constraint::Min(1),
+        ];
+        let table = Table::new(rows).widths(&constraints);
+        Widget::render(table, area, buf);
+    }
+}
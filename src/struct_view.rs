@@ -0,0 +1,566 @@
+use crate::{keymap::Action, memory_view::MemoryProvider, Address, Keymap, Theme};
+use crossterm::event::{KeyEvent, KeyEventKind};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, Row, StatefulWidget, Table, Widget},
+};
+use std::{borrow::Cow, collections::HashSet};
+
+/// The type of a field decoded by the [`StructView`]. Values are little endian.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldType {
+    U8,
+    U16,
+    U32,
+    U64,
+    I8,
+    I16,
+    I32,
+    I64,
+    F32,
+    F64,
+    Bool,
+    /// An address pointing to a value of the inner type.
+    Pointer(Box<FieldType>),
+    /// A fixed amount of contiguous values of the inner type.
+    Array(Box<FieldType>, usize),
+    /// A sequence of fields, laid out without padding.
+    Struct(Vec<Field>),
+}
+
+impl FieldType {
+    /// Size of the type, in bytes.
+    pub fn size(&self) -> usize {
+        match self {
+            Self::U8 | Self::I8 | Self::Bool => 1,
+            Self::U16 | Self::I16 => 2,
+            Self::U32 | Self::I32 | Self::F32 => 4,
+            Self::U64 | Self::I64 | Self::F64 => 8,
+            Self::Pointer(_) => std::mem::size_of::<Address>(),
+            Self::Array(ty, len) => ty.size() * len,
+            Self::Struct(fields) => fields.iter().map(|field| field.ty.size()).sum(),
+        }
+    }
+
+    /// A short, C-like name of the type.
+    pub fn name(&self) -> Cow<'static, str> {
+        match self {
+            Self::U8 => "u8".into(),
+            Self::U16 => "u16".into(),
+            Self::U32 => "u32".into(),
+            Self::U64 => "u64".into(),
+            Self::I8 => "i8".into(),
+            Self::I16 => "i16".into(),
+            Self::I32 => "i32".into(),
+            Self::I64 => "i64".into(),
+            Self::F32 => "f32".into(),
+            Self::F64 => "f64".into(),
+            Self::Bool => "bool".into(),
+            Self::Pointer(ty) => format!("*{}", ty.name()).into(),
+            Self::Array(ty, len) => format!("[{}; {len}]", ty.name()).into(),
+            Self::Struct(_) => "struct".into(),
+        }
+    }
+
+    /// Parses a [name](Self::name) of a primitive type, e.g. `u16`, or of a pointer to one,
+    /// e.g. `*u32`.
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim();
+        if let Some(target) = name.strip_prefix('*') {
+            return Self::parse(target).map(|target| Self::Pointer(Box::new(target)));
+        }
+
+        Some(match name {
+            "u8" => Self::U8,
+            "u16" => Self::U16,
+            "u32" => Self::U32,
+            "u64" => Self::U64,
+            "i8" => Self::I8,
+            "i16" => Self::I16,
+            "i32" => Self::I32,
+            "i64" => Self::I64,
+            "f32" => Self::F32,
+            "f64" => Self::F64,
+            "bool" => Self::Bool,
+            _ => return None,
+        })
+    }
+
+    fn is_expandable(&self) -> bool {
+        matches!(self, Self::Pointer(_) | Self::Array(..) | Self::Struct(_))
+    }
+}
+
+/// How the value of a primitive field is displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValueFormat {
+    #[default]
+    Decimal,
+    Hex,
+    Binary,
+    Char,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: Cow<'static, str>,
+    pub ty: FieldType,
+    pub format: ValueFormat,
+}
+
+impl Field {
+    pub fn new(name: impl Into<Cow<'static, str>>, ty: FieldType) -> Self {
+        Self {
+            name: name.into(),
+            ty,
+            format: ValueFormat::default(),
+        }
+    }
+
+    pub fn format(self, format: ValueFormat) -> Self {
+        Self { format, ..self }
+    }
+}
+
+/// Parses a record of comma separated fields, each either a type or a name and a type, e.g.
+/// `x: u16, y: u16, f32`. Fields without a name are named after their type. Types are parsed
+/// with [`FieldType::parse`].
+pub fn parse_record(spec: &str) -> Option<Vec<Field>> {
+    spec.split(',')
+        .map(|field| {
+            let (name, ty) = match field.split_once(':') {
+                Some((name, ty)) => (name.trim(), FieldType::parse(ty)?),
+                None => {
+                    let ty = FieldType::parse(field)?;
+                    (field.trim(), ty)
+                }
+            };
+
+            (!name.is_empty()).then(|| Field::new(name.to_owned(), ty))
+        })
+        .collect()
+}
+
+fn read_bytes<const N: usize>(provider: &dyn MemoryProvider, address: Address) -> Option<[u8; N]> {
+    let mut buf = [None; N];
+    provider.read_to_buf(address, &mut buf);
+
+    let mut bytes = [0; N];
+    for (byte, value) in bytes.iter_mut().zip(buf) {
+        *byte = value?;
+    }
+
+    Some(bytes)
+}
+
+fn format_integer(value: u64, signed: Option<i64>, size: usize, format: ValueFormat) -> String {
+    match format {
+        ValueFormat::Decimal => match signed {
+            Some(signed) => signed.to_string(),
+            None => value.to_string(),
+        },
+        ValueFormat::Hex => format!("0x{value:0width$X}", width = size * 2),
+        ValueFormat::Binary => format!("0b{value:0width$b}", width = size * 8),
+        ValueFormat::Char => match char::from_u32(value as u32) {
+            Some(c) if !c.is_control() => format!("{c:?}"),
+            _ => format!("0x{value:0width$X}", width = size * 2),
+        },
+    }
+}
+
+/// Maximum width of the values [`format_value`] returns for a type, in cells. Longer values, like
+/// some floats, are rare enough to be cut.
+pub(crate) fn value_width(ty: &FieldType, format: ValueFormat) -> usize {
+    let size = ty.size();
+    match (ty, format) {
+        (FieldType::Bool, _) => 5,
+        (FieldType::Pointer(_), _) => 10,
+        (FieldType::Array(..) | FieldType::Struct(_), _) => 6,
+        (FieldType::F32, ValueFormat::Decimal) => 14,
+        (FieldType::F64, ValueFormat::Decimal) => 24,
+        (FieldType::U8, ValueFormat::Decimal) => 3,
+        (FieldType::I8, ValueFormat::Decimal) => 4,
+        (FieldType::U16, ValueFormat::Decimal) => 5,
+        (FieldType::I16, ValueFormat::Decimal) => 6,
+        (FieldType::U32, ValueFormat::Decimal) => 10,
+        (FieldType::I32, ValueFormat::Decimal) => 11,
+        (_, ValueFormat::Decimal) => 20,
+        (_, ValueFormat::Hex | ValueFormat::Char) => 2 + 2 * size,
+        (_, ValueFormat::Binary) => 2 + 8 * size,
+    }
+}
+
+/// Decodes a primitive value at `address`. Returns `None` if it can't be read.
+pub(crate) fn format_value(
+    provider: &dyn MemoryProvider,
+    ty: &FieldType,
+    format: ValueFormat,
+    address: Address,
+) -> Option<String> {
+    let text = match ty {
+        FieldType::U8 => {
+            let [a] = read_bytes(provider, address)?;
+            format_integer(a as u64, None, 1, format)
+        }
+        FieldType::I8 => {
+            let [a] = read_bytes(provider, address)?;
+            format_integer(a as u64, Some(a as i8 as i64), 1, format)
+        }
+        FieldType::U16 => {
+            let value = u16::from_le_bytes(read_bytes(provider, address)?);
+            format_integer(value as u64, None, 2, format)
+        }
+        FieldType::I16 => {
+            let value = u16::from_le_bytes(read_bytes(provider, address)?);
+            format_integer(value as u64, Some(value as i16 as i64), 2, format)
+        }
+        FieldType::U32 => {
+            let value = u32::from_le_bytes(read_bytes(provider, address)?);
+            format_integer(value as u64, None, 4, format)
+        }
+        FieldType::I32 => {
+            let value = u32::from_le_bytes(read_bytes(provider, address)?);
+            format_integer(value as u64, Some(value as i32 as i64), 4, format)
+        }
+        FieldType::U64 => {
+            let value = u64::from_le_bytes(read_bytes(provider, address)?);
+            format_integer(value, None, 8, format)
+        }
+        FieldType::I64 => {
+            let value = u64::from_le_bytes(read_bytes(provider, address)?);
+            format_integer(value, Some(value as i64), 8, format)
+        }
+        FieldType::F32 => {
+            let bytes = read_bytes(provider, address)?;
+            match format {
+                ValueFormat::Decimal => format!("{:?}", f32::from_le_bytes(bytes)),
+                _ => format_integer(u32::from_le_bytes(bytes) as u64, None, 4, format),
+            }
+        }
+        FieldType::F64 => {
+            let bytes = read_bytes(provider, address)?;
+            match format {
+                ValueFormat::Decimal => format!("{:?}", f64::from_le_bytes(bytes)),
+                _ => format_integer(u64::from_le_bytes(bytes), None, 8, format),
+            }
+        }
+        FieldType::Bool => {
+            let [a] = read_bytes(provider, address)?;
+            match a {
+                0 => "false".to_owned(),
+                1 => "true".to_owned(),
+                _ => format!("true ({a})"),
+            }
+        }
+        FieldType::Pointer(_) => {
+            let target = Address::from_le_bytes(read_bytes(provider, address)?);
+            if target == 0 {
+                "null".to_owned()
+            } else {
+                format!("0x{target:08X}")
+            }
+        }
+        FieldType::Array(_, len) => format!("[{len}]"),
+        FieldType::Struct(fields) => format!("{{{}}}", fields.len()),
+    };
+
+    Some(text)
+}
+
+/// A flattened, visible node of the tree.
+struct StructRow {
+    path: Vec<usize>,
+    depth: usize,
+    name: Cow<'static, str>,
+    type_name: Cow<'static, str>,
+    value: Option<String>,
+    address: Address,
+    expandable: bool,
+}
+
+pub struct StructViewState {
+    /// The address the root field is decoded at.
+    pub root: Address,
+
+    expanded: HashSet<Vec<usize>>,
+    selected: usize,
+    offset: usize,
+
+    /// Paths of the rows, as of the last render.
+    row_paths: Vec<Vec<usize>>,
+    /// Addresses of the rows, as of the last render.
+    row_addresses: Vec<Address>,
+    page: usize,
+}
+
+impl StructViewState {
+    pub fn new(root: Address) -> Self {
+        let mut expanded = HashSet::new();
+        expanded.insert(Vec::new());
+
+        Self {
+            root,
+            expanded,
+            selected: 0,
+            offset: 0,
+            row_paths: Vec::new(),
+            row_addresses: Vec::new(),
+            page: 1,
+        }
+    }
+
+    /// Address of the selected field, as of the last render.
+    pub fn selected_address(&self) -> Option<Address> {
+        self.row_addresses.get(self.selected).copied()
+    }
+
+    /// Expands or collapses the selected node.
+    pub fn toggle_selected(&mut self) {
+        if let Some(path) = self.row_paths.get(self.selected) {
+            if !self.expanded.remove(path) {
+                self.expanded.insert(path.clone());
+            }
+        }
+    }
+
+    /// Collapses every node but the root.
+    pub fn collapse_all(&mut self) {
+        self.expanded.retain(|path| path.is_empty());
+        self.selected = 0;
+    }
+
+    fn collapse_selected(&mut self) {
+        let Some(path) = self.row_paths.get(self.selected) else {
+            return;
+        };
+
+        if !self.expanded.remove(path) && !path.is_empty() {
+            // already collapsed, move to the parent instead
+            let parent = &path[..path.len() - 1];
+            if let Some(index) = self.row_paths.iter().position(|p| p == parent) {
+                self.selected = index;
+            }
+        }
+    }
+
+    /// Handles a key event with the [standard keymap](Keymap::standard).
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        self.handle_key_with(key, Keymap::standard())
+    }
+
+    /// Handles a key event, translated to an action by `keymap`. Returns whether the event was
+    /// consumed.
+    pub fn handle_key_with(&mut self, key: KeyEvent, keymap: &Keymap) -> bool {
+        if key.kind == KeyEventKind::Release {
+            return false;
+        }
+
+        let last = self.row_paths.len().saturating_sub(1);
+        match keymap.action(key) {
+            Some(Action::Up) => self.selected = self.selected.saturating_sub(1),
+            Some(Action::Down) => self.selected = (self.selected + 1).min(last),
+            Some(Action::PageUp) => self.selected = self.selected.saturating_sub(self.page),
+            Some(Action::PageDown) => self.selected = (self.selected + self.page).min(last),
+            Some(Action::Activate | Action::Toggle) => self.toggle_selected(),
+            Some(Action::Right) => {
+                if let Some(path) = self.row_paths.get(self.selected) {
+                    self.expanded.insert(path.clone());
+                }
+            }
+            Some(Action::Left) => self.collapse_selected(),
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+/// A tree of typed fields decoded live from a [`MemoryProvider`].
+pub struct StructView<'a> {
+    /// The memory provider.
+    memory_provider: &'a dyn MemoryProvider,
+
+    /// The field at the root of the tree.
+    root: &'a Field,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> StructView<'a> {
+    pub fn new(memory_provider: &'a dyn MemoryProvider, root: &'a Field) -> Self {
+        Self {
+            memory_provider,
+            root,
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn flatten(
+        &self,
+        name: Cow<'static, str>,
+        ty: &FieldType,
+        format: ValueFormat,
+        address: Address,
+        path: &mut Vec<usize>,
+        expanded: &HashSet<Vec<usize>>,
+        rows: &mut Vec<StructRow>,
+    ) {
+        let value = format_value(self.memory_provider, ty, format, address);
+        rows.push(StructRow {
+            path: path.clone(),
+            depth: path.len(),
+            name,
+            type_name: ty.name(),
+            value,
+            address,
+            expandable: ty.is_expandable(),
+        });
+
+        if !ty.is_expandable() || !expanded.contains(path) {
+            return;
+        }
+
+        match ty {
+            FieldType::Struct(fields) => {
+                let mut offset = address;
+                for (index, field) in fields.iter().enumerate() {
+                    path.push(index);
+                    self.flatten(
+                        field.name.clone(),
+                        &field.ty,
+                        field.format,
+                        offset,
+                        path,
+                        expanded,
+                        rows,
+                    );
+                    path.pop();
+                    offset = offset.wrapping_add(field.ty.size() as Address);
+                }
+            }
+            FieldType::Array(inner, len) => {
+                let stride = inner.size() as Address;
+                for index in 0..*len {
+                    path.push(index);
+                    self.flatten(
+                        format!("[{index}]").into(),
+                        inner,
+                        format,
+                        address.wrapping_add(stride.wrapping_mul(index as Address)),
+                        path,
+                        expanded,
+                        rows,
+                    );
+                    path.pop();
+                }
+            }
+            FieldType::Pointer(inner) => {
+                let target = read_bytes(self.memory_provider, address).map(Address::from_le_bytes);
+                if let Some(target) = target.filter(|&target| target != 0) {
+                    path.push(0);
+                    self.flatten("*".into(), inner, format, target, path, expanded, rows);
+                    path.pop();
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<'a> StatefulWidget for StructView<'a> {
+    type State = StructViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = self.wrap_in_block(area, buf);
+
+        let mut rows = Vec::new();
+        self.flatten(
+            self.root.name.clone(),
+            &self.root.ty,
+            self.root.format,
+            state.root,
+            &mut Vec::new(),
+            &state.expanded,
+            &mut rows,
+        );
+
+        // update state
+        state.selected = state.selected.min(rows.len().saturating_sub(1));
+        state.page = (area.height as usize).max(1);
+        if state.selected < state.offset {
+            state.offset = state.selected;
+        } else if state.selected >= state.offset + state.page {
+            state.offset = state.selected + 1 - state.page;
+        }
+        state.row_paths = rows.iter().map(|row| row.path.clone()).collect();
+        state.row_addresses = rows.iter().map(|row| row.address).collect();
+
+        let table_rows = rows
+            .iter()
+            .enumerate()
+            .skip(state.offset)
+            .take(state.page)
+            .map(|(index, row)| {
+                let marker = match (row.expandable, state.expanded.contains(&row.path)) {
+                    (false, _) => " ",
+                    (true, false) => "▸",
+                    (true, true) => "▾",
+                };
+                let name = Line::from(vec![
+                    Span::raw("  ".repeat(row.depth)),
+                    Span::styled(marker, self.theme.muted),
+                    Span::raw(" "),
+                    Span::styled(row.name.clone(), Style::default().light_blue()),
+                ]);
+                let value = match &row.value {
+                    Some(value) => Span::styled(value.clone(), Style::default().light_green()),
+                    None => Span::styled("??", self.theme.muted),
+                };
+                let type_name = Span::styled(row.type_name.clone(), self.theme.muted);
+
+                let row_widget = Row::new([name, Line::from(value), Line::from(type_name)]);
+                if index == state.selected {
+                    row_widget.style(self.theme.selection)
+                } else {
+                    row_widget
+                }
+            });
+
+        let constraints = [
+            Constraint::Percentage(45),
+            Constraint::Percentage(35),
+            Constraint::Percentage(20),
+        ];
+        let table = Table::new(table_rows).widths(&constraints);
+        Widget::render(table, area, buf);
+    }
+}
@@ -0,0 +1,327 @@
+//! Loading of [`MmioRegister`] definitions from CMSIS-SVD files.
+
+use crate::{
+    mmio_view::{Access, EnumeratedValue, MmioField, MmioRegister, RegisterWidth},
+    Address,
+};
+use eyre::{bail, eyre, Result, WrapErr};
+use roxmltree::{Document, Node};
+use std::borrow::Cow;
+
+/// A peripheral described by an SVD file, with its registers at absolute addresses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Peripheral {
+    pub name: String,
+    pub base_address: Address,
+    pub registers: Vec<MmioRegister>,
+}
+
+/// Properties inherited from the device down to registers and fields.
+#[derive(Debug, Clone, Copy)]
+struct Defaults {
+    size: u32,
+    access: Access,
+}
+
+impl Defaults {
+    fn inherit(self, node: Node) -> Result<Self> {
+        Ok(Self {
+            size: optional_number(node, "size")?.map_or(self.size, |size| size as u32),
+            access: optional_access(node)?.unwrap_or(self.access),
+        })
+    }
+}
+
+/// Parses an SVD number, which can be decimal, hex (`0x`) or binary (`#` or `0b`).
+fn parse_number(text: &str) -> Result<u64> {
+    let text = text.trim();
+    let (digits, radix) =
+        if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+            (hex, 16)
+        } else if let Some(binary) = text.strip_prefix('#').or_else(|| text.strip_prefix("0b")) {
+            (binary, 2)
+        } else {
+            (text, 10)
+        };
+
+    // "don't care" bits in enumerated values are treated as zero
+    let digits = digits.replace(['x', 'X'], "0");
+    u64::from_str_radix(&digits, radix).wrap_err_with(|| format!("invalid number {text:?}"))
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children()
+        .find(|child| child.is_element() && child.tag_name().name() == name)
+}
+
+fn children<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children()
+        .filter(move |child| child.is_element() && child.tag_name().name() == name)
+}
+
+fn optional_text<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    child(node, name)
+        .and_then(|child| child.text())
+        .map(str::trim)
+}
+
+fn text<'a>(node: Node<'a, '_>, name: &str) -> Result<&'a str> {
+    optional_text(node, name)
+        .ok_or_else(|| eyre!("missing <{name}> in <{}>", node.tag_name().name()))
+}
+
+fn optional_number(node: Node, name: &str) -> Result<Option<u64>> {
+    optional_text(node, name).map(parse_number).transpose()
+}
+
+fn number(node: Node, name: &str) -> Result<u64> {
+    parse_number(text(node, name)?)
+}
+
+fn optional_access(node: Node) -> Result<Option<Access>> {
+    let Some(access) = optional_text(node, "access") else {
+        return Ok(None);
+    };
+
+    let access = match access {
+        "read-only" => Access::ReadOnly,
+        "write-only" => Access::WriteOnly,
+        "read-write" => Access::ReadWrite,
+        "writeOnce" => Access::WriteOnce,
+        "read-writeOnce" => Access::ReadWriteOnce,
+        _ => bail!("unknown access {access:?}"),
+    };
+
+    Ok(Some(access))
+}
+
+/// Expands `dim` arrays into the names and offsets of each of their elements.
+fn expand_dim(node: Node, name: &str) -> Result<Vec<(String, u64)>> {
+    let Some(dim) = optional_number(node, "dim")? else {
+        return Ok(vec![(name.to_owned(), 0)]);
+    };
+    let increment = number(node, "dimIncrement")?;
+
+    let indices: Vec<String> = match optional_text(node, "dimIndex") {
+        Some(index) if index.contains('-') => {
+            let (start, end) = index
+                .split_once('-')
+                .ok_or_else(|| eyre!("invalid dimIndex {index:?}"))?;
+            match (start.parse::<u64>(), end.parse::<u64>()) {
+                (Ok(start), Ok(end)) => (start..=end).map(|i| i.to_string()).collect(),
+                _ => {
+                    // letter ranges, e.g. A-D
+                    let (start, end) = (start.chars().next(), end.chars().next());
+                    let (Some(start), Some(end)) = (start, end) else {
+                        bail!("invalid dimIndex {index:?}");
+                    };
+                    (start..=end).map(String::from).collect()
+                }
+            }
+        }
+        Some(index) => index.split(',').map(|i| i.trim().to_owned()).collect(),
+        None => (0..dim).map(|i| i.to_string()).collect(),
+    };
+
+    Ok(indices
+        .into_iter()
+        .take(dim as usize)
+        .enumerate()
+        .map(|(i, index)| {
+            let name = name.replace("[%s]", &index).replace("%s", &index);
+            (name, i as u64 * increment)
+        })
+        .collect())
+}
+
+fn parse_field(node: Node, defaults: Defaults) -> Result<Vec<MmioField>> {
+    let name = text(node, "name")?;
+
+    let (offset, width) = if let Some(offset) = optional_number(node, "bitOffset")? {
+        let width = optional_number(node, "bitWidth")?.unwrap_or(1);
+        (offset, width)
+    } else if let Some(lsb) = optional_number(node, "lsb")? {
+        let msb = number(node, "msb")?;
+        (lsb, msb.saturating_sub(lsb) + 1)
+    } else if let Some(range) = optional_text(node, "bitRange") {
+        let range = range.trim_start_matches('[').trim_end_matches(']');
+        let (msb, lsb) = range
+            .split_once(':')
+            .ok_or_else(|| eyre!("invalid bitRange {range:?}"))?;
+        let (msb, lsb) = (parse_number(msb)?, parse_number(lsb)?);
+        (lsb, msb.saturating_sub(lsb) + 1)
+    } else {
+        bail!("field {name:?} has no bit range");
+    };
+
+    let access = optional_access(node)?.unwrap_or(defaults.access);
+
+    let mut enumerated_values = Vec::new();
+    for values in children(node, "enumeratedValues") {
+        for value in children(values, "enumeratedValue") {
+            // default values have no specific value, so there's nothing to match
+            let Some(number) = optional_number(value, "value")? else {
+                continue;
+            };
+
+            enumerated_values.push(EnumeratedValue {
+                name: Cow::Owned(text(value, "name")?.to_owned()),
+                value: number as u32,
+            });
+        }
+    }
+
+    Ok(expand_dim(node, name)?
+        .into_iter()
+        .map(|(name, increment)| MmioField {
+            name: Cow::Owned(name),
+            offset: (offset + increment) as u32,
+            width: width as u32,
+            access,
+            enumerated_values: enumerated_values.clone(),
+        })
+        .collect())
+}
+
+fn parse_register(
+    node: Node,
+    base: u64,
+    defaults: Defaults,
+    prefix: &str,
+) -> Result<Vec<MmioRegister>> {
+    let name = text(node, "name")?;
+    let offset = number(node, "addressOffset")?;
+    let defaults = defaults.inherit(node)?;
+
+    let width = match defaults.size {
+        8 => RegisterWidth::W8,
+        16 => RegisterWidth::W16,
+        32 => RegisterWidth::W32,
+        // wider registers can't be displayed
+        _ => return Ok(Vec::new()),
+    };
+
+    let mut fields = Vec::new();
+    if let Some(node) = child(node, "fields") {
+        for field in children(node, "field") {
+            fields.extend(parse_field(field, defaults)?);
+        }
+    }
+    fields.sort_by_key(|field| field.offset);
+
+    Ok(expand_dim(node, name)?
+        .into_iter()
+        .map(|(name, increment)| MmioRegister {
+            address: (base + offset + increment) as Address,
+            name: Cow::Owned(format!("{prefix}{name}")),
+            width,
+            access: defaults.access,
+            fields: fields.clone(),
+        })
+        .collect())
+}
+
+/// Parses the registers and clusters inside of `node`.
+fn parse_registers(
+    node: Node,
+    base: u64,
+    defaults: Defaults,
+    prefix: &str,
+) -> Result<Vec<MmioRegister>> {
+    let mut registers = Vec::new();
+    for child in node.children().filter(|child| child.is_element()) {
+        match child.tag_name().name() {
+            "register" => registers.extend(parse_register(child, base, defaults, prefix)?),
+            "cluster" => {
+                let name = text(child, "name")?;
+                let offset = number(child, "addressOffset")?;
+                let defaults = defaults.inherit(child)?;
+
+                for (name, increment) in expand_dim(child, name)? {
+                    let prefix = format!("{prefix}{name}.");
+                    registers.extend(parse_registers(
+                        child,
+                        base + offset + increment,
+                        defaults,
+                        &prefix,
+                    )?);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    Ok(registers)
+}
+
+/// Parses a CMSIS-SVD document into its peripherals.
+pub fn parse(svd: &str) -> Result<Vec<Peripheral>> {
+    let document = Document::parse(svd).wrap_err("invalid SVD document")?;
+    let device = document.root_element();
+    if device.tag_name().name() != "device" {
+        bail!("root element is not <device>");
+    }
+
+    let defaults = Defaults {
+        size: 32,
+        access: Access::ReadWrite,
+    }
+    .inherit(device)?;
+
+    let peripheral_nodes: Vec<_> = child(device, "peripherals")
+        .map(|peripherals| children(peripherals, "peripheral").collect())
+        .unwrap_or_default();
+
+    let mut peripherals = Vec::with_capacity(peripheral_nodes.len());
+    for node in &peripheral_nodes {
+        let name = text(*node, "name")?;
+        let base = number(*node, "baseAddress")?;
+
+        // derived peripherals reuse the registers of another one unless they define their own
+        let source = match node.attribute("derivedFrom") {
+            Some(derived_from) if child(*node, "registers").is_none() => peripheral_nodes
+                .iter()
+                .find(|other| optional_text(**other, "name") == Some(derived_from))
+                .copied()
+                .ok_or_else(|| {
+                    eyre!("{name:?} derives from unknown peripheral {derived_from:?}")
+                })?,
+            _ => *node,
+        };
+
+        let defaults = defaults.inherit(source)?.inherit(*node)?;
+        let registers = match child(source, "registers") {
+            Some(registers) => parse_registers(registers, base, defaults, "")
+                .wrap_err_with(|| format!("in peripheral {name:?}"))?,
+            None => Vec::new(),
+        };
+
+        peripherals.push(Peripheral {
+            name: name.to_owned(),
+            base_address: base as Address,
+            registers,
+        });
+    }
+
+    Ok(peripherals)
+}
+
+/// Parses a CMSIS-SVD document into a flat list of registers, named `PERIPHERAL.REGISTER`.
+pub fn parse_registers_flat(svd: &str) -> Result<Vec<MmioRegister>> {
+    Ok(parse(svd)?
+        .into_iter()
+        .flat_map(|peripheral| {
+            let name = peripheral.name;
+            peripheral
+                .registers
+                .into_iter()
+                .map(move |register| MmioRegister {
+                    name: Cow::Owned(format!("{name}.{}", register.name)),
+                    ..register
+                })
+        })
+        .collect())
+}
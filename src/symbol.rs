@@ -0,0 +1,219 @@
+use crate::Address;
+use eyre::{bail, Result};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Symbol {
+    pub address: Address,
+    pub name: String,
+}
+
+impl Symbol {
+    pub fn new(address: Address, name: impl Into<String>) -> Self {
+        Self {
+            address,
+            name: name.into(),
+        }
+    }
+}
+
+pub trait SymbolProvider {
+    /// Returns every known symbol.
+    fn symbols(&self) -> Vec<Symbol>;
+
+    /// Returns the symbol located exactly at `address`, if any.
+    fn symbol_at(&self, address: Address) -> Option<Symbol> {
+        self.symbols()
+            .into_iter()
+            .find(|symbol| symbol.address == address)
+    }
+
+    /// Returns the address of the symbol called `name`, if any.
+    fn lookup(&self, name: &str) -> Option<Address> {
+        self.symbols()
+            .into_iter()
+            .find(|symbol| symbol.name == name)
+            .map(|symbol| symbol.address)
+    }
+
+    /// Whether [`rename`](Self::rename) and [`add_label`](Self::add_label) are supported, e.g. to
+    /// annotate code interactively.
+    fn is_mutable(&self) -> bool {
+        false
+    }
+
+    /// Renames the symbol at `address`. Fails if there is none, or if symbols are read-only.
+    fn rename(&mut self, address: Address, _name: &str) -> Result<()> {
+        bail!("can't rename the symbol at {address:08X}: symbols are read-only")
+    }
+
+    /// Adds a symbol at `address`. Fails if there already is one, or if symbols are read-only.
+    fn add_label(&mut self, address: Address, _name: &str) -> Result<()> {
+        bail!("can't add a label at {address:08X}: symbols are read-only")
+    }
+}
+
+impl SymbolProvider for [Symbol] {
+    fn symbols(&self) -> Vec<Symbol> {
+        self.to_vec()
+    }
+
+    fn symbol_at(&self, address: Address) -> Option<Symbol> {
+        self.iter()
+            .find(|symbol| symbol.address == address)
+            .cloned()
+    }
+
+    fn lookup(&self, name: &str) -> Option<Address> {
+        self.iter()
+            .find(|symbol| symbol.name == name)
+            .map(|symbol| symbol.address)
+    }
+
+    /// Renames every symbol at `address`. Labels can't be added to a slice.
+    fn rename(&mut self, address: Address, name: &str) -> Result<()> {
+        let mut found = false;
+        for symbol in self.iter_mut().filter(|symbol| symbol.address == address) {
+            symbol.name = name.to_owned();
+            found = true;
+        }
+
+        if !found {
+            bail!("there is no symbol at {address:08X}");
+        }
+
+        Ok(())
+    }
+}
+
+impl SymbolProvider for Vec<Symbol> {
+    fn symbols(&self) -> Vec<Symbol> {
+        self.as_slice().symbols()
+    }
+
+    fn symbol_at(&self, address: Address) -> Option<Symbol> {
+        self.as_slice().symbol_at(address)
+    }
+
+    fn lookup(&self, name: &str) -> Option<Address> {
+        self.as_slice().lookup(name)
+    }
+
+    fn is_mutable(&self) -> bool {
+        true
+    }
+
+    fn rename(&mut self, address: Address, name: &str) -> Result<()> {
+        self.as_mut_slice().rename(address, name)
+    }
+
+    fn add_label(&mut self, address: Address, name: &str) -> Result<()> {
+        if let Some(symbol) = self.symbol_at(address) {
+            bail!(
+                "there already is a symbol at {address:08X}: `{}`",
+                symbol.name
+            );
+        }
+
+        self.push(Symbol::new(address, name));
+        Ok(())
+    }
+}
+
+/// An address-indexed symbol table, with at most one symbol per address.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolTable {
+    symbols: BTreeMap<Address, String>,
+}
+
+impl SymbolTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a symbol, replacing any other at the same address.
+    pub fn insert(&mut self, address: Address, name: impl Into<String>) {
+        self.symbols.insert(address, name.into());
+    }
+
+    pub fn remove(&mut self, address: Address) -> Option<String> {
+        self.symbols.remove(&address)
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Address, &str)> {
+        self.symbols
+            .iter()
+            .map(|(address, name)| (*address, name.as_str()))
+    }
+
+    /// Returns the closest symbol at or before `address`, along with the offset from it.
+    pub fn nearest(&self, address: Address) -> Option<(Address, &str, Address)> {
+        self.symbols
+            .range(..=address)
+            .next_back()
+            .map(|(start, name)| (*start, name.as_str(), address - start))
+    }
+}
+
+impl FromIterator<Symbol> for SymbolTable {
+    fn from_iter<T: IntoIterator<Item = Symbol>>(iter: T) -> Self {
+        Self {
+            symbols: iter
+                .into_iter()
+                .map(|symbol| (symbol.address, symbol.name))
+                .collect(),
+        }
+    }
+}
+
+impl SymbolProvider for SymbolTable {
+    fn symbols(&self) -> Vec<Symbol> {
+        self.symbols
+            .iter()
+            .map(|(address, name)| Symbol::new(*address, name.clone()))
+            .collect()
+    }
+
+    fn symbol_at(&self, address: Address) -> Option<Symbol> {
+        self.symbols
+            .get(&address)
+            .map(|name| Symbol::new(address, name.clone()))
+    }
+
+    fn lookup(&self, name: &str) -> Option<Address> {
+        self.symbols
+            .iter()
+            .find(|(_, symbol)| *symbol == name)
+            .map(|(address, _)| *address)
+    }
+
+    fn is_mutable(&self) -> bool {
+        true
+    }
+
+    fn rename(&mut self, address: Address, name: &str) -> Result<()> {
+        match self.symbols.get_mut(&address) {
+            Some(symbol) => *symbol = name.to_owned(),
+            None => bail!("there is no symbol at {address:08X}"),
+        }
+
+        Ok(())
+    }
+
+    fn add_label(&mut self, address: Address, name: &str) -> Result<()> {
+        if let Some(symbol) = self.symbols.get(&address) {
+            bail!("there already is a symbol at {address:08X}: `{symbol}`");
+        }
+
+        self.insert(address, name);
+        Ok(())
+    }
+}
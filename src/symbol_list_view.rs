@@ -0,0 +1,266 @@
+use crate::{
+    fuzzy::fuzzy_score,
+    i18n::{self, strings},
+    keymap::Action,
+    symbol::{Symbol, SymbolProvider},
+    Address, Keymap, Theme,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, Row, StatefulWidget, Table, Widget},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SymbolSort {
+    #[default]
+    Address,
+    Name,
+}
+
+pub struct SymbolListViewState {
+    /// The fuzzy filter applied to symbol names.
+    pub filter: String,
+    /// Whether key events are being typed into the filter.
+    pub editing_filter: bool,
+    pub sort: SymbolSort,
+
+    selected: usize,
+    offset: usize,
+    page: usize,
+    /// The symbols shown, as of the last render.
+    visible: Vec<Symbol>,
+}
+
+impl Default for SymbolListViewState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SymbolListViewState {
+    pub fn new() -> Self {
+        Self {
+            filter: String::new(),
+            editing_filter: false,
+            sort: SymbolSort::default(),
+            selected: 0,
+            offset: 0,
+            page: 1,
+            visible: Vec::new(),
+        }
+    }
+
+    /// The selected symbol, as of the last render.
+    pub fn selected_symbol(&self) -> Option<&Symbol> {
+        self.visible.get(self.selected)
+    }
+
+    /// Handles a key event with the [standard keymap](Keymap::standard).
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Address> {
+        self.handle_key_with(key, Keymap::standard())
+    }
+
+    /// Handles a key event, translated to an action by `keymap`. Returns the address of the
+    /// selected symbol when it is activated, so that other views can jump to it.
+    pub fn handle_key_with(&mut self, key: KeyEvent, keymap: &Keymap) -> Option<Address> {
+        if key.kind == KeyEventKind::Release {
+            return None;
+        }
+
+        if self.editing_filter {
+            match key.code {
+                KeyCode::Esc => self.editing_filter = false,
+                KeyCode::Enter => {
+                    self.editing_filter = false;
+                    return self.selected_symbol().map(|symbol| symbol.address);
+                }
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                    self.selected = 0;
+                }
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.filter.push(c);
+                    self.selected = 0;
+                }
+                KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+                KeyCode::Down => self.selected += 1,
+                _ => (),
+            }
+
+            return None;
+        }
+
+        match keymap.action(key) {
+            Some(Action::Search) => self.editing_filter = true,
+            Some(Action::Sort) => {
+                self.sort = match self.sort {
+                    SymbolSort::Address => SymbolSort::Name,
+                    SymbolSort::Name => SymbolSort::Address,
+                }
+            }
+            Some(Action::Cancel) => {
+                self.filter.clear();
+                self.selected = 0;
+            }
+            Some(Action::Up) => self.selected = self.selected.saturating_sub(1),
+            Some(Action::Down) => self.selected += 1,
+            Some(Action::PageUp) => self.selected = self.selected.saturating_sub(self.page),
+            Some(Action::PageDown) => self.selected += self.page,
+            Some(Action::Home) => self.selected = 0,
+            Some(Action::End) => self.selected = usize::MAX,
+            Some(Action::Activate) => return self.selected_symbol().map(|symbol| symbol.address),
+            _ => (),
+        }
+
+        None
+    }
+}
+
+/// A filterable, sortable list of symbols.
+pub struct SymbolListView<'a> {
+    /// The symbol provider.
+    symbol_provider: &'a dyn SymbolProvider,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> SymbolListView<'a> {
+    pub fn new(symbol_provider: &'a dyn SymbolProvider) -> Self {
+        Self {
+            symbol_provider,
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+}
+
+impl<'a> StatefulWidget for SymbolListView<'a> {
+    type State = SymbolListViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = self.wrap_in_block(area, buf);
+        if area.height == 0 {
+            return;
+        }
+
+        let mut symbols: Vec<(i64, Symbol)> = self
+            .symbol_provider
+            .symbols()
+            .into_iter()
+            .filter_map(|symbol| {
+                let score = if state.filter.is_empty() {
+                    0
+                } else {
+                    fuzzy_score(&state.filter, &symbol.name)?
+                };
+                Some((score, symbol))
+            })
+            .collect();
+
+        match state.sort {
+            SymbolSort::Address => {
+                symbols.sort_by(|(sa, a), (sb, b)| sb.cmp(sa).then(a.address.cmp(&b.address)))
+            }
+            SymbolSort::Name => {
+                symbols.sort_by(|(sa, a), (sb, b)| sb.cmp(sa).then(a.name.cmp(&b.name)))
+            }
+        }
+
+        // update state
+        state.visible = symbols.into_iter().map(|(_, symbol)| symbol).collect();
+        state.selected = state.selected.min(state.visible.len().saturating_sub(1));
+
+        let list_area = Rect {
+            y: area.y + 1,
+            height: area.height - 1,
+            ..area
+        };
+        state.page = (list_area.height as usize).max(1);
+        if state.selected < state.offset {
+            state.offset = state.selected;
+        } else if state.selected >= state.offset + state.page {
+            state.offset = state.selected + 1 - state.page;
+        }
+
+        // render!
+        let strings = strings();
+        let sort = match state.sort {
+            SymbolSort::Address => &strings.by_address,
+            SymbolSort::Name => &strings.by_name,
+        };
+        let filter_style = if state.editing_filter {
+            Style::default().light_yellow().underlined()
+        } else {
+            Style::default().light_yellow()
+        };
+        let header = Line::from(vec![
+            Span::styled("/", Style::default().light_blue().bold()),
+            Span::styled(state.filter.as_str(), filter_style),
+            Span::styled(
+                format!(
+                    "  {}",
+                    i18n::fill(&strings.symbol_count, &[&state.visible.len(), sort])
+                ),
+                self.theme.muted,
+            ),
+        ]);
+        buf.set_line(area.x, area.y, &header, area.width);
+
+        let rows = state
+            .visible
+            .iter()
+            .enumerate()
+            .skip(state.offset)
+            .take(state.page)
+            .map(|(index, symbol)| {
+                let row = Row::new([
+                    Line::from(Span::styled(
+                        format!("{:08X}", symbol.address),
+                        self.theme.address,
+                    )),
+                    Line::from(Span::styled(
+                        symbol.name.as_str(),
+                        Style::default().light_green(),
+                    )),
+                ]);
+
+                if index == state.selected {
+                    row.style(self.theme.selection)
+                } else {
+                    row
+                }
+            });
+
+        let constraints = [Constraint::Length(8), Constraint::Min(1)];
+        let table = Table::new(rows).widths(&constraints);
+        Widget::render(table, list_area, buf);
+    }
+}
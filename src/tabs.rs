@@ -1,3 +1,4 @@
+use crate::Theme;
 use ratatui::{
     prelude::{Buffer, Rect, *},
     widgets::{Block, Paragraph, Widget},
@@ -8,6 +9,7 @@ pub struct Tabs<'a> {
     pub titles: &'a [Cow<'a, str>],
     pub selected: usize,
     pub block: Option<Block<'a>>,
+    pub theme: Theme,
 }
 
 impl<'a> Tabs<'a> {
@@ -16,6 +18,7 @@ impl<'a> Tabs<'a> {
             titles,
             selected,
             block: None,
+            theme: Theme::default(),
         }
     }
 
@@ -26,6 +29,13 @@ impl<'a> Tabs<'a> {
         }
     }
 
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
     fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
         if let Some(block) = self.block.take() {
             let inner_area = block.inner(area);
@@ -51,9 +61,9 @@ impl<'a> Widget for Tabs<'a> {
 
         for (index, (title, area)) in self.titles.iter().zip(chunks.iter()).enumerate() {
             let style = if index == self.selected {
-                Style::default().bold().underlined().white()
+                self.theme.tab_selected
             } else {
-                Style::default().dark_gray()
+                self.theme.tab
             };
             let paragraph = Paragraph::new(title.clone())
                 .alignment(Alignment::Center)
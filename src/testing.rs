@@ -0,0 +1,94 @@
+//! Headless rendering of widgets, for golden-output tests of layouts.
+
+use ratatui::{
+    backend::TestBackend,
+    buffer::Cell,
+    prelude::{Buffer, Rect, Style},
+    widgets::{StatefulWidget, Widget},
+    Terminal,
+};
+
+/// Renders a widget to a buffer of the given size, through a terminal with a [`TestBackend`].
+pub fn render(widget: impl Widget, width: u16, height: u16) -> Buffer {
+    draw(width, height, |area, buf| widget.render(area, buf))
+}
+
+/// Renders a stateful widget to a buffer of the given size, through a terminal with a
+/// [`TestBackend`]. The state is updated like it would be in a real terminal.
+pub fn render_stateful<W>(widget: W, state: &mut W::State, width: u16, height: u16) -> Buffer
+where
+    W: StatefulWidget,
+{
+    draw(width, height, |area, buf| widget.render(area, buf, state))
+}
+
+/// Renders a widget to text. See [`to_string`].
+pub fn render_to_string(widget: impl Widget, width: u16, height: u16) -> String {
+    to_string(&render(widget, width, height))
+}
+
+/// Renders a stateful widget to text. See [`to_string`].
+pub fn render_stateful_to_string<W>(
+    widget: W,
+    state: &mut W::State,
+    width: u16,
+    height: u16,
+) -> String
+where
+    W: StatefulWidget,
+{
+    to_string(&render_stateful(widget, state, width, height))
+}
+
+/// The text of a buffer, one line per row, without styles. Trailing whitespace of each row is
+/// trimmed, so that golden outputs don't depend on invisible characters.
+pub fn to_string(buffer: &Buffer) -> String {
+    rows(buffer)
+        .map(|row| {
+            let line: String = row.iter().map(|cell| cell.symbol.as_str()).collect();
+            line.trim_end().to_owned()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The symbols and styles of a buffer, row by row.
+pub fn grid(buffer: &Buffer) -> Vec<Vec<(String, Style)>> {
+    rows(buffer)
+        .map(|row| {
+            row.iter()
+                .map(|cell| (cell.symbol.clone(), cell.style()))
+                .collect()
+        })
+        .collect()
+}
+
+fn rows(buffer: &Buffer) -> impl Iterator<Item = &[Cell]> {
+    let width = (buffer.area.width as usize).max(1);
+    buffer.content.chunks(width)
+}
+
+fn draw(width: u16, height: u16, render: impl FnOnce(Rect, &mut Buffer)) -> Buffer {
+    let mut terminal =
+        Terminal::new(TestBackend::new(width, height)).expect("test backend never fails");
+
+    let mut render = Some(render);
+    terminal
+        .draw(|frame| {
+            if let Some(render) = render.take() {
+                frame.render_widget(Draw(render), frame.size());
+            }
+        })
+        .expect("test backend never fails");
+
+    terminal.backend().buffer().clone()
+}
+
+/// A rendering function as a widget, since frames only lend their buffer to widgets.
+struct Draw<F>(F);
+
+impl<F: FnOnce(Rect, &mut Buffer)> Widget for Draw<F> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        (self.0)(area, buf);
+    }
+}
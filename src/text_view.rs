@@ -0,0 +1,287 @@
+use crate::{keymap::Action, Keymap, Theme};
+use crossterm::event::{KeyEvent, KeyEventKind};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, StatefulWidget, Widget},
+};
+
+/// Read-only text with an index of its lines, so that any line can be reached without scanning
+/// the whole text.
+#[derive(Debug, Clone, Default)]
+pub struct TextBuffer {
+    text: String,
+    /// Byte offset of the start of each line.
+    line_starts: Vec<usize>,
+}
+
+impl TextBuffer {
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .filter(|&start| start < text.len())
+            .collect();
+
+        Self { text, line_starts }
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Gets a line, without its terminator.
+    pub fn line(&self, index: usize) -> Option<&str> {
+        let start = *self.line_starts.get(index)?;
+        let end = self
+            .line_starts
+            .get(index + 1)
+            .copied()
+            .unwrap_or(self.text.len());
+
+        let line = &self.text[start..end];
+        let line = line.strip_suffix('\n').unwrap_or(line);
+        Some(line.strip_suffix('\r').unwrap_or(line))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Index of the first line after (or before, if `backwards`) `from` containing `query`,
+    /// wrapping around.
+    pub fn find(&self, query: &str, from: usize, backwards: bool) -> Option<usize> {
+        let count = self.line_count();
+        if query.is_empty() || count == 0 {
+            return None;
+        }
+
+        (1..=count)
+            .map(|step| {
+                if backwards {
+                    (from + count - step % count) % count
+                } else {
+                    (from + step) % count
+                }
+            })
+            .find(|&index| self.line(index).is_some_and(|line| line.contains(query)))
+    }
+}
+
+pub struct TextViewState {
+    /// Index of the first visible line.
+    pub top: usize,
+    /// Amount of columns scrolled to the right.
+    pub column: usize,
+    /// Text to highlight.
+    pub highlight: Option<String>,
+
+    /// Amount of lines, as of the last render.
+    len: usize,
+    page: usize,
+}
+
+impl Default for TextViewState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TextViewState {
+    pub fn new() -> Self {
+        Self {
+            top: 0,
+            column: 0,
+            highlight: None,
+            len: 0,
+            page: 1,
+        }
+    }
+
+    /// Scrolls to the next (or previous, if `backwards`) line containing the highlighted text.
+    /// Returns whether a match was found.
+    pub fn find_next(&mut self, buffer: &TextBuffer, backwards: bool) -> bool {
+        let Some(query) = &self.highlight else {
+            return false;
+        };
+
+        match buffer.find(query, self.top, backwards) {
+            Some(line) => {
+                self.top = line;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Handles a key event with the [standard keymap](Keymap::standard).
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        self.handle_key_with(key, Keymap::standard())
+    }
+
+    /// Handles a key event, translated to an action by `keymap`. Returns whether the event was
+    /// consumed.
+    pub fn handle_key_with(&mut self, key: KeyEvent, keymap: &Keymap) -> bool {
+        if key.kind == KeyEventKind::Release {
+            return false;
+        }
+
+        let last = self.len.saturating_sub(1);
+        match keymap.action(key) {
+            Some(Action::Up) => self.top = self.top.saturating_sub(1),
+            Some(Action::Down) => self.top = (self.top + 1).min(last),
+            Some(Action::Left) => self.column = self.column.saturating_sub(1),
+            Some(Action::Right) => self.column += 1,
+            Some(Action::PageUp) => self.top = self.top.saturating_sub(self.page),
+            Some(Action::PageDown) => self.top = (self.top + self.page).min(last),
+            Some(Action::Home) => {
+                self.top = 0;
+                self.column = 0;
+            }
+            Some(Action::End) => self.top = last.saturating_sub(self.page.saturating_sub(1)),
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+/// A scrollable view of large read-only text. Only the visible lines are laid out, so its cost
+/// doesn't depend on the size of the text.
+pub struct TextView<'a> {
+    buffer: &'a TextBuffer,
+
+    /// Whether to show line numbers.
+    line_numbers: bool,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> TextView<'a> {
+    pub fn new(buffer: &'a TextBuffer) -> Self {
+        Self {
+            buffer,
+            line_numbers: true,
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn line_numbers(self, line_numbers: bool) -> Self {
+        Self {
+            line_numbers,
+            ..self
+        }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+
+    /// Splits the visible part of a line into spans, highlighting occurrences of `highlight`.
+    fn line_spans(line: &'a str, column: usize, highlight: Option<&str>) -> Vec<Span<'a>> {
+        let start = line
+            .char_indices()
+            .nth(column)
+            .map_or(line.len(), |(index, _)| index);
+
+        let mut matches = Vec::new();
+        if let Some(query) = highlight.filter(|query| !query.is_empty()) {
+            matches.extend(
+                line.match_indices(query)
+                    .map(|(index, text)| (index, index + text.len())),
+            );
+        }
+
+        let mut spans = Vec::new();
+        let mut current = start;
+        for (a, b) in matches {
+            if b <= current {
+                continue;
+            }
+
+            let a = a.max(current);
+            if a > current {
+                spans.push(Span::raw(&line[current..a]));
+            }
+            spans.push(Span::styled(
+                &line[a..b],
+                Style::default().black().on_light_yellow(),
+            ));
+            current = b;
+        }
+        if current < line.len() {
+            spans.push(Span::raw(&line[current..]));
+        }
+
+        spans
+    }
+}
+
+impl<'a> StatefulWidget for TextView<'a> {
+    type State = TextViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = self.wrap_in_block(area, buf);
+
+        // update state
+        state.len = self.buffer.line_count();
+        state.page = (area.height as usize).max(1);
+        state.top = state.top.min(state.len.saturating_sub(1));
+
+        let gutter = if self.line_numbers {
+            state.len.max(1).to_string().len() as u16 + 1
+        } else {
+            0
+        };
+        if area.width <= gutter {
+            return;
+        }
+
+        let text_x = area.x + gutter;
+        let text_width = area.width - gutter;
+        for (y, index) in (area.top()..area.bottom()).zip(state.top..state.len) {
+            if self.line_numbers {
+                buf.set_stringn(
+                    area.x,
+                    y,
+                    format!("{:>width$}", index + 1, width = gutter as usize - 1),
+                    gutter as usize,
+                    self.theme.muted,
+                );
+            }
+
+            let Some(line) = self.buffer.line(index) else {
+                continue;
+            };
+            let line = Line::from(Self::line_spans(
+                line,
+                state.column,
+                state.highlight.as_deref(),
+            ));
+            buf.set_line(text_x, y, &line, text_width);
+        }
+    }
+}
@@ -0,0 +1,375 @@
+use ratatui::prelude::*;
+
+/// How byte values are colored in the memory views.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ByteGradient {
+    /// Cyan to magenta.
+    #[default]
+    Cool,
+    /// Purple to yellow, through orange.
+    Warm,
+    /// Blue to yellow, through green.
+    Viridis,
+    /// Dark gray to white.
+    Grays,
+    /// Blue to yellow, distinguishable with any kind of color blindness.
+    Cividis,
+    /// The same color for every value.
+    Solid(Color),
+    /// The default color of the terminal for every value.
+    None,
+}
+
+impl ByteGradient {
+    pub fn color(self, value: u8) -> Color {
+        let gradient = match self {
+            Self::Cool => colorous::COOL,
+            Self::Warm => colorous::WARM,
+            Self::Viridis => colorous::VIRIDIS,
+            Self::Cividis => colorous::CIVIDIS,
+            Self::Grays => {
+                // avoid the darkest grays, which are unreadable on dark backgrounds
+                let level = 96 + (value as u16 * 159 / 255) as u8;
+                return Color::Rgb(level, level, level);
+            }
+            Self::Solid(color) => return color,
+            Self::None => return Color::Reset,
+        };
+
+        let color = gradient.eval_rational(value as usize, 256);
+        Color::Rgb(color.r, color.g, color.b)
+    }
+}
+
+/// Styles shared by every widget, so that an application looks consistent. Widgets use
+/// [`Theme::default`] unless given another one through their `theme` builder method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Theme {
+    /// Colors of byte values.
+    pub gradient: ByteGradient,
+    /// Addresses, e.g. the address column of the memory view.
+    pub address: Style,
+    /// Textual representations of memory, e.g. the ASCII column of the memory view.
+    pub ascii: Style,
+    /// Informational text, e.g. the info bar of the memory view.
+    pub info: Style,
+    /// Titles inside of widgets.
+    pub title: Style,
+    /// Table headers.
+    pub header: Style,
+    /// Secondary text and separators.
+    pub muted: Style,
+    /// Borders of unfocused panes.
+    pub border: Style,
+    /// Border of the focused pane.
+    pub border_focused: Style,
+    /// Patched onto the value under the cursor.
+    pub cursor: Style,
+    /// Patched onto values equal to the one under the cursor, when they are highlighted.
+    pub occurrence: Style,
+    /// Selected rows of lists and tables.
+    pub selection: Style,
+    /// The current position of execution, e.g. the program counter.
+    pub current: Style,
+    /// Breakpoint markers.
+    pub breakpoint: Style,
+    /// Values modified but not committed yet.
+    pub modified: Style,
+    /// Patched onto values that might be outdated, e.g. while they are fetched again.
+    pub stale: Style,
+    /// Things that need attention, like differences or invalid entries.
+    pub alert: Style,
+    /// Titles of unselected tabs.
+    pub tab: Style,
+    /// Title of the selected tab.
+    pub tab_selected: Style,
+    /// Background of the status bar.
+    pub status_bar: Style,
+    /// Highlighted segment of the status bar, e.g. the current mode.
+    pub status_accent: Style,
+    /// Read-only memory, e.g. in the region bar.
+    pub rom: Style,
+    /// Writable memory.
+    pub ram: Style,
+    /// Memory-mapped peripherals.
+    pub mmio: Style,
+    /// Address ranges without memory.
+    pub unmapped: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            gradient: ByteGradient::Cool,
+            address: Style::default().light_magenta(),
+            ascii: Style::default().light_blue(),
+            info: Style::default().light_green(),
+            title: Style::default().light_blue().bold(),
+            header: Style::default().white().bold(),
+            muted: Style::default().dark_gray(),
+            border: Style::default(),
+            border_focused: Style::default().light_blue().bold(),
+            cursor: Style::default().bold().on_light_red(),
+            occurrence: Style::default().on_blue(),
+            selection: Style::default().bold().on_dark_gray(),
+            current: Style::default().on_dark_gray(),
+            breakpoint: Style::default().light_red(),
+            modified: Style::default().light_yellow().bold().italic(),
+            stale: Style::default().dim(),
+            alert: Style::default().black().on_light_red().bold(),
+            tab: Style::default().dark_gray(),
+            tab_selected: Style::default().white().bold().underlined(),
+            status_bar: Style::default().on_dark_gray(),
+            status_accent: Style::default().black().on_light_blue().bold(),
+            rom: Style::default().light_blue(),
+            ram: Style::default().light_green(),
+            mmio: Style::default().light_magenta(),
+            unmapped: Style::default().dark_gray(),
+        }
+    }
+}
+
+impl Theme {
+    /// For terminals with a light background.
+    pub fn light() -> Self {
+        Self {
+            gradient: ByteGradient::Viridis,
+            address: Style::default().magenta(),
+            ascii: Style::default().blue(),
+            info: Style::default().green(),
+            title: Style::default().blue().bold(),
+            header: Style::default().black().bold(),
+            muted: Style::default().gray(),
+            border: Style::default(),
+            border_focused: Style::default().blue().bold(),
+            cursor: Style::default().white().bold().on_red(),
+            occurrence: Style::default().on_light_cyan(),
+            selection: Style::default().bold().on_gray(),
+            current: Style::default().on_gray(),
+            breakpoint: Style::default().red(),
+            modified: Style::default()
+                .fg(Color::Rgb(0xB5, 0x89, 0x00))
+                .bold()
+                .italic(),
+            stale: Style::default().dim(),
+            alert: Style::default().white().on_red().bold(),
+            tab: Style::default().gray(),
+            tab_selected: Style::default().black().bold().underlined(),
+            status_bar: Style::default().black().on_gray(),
+            status_accent: Style::default().white().on_blue().bold(),
+            rom: Style::default().blue(),
+            ram: Style::default().green(),
+            mmio: Style::default().magenta(),
+            unmapped: Style::default().gray(),
+        }
+    }
+
+    /// Based on the Solarized dark palette.
+    pub fn solarized() -> Self {
+        const BASE02: Color = Color::Rgb(0x07, 0x36, 0x42);
+        const BASE01: Color = Color::Rgb(0x58, 0x6E, 0x75);
+        const BASE1: Color = Color::Rgb(0x93, 0xA1, 0xA1);
+        const YELLOW: Color = Color::Rgb(0xB5, 0x89, 0x00);
+        const ORANGE: Color = Color::Rgb(0xCB, 0x4B, 0x16);
+        const RED: Color = Color::Rgb(0xDC, 0x32, 0x2F);
+        const MAGENTA: Color = Color::Rgb(0xD3, 0x36, 0x82);
+        const BLUE: Color = Color::Rgb(0x26, 0x8B, 0xD2);
+        const CYAN: Color = Color::Rgb(0x2A, 0xA1, 0x98);
+        const GREEN: Color = Color::Rgb(0x85, 0x99, 0x00);
+
+        Self {
+            gradient: ByteGradient::Warm,
+            address: Style::default().fg(MAGENTA),
+            ascii: Style::default().fg(CYAN),
+            info: Style::default().fg(GREEN),
+            title: Style::default().fg(BLUE).bold(),
+            header: Style::default().fg(BASE1).bold(),
+            muted: Style::default().fg(BASE01),
+            border: Style::default().fg(BASE01),
+            border_focused: Style::default().fg(BLUE),
+            cursor: Style::default().bold().bg(ORANGE),
+            occurrence: Style::default().fg(BASE02).bg(CYAN),
+            selection: Style::default().bold().bg(BASE02),
+            current: Style::default().bg(BASE02),
+            breakpoint: Style::default().fg(RED),
+            modified: Style::default().fg(YELLOW).bold().italic(),
+            stale: Style::default().dim(),
+            alert: Style::default().fg(BASE02).bg(RED).bold(),
+            tab: Style::default().fg(BASE01),
+            tab_selected: Style::default().fg(BASE1).bold().underlined(),
+            status_bar: Style::default().bg(BASE02),
+            status_accent: Style::default().fg(BASE02).bg(BLUE).bold(),
+            rom: Style::default().fg(BLUE),
+            ram: Style::default().fg(GREEN),
+            mmio: Style::default().fg(MAGENTA),
+            unmapped: Style::default().fg(BASE01),
+        }
+    }
+
+    /// Shades of gray only.
+    pub fn monochrome() -> Self {
+        Self {
+            gradient: ByteGradient::Grays,
+            address: Style::default().gray(),
+            ascii: Style::default().white(),
+            info: Style::default().white(),
+            title: Style::default().white().bold(),
+            header: Style::default().white().bold().underlined(),
+            muted: Style::default().dark_gray(),
+            border: Style::default().dark_gray(),
+            border_focused: Style::default().white().bold(),
+            cursor: Style::default().bold().reversed(),
+            occurrence: Style::default().bold().on_gray(),
+            selection: Style::default().bold().on_dark_gray(),
+            current: Style::default().on_dark_gray(),
+            breakpoint: Style::default().white().bold(),
+            modified: Style::default().white().bold().italic(),
+            stale: Style::default().dim(),
+            alert: Style::default().bold().reversed(),
+            tab: Style::default().dark_gray(),
+            tab_selected: Style::default().white().bold().underlined(),
+            status_bar: Style::default().on_dark_gray(),
+            status_accent: Style::default().bold().reversed(),
+            rom: Style::default().gray(),
+            ram: Style::default().white(),
+            mmio: Style::default().dark_gray(),
+            unmapped: Style::default().dark_gray(),
+        }
+    }
+
+    /// Safe for red-green color blindness: blues, oranges and yellows from the Okabe-Ito
+    /// palette, and no meaning carried by red versus green.
+    pub fn deuteranopia() -> Self {
+        const ORANGE: Color = Color::Rgb(0xE6, 0x9F, 0x00);
+        const SKY_BLUE: Color = Color::Rgb(0x56, 0xB4, 0xE9);
+        const YELLOW: Color = Color::Rgb(0xF0, 0xE4, 0x42);
+        const BLUE: Color = Color::Rgb(0x00, 0x72, 0xB2);
+        const VERMILLION: Color = Color::Rgb(0xD5, 0x5E, 0x00);
+
+        Self {
+            gradient: ByteGradient::Cividis,
+            address: Style::default().fg(ORANGE),
+            ascii: Style::default().fg(SKY_BLUE),
+            info: Style::default().fg(SKY_BLUE),
+            title: Style::default().fg(SKY_BLUE).bold(),
+            header: Style::default().white().bold(),
+            muted: Style::default().dark_gray(),
+            border: Style::default(),
+            border_focused: Style::default().fg(SKY_BLUE).bold(),
+            cursor: Style::default().black().bold().bg(YELLOW),
+            occurrence: Style::default().black().bg(SKY_BLUE),
+            selection: Style::default().bold().on_dark_gray(),
+            current: Style::default().white().bg(BLUE),
+            breakpoint: Style::default().fg(VERMILLION).bold(),
+            modified: Style::default().fg(YELLOW).bold().italic(),
+            stale: Style::default().dim(),
+            alert: Style::default().black().bg(ORANGE).bold(),
+            tab: Style::default().dark_gray(),
+            tab_selected: Style::default().white().bold().underlined(),
+            status_bar: Style::default().on_dark_gray(),
+            status_accent: Style::default().black().bg(SKY_BLUE).bold(),
+            rom: Style::default().fg(SKY_BLUE),
+            ram: Style::default().fg(YELLOW),
+            mmio: Style::default().fg(VERMILLION),
+            unmapped: Style::default().dark_gray(),
+        }
+    }
+
+    /// No colors at all, only bold, underlined and reversed text, for terminals with few or no
+    /// colors. Dim and italic text are left out, as many terminals render them poorly or not at
+    /// all.
+    pub fn attributes_only() -> Self {
+        Self {
+            gradient: ByteGradient::None,
+            address: Style::default(),
+            ascii: Style::default(),
+            info: Style::default(),
+            title: Style::default().bold(),
+            header: Style::default().bold().underlined(),
+            muted: Style::default(),
+            border: Style::default(),
+            border_focused: Style::default().bold(),
+            cursor: Style::default().bold().reversed(),
+            occurrence: Style::default().bold().underlined(),
+            selection: Style::default().reversed(),
+            current: Style::default().underlined(),
+            breakpoint: Style::default().bold(),
+            modified: Style::default().bold().underlined(),
+            stale: Style::default().underlined(),
+            alert: Style::default().bold().reversed(),
+            tab: Style::default(),
+            tab_selected: Style::default().bold().underlined(),
+            status_bar: Style::default().reversed(),
+            status_accent: Style::default().bold(),
+            rom: Style::default().bold(),
+            ram: Style::default(),
+            // drawn as blank cells, as the bar is made of full blocks
+            mmio: Style::default().reversed(),
+            unmapped: Style::default(),
+        }
+    }
+
+    /// Names of the built-in themes, as accepted by [`Theme::preset`].
+    pub const PRESETS: &'static [&'static str] = &[
+        "default",
+        "light",
+        "solarized",
+        "monochrome",
+        "deuteranopia",
+        "attributes-only",
+    ];
+
+    /// A built-in theme, by name. `dark` is accepted as an alias of `default`.
+    pub fn preset(name: &str) -> Option<Self> {
+        Some(match name {
+            "default" | "dark" => Self::default(),
+            "light" => Self::light(),
+            "solarized" => Self::solarized(),
+            "monochrome" => Self::monochrome(),
+            "deuteranopia" => Self::deuteranopia(),
+            "attributes-only" => Self::attributes_only(),
+            _ => return None,
+        })
+    }
+
+    /// Style of a byte value, from the gradient.
+    pub fn byte(&self, value: u8) -> Style {
+        Style::default().fg(self.gradient.color(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_are_distinct() {
+        let themes: Vec<_> = Theme::PRESETS
+            .iter()
+            .map(|name| Theme::preset(name).unwrap())
+            .collect();
+
+        for (index, theme) in themes.iter().enumerate() {
+            assert!(
+                !themes[..index].contains(theme),
+                "{} duplicates another preset",
+                Theme::PRESETS[index]
+            );
+        }
+    }
+
+    #[test]
+    fn region_kinds_are_distinct() {
+        // unmapped memory is drawn with its own symbol, so only the mapped kinds need to differ
+        for name in Theme::PRESETS {
+            let theme = Theme::preset(name).unwrap();
+            let styles = [theme.rom, theme.ram, theme.mmio];
+            for (index, style) in styles.iter().enumerate() {
+                assert!(!styles[..index].contains(style), "{name}");
+            }
+        }
+    }
+}
@@ -0,0 +1,461 @@
+#[cfg(feature = "graphics")]
+use crate::graphics::{GraphicsProtocol, Image};
+use crate::{
+    i18n::{self, strings},
+    keymap::Action,
+    memory_view::MemoryProvider,
+    Address, Keymap, Theme,
+};
+use crossterm::event::{KeyEvent, KeyEventKind};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, StatefulWidget, Widget},
+};
+
+/// How pixels are drawn into terminal cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PixelMode {
+    /// Each cell holds two vertically stacked pixels, in full color.
+    #[default]
+    HalfBlock,
+    /// Each cell holds a 2x4 braille dot matrix. Pixels with index 0 are off, and the cell takes
+    /// the color of its highest index.
+    Braille,
+}
+
+/// Layout of the tile data in memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TileFormat {
+    /// Tile width, in pixels.
+    pub width: u16,
+    /// Tile height, in pixels.
+    pub height: u16,
+    /// Bits per pixel. Must be 1, 2, 4 or 8.
+    pub bits_per_pixel: u8,
+    /// Whether the first pixel of a byte is in its most significant bits. Otherwise, pixels are
+    /// packed starting from the least significant bits.
+    pub msb_first: bool,
+}
+
+impl Default for TileFormat {
+    fn default() -> Self {
+        Self {
+            width: 8,
+            height: 8,
+            bits_per_pixel: 4,
+            msb_first: false,
+        }
+    }
+}
+
+impl TileFormat {
+    /// Size of a tile, in bytes.
+    pub fn tile_size(&self) -> usize {
+        (self.width as usize * self.height as usize * self.bits_per_pixel as usize).div_ceil(8)
+    }
+
+    /// Decodes the pixel at (`x`, `y`) of a tile. Returns `None` if its data isn't available, or
+    /// if the format has an unsupported depth.
+    fn pixel(&self, tile: &[Option<u8>], x: u16, y: u16) -> Option<u8> {
+        let bpp = self.bits_per_pixel as usize;
+        // other depths would spread pixels over several bytes
+        if !matches!(bpp, 1 | 2 | 4 | 8) {
+            return None;
+        }
+
+        let bit = (y as usize * self.width as usize + x as usize) * bpp;
+        let byte = tile.get(bit / 8).copied().flatten()?;
+
+        let shift = if self.msb_first {
+            8 - bpp - bit % 8
+        } else {
+            bit % 8
+        };
+        let mask = ((1u16 << bpp) - 1) as u8;
+
+        Some((byte >> shift) & mask)
+    }
+}
+
+pub struct TileViewState {
+    /// Address of the first tile.
+    pub base: Address,
+    /// Index of the tile under the cursor.
+    pub cursor: usize,
+
+    /// Index of the first visible row of tiles.
+    offset: usize,
+    tiles_per_row: usize,
+    visible_rows: usize,
+    tile_size: usize,
+    buffer: Vec<Option<u8>>,
+    /// Image of the tiles to write to the terminal, and the last one that was.
+    #[cfg(feature = "graphics")]
+    pending_image: Option<(Rect, Image)>,
+    #[cfg(feature = "graphics")]
+    last_image: Option<(Rect, Image)>,
+}
+
+impl TileViewState {
+    pub fn new(base: Address) -> Self {
+        Self {
+            base,
+            cursor: 0,
+            offset: 0,
+            tiles_per_row: 1,
+            visible_rows: 1,
+            tile_size: 0,
+            buffer: Vec::new(),
+            #[cfg(feature = "graphics")]
+            pending_image: None,
+            #[cfg(feature = "graphics")]
+            last_image: None,
+        }
+    }
+
+    /// The image of the tiles and the area it covers, when drawn with a
+    /// [graphics protocol](TileView::graphics) and it changed since the last call. It should be
+    /// [written](crate::graphics::write_image) to the terminal once the frame is drawn.
+    #[cfg(feature = "graphics")]
+    pub fn take_image(&mut self) -> Option<(Rect, Image)> {
+        self.pending_image.take()
+    }
+
+    /// Makes the next render produce an [image](Self::take_image) even if it didn't change, e.g.
+    /// after something was drawn over it.
+    #[cfg(feature = "graphics")]
+    pub fn redraw_image(&mut self) {
+        self.last_image = None;
+    }
+
+    /// Address of the tile under the cursor, as of the last render.
+    pub fn selected_address(&self) -> Address {
+        self.base
+            .wrapping_add((self.cursor * self.tile_size) as Address)
+    }
+
+    /// Handles a key event with the [standard keymap](Keymap::standard).
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        self.handle_key_with(key, Keymap::standard())
+    }
+
+    /// Handles a key event, translated to an action by `keymap`. Returns whether the event was
+    /// consumed.
+    pub fn handle_key_with(&mut self, key: KeyEvent, keymap: &Keymap) -> bool {
+        if key.kind == KeyEventKind::Release {
+            return false;
+        }
+
+        let row = self.tiles_per_row;
+        match keymap.action(key) {
+            Some(Action::Left) => self.cursor = self.cursor.saturating_sub(1),
+            Some(Action::Right) => self.cursor += 1,
+            Some(Action::Up) => self.cursor = self.cursor.saturating_sub(row),
+            Some(Action::Down) => self.cursor += row,
+            Some(Action::PageUp) => {
+                self.cursor = self.cursor.saturating_sub(row * self.visible_rows)
+            }
+            Some(Action::PageDown) => self.cursor += row * self.visible_rows,
+            Some(Action::Home) => self.cursor = 0,
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+/// A memory range interpreted as tiled graphics.
+pub struct TileView<'a> {
+    /// The memory provider.
+    memory_provider: &'a dyn MemoryProvider,
+
+    /// Maps pixel values to colors.
+    palette: &'a dyn Fn(u8) -> Color,
+
+    format: TileFormat,
+    mode: PixelMode,
+
+    /// Protocol to draw the tiles as an image with, rather than with characters.
+    #[cfg(feature = "graphics")]
+    graphics: Option<GraphicsProtocol>,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> TileView<'a> {
+    pub fn new(memory_provider: &'a dyn MemoryProvider, palette: &'a dyn Fn(u8) -> Color) -> Self {
+        Self {
+            memory_provider,
+            palette,
+            format: TileFormat::default(),
+            mode: PixelMode::default(),
+            #[cfg(feature = "graphics")]
+            graphics: None,
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn format(self, format: TileFormat) -> Self {
+        Self { format, ..self }
+    }
+
+    pub fn mode(self, mode: PixelMode) -> Self {
+        Self { mode, ..self }
+    }
+
+    /// Draws the tiles as a real image with `protocol`, usually
+    /// [detected](GraphicsProtocol::detect), rather than with characters. Each cell then holds
+    /// 2x4 pixels, and the image is left to the application to
+    /// [write](TileViewState::take_image). With `None`, the [mode](Self::mode) is used instead.
+    #[cfg(feature = "graphics")]
+    pub fn graphics(self, graphics: Option<GraphicsProtocol>) -> Self {
+        Self { graphics, ..self }
+    }
+
+    #[cfg(feature = "graphics")]
+    fn uses_graphics(&self) -> bool {
+        self.graphics.is_some()
+    }
+
+    #[cfg(not(feature = "graphics"))]
+    fn uses_graphics(&self) -> bool {
+        false
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+
+    /// Size of a tile, in cells.
+    fn tile_cells(&self) -> (u16, u16) {
+        match self.mode {
+            // images hold as many pixels per cell as braille
+            _ if self.uses_graphics() => (
+                self.format.width.div_ceil(2),
+                self.format.height.div_ceil(4),
+            ),
+            PixelMode::HalfBlock => (self.format.width, self.format.height.div_ceil(2)),
+            PixelMode::Braille => (
+                self.format.width.div_ceil(2),
+                self.format.height.div_ceil(4),
+            ),
+        }
+    }
+
+    fn render_tile(&self, tile: &[Option<u8>], x: u16, y: u16, area: Rect, buf: &mut Buffer) {
+        let (cells_x, cells_y) = self.tile_cells();
+        let format = &self.format;
+
+        for cy in 0..cells_y {
+            for cx in 0..cells_x {
+                let (px, py) = (x + cx, y + cy);
+                if px >= area.right() || py >= area.bottom() {
+                    continue;
+                }
+
+                let cell = buf.get_mut(px, py);
+                match self.mode {
+                    PixelMode::HalfBlock => {
+                        let top = format.pixel(tile, cx, cy * 2);
+                        let bottom = (cy * 2 + 1 < format.height)
+                            .then(|| format.pixel(tile, cx, cy * 2 + 1))
+                            .flatten();
+
+                        match (top, bottom) {
+                            (None, None) => {
+                                cell.set_symbol("◦").set_style(self.theme.muted);
+                            }
+                            _ => {
+                                let color = |pixel: Option<u8>| {
+                                    pixel.map_or(Color::Reset, |pixel| (self.palette)(pixel))
+                                };
+                                cell.set_symbol("▀")
+                                    .set_fg(color(top))
+                                    .set_bg(color(bottom));
+                            }
+                        }
+                    }
+                    PixelMode::Braille => {
+                        // dot bit for each (x, y) position inside a braille cell
+                        const DOTS: [[u32; 2]; 4] =
+                            [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+                        let mut dots = 0;
+                        let mut highest = None;
+                        for (dy, row) in DOTS.iter().enumerate() {
+                            for (dx, dot) in row.iter().enumerate() {
+                                let (tx, ty) = (cx * 2 + dx as u16, cy * 4 + dy as u16);
+                                if tx >= format.width || ty >= format.height {
+                                    continue;
+                                }
+
+                                if let Some(pixel) = format.pixel(tile, tx, ty) {
+                                    if pixel != 0 {
+                                        dots |= dot;
+                                        highest = highest.max(Some(pixel));
+                                    }
+                                }
+                            }
+                        }
+
+                        let symbol = char::from_u32(0x2800 + dots).unwrap_or(' ');
+                        cell.set_char(symbol)
+                            .set_fg(highest.map_or(Color::Reset, |pixel| (self.palette)(pixel)));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Draws the tiles of the grid into an image, with 2x4 pixels per cell. Pixels whose data
+    /// isn't available take the muted color of the theme, and the tile under the cursor is
+    /// framed.
+    #[cfg(feature = "graphics")]
+    fn render_image(&self, grid: Rect, state: &TileViewState) -> Image {
+        let (cells_x, cells_y) = self.tile_cells();
+        let (stride_x, stride_y) = (cells_x + 1, cells_y.max(1));
+        let format = &self.format;
+        let first_tile = state.offset * state.tiles_per_row;
+
+        let unavailable = self.theme.muted.fg.unwrap_or(Color::DarkGray);
+        let marker = self.theme.border_focused.fg.unwrap_or(Color::LightYellow);
+        let mut image = Image::new(grid.width * 2, grid.height * 4);
+        for (index, tile) in state.buffer.chunks(state.tile_size.max(1)).enumerate() {
+            let (column, row) = (index % state.tiles_per_row, index / state.tiles_per_row);
+            let x = (1 + column as u16 * stride_x) * 2;
+            let y = row as u16 * stride_y * 4;
+
+            for ty in 0..format.height {
+                for tx in 0..format.width {
+                    let color = format
+                        .pixel(tile, tx, ty)
+                        .map_or(unavailable, |pixel| (self.palette)(pixel));
+                    image.set(x + tx, y + ty, color);
+                }
+            }
+
+            if first_tile + index == state.cursor {
+                for ty in 0..cells_y * 4 {
+                    image.set(x - 1, y + ty, marker);
+                    image.set(x + cells_x * 2, y + ty, marker);
+                }
+            }
+        }
+
+        image
+    }
+}
+
+impl<'a> StatefulWidget for TileView<'a> {
+    type State = TileViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = self.wrap_in_block(area, buf);
+        if area.height < 2 || area.width < 2 {
+            return;
+        }
+
+        // one line at the bottom for the info, one column between tiles for the cursor
+        let grid = Rect {
+            height: area.height - 1,
+            ..area
+        };
+        let (cells_x, cells_y) = self.tile_cells();
+        let (stride_x, stride_y) = (cells_x + 1, cells_y.max(1));
+
+        // update state
+        state.tile_size = self.format.tile_size();
+        state.tiles_per_row = ((grid.width / stride_x) as usize).max(1);
+        state.visible_rows = ((grid.height / stride_y) as usize).max(1);
+
+        let cursor_row = state.cursor / state.tiles_per_row;
+        if cursor_row < state.offset {
+            state.offset = cursor_row;
+        } else if cursor_row >= state.offset + state.visible_rows {
+            state.offset = cursor_row + 1 - state.visible_rows;
+        }
+
+        let first_tile = state.offset * state.tiles_per_row;
+        let tile_count = state.visible_rows * state.tiles_per_row;
+        let start = state
+            .base
+            .wrapping_add((first_tile * state.tile_size) as Address);
+
+        state.buffer.clear();
+        state.buffer.resize(tile_count * state.tile_size, None);
+        self.memory_provider.read_to_buf(start, &mut state.buffer);
+
+        // render!
+        #[cfg(feature = "graphics")]
+        if self.graphics.is_some() {
+            let image = (grid, self.render_image(grid, state));
+            if state.last_image.as_ref() != Some(&image) {
+                state.pending_image = Some(image.clone());
+                state.last_image = Some(image);
+            }
+        }
+
+        // images are written by the application instead
+        if !self.uses_graphics() {
+            for (index, tile) in state.buffer.chunks(state.tile_size.max(1)).enumerate() {
+                let (column, row) = (index % state.tiles_per_row, index / state.tiles_per_row);
+                let x = grid.x + 1 + column as u16 * stride_x;
+                let y = grid.y + row as u16 * stride_y;
+
+                self.render_tile(tile, x, y, grid, buf);
+
+                if first_tile + index == state.cursor {
+                    let marker = self.theme.border_focused;
+                    for cy in y..(y + cells_y).min(grid.bottom()) {
+                        buf.set_string(x - 1, cy, "▐", marker);
+                        if x + cells_x < grid.right() {
+                            buf.set_string(x + cells_x, cy, "▌", marker);
+                        }
+                    }
+                }
+            }
+        }
+
+        let info = i18n::fill(
+            &strings().tile_info,
+            &[
+                &state.cursor,
+                &format!("{:08X}", state.selected_address()),
+                &self.format.width,
+                &self.format.height,
+                &self.format.bits_per_pixel,
+            ],
+        );
+        buf.set_stringn(
+            area.x,
+            area.bottom() - 1,
+            info,
+            area.width as usize,
+            self.theme.info,
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use crate::{i18n::strings, Theme};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, Row, Table, Widget},
+};
+use std::borrow::Cow;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timer {
+    pub name: Cow<'static, str>,
+    /// Current value of the counter.
+    pub count: u32,
+    /// Value the counter is reloaded with once it overflows.
+    pub reload: u32,
+    /// Value at which the counter overflows.
+    pub overflow: u32,
+    /// Amount of input clocks per counter increment.
+    pub prescaler: u32,
+    pub enabled: bool,
+    /// Whether the timer's interrupt is pending.
+    pub irq_pending: bool,
+}
+
+impl Timer {
+    /// Progress from the reload value toward overflow, in the `0.0..=1.0` range.
+    pub fn progress(&self) -> f64 {
+        let span = self.overflow.saturating_sub(self.reload);
+        if span == 0 {
+            return 0.0;
+        }
+
+        let elapsed = self.count.saturating_sub(self.reload).min(span);
+        elapsed as f64 / span as f64
+    }
+}
+
+pub trait TimerProvider {
+    /// Returns the current state of the hardware timers.
+    fn timers(&self) -> Vec<Timer>;
+}
+
+impl TimerProvider for [Timer] {
+    fn timers(&self) -> Vec<Timer> {
+        self.to_vec()
+    }
+}
+
+impl TimerProvider for Vec<Timer> {
+    fn timers(&self) -> Vec<Timer> {
+        self.clone()
+    }
+}
+
+/// Hardware timers with a progress bar toward overflow each. Timers are re-read on every render.
+pub struct TimersView<'a> {
+    timer_provider: &'a dyn TimerProvider,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> TimersView<'a> {
+    pub fn new(timer_provider: &'a dyn TimerProvider) -> Self {
+        Self {
+            timer_provider,
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+
+    fn progress_bar(&self, timer: &Timer, width: u16) -> Line<'static> {
+        let width = width as usize;
+        let filled = (timer.progress() * width as f64).round() as usize;
+        let style = if !timer.enabled {
+            self.theme.muted
+        } else if timer.irq_pending {
+            self.theme.breakpoint
+        } else {
+            self.theme.info
+        };
+
+        Line::from(vec![
+            Span::styled("█".repeat(filled), style),
+            Span::styled("░".repeat(width - filled), self.theme.muted),
+        ])
+    }
+}
+
+impl<'a> Widget for TimersView<'a> {
+    fn render(mut self, area: Rect, buf: &mut Buffer) {
+        let area = self.wrap_in_block(area, buf);
+        if area.height == 0 {
+            return;
+        }
+
+        let timers = self.timer_provider.timers();
+        let name_width = timers
+            .iter()
+            .map(|timer| timer.name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(4) as u16;
+
+        let constraints = [
+            Constraint::Length(name_width),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(6),
+            Constraint::Length(3),
+            Constraint::Min(1),
+        ];
+        // the table inserts a column of spacing between each of them
+        let fixed: u16 = name_width + 8 + 8 + 6 + 3 + constraints.len() as u16 - 1;
+        let bar_width = area.width.saturating_sub(fixed);
+
+        let strings = strings();
+        let header = Row::new([
+            &*strings.name,
+            &*strings.count,
+            &*strings.reload,
+            &*strings.prescaler,
+            &*strings.irq,
+            &*strings.progress,
+        ])
+        .style(self.theme.header);
+        let rows = timers.iter().map(|timer| {
+            let name_style = if timer.enabled {
+                Style::default().light_blue()
+            } else {
+                self.theme.muted
+            };
+            let irq = if timer.irq_pending {
+                Span::styled("!", self.theme.alert)
+            } else {
+                Span::styled("-", self.theme.muted)
+            };
+
+            Row::new([
+                Line::from(Span::styled(timer.name.clone(), name_style)),
+                Line::from(Span::styled(
+                    format!("{:08X}", timer.count),
+                    Style::default().light_green(),
+                )),
+                Line::from(Span::styled(
+                    format!("{:08X}", timer.reload),
+                    Style::default().light_magenta(),
+                )),
+                Line::from(Span::styled(
+                    format!("/{}", timer.prescaler),
+                    Style::default().light_yellow(),
+                )),
+                Line::from(irq),
+                self.progress_bar(timer, bar_width),
+            ])
+        });
+
+        let table = Table::new(rows).header(header).widths(&constraints);
+        Widget::render(table, area, buf);
+    }
+}
@@ -0,0 +1,257 @@
+use crate::{keymap::Action, Address, Keymap, Theme};
+use crossterm::event::{KeyEvent, KeyEventKind};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, Row, StatefulWidget, Table, Widget},
+};
+use std::{borrow::Cow, collections::VecDeque};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEntry {
+    /// Address of the executed instruction.
+    pub address: Address,
+    /// Timestamp or cycle count at which the instruction was executed.
+    pub cycle: u64,
+    /// Optional event associated with the entry (interrupt, exception...).
+    pub event: Option<Cow<'static, str>>,
+}
+
+/// A ring buffer of executed instructions. Once full, the oldest entries are dropped.
+#[derive(Debug, Clone)]
+pub struct TraceBuffer {
+    entries: VecDeque<TraceEntry>,
+    capacity: usize,
+    /// Amount of entries ever pushed.
+    pushed: u64,
+}
+
+impl TraceBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+            pushed: 0,
+        }
+    }
+
+    pub fn push(&mut self, entry: TraceEntry) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+
+        self.entries.push_back(entry);
+        self.pushed += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &TraceEntry> {
+        self.entries.iter()
+    }
+
+    /// Sequence number of the oldest entry still in the buffer.
+    fn first_sequence(&self) -> u64 {
+        self.pushed - self.entries.len() as u64
+    }
+
+    /// Gets an entry by its sequence number, i.e. its index among every entry ever pushed.
+    fn get_by_sequence(&self, sequence: u64) -> Option<&TraceEntry> {
+        let index = sequence.checked_sub(self.first_sequence())?;
+        self.entries.get(index as usize)
+    }
+}
+
+pub struct TraceViewState {
+    /// Whether the view sticks to the most recent entry.
+    pub follow_tail: bool,
+
+    /// Sequence number of the selected entry.
+    selected: u64,
+    /// Range of valid sequence numbers, as of the last render.
+    first: u64,
+    last: u64,
+    page: u64,
+    selected_address: Option<Address>,
+}
+
+impl Default for TraceViewState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TraceViewState {
+    pub fn new() -> Self {
+        Self {
+            follow_tail: true,
+            selected: 0,
+            first: 0,
+            last: 0,
+            page: 1,
+            selected_address: None,
+        }
+    }
+
+    /// Address of the selected entry, as of the last render.
+    pub fn selected_address(&self) -> Option<Address> {
+        self.selected_address
+    }
+
+    fn select(&mut self, selected: u64) {
+        self.selected = selected.clamp(self.first, self.last);
+        self.follow_tail = false;
+    }
+
+    /// Handles a key event with the [standard keymap](Keymap::standard).
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Address> {
+        self.handle_key_with(key, Keymap::standard())
+    }
+
+    /// Handles a key event, translated to an action by `keymap`. Returns the address of the
+    /// selected entry when it is activated, so that other views can be retargeted to it.
+    pub fn handle_key_with(&mut self, key: KeyEvent, keymap: &Keymap) -> Option<Address> {
+        if key.kind == KeyEventKind::Release {
+            return None;
+        }
+
+        match keymap.action(key) {
+            Some(Action::Up) => self.select(self.selected.saturating_sub(1)),
+            Some(Action::Down) => self.select(self.selected + 1),
+            Some(Action::PageUp) => self.select(self.selected.saturating_sub(self.page)),
+            Some(Action::PageDown) => self.select(self.selected + self.page),
+            Some(Action::Home) => self.select(self.first),
+            Some(Action::End | Action::Follow) => self.follow_tail = true,
+            Some(Action::Activate) => return self.selected_address,
+            _ => (),
+        }
+
+        None
+    }
+}
+
+/// The most recent entries of a [`TraceBuffer`], oldest at the top.
+pub struct TraceView<'a> {
+    /// The trace being displayed.
+    trace: &'a TraceBuffer,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> TraceView<'a> {
+    pub fn new(trace: &'a TraceBuffer) -> Self {
+        Self {
+            trace,
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+}
+
+impl<'a> StatefulWidget for TraceView<'a> {
+    type State = TraceViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = self.wrap_in_block(area, buf);
+
+        // update state
+        state.first = self.trace.first_sequence();
+        state.last = self.trace.pushed.saturating_sub(1).max(state.first);
+        state.page = (area.height as u64).max(1);
+        state.selected = if state.follow_tail {
+            state.last
+        } else {
+            state.selected.clamp(state.first, state.last)
+        };
+        state.selected_address = self
+            .trace
+            .get_by_sequence(state.selected)
+            .map(|entry| entry.address);
+
+        // keep the selected entry at the bottom when following, centered otherwise
+        let end = if state.follow_tail {
+            state.last + 1
+        } else {
+            (state.selected + state.page / 2 + 1).clamp(
+                (state.first + state.page).min(state.last + 1),
+                state.last + 1,
+            )
+        };
+        let start = end.saturating_sub(state.page).max(state.first);
+
+        let rows = (start..end).filter_map(|sequence| {
+            let entry = self.trace.get_by_sequence(sequence)?;
+            let event = entry
+                .event
+                .clone()
+                .map(|event| Span::styled(event, Style::default().light_yellow()))
+                .unwrap_or_default();
+
+            let row = Row::new([
+                Line::from(Span::styled(
+                    format!("{:>12}", entry.cycle),
+                    self.theme.muted,
+                )),
+                Line::from(Span::styled(
+                    format!("{:08X}", entry.address),
+                    self.theme.address,
+                )),
+                Line::from(event),
+            ]);
+
+            Some(if sequence == state.selected && !state.follow_tail {
+                row.style(self.theme.selection)
+            } else {
+                row
+            })
+        });
+
+        let constraints = [
+            Constraint::Length(12),
+            Constraint::Length(8),
+            Constraint::Min(0),
+        ];
+        let table = Table::new(rows).widths(&constraints);
+        Widget::render(table, area, buf);
+    }
+}
@@ -0,0 +1,285 @@
+use crate::{
+    i18n::strings,
+    keymap::Action,
+    memory_view::{MemoryProvider, Region},
+    symbol::SymbolProvider,
+    Address, Keymap, Theme,
+};
+use crossterm::event::{KeyEvent, KeyEventKind};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, Row, StatefulWidget, Table, Widget},
+};
+use std::borrow::Cow;
+
+/// Size of a vector table entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EntrySize {
+    U16,
+    #[default]
+    U32,
+}
+
+impl EntrySize {
+    pub fn size(self) -> usize {
+        match self {
+            Self::U16 => 2,
+            Self::U32 => 4,
+        }
+    }
+}
+
+pub struct VectorTableViewState {
+    selected: usize,
+    offset: usize,
+    page: usize,
+    len: usize,
+    selected_target: Option<Address>,
+}
+
+impl Default for VectorTableViewState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VectorTableViewState {
+    pub fn new() -> Self {
+        Self {
+            selected: 0,
+            offset: 0,
+            page: 1,
+            len: 0,
+            selected_target: None,
+        }
+    }
+
+    /// Target of the selected vector, as of the last render.
+    pub fn selected_target(&self) -> Option<Address> {
+        self.selected_target
+    }
+
+    /// Handles a key event with the [standard keymap](Keymap::standard).
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Address> {
+        self.handle_key_with(key, Keymap::standard())
+    }
+
+    /// Handles a key event, translated to an action by `keymap`. Returns the target of the selected
+    /// vector when it is activated, so that the instruction view can jump to it.
+    pub fn handle_key_with(&mut self, key: KeyEvent, keymap: &Keymap) -> Option<Address> {
+        if key.kind == KeyEventKind::Release {
+            return None;
+        }
+
+        let last = self.len.saturating_sub(1);
+        match keymap.action(key) {
+            Some(Action::Up) => self.selected = self.selected.saturating_sub(1),
+            Some(Action::Down) => self.selected = (self.selected + 1).min(last),
+            Some(Action::PageUp) => self.selected = self.selected.saturating_sub(self.page),
+            Some(Action::PageDown) => self.selected = (self.selected + self.page).min(last),
+            Some(Action::Home) => self.selected = 0,
+            Some(Action::End) => self.selected = last,
+            Some(Action::Activate) => return self.selected_target,
+            _ => (),
+        }
+
+        None
+    }
+}
+
+/// An interrupt/exception vector table decoded from memory. Entries are little endian.
+pub struct VectorTableView<'a> {
+    /// The memory provider.
+    memory_provider: &'a dyn MemoryProvider,
+
+    /// Address of the first entry.
+    base: Address,
+    /// Amount of entries.
+    count: usize,
+    entry_size: EntrySize,
+    /// Mask applied to entries to get their target, e.g. `!1` to clear the Thumb bit.
+    target_mask: Address,
+
+    /// Names of the slots, in order.
+    names: &'a [Cow<'a, str>],
+
+    /// Symbol provider used to name targets.
+    symbol_provider: Option<&'a dyn SymbolProvider>,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> VectorTableView<'a> {
+    pub fn new(memory_provider: &'a dyn MemoryProvider, base: Address, count: usize) -> Self {
+        Self {
+            memory_provider,
+            base,
+            count,
+            entry_size: EntrySize::default(),
+            target_mask: Address::MAX,
+            names: &[],
+            symbol_provider: None,
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn entry_size(self, entry_size: EntrySize) -> Self {
+        Self { entry_size, ..self }
+    }
+
+    pub fn target_mask(self, target_mask: Address) -> Self {
+        Self {
+            target_mask,
+            ..self
+        }
+    }
+
+    pub fn names(self, names: &'a [Cow<'a, str>]) -> Self {
+        Self { names, ..self }
+    }
+
+    pub fn symbol_provider(self, symbol_provider: &'a dyn SymbolProvider) -> Self {
+        Self {
+            symbol_provider: Some(symbol_provider),
+            ..self
+        }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+
+    /// Whether `address` is backed by memory, according to the provider's regions or, if it
+    /// has none, to whether it can be read.
+    fn is_mapped(&self, regions: &[Region], address: Address) -> bool {
+        if regions.is_empty() {
+            let mut probe = [None];
+            self.memory_provider.read_to_buf(address, &mut probe);
+            probe[0].is_some()
+        } else {
+            regions.iter().any(|region| region.contains(address))
+        }
+    }
+}
+
+impl<'a> StatefulWidget for VectorTableView<'a> {
+    type State = VectorTableViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = self.wrap_in_block(area, buf);
+
+        let size = self.entry_size.size();
+        let mut table = vec![None; self.count * size];
+        self.memory_provider.read_to_buf(self.base, &mut table);
+
+        let targets: Vec<Option<Address>> = table
+            .chunks(size)
+            .map(|entry| {
+                let mut bytes = [0; 4];
+                for (byte, value) in bytes.iter_mut().zip(entry) {
+                    *byte = (*value)?;
+                }
+                Some(Address::from_le_bytes(bytes) & self.target_mask)
+            })
+            .collect();
+
+        // update state
+        state.len = self.count;
+        state.selected = state.selected.min(state.len.saturating_sub(1));
+        state.page = (area.height as usize).max(1);
+        if state.selected < state.offset {
+            state.offset = state.selected;
+        } else if state.selected >= state.offset + state.page {
+            state.offset = state.selected + 1 - state.page;
+        }
+        state.selected_target = targets.get(state.selected).copied().flatten();
+
+        let regions = self.memory_provider.regions();
+        let strings = strings();
+        let rows = targets
+            .iter()
+            .enumerate()
+            .skip(state.offset)
+            .take(state.page)
+            .map(|(index, target)| {
+                let name = self
+                    .names
+                    .get(index)
+                    .cloned()
+                    .unwrap_or_else(|| Cow::from(format!("#{index}")));
+
+                let (target, symbol) = match target {
+                    Some(target) => {
+                        let mapped = self.is_mapped(&regions, *target);
+                        let target_style = if mapped {
+                            self.theme.address
+                        } else {
+                            self.theme.alert
+                        };
+                        let symbol = self
+                            .symbol_provider
+                            .and_then(|provider| provider.symbol_at(*target));
+                        let symbol = match symbol {
+                            Some(symbol) => Span::styled(symbol.name, self.theme.info),
+                            None if !mapped => {
+                                Span::styled(strings.unmapped.to_string(), self.theme.muted)
+                            }
+                            None => Span::raw(""),
+                        };
+
+                        (Span::styled(format!("{target:08X}"), target_style), symbol)
+                    }
+                    None => (Span::styled("--------", self.theme.muted), Span::raw("")),
+                };
+
+                let row = Row::new([
+                    Line::from(Span::styled(
+                        format!("{:08X}", self.base.wrapping_add((index * size) as Address)),
+                        self.theme.muted,
+                    )),
+                    Line::from(Span::styled(name, self.theme.ascii)),
+                    Line::from(target),
+                    Line::from(symbol),
+                ]);
+
+                if index == state.selected {
+                    row.style(self.theme.selection)
+                } else {
+                    row
+                }
+            });
+
+        let constraints = [
+            Constraint::Length(8),
+            Constraint::Percentage(30),
+            Constraint::Length(8),
+            Constraint::Min(1),
+        ];
+        let table = Table::new(rows).widths(&constraints);
+        Widget::render(table, area, buf);
+    }
+}
@@ -0,0 +1,258 @@
+use crate::{keymap::Action, Address, Keymap, Theme};
+use crossterm::event::{KeyEvent, KeyEventKind};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{Block, Row, StatefulWidget, Table, Widget},
+};
+use std::collections::VecDeque;
+
+/// A single trigger of a watchpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchHit {
+    /// Timestamp or cycle count of the hit.
+    pub timestamp: u64,
+    /// Address of the watched value.
+    pub address: Address,
+    /// Value before the access that triggered the watchpoint.
+    pub old: u64,
+    /// Value after the access, the same as the old one for reads.
+    pub new: u64,
+    /// Size of the watched value, in bytes.
+    pub size: u8,
+    /// Program counter of the instruction that triggered the watchpoint, if known.
+    pub pc: Option<Address>,
+}
+
+/// A ring buffer of watchpoint hits. Once full, the oldest hits are dropped.
+#[derive(Debug, Clone)]
+pub struct WatchHitLog {
+    hits: VecDeque<WatchHit>,
+    capacity: usize,
+}
+
+impl WatchHitLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            hits: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn push(&mut self, hit: WatchHit) {
+        if self.hits.len() == self.capacity {
+            self.hits.pop_front();
+        }
+
+        self.hits.push_back(hit);
+    }
+
+    pub fn clear(&mut self) {
+        self.hits.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.hits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hits.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &WatchHit> {
+        self.hits.iter()
+    }
+}
+
+pub struct WatchHitViewState {
+    /// Whether the view sticks to the most recent hit.
+    pub follow_tail: bool,
+
+    selected: usize,
+    page: usize,
+    len: usize,
+    selected_hit: Option<WatchHit>,
+}
+
+impl Default for WatchHitViewState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WatchHitViewState {
+    pub fn new() -> Self {
+        Self {
+            follow_tail: true,
+            selected: 0,
+            page: 1,
+            len: 0,
+            selected_hit: None,
+        }
+    }
+
+    /// The selected hit, as of the last render.
+    pub fn selected_hit(&self) -> Option<&WatchHit> {
+        self.selected_hit.as_ref()
+    }
+
+    fn select(&mut self, selected: usize) {
+        self.selected = selected.min(self.len.saturating_sub(1));
+        self.follow_tail = false;
+    }
+
+    /// Handles a key event with the [standard keymap](Keymap::standard).
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<WatchHit> {
+        self.handle_key_with(key, Keymap::standard())
+    }
+
+    /// Handles a key event, translated to an action by `keymap`. Returns the selected hit when it
+    /// is activated, so that the memory view can be moved to its
+    /// [address](WatchHit::address) and the instruction view to its [PC](WatchHit::pc).
+    pub fn handle_key_with(&mut self, key: KeyEvent, keymap: &Keymap) -> Option<WatchHit> {
+        if key.kind == KeyEventKind::Release {
+            return None;
+        }
+
+        match keymap.action(key) {
+            Some(Action::Up) => self.select(self.selected.saturating_sub(1)),
+            Some(Action::Down) => self.select(self.selected + 1),
+            Some(Action::PageUp) => self.select(self.selected.saturating_sub(self.page)),
+            Some(Action::PageDown) => self.select(self.selected + self.page),
+            Some(Action::Home) => self.select(0),
+            Some(Action::End | Action::Follow) => self.follow_tail = true,
+            Some(Action::Activate) => return self.selected_hit,
+            _ => (),
+        }
+
+        None
+    }
+}
+
+/// A scrolling history of watchpoint hits: when each happened, which address was hit, how its
+/// value changed and which instruction changed it.
+pub struct WatchHitView<'a> {
+    /// The hits being displayed.
+    log: &'a WatchHitLog,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> WatchHitView<'a> {
+    pub fn new(log: &'a WatchHitLog) -> Self {
+        Self {
+            log,
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+}
+
+impl<'a> StatefulWidget for WatchHitView<'a> {
+    type State = WatchHitViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = self.wrap_in_block(area, buf);
+
+        // update state
+        state.len = self.log.len();
+        state.page = (area.height as usize).max(1);
+        state.selected = if state.follow_tail {
+            state.len.saturating_sub(1)
+        } else {
+            state.selected.min(state.len.saturating_sub(1))
+        };
+        state.selected_hit = self.log.iter().nth(state.selected).copied();
+
+        let end = if state.follow_tail {
+            state.len
+        } else {
+            (state.selected + state.page / 2 + 1).clamp(state.page.min(state.len), state.len)
+        };
+        let start = end.saturating_sub(state.page);
+
+        let rows = self
+            .log
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(end - start)
+            .map(|(index, hit)| {
+                let digits = hit.size as usize * 2;
+                let new_style = if hit.new == hit.old {
+                    Style::default().light_blue()
+                } else {
+                    self.theme.modified
+                };
+                let pc = match hit.pc {
+                    Some(pc) => Span::styled(format!("{pc:08X}"), self.theme.address),
+                    None => Span::styled("?", self.theme.muted),
+                };
+
+                let row = Row::new([
+                    Line::from(Span::styled(
+                        format!("{:>12}", hit.timestamp),
+                        self.theme.muted,
+                    )),
+                    Line::from(Span::styled(
+                        format!("{:08X}", hit.address),
+                        self.theme.address,
+                    )),
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{:0digits$X}", hit.old),
+                            Style::default().light_blue(),
+                        ),
+                        Span::styled("→", self.theme.muted),
+                        Span::styled(format!("{:0digits$X}", hit.new), new_style),
+                    ]),
+                    Line::from(pc),
+                ]);
+
+                if index == state.selected && !state.follow_tail {
+                    row.style(self.theme.selection)
+                } else {
+                    row
+                }
+            });
+
+        let constraints = [
+            Constraint::Length(12),
+            Constraint::Length(8),
+            Constraint::Length(33),
+            Constraint::Min(0),
+        ];
+        let table = Table::new(rows).widths(&constraints);
+        Widget::render(table, area, buf);
+    }
+}
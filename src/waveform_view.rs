@@ -0,0 +1,250 @@
+use crate::{memory_view::MemoryProvider, Address, Theme};
+use ratatui::{
+    prelude::{Buffer, Rect, *},
+    widgets::{
+        canvas::{Canvas, Line as CanvasLine},
+        Block, Widget,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SampleFormat {
+    /// Unsigned 8-bit samples, centered at 0x80.
+    U8,
+    /// Signed 16-bit little endian samples.
+    #[default]
+    I16,
+}
+
+impl SampleFormat {
+    /// Size of a sample, in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            Self::U8 => 1,
+            Self::I16 => 2,
+        }
+    }
+
+    /// Decodes a sample into the `-1.0..=1.0` range.
+    fn decode(self, bytes: &[Option<u8>]) -> Option<f64> {
+        match self {
+            Self::U8 => Some((bytes[0]? as f64 - 128.0) / 128.0),
+            Self::I16 => Some(i16::from_le_bytes([bytes[0]?, bytes[1]?]) as f64 / 32768.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Channels {
+    #[default]
+    Mono,
+    /// Interleaved left and right samples.
+    Stereo,
+}
+
+impl Channels {
+    pub fn count(self) -> usize {
+        match self {
+            Self::Mono => 1,
+            Self::Stereo => 2,
+        }
+    }
+}
+
+/// A memory range interpreted as PCM samples, plotted with braille characters.
+pub struct WaveformView<'a> {
+    /// The memory provider.
+    memory_provider: &'a dyn MemoryProvider,
+
+    /// Address of the first sample.
+    base: Address,
+    /// Amount of frames (one sample per channel) to plot.
+    frames: usize,
+
+    format: SampleFormat,
+    channels: Channels,
+    /// Sample rate, in Hz.
+    rate: u32,
+
+    /// Block to draw inside.
+    block: Option<Block<'a>>,
+
+    theme: Theme,
+}
+
+impl<'a> WaveformView<'a> {
+    pub fn new(memory_provider: &'a dyn MemoryProvider, base: Address, frames: usize) -> Self {
+        Self {
+            memory_provider,
+            base,
+            frames,
+            format: SampleFormat::default(),
+            channels: Channels::default(),
+            rate: 44100,
+            block: None,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn format(self, format: SampleFormat) -> Self {
+        Self { format, ..self }
+    }
+
+    pub fn channels(self, channels: Channels) -> Self {
+        Self { channels, ..self }
+    }
+
+    pub fn rate(self, rate: u32) -> Self {
+        Self { rate, ..self }
+    }
+
+    pub fn block(self, block: Block<'a>) -> Self {
+        Self {
+            block: Some(block),
+            ..self
+        }
+    }
+
+    pub fn theme(self, theme: &Theme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn wrap_in_block(&mut self, area: Rect, buf: &mut Buffer) -> Rect {
+        if let Some(block) = self.block.take() {
+            let inner_area = block.inner(area);
+            block.render(area, buf);
+            inner_area
+        } else {
+            area
+        }
+    }
+
+    /// Reduces the samples to a (min, max) pair per plotted column.
+    fn columns(samples: &[Option<f64>], columns: usize) -> Vec<Option<(f64, f64)>> {
+        let columns = columns.max(1);
+        (0..columns)
+            .map(|column| {
+                let start = column * samples.len() / columns;
+                let end = ((column + 1) * samples.len() / columns).max(start + 1);
+                samples
+                    .get(start..end.min(samples.len()))?
+                    .iter()
+                    .flatten()
+                    .fold(None, |acc: Option<(f64, f64)>, &sample| match acc {
+                        Some((min, max)) => Some((min.min(sample), max.max(sample))),
+                        None => Some((sample, sample)),
+                    })
+            })
+            .collect()
+    }
+
+    fn render_channel(&self, samples: &[Option<f64>], color: Color, area: Rect, buf: &mut Buffer) {
+        // braille cells are two dots wide
+        let columns = Self::columns(samples, area.width as usize * 2);
+        let width = columns.len() as f64;
+        let baseline = self.theme.muted.fg.unwrap_or(Color::Reset);
+
+        let canvas = Canvas::default()
+            .marker(Marker::Braille)
+            .x_bounds([0.0, width])
+            .y_bounds([-1.0, 1.0])
+            .paint(|ctx| {
+                ctx.draw(&CanvasLine {
+                    x1: 0.0,
+                    y1: 0.0,
+                    x2: width,
+                    y2: 0.0,
+                    color: baseline,
+                });
+
+                let mut previous: Option<(f64, f64)> = None;
+                for (x, column) in columns.iter().enumerate() {
+                    let Some((min, max)) = *column else {
+                        previous = None;
+                        continue;
+                    };
+
+                    let x = x as f64;
+                    ctx.draw(&CanvasLine {
+                        x1: x,
+                        y1: min,
+                        x2: x,
+                        y2: max,
+                        color,
+                    });
+
+                    // connect to the previous column so that sparse waves stay continuous
+                    if let Some((prev_min, prev_max)) = previous {
+                        let y = (prev_min + prev_max) / 2.0;
+                        ctx.draw(&CanvasLine {
+                            x1: x - 1.0,
+                            y1: y,
+                            x2: x,
+                            y2: (min + max) / 2.0,
+                            color,
+                        });
+                    }
+                    previous = Some((min, max));
+                }
+            });
+
+        canvas.render(area, buf);
+    }
+}
+
+impl<'a> Widget for WaveformView<'a> {
+    fn render(mut self, area: Rect, buf: &mut Buffer) {
+        let area = self.wrap_in_block(area, buf);
+        if area.height < 2 || area.width == 0 {
+            return;
+        }
+
+        let channels = self.channels.count();
+        let size = self.format.size();
+        let mut data = vec![None; self.frames * channels * size];
+        self.memory_provider.read_to_buf(self.base, &mut data);
+
+        let samples: Vec<Option<f64>> = data
+            .chunks(size)
+            .map(|sample| self.format.decode(sample))
+            .collect();
+
+        let plot = Rect {
+            height: area.height - 1,
+            ..area
+        };
+        // canvases only take colors, which themes without any leave to the terminal
+        let colors =
+            [self.theme.ascii, self.theme.address].map(|style| style.fg.unwrap_or(Color::Reset));
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Ratio(1, channels as u32); channels])
+            .split(plot);
+
+        for (channel, (area, color)) in chunks.iter().zip(colors).enumerate() {
+            let channel_samples: Vec<Option<f64>> = samples
+                .iter()
+                .skip(channel)
+                .step_by(channels)
+                .copied()
+                .collect();
+            self.render_channel(&channel_samples, color, *area, buf);
+        }
+
+        let duration = self.frames as f64 * 1000.0 / self.rate.max(1) as f64;
+        let info = format!(
+            "{:08X}  {} frames  {:.2} ms @ {} Hz",
+            self.base, self.frames, duration, self.rate
+        );
+        buf.set_stringn(
+            area.x,
+            area.bottom() - 1,
+            info,
+            area.width as usize,
+            self.theme.info,
+        );
+    }
+}